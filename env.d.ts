@@ -14,6 +14,7 @@ declare interface WindowAttrs {
     maximizable ?: boolean,
     closable ?: boolean,
     preferredRenderers ?: RenderBackend | RenderBackend[],
+    headless ?: boolean,
 }
 
 declare interface ResizeDetail {
@@ -41,6 +42,8 @@ declare interface MouseDetail {
     windowY: number;
     screenX: number;
     screenY: number;
+    /** Number of consecutive clicks at the same position */
+    clickCount: number;
 }
 
 declare interface CaretDetail {
@@ -80,6 +83,15 @@ declare interface ScrollDetail {
     scrollLeft: number;
 }
 
+declare interface AnimationDetail {
+    animationName: string;
+    elapsedTime: number;
+}
+
+declare interface LinkClickDetail {
+    url: string;
+}
+
 declare interface BoundsChangeDetail {
     originBounds: ElementRect,
 }
@@ -109,6 +121,8 @@ declare type Align =
 declare interface SelectOption {
     label: string,
     value: string,
+    group?: string,
+    disabled?: boolean,
 }
 
 declare interface AlertOptions {
@@ -129,6 +143,13 @@ declare interface StyleProps extends Record<string, number | string>{
     backgroundColor?: string;
     fontSize?: number;
     lineHeight?: number;
+    textAlign?: "left" | "right" | "center" | "justify" | "start" | "end";
+    textOverflow?: "clip" | "ellipsis";
+    whiteSpace?: "normal" | "nowrap" | "pre" | "pre-wrap";
+    wordBreak?: "normal" | "break-all" | "keep-all";
+    lineClamp?: number | "none";
+    letterSpacing?: number | string;
+    textTransform?: "none" | "uppercase" | "lowercase" | "capitalize";
 
     borderTop?: string;
     borderRight?: string;
@@ -181,8 +202,13 @@ declare interface StyleProps extends Record<string, number | string>{
     left?: number | string,
     transform?: string,
     animationName?: string,
-    animationDuration?: number,
+    animationDuration?: number | string,
     animationIterationCount?: number,
+    animationTimingFunction?: string,
+    animationDelay?: number | string,
+    animationDirection?: 'normal' | 'reverse' | 'alternate' | 'alternate-reverse',
+    animationFillMode?: 'none' | 'forwards' | 'backwards' | 'both',
+    animationPlayState?: 'running' | 'paused',
 
     // short hands
     background?: string,
@@ -216,7 +242,18 @@ declare function path_join(path: string, other: string): string;
 declare function animation_create(name: string, keyFrames: Record<string, Record<string, any>>)
 
 declare interface TypefaceParams {
-    family: string,
+    /**
+     * Path of font file, path with `res://` prefix is read from resources
+     */
+    path?: string,
+    /**
+     * Content of font file
+     */
+    data?: number[],
+    /**
+     * Family of installed fonts
+     */
+    family?: string,
     weight?: string,
 }
 
@@ -41,6 +41,8 @@ declare interface MouseDetail {
     windowY: number;
     screenX: number;
     screenY: number;
+    /** Number of consecutive clicks at the same position */
+    clickCount: number;
 }
 
 declare interface CaretDetail {
@@ -80,6 +82,15 @@ declare interface ScrollDetail {
     scrollLeft: number;
 }
 
+declare interface AnimationDetail {
+    animationName: string;
+    elapsedTime: number;
+}
+
+declare interface LinkClickDetail {
+    url: string;
+}
+
 declare interface BoundsChangeDetail {
     originBounds: ElementRect,
 }
@@ -109,6 +120,8 @@ declare type Align =
 declare interface SelectOption {
     label: string,
     value: string,
+    group?: string,
+    disabled?: boolean,
 }
 
 declare interface AlertOptions {
@@ -129,6 +142,13 @@ declare interface StyleProps extends Record<string, number | string>{
     backgroundColor?: string;
     fontSize?: number;
     lineHeight?: number;
+    textAlign?: "left" | "right" | "center" | "justify" | "start" | "end";
+    textOverflow?: "clip" | "ellipsis";
+    whiteSpace?: "normal" | "nowrap" | "pre" | "pre-wrap";
+    wordBreak?: "normal" | "break-all" | "keep-all";
+    lineClamp?: number | "none";
+    letterSpacing?: number | string;
+    textTransform?: "none" | "uppercase" | "lowercase" | "capitalize";
 
     borderTop?: string;
     borderRight?: string;
@@ -181,8 +201,13 @@ declare interface StyleProps extends Record<string, number | string>{
     left?: number | string,
     transform?: string,
     animationName?: string,
-    animationDuration?: number,
+    animationDuration?: number | string,
     animationIterationCount?: number,
+    animationTimingFunction?: string,
+    animationDelay?: number | string,
+    animationDirection?: 'normal' | 'reverse' | 'alternate' | 'alternate-reverse',
+    animationFillMode?: 'none' | 'forwards' | 'backwards' | 'both',
+    animationPlayState?: 'running' | 'paused',
 
     // short hands
     background?: string,
@@ -216,7 +241,18 @@ declare function path_join(path: string, other: string): string;
 declare function animation_create(name: string, keyFrames: Record<string, Record<string, any>>)
 
 declare interface TypefaceParams {
-    family: string,
+    /**
+     * Path of font file, path with `res://` prefix is read from resources
+     */
+    path?: string,
+    /**
+     * Content of font file
+     */
+    data?: number[],
+    /**
+     * Family of installed fonts
+     */
+    family?: string,
     weight?: string,
 }
 
@@ -394,6 +430,49 @@ declare class Window {
         width?: number;
         height?: number;
     }): Popup;
+    /**
+     * Register a command which can be bound to key chords
+     * @param name {string}
+     * @param callback {() => void}
+     */
+    registerCommand(name: string, callback: () => void): void;
+    /**
+     *
+     * @param name {string}
+     */
+    unregisterCommand(name: string): void;
+    /**
+     * The command is disabled while the predicate returns false
+     * @param name {string}
+     * @param predicate {() => boolean}
+     */
+    setCommandEnabledPredicate(name: string, predicate: () => boolean): void;
+    /**
+     * Bind a key chord like "Ctrl+K Ctrl+S" to the command, the binding only works when the
+     * focused element is inside the scope if any
+     * @param chord {string}
+     * @param command {string}
+     * @param scope {Element | undefined}
+     */
+    bindKey(chord: string, command: string, scope?: Element): void;
+    /**
+     *
+     * @param chord {string}
+     * @param command {string}
+     */
+    unbindKey(chord: string, command: string): void;
+    /**
+     * Returns the key chords bound to the command, e.g. ["Ctrl+K Ctrl+S"]
+     * @param command {string}
+     * @returns {string[]}
+     */
+    getCommandBindings(command: string): string[];
+    /**
+     * Execute the command if it is enabled
+     * @param name {string}
+     * @returns {boolean}
+     */
+    executeCommand(name: string): boolean;
     /**
      *
      * @param message {string | Element}
@@ -481,6 +560,18 @@ declare class Window {
         x: number;
         y: number;
     };
+    /**
+     * Capture the current frame as png bytes
+     * @param element {Element | undefined} only capture the area of the element if specified
+     * @returns {number[]}
+     */
+    capture(element?: Element): number[];
+    /**
+     * Compare the captured frame with the golden png file, the file is created if not exists
+     * @param path {string}
+     * @param element {Element | undefined}
+     */
+    matchSnapshot(path: string, element?: Element): void;
     close(): void;
     /**
      *
@@ -525,6 +616,51 @@ declare class Window {
     removeEventListener(type: any, callback: any): void;
     
 }
+/**
+ * Inject synthetic input into a window, for scripted ui tests.
+ * Targets are elements or positions in window coordinates.
+ */
+declare class WindowDriver {
+    /**
+     *
+     * @param window {Window}
+     */
+    constructor(window: Window);
+    mouseMove(target: Element | { x: number; y: number }): void;
+    /**
+     *
+     * @param target {Element | {x: number, y: number}}
+     * @param button {number} 1 = left, 2 = right, 3 = middle
+     */
+    mouseDown(target: Element | { x: number; y: number }, button?: number): void;
+    mouseUp(target: Element | { x: number; y: number }, button?: number): void;
+    click(target: Element | { x: number; y: number }): void;
+    wheel(target: Element | { x: number; y: number }, cols: number, rows: number): void;
+    /**
+     *
+     * @param key {string} named key(e.g. "Enter") or character
+     * @param modifiers {number} combination of KEY_MOD_*
+     */
+    keyDown(key: string, modifiers?: number): void;
+    keyUp(key: string, modifiers?: number): void;
+    pressKey(key: string, modifiers?: number): void;
+    /**
+     * Commit text to the focused element like an input method does
+     * @param text {string}
+     */
+    input(text: string): void;
+    focus(element: Element): void;
+    touchStart(id: number, target: Element | { x: number; y: number }): void;
+    touchMove(id: number, target: Element | { x: number; y: number }): void;
+    touchEnd(id: number, target: Element | { x: number; y: number }): void;
+    touchCancel(id: number, target: Element | { x: number; y: number }): void;
+    tap(target: Element | { x: number; y: number }): void;
+    swipe(from: Element | { x: number; y: number }, to: Element | { x: number; y: number }, steps?: number): void;
+    /**
+     * Run the pending update of window
+     */
+    flush(): void;
+}
 /**
  * @template D
  * @template E
@@ -648,6 +784,11 @@ declare class Element {
      * Request focus on the current element
      */
     focus(): void;
+    /**
+     * Capture the painted area of the current element as png bytes
+     * @returns {number[] | undefined}
+     */
+    capture(): number[] | undefined;
     /**
      * Get the window of element
      * @returns {Window}
@@ -751,6 +892,21 @@ declare class Element {
      * @param callback {(event: IVoidEvent) => void}
      */
     bindBlur(callback: (event: IVoidEvent) => void): void;
+    /**
+     *
+     * @param callback {(event: IAnimationEvent) => void}
+     */
+    bindAnimationStart(callback: (event: IAnimationEvent) => void): void;
+    /**
+     *
+     * @param callback {(event: IAnimationEvent) => void}
+     */
+    bindAnimationIteration(callback: (event: IAnimationEvent) => void): void;
+    /**
+     *
+     * @param callback {(event: IAnimationEvent) => void}
+     */
+    bindAnimationEnd(callback: (event: IAnimationEvent) => void): void;
     /**
      *
      * @param callback {(event: IMouseEvent) => void}
@@ -853,6 +1009,24 @@ declare class LabelElement extends Element {
      * @param text {string}
      */
     set text(text: string);
+    /**
+     * Set content with images and placeholders, which are represented by U+FFFC in text
+     * @param elements {TextElementUnit[]}
+     */
+    setElements(elements: TextElementUnit[]): void;
+    /**
+     * Highlight occurrences of the query, the first match is activated and scrolled into view
+     * @param query {string}
+     * @param options {FindOptions | undefined}
+     * @returns {[[number, number], [number, number]][]} (start, end) of matches
+     */
+    find(query: string, options?: FindOptions): [[number, number], [number, number]][];
+    /**
+     * Activate the match and scroll it into view
+     * @param index {number}
+     */
+    setActiveMatch(index: number): void;
+    clearFind(): void;
 }
 declare class CheckboxElement extends Element {
     constructor();
@@ -942,6 +1116,26 @@ declare class SelectElement extends Element {
      * @returns {string}
      */
     get value(): string;
+    /**
+     *
+     * @param values {string[]}
+     */
+    set values(values: string[]);
+    /**
+     *
+     * @returns {string[]}
+     */
+    get values(): string[];
+    /**
+     *
+     * @param multiple {boolean}
+     */
+    set multiple(multiple: boolean);
+    /**
+     *
+     * @returns {boolean}
+     */
+    get multiple(): boolean;
     /**
      *
      * @param options {SelectOption[]}
@@ -974,6 +1168,108 @@ declare class SelectElement extends Element {
     get disabled(): boolean;
     bindChange(callback: any): void;
 }
+declare class ListViewElement extends Element {
+    constructor();
+    /**
+     * Set the functions creating and binding the elements of items. Elements are recycled when
+     * scrolled out, so bind may be called many times on an element with different items.
+     *
+     * @param create {() => Element}
+     * @param bind {(element: Element, index: number) => void}
+     */
+    setItemFactory(create: () => Element, bind: (element: Element, index: number) => void): void;
+    /**
+     *
+     * @param count {number}
+     */
+    set itemCount(count: number);
+    /**
+     *
+     * @returns {number}
+     */
+    get itemCount(): number;
+    /**
+     * Height of the rows not measured yet
+     * @param height {number}
+     */
+    set estimatedRowHeight(height: number);
+    /**
+     *
+     * @returns {number}
+     */
+    get estimatedRowHeight(): number;
+    /**
+     *
+     * @returns {number}
+     */
+    get firstVisibleItem(): number;
+    /**
+     * Bind the visible items again after the data changed
+     */
+    refresh(): void;
+    /**
+     *
+     * @param index {number}
+     */
+    scrollToItem(index: number): void;
+    scrollBy(value: any): void;
+}
+declare class GridViewElement extends Element {
+    constructor();
+    /**
+     * Set the functions creating and binding the elements of items. Elements are recycled when
+     * scrolled out, so bind may be called many times on an element with different items.
+     *
+     * @param create {() => Element}
+     * @param bind {(element: Element, index: number) => void}
+     */
+    setItemFactory(create: () => Element, bind: (element: Element, index: number) => void): void;
+    /**
+     *
+     * @param columns {number}
+     */
+    set columns(columns: number);
+    /**
+     *
+     * @returns {number}
+     */
+    get columns(): number;
+    /**
+     *
+     * @param count {number}
+     */
+    set itemCount(count: number);
+    /**
+     *
+     * @returns {number}
+     */
+    get itemCount(): number;
+    /**
+     * Height of the rows not measured yet
+     * @param height {number}
+     */
+    set estimatedRowHeight(height: number);
+    /**
+     *
+     * @returns {number}
+     */
+    get estimatedRowHeight(): number;
+    /**
+     *
+     * @returns {number}
+     */
+    get firstVisibleItem(): number;
+    /**
+     * Bind the visible items again after the data changed
+     */
+    refresh(): void;
+    /**
+     *
+     * @param index {number}
+     */
+    scrollToItem(index: number): void;
+    scrollBy(value: any): void;
+}
 /**
  * @typedef {{
  *   type: "text",
@@ -1034,22 +1330,63 @@ declare class ParagraphElement extends Element {
  *   fontFamilies ?: string[],
  *   fontSize ?: number,
  *   color ?: string,
- *   backgroundColor ?: string
+ *   backgroundColor ?: string,
+ *   letterSpacing ?: number,
+ *   wordSpacing ?: number,
+ *   textDecorationStyle ?: "solid" | "double" | "dotted" | "dashed" | "wavy",
+ *   textDecorationColor ?: string,
+ *   textDecorationThickness ?: number,
+ *   verticalAlign ?: "super" | "sub",
+ *   link ?: string
  * }} TextUnit
+ *
+ * @typedef {{
+ *   type: "image",
+ *   src: string,
+ *   width: number,
+ *   height: number,
+ *   verticalAlign ?: "baseline" | "middle" | "top" | "bottom",
+ *   baselineOffset ?: number
+ * }} ImageUnit
+ *
+ * @typedef {{
+ *   type: "placeholder",
+ *   width: number,
+ *   height: number,
+ *   verticalAlign ?: "baseline" | "middle" | "top" | "bottom",
+ *   baselineOffset ?: number
+ * }} PlaceholderUnit
+ *
+ * @typedef {TextUnit | ImageUnit | PlaceholderUnit} TextElementUnit
+ *
+ * @typedef {{
+ *   start: number,
+ *   end: number,
+ *   kind: "underline" | "highlight" | "squiggle",
+ *   color ?: string,
+ *   group ?: string
+ * }} DecorationRange
+ *
+ * @typedef {{
+ *   caseSensitive ?: boolean,
+ *   wholeWord ?: boolean,
+ *   highlightColor ?: string,
+ *   activeHighlightColor ?: string
+ * }} FindOptions
  */
 declare class RichTextElement extends Element {
     constructor();
     /**
      *
-     * @param units {TextUnit[]}
+     * @param units {TextElementUnit[]}
      */
-    addLine(units: TextUnit[]): void;
+    addLine(units: TextElementUnit[]): void;
     /**
      *
      * @param index {number}
-     * @param units {TextUnit[]}
+     * @param units {TextElementUnit[]}
      */
-    insertLine(index: number, units: TextUnit[]): void;
+    insertLine(index: number, units: TextElementUnit[]): void;
     /**
      *
      * @param index {number}
@@ -1058,21 +1395,61 @@ declare class RichTextElement extends Element {
     /**
      *
      * @param index {number}
-     * @param units {TextUnit[]}
+     * @param units {TextElementUnit[]}
      */
-    updateLine(index: number, units: TextUnit[]): void;
+    updateLine(index: number, units: TextElementUnit[]): void;
     clear(): void;
     /**
      *
-     * @param units {TextUnit[]}
+     * @param units {TextElementUnit[]}
      * @return {[number, number]}
      */
-    measureLine(units: TextUnit[]): [number, number];
+    measureLine(units: TextElementUnit[]): [number, number];
+    /**
+     * Bounds of the atom relative to the element, used to place widgets over placeholders
+     * @param row {number}
+     * @param col {number}
+     * @returns {ElementRect | undefined}
+     */
+    getCharRect(row: number, col: number): ElementRect | undefined;
     /**
      *
      * @returns {string | undefined}
      */
     get selectionText(): string | undefined;
+    /**
+     * Highlight occurrences of the query, the first match is activated and scrolled into view
+     * @param query {string}
+     * @param options {FindOptions | undefined}
+     * @returns {[[number, number], [number, number]][]} (start, end) of matches
+     */
+    find(query: string, options?: FindOptions): [[number, number], [number, number]][];
+    /**
+     * Activate the match and scroll it into view
+     * @param index {number}
+     */
+    setActiveMatch(index: number): void;
+    clearFind(): void;
+    /**
+     * Decorate chars between start and end, the offsets are not updated when lines are inserted or deleted and all decorations are removed by clear()
+     * @param decoration {DecorationRange}
+     */
+    addDecoration(decoration: DecorationRange): void;
+    /**
+     * Remove decorations of the group, all decorations are removed if group is not specified
+     * @param group {string | undefined}
+     */
+    removeDecorations(group?: string): void;
+    /**
+     *
+     * @returns {DecorationRange[]}
+     */
+    get decorations(): DecorationRange[];
+    /**
+     * Bind handler of clicking text units with link
+     * @param callback {(event: ILinkClickEvent) => void}
+     */
+    bindLinkClick(callback: (event: ILinkClickEvent) => void): void;
 }
 declare class ImageElement extends Element {
     constructor();
@@ -1191,6 +1568,27 @@ declare class TextInputElement extends Element {
      * @returns {boolean}
      */
     get disabled(): boolean;
+    /**
+     * Decorate chars between start and end, the decoration moves with the chars when the text is edited
+     * @param decoration {DecorationRange}
+     */
+    addDecoration(decoration: DecorationRange): void;
+    /**
+     * Remove decorations of the group, all decorations are removed if group is not specified
+     * @param group {string | undefined}
+     */
+    removeDecorations(group?: string): void;
+    /**
+     *
+     * @returns {DecorationRange[]}
+     */
+    get decorations(): DecorationRange[];
+    /**
+     * Suggestions for the misspelled word at the char offset
+     * @param charOffset {number}
+     * @returns {string[]}
+     */
+    getSpellingSuggestions(charOffset: number): string[];
     /**
      *
      * @param callback {(e: ITextEvent) => void}
@@ -1231,6 +1629,11 @@ declare class TextEditElement extends Element {
      * @param charOffset {number}
      */
     setCaretByCharOffset(charOffset: number): void;
+    /**
+     * Insert elements at the caret, images and placeholders are represented by U+FFFC in text
+     * @param elements {TextElementUnit[]}
+     */
+    insertElements(elements: TextElementUnit[]): void;
     /**
      *
      * @param value {boolean}
@@ -1241,6 +1644,40 @@ declare class TextEditElement extends Element {
      * @returns {boolean}
      */
     get disabled(): boolean;
+    /**
+     * Highlight occurrences of the query, the first match is activated and scrolled into view
+     * @param query {string}
+     * @param options {FindOptions | undefined}
+     * @returns {[[number, number], [number, number]][]} (start, end) of matches
+     */
+    find(query: string, options?: FindOptions): [[number, number], [number, number]][];
+    /**
+     * Activate the match and scroll it into view
+     * @param index {number}
+     */
+    setActiveMatch(index: number): void;
+    clearFind(): void;
+    /**
+     * Decorate chars between start and end, the decoration moves with the chars when the text is edited
+     * @param decoration {DecorationRange}
+     */
+    addDecoration(decoration: DecorationRange): void;
+    /**
+     * Remove decorations of the group, all decorations are removed if group is not specified
+     * @param group {string | undefined}
+     */
+    removeDecorations(group?: string): void;
+    /**
+     *
+     * @returns {DecorationRange[]}
+     */
+    get decorations(): DecorationRange[];
+    /**
+     * Suggestions for the misspelled word at the char offset
+     * @param charOffset {number}
+     * @returns {string[]}
+     */
+    getSpellingSuggestions(charOffset: number): string[];
     /**
      *
      * @param callback {(e: ITextEvent) => void}
@@ -1379,6 +1816,42 @@ declare type TextUnit = {
     fontSize?: number;
     color?: string;
     backgroundColor?: string;
+    letterSpacing?: number;
+    wordSpacing?: number;
+    textDecorationStyle?: "solid" | "double" | "dotted" | "dashed" | "wavy";
+    textDecorationColor?: string;
+    textDecorationThickness?: number;
+    verticalAlign?: "super" | "sub";
+    link?: string;
+};
+declare type ImageUnit = {
+    type: "image";
+    src: string;
+    width: number;
+    height: number;
+    verticalAlign?: "baseline" | "middle" | "top" | "bottom";
+    baselineOffset?: number;
+};
+declare type PlaceholderUnit = {
+    type: "placeholder";
+    width: number;
+    height: number;
+    verticalAlign?: "baseline" | "middle" | "top" | "bottom";
+    baselineOffset?: number;
+};
+declare type TextElementUnit = TextUnit | ImageUnit | PlaceholderUnit;
+declare type FindOptions = {
+    caseSensitive?: boolean;
+    wholeWord?: boolean;
+    highlightColor?: string;
+    activeHighlightColor?: string;
+};
+declare type DecorationRange = {
+    start: number;
+    end: number;
+    kind: "underline" | "highlight" | "squiggle";
+    color?: string;
+    group?: string;
 };
 /**
  * <T>
@@ -1399,6 +1872,8 @@ declare type IMouseWheelEvent = IEvent<MouseWheelDetail>;
 declare type ITextEvent = IEvent<TextDetail>;
 declare type ITouchEvent = IEvent<TouchDetail>;
 declare type IScrollEvent = IEvent<ScrollDetail>;
+declare type IAnimationEvent = IEvent<AnimationDetail>;
+declare type ILinkClickEvent = IEvent<LinkClickDetail>;
 declare type IDroppedFileEvent = IEvent<string>;
 declare type IHoveredFileEvent = IEvent<string>;
 declare class Clipboard {
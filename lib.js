@@ -15,6 +15,8 @@ const VT_RADIO = "radio"
 const VT_RADIO_GROUP = "radio-group"
 const VT_RICH_TEXT = "rich-text"
 const VT_SELECT = "select"
+const VT_LIST_VIEW = "list-view"
+const VT_GRID_VIEW = "grid-view"
 const VT_DIALOG = "dialog";
 const VT_DIALOG_TITLE = "dialog-title";
 
@@ -178,6 +180,137 @@ export class StandardMenuItem {
         return StandardMenuItem_get_disabled(this.#handle);
     }
 
+    /**
+     *
+     * @param value {string | undefined} path of the image shown before the label
+     */
+    set icon(value) {
+        StandardMenuItem_set_icon(this.#handle, value);
+    }
+
+    /**
+     *
+     * @param value {string | undefined} shortcut like "Ctrl+S"
+     */
+    set accelerator(value) {
+        StandardMenuItem_set_accelerator(this.#handle, value);
+    }
+
+    /**
+     * Execute the command registered by Window.registerCommand instead of the callback, the key chords bound to the command are shown after the label
+     * @param value {string | undefined}
+     */
+    set command(value) {
+        StandardMenuItem_set_command(this.#handle, value);
+    }
+
+    get handle() {
+        return this.#handle;
+    }
+}
+
+export class CheckMenuItem {
+    #handle;
+
+    /**
+     *
+     * @param label {string}
+     * @param callback {(checked: boolean) => void}
+     * @param radioGroup {string | undefined} items with the same radio group are checked exclusively
+     */
+    constructor(label, callback, radioGroup) {
+        this.#handle = CheckMenuItem_js_new(label, radioGroup, callback);
+    }
+
+    /**
+     *
+     * @param value {boolean}
+     */
+    set checked(value) {
+        CheckMenuItem_set_checked(this.#handle, value);
+    }
+
+    /**
+     *
+     * @returns {boolean}
+     */
+    get checked() {
+        return CheckMenuItem_get_checked(this.#handle);
+    }
+
+    /**
+     *
+     * @param value {boolean}
+     */
+    set disabled(value) {
+        CheckMenuItem_set_disabled(this.#handle, value);
+    }
+
+    /**
+     *
+     * @returns {boolean}
+     */
+    get disabled() {
+        return CheckMenuItem_get_disabled(this.#handle);
+    }
+
+    /**
+     *
+     * @param value {string | undefined}
+     */
+    set icon(value) {
+        CheckMenuItem_set_icon(this.#handle, value);
+    }
+
+    /**
+     *
+     * @param value {string | undefined}
+     */
+    set accelerator(value) {
+        CheckMenuItem_set_accelerator(this.#handle, value);
+    }
+
+    get handle() {
+        return this.#handle;
+    }
+}
+
+export class SubmenuItem {
+    #handle;
+
+    /**
+     *
+     * @param label {string}
+     * @param menu {Menu}
+     */
+    constructor(label, menu) {
+        this.#handle = SubmenuItem_new(label, menu.handle);
+    }
+
+    /**
+     *
+     * @param value {boolean}
+     */
+    set disabled(value) {
+        SubmenuItem_set_disabled(this.#handle, value);
+    }
+
+    /**
+     *
+     * @returns {boolean}
+     */
+    get disabled() {
+        return SubmenuItem_get_disabled(this.#handle);
+    }
+
+    /**
+     *
+     * @param value {string | undefined}
+     */
+    set icon(value) {
+        SubmenuItem_set_icon(this.#handle, value);
+    }
+
     get handle() {
         return this.#handle;
     }
@@ -198,6 +331,22 @@ export class Menu {
         Menu_add_standard_item(this.#handle, item.handle);
     }
 
+    /**
+     *
+     * @param item {CheckMenuItem}
+     */
+    addCheckItem(item) {
+        Menu_add_check_item(this.#handle, item.handle);
+    }
+
+    /**
+     *
+     * @param item {SubmenuItem}
+     */
+    addSubmenu(item) {
+        Menu_add_submenu(this.#handle, item.handle);
+    }
+
     addSeparator() {
         Menu_add_separator(this.#handle);
     }
@@ -317,6 +466,94 @@ export class Window {
         Window_popup_menu(this.#windowHandle, menu.handle, x, y);
     }
 
+    /**
+     * Trigger the items of the menu by their accelerators even if the menu is not shown
+     * @param menu {Menu}
+     */
+    registerMenuShortcuts(menu) {
+        Window_register_menu_shortcuts(this.#windowHandle, menu.handle);
+    }
+
+    /**
+     *
+     * @param menu {Menu}
+     */
+    unregisterMenuShortcuts(menu) {
+        Window_unregister_menu_shortcuts(this.#windowHandle, menu.handle);
+    }
+
+    /**
+     * Show the menu as the menu bar of the window, the menu bar is removed if menu is null
+     * @param menu {Menu | null}
+     */
+    setMenubar(menu) {
+        Window_set_menubar(this.#windowHandle, menu ? menu.handle : undefined);
+    }
+
+    /**
+     * Register a command which can be bound to key chords
+     * @param name {string}
+     * @param callback {() => void}
+     */
+    registerCommand(name, callback) {
+        Window_register_command(this.#windowHandle, name, callback);
+    }
+
+    /**
+     *
+     * @param name {string}
+     */
+    unregisterCommand(name) {
+        Window_unregister_command(this.#windowHandle, name);
+    }
+
+    /**
+     * The command is disabled while the predicate returns false
+     * @param name {string}
+     * @param predicate {() => boolean}
+     */
+    setCommandEnabledPredicate(name, predicate) {
+        Window_set_command_enabled_predicate(this.#windowHandle, name, predicate);
+    }
+
+    /**
+     * Bind a key chord like "Ctrl+K Ctrl+S" to the command, the binding only works when the
+     * focused element is inside the scope if any
+     * @param chord {string}
+     * @param command {string}
+     * @param scope {Element | undefined}
+     */
+    bindKey(chord, command, scope) {
+        Window_bind_key(this.#windowHandle, chord, command, scope ? scope.handle : undefined);
+    }
+
+    /**
+     *
+     * @param chord {string}
+     * @param command {string}
+     */
+    unbindKey(chord, command) {
+        Window_unbind_key(this.#windowHandle, chord, command);
+    }
+
+    /**
+     * Returns the key chords bound to the command, e.g. ["Ctrl+K Ctrl+S"]
+     * @param command {string}
+     * @returns {string[]}
+     */
+    getCommandBindings(command) {
+        return Window_get_command_bindings(this.#windowHandle, command);
+    }
+
+    /**
+     * Execute the command if it is enabled
+     * @param name {string}
+     * @returns {boolean}
+     */
+    executeCommand(name) {
+        return Window_execute_command(this.#windowHandle, name);
+    }
+
     /**
      *
      * @param message {string | Element}
@@ -542,6 +779,24 @@ export class Window {
         return {x, y}
     }
 
+    /**
+     * Capture the current frame as png bytes
+     * @param element {Element | undefined} only capture the area of the element if specified
+     * @returns {number[]}
+     */
+    capture(element) {
+        return Window_capture(this.#windowHandle, element?.handle);
+    }
+
+    /**
+     * Compare the captured frame with the golden png file, the file is created if not exists
+     * @param path {string}
+     * @param element {Element | undefined}
+     */
+    matchSnapshot(path, element) {
+        Window_match_snapshot(this.#windowHandle, path, element?.handle);
+    }
+
     close() {
         Window_close(this.#windowHandle);
     }
@@ -625,6 +880,196 @@ export class Window {
 
 }
 
+/**
+ * Inject synthetic input into a window, for scripted ui tests.
+ * Targets are elements or positions in window coordinates.
+ */
+export class WindowDriver {
+
+    #windowHandle;
+
+    /**
+     *
+     * @param window {Window}
+     */
+    constructor(window) {
+        this.#windowHandle = window.handle;
+    }
+
+    /**
+     *
+     * @param target {Element | {x: number, y: number}}
+     * @returns {[number, number]}
+     */
+    #resolve(target) {
+        if (target instanceof Element) {
+            return WindowDriver_element_center(this.#windowHandle, target.eid);
+        }
+        return [target.x, target.y];
+    }
+
+    /**
+     *
+     * @param target {Element | {x: number, y: number}}
+     */
+    mouseMove(target) {
+        const [x, y] = this.#resolve(target);
+        WindowDriver_mouse_move(this.#windowHandle, x, y);
+    }
+
+    /**
+     *
+     * @param target {Element | {x: number, y: number}}
+     * @param button {number} 1 = left, 2 = right, 3 = middle
+     */
+    mouseDown(target, button = 1) {
+        const [x, y] = this.#resolve(target);
+        WindowDriver_mouse_down(this.#windowHandle, x, y, button);
+    }
+
+    /**
+     *
+     * @param target {Element | {x: number, y: number}}
+     * @param button {number} 1 = left, 2 = right, 3 = middle
+     */
+    mouseUp(target, button = 1) {
+        const [x, y] = this.#resolve(target);
+        WindowDriver_mouse_up(this.#windowHandle, x, y, button);
+    }
+
+    /**
+     *
+     * @param target {Element | {x: number, y: number}}
+     */
+    click(target) {
+        const [x, y] = this.#resolve(target);
+        WindowDriver_click(this.#windowHandle, x, y);
+    }
+
+    /**
+     *
+     * @param target {Element | {x: number, y: number}}
+     * @param cols {number}
+     * @param rows {number}
+     */
+    wheel(target, cols, rows) {
+        const [x, y] = this.#resolve(target);
+        WindowDriver_wheel(this.#windowHandle, x, y, cols, rows);
+    }
+
+    /**
+     *
+     * @param key {string} named key(e.g. "Enter") or character
+     * @param modifiers {number} combination of KEY_MOD_*
+     */
+    keyDown(key, modifiers = 0) {
+        WindowDriver_key(this.#windowHandle, key, modifiers, true);
+    }
+
+    /**
+     *
+     * @param key {string}
+     * @param modifiers {number}
+     */
+    keyUp(key, modifiers = 0) {
+        WindowDriver_key(this.#windowHandle, key, modifiers, false);
+    }
+
+    /**
+     *
+     * @param key {string}
+     * @param modifiers {number}
+     */
+    pressKey(key, modifiers = 0) {
+        WindowDriver_press_key(this.#windowHandle, key, modifiers);
+    }
+
+    /**
+     * Commit text to the focused element like an input method does
+     * @param text {string}
+     */
+    input(text) {
+        WindowDriver_input(this.#windowHandle, text);
+    }
+
+    /**
+     *
+     * @param element {Element}
+     */
+    focus(element) {
+        WindowDriver_focus_element(this.#windowHandle, element.eid);
+    }
+
+    /**
+     *
+     * @param id {number}
+     * @param target {Element | {x: number, y: number}}
+     */
+    touchStart(id, target) {
+        const [x, y] = this.#resolve(target);
+        WindowDriver_touch_start(this.#windowHandle, id, x, y);
+    }
+
+    /**
+     *
+     * @param id {number}
+     * @param target {Element | {x: number, y: number}}
+     */
+    touchMove(id, target) {
+        const [x, y] = this.#resolve(target);
+        WindowDriver_touch_move(this.#windowHandle, id, x, y);
+    }
+
+    /**
+     *
+     * @param id {number}
+     * @param target {Element | {x: number, y: number}}
+     */
+    touchEnd(id, target) {
+        const [x, y] = this.#resolve(target);
+        WindowDriver_touch_end(this.#windowHandle, id, x, y);
+    }
+
+    /**
+     *
+     * @param id {number}
+     * @param target {Element | {x: number, y: number}}
+     */
+    touchCancel(id, target) {
+        const [x, y] = this.#resolve(target);
+        WindowDriver_touch_cancel(this.#windowHandle, id, x, y);
+    }
+
+    /**
+     *
+     * @param target {Element | {x: number, y: number}}
+     */
+    tap(target) {
+        const [x, y] = this.#resolve(target);
+        WindowDriver_tap(this.#windowHandle, x, y);
+    }
+
+    /**
+     *
+     * @param from {Element | {x: number, y: number}}
+     * @param to {Element | {x: number, y: number}}
+     * @param steps {number}
+     */
+    swipe(from, to, steps = 10) {
+        const [x1, y1] = this.#resolve(from);
+        const [x2, y2] = this.#resolve(to);
+        WindowDriver_swipe(this.#windowHandle, x1, y1, x2, y2, steps);
+    }
+
+    /**
+     * Run the pending update of window
+     */
+    flush() {
+        WindowDriver_flush(this.#windowHandle);
+    }
+
+}
+
 /**
  * @template D
  * @template E
@@ -1018,6 +1463,14 @@ export class Element {
         Element_focus(this.handle);
     }
 
+    /**
+     * Capture the painted area of the current element as png bytes
+     * @returns {number[] | undefined}
+     */
+    capture() {
+        return Element_capture(this.handle);
+    }
+
     set tooltip(text) {
         Element_set_tooltip(this.handle, text);
     }
@@ -1179,26 +1632,50 @@ export class Element {
 
     /**
      *
-     * @param callback {(event: IBoundsChangeEvent) => void}
+     * @param callback {(event: IBoundsChangeEvent) => void}
+     */
+    bindBoundsChange(callback) {
+        this.bindEvent("boundschange", callback);
+    }
+
+    /**
+     *
+     * @param callback {(event: IVoidEvent) => void}
+     */
+    bindFocus(callback) {
+        this.bindEvent("focus", callback);
+    }
+
+    /**
+     *
+     * @param callback {(event: IVoidEvent) => void}
+     */
+    bindBlur(callback) {
+        this.bindEvent("blur", callback);
+    }
+
+    /**
+     *
+     * @param callback {(event: IAnimationEvent) => void}
      */
-    bindBoundsChange(callback) {
-        this.bindEvent("boundschange", callback);
+    bindAnimationStart(callback) {
+        this.bindEvent("animationstart", callback);
     }
 
     /**
      *
-     * @param callback {(event: IVoidEvent) => void}
+     * @param callback {(event: IAnimationEvent) => void}
      */
-    bindFocus(callback) {
-        this.bindEvent("focus", callback);
+    bindAnimationIteration(callback) {
+        this.bindEvent("animationiteration", callback);
     }
 
     /**
      *
-     * @param callback {(event: IVoidEvent) => void}
+     * @param callback {(event: IAnimationEvent) => void}
      */
-    bindBlur(callback) {
-        this.bindEvent("blur", callback);
+    bindAnimationEnd(callback) {
+        this.bindEvent("animationend", callback);
     }
 
     /**
@@ -1420,6 +1897,36 @@ export class LabelElement extends Element {
         Label_set_text(this.handle, text);
     }
 
+    /**
+     * Set content with images and placeholders, which are represented by U+FFFC in text
+     * @param elements {TextElementUnit[]}
+     */
+    setElements(elements) {
+        Label_set_elements(this.handle, elements);
+    }
+
+    /**
+     * Highlight occurrences of the query, the first match is activated and scrolled into view
+     * @param query {string}
+     * @param options {FindOptions | undefined}
+     * @returns {[[number, number], [number, number]][]} (start, end) of matches
+     */
+    find(query, options) {
+        return Label_find(this.handle, query, options);
+    }
+
+    /**
+     * Activate the match and scroll it into view
+     * @param index {number}
+     */
+    setActiveMatch(index) {
+        Label_set_active_match(this.handle, index);
+    }
+
+    clearFind() {
+        Label_clear_find(this.handle);
+    }
+
 }
 
 export class CheckboxElement extends Element {
@@ -1570,6 +2077,38 @@ export class SelectElement extends Element {
         return Select_get_value(this.handle);
     }
 
+    /**
+     *
+     * @param values {string[]}
+     */
+    set values(values) {
+        Select_set_values(this.handle, values.map(v => v + ""));
+    }
+
+    /**
+     *
+     * @returns {string[]}
+     */
+    get values() {
+        return Select_get_values(this.handle);
+    }
+
+    /**
+     *
+     * @param multiple {boolean}
+     */
+    set multiple(multiple) {
+        Select_set_multiple(this.handle, multiple);
+    }
+
+    /**
+     *
+     * @returns {boolean}
+     */
+    get multiple() {
+        return Select_is_multiple(this.handle);
+    }
+
     /**
      *
      * @param options {SelectOption[]}
@@ -1624,6 +2163,184 @@ export class SelectElement extends Element {
 
 }
 
+export class ListViewElement extends Element {
+    constructor() {
+        super(VT_LIST_VIEW);
+    }
+
+    /**
+     * Set the functions creating and binding the elements of items. Elements are recycled when
+     * scrolled out, so bind may be called many times on an element with different items.
+     *
+     * @param create {() => Element}
+     * @param bind {(element: Element, index: number) => void}
+     */
+    setItemFactory(create, bind) {
+        ListView_set_item_factory(this.handle, () => create().handle, (handle, index) => {
+            bind(Element.fromHandle(handle), index);
+        });
+    }
+
+    /**
+     *
+     * @param count {number}
+     */
+    set itemCount(count) {
+        ListView_set_item_count(this.handle, count);
+    }
+
+    /**
+     *
+     * @returns {number}
+     */
+    get itemCount() {
+        return ListView_get_item_count(this.handle);
+    }
+
+    /**
+     * Height of the rows not measured yet
+     * @param height {number}
+     */
+    set estimatedRowHeight(height) {
+        ListView_set_estimated_row_height(this.handle, height);
+    }
+
+    /**
+     *
+     * @returns {number}
+     */
+    get estimatedRowHeight() {
+        return ListView_get_estimated_row_height(this.handle);
+    }
+
+    /**
+     *
+     * @returns {number}
+     */
+    get firstVisibleItem() {
+        return ListView_get_first_visible_item(this.handle);
+    }
+
+    /**
+     * Bind the visible items again after the data changed
+     */
+    refresh() {
+        ListView_refresh(this.handle);
+    }
+
+    /**
+     *
+     * @param index {number}
+     */
+    scrollToItem(index) {
+        ListView_scroll_to_item(this.handle, index);
+    }
+
+    scrollBy(value) {
+        value.x = value.x || 0;
+        value.y = value.y || 0;
+        ListView_scroll_by(this.handle, value);
+    }
+
+}
+
+export class GridViewElement extends Element {
+    constructor() {
+        super(VT_GRID_VIEW);
+    }
+
+    /**
+     * Set the functions creating and binding the elements of items. Elements are recycled when
+     * scrolled out, so bind may be called many times on an element with different items.
+     *
+     * @param create {() => Element}
+     * @param bind {(element: Element, index: number) => void}
+     */
+    setItemFactory(create, bind) {
+        GridView_set_item_factory(this.handle, () => create().handle, (handle, index) => {
+            bind(Element.fromHandle(handle), index);
+        });
+    }
+
+    /**
+     *
+     * @param columns {number}
+     */
+    set columns(columns) {
+        GridView_set_columns(this.handle, columns);
+    }
+
+    /**
+     *
+     * @returns {number}
+     */
+    get columns() {
+        return GridView_get_columns(this.handle);
+    }
+
+    /**
+     *
+     * @param count {number}
+     */
+    set itemCount(count) {
+        GridView_set_item_count(this.handle, count);
+    }
+
+    /**
+     *
+     * @returns {number}
+     */
+    get itemCount() {
+        return GridView_get_item_count(this.handle);
+    }
+
+    /**
+     * Height of the rows not measured yet
+     * @param height {number}
+     */
+    set estimatedRowHeight(height) {
+        GridView_set_estimated_row_height(this.handle, height);
+    }
+
+    /**
+     *
+     * @returns {number}
+     */
+    get estimatedRowHeight() {
+        return GridView_get_estimated_row_height(this.handle);
+    }
+
+    /**
+     *
+     * @returns {number}
+     */
+    get firstVisibleItem() {
+        return GridView_get_first_visible_item(this.handle);
+    }
+
+    /**
+     * Bind the visible items again after the data changed
+     */
+    refresh() {
+        GridView_refresh(this.handle);
+    }
+
+    /**
+     *
+     * @param index {number}
+     */
+    scrollToItem(index) {
+        GridView_scroll_to_item(this.handle, index);
+    }
+
+    scrollBy(value) {
+        value.x = value.x || 0;
+        value.y = value.y || 0;
+        GridView_scroll_by(this.handle, value);
+    }
+
+}
+
 /**
  * @typedef {{
  *   type: "text",
@@ -1633,8 +2350,49 @@ export class SelectElement extends Element {
  *   fontFamilies ?: string[],
  *   fontSize ?: number,
  *   color ?: string,
- *   backgroundColor ?: string
+ *   backgroundColor ?: string,
+ *   letterSpacing ?: number,
+ *   wordSpacing ?: number,
+ *   textDecorationStyle ?: "solid" | "double" | "dotted" | "dashed" | "wavy",
+ *   textDecorationColor ?: string,
+ *   textDecorationThickness ?: number,
+ *   verticalAlign ?: "super" | "sub",
+ *   link ?: string
  * }} TextUnit
+ *
+ * @typedef {{
+ *   type: "image",
+ *   src: string,
+ *   width: number,
+ *   height: number,
+ *   verticalAlign ?: "baseline" | "middle" | "top" | "bottom",
+ *   baselineOffset ?: number
+ * }} ImageUnit
+ *
+ * @typedef {{
+ *   type: "placeholder",
+ *   width: number,
+ *   height: number,
+ *   verticalAlign ?: "baseline" | "middle" | "top" | "bottom",
+ *   baselineOffset ?: number
+ * }} PlaceholderUnit
+ *
+ * @typedef {TextUnit | ImageUnit | PlaceholderUnit} TextElementUnit
+ *
+ * @typedef {{
+ *   start: number,
+ *   end: number,
+ *   kind: "underline" | "highlight" | "squiggle",
+ *   color ?: string,
+ *   group ?: string
+ * }} DecorationRange
+ *
+ * @typedef {{
+ *   caseSensitive ?: boolean,
+ *   wholeWord ?: boolean,
+ *   highlightColor ?: string,
+ *   activeHighlightColor ?: string
+ * }} FindOptions
  */
 export class RichTextElement extends Element {
     constructor() {
@@ -1643,7 +2401,7 @@ export class RichTextElement extends Element {
 
     /**
      *
-     * @param units {TextUnit[]}
+     * @param units {TextElementUnit[]}
      */
     addLine(units) {
         RichText_add_line(this.handle, units);
@@ -1652,7 +2410,7 @@ export class RichTextElement extends Element {
     /**
      *
      * @param index {number}
-     * @param units {TextUnit[]}
+     * @param units {TextElementUnit[]}
      */
     insertLine(index, units) {
         RichText_insert_line(this.handle, index, units);
@@ -1670,7 +2428,7 @@ export class RichTextElement extends Element {
     /**
      *
      * @param index {number}
-     * @param units {TextUnit[]}
+     * @param units {TextElementUnit[]}
      */
     updateLine(index, units) {
         RichText_update_line(this.handle, index, units);
@@ -1682,13 +2440,23 @@ export class RichTextElement extends Element {
 
     /**
      *
-     * @param units {TextUnit[]}
+     * @param units {TextElementUnit[]}
      * @return {[number, number]}
      */
     measureLine(units) {
         return RichText_measure_line(this.handle, units);
     }
 
+    /**
+     * Bounds of the atom relative to the element, used to place widgets over placeholders
+     * @param row {number}
+     * @param col {number}
+     * @returns {ElementRect | undefined}
+     */
+    getCharRect(row, col) {
+        return RichText_get_char_rect(this.handle, row, col);
+    }
+
     /**
      *
      * @returns {string | undefined}
@@ -1697,6 +2465,60 @@ export class RichTextElement extends Element {
         return RichText_get_selection_text(this.handle);
     }
 
+    /**
+     * Highlight occurrences of the query, the first match is activated and scrolled into view
+     * @param query {string}
+     * @param options {FindOptions | undefined}
+     * @returns {[[number, number], [number, number]][]} (start, end) of matches
+     */
+    find(query, options) {
+        return RichText_find(this.handle, query, options);
+    }
+
+    /**
+     * Activate the match and scroll it into view
+     * @param index {number}
+     */
+    setActiveMatch(index) {
+        RichText_set_active_match(this.handle, index);
+    }
+
+    clearFind() {
+        RichText_clear_find(this.handle);
+    }
+
+    /**
+     * Decorate chars between start and end, the offsets are not updated when lines are inserted or deleted and all decorations are removed by clear()
+     * @param decoration {DecorationRange}
+     */
+    addDecoration(decoration) {
+        RichText_add_decoration(this.handle, decoration);
+    }
+
+    /**
+     * Remove decorations of the group, all decorations are removed if group is not specified
+     * @param group {string | undefined}
+     */
+    removeDecorations(group) {
+        RichText_remove_decorations(this.handle, group);
+    }
+
+    /**
+     *
+     * @returns {DecorationRange[]}
+     */
+    get decorations() {
+        return RichText_get_decorations(this.handle);
+    }
+
+    /**
+     * Bind handler of clicking text units with link
+     * @param callback {(event: ILinkClickEvent) => void}
+     */
+    bindLinkClick(callback) {
+        this.bindEvent("linkclick", callback);
+    }
+
 }
 
 export class ImageElement extends Element {
@@ -1775,6 +2597,39 @@ export class TextInputElement extends Element {
     }
 
 
+    /**
+     * Decorate chars between start and end, the decoration moves with the chars when the text is edited
+     * @param decoration {DecorationRange}
+     */
+    addDecoration(decoration) {
+        TextInput_add_decoration(this.handle, decoration);
+    }
+
+    /**
+     * Remove decorations of the group, all decorations are removed if group is not specified
+     * @param group {string | undefined}
+     */
+    removeDecorations(group) {
+        TextInput_remove_decorations(this.handle, group);
+    }
+
+    /**
+     *
+     * @returns {DecorationRange[]}
+     */
+    get decorations() {
+        return TextInput_get_decorations(this.handle);
+    }
+
+    /**
+     * Suggestions for the misspelled word at the char offset
+     * @param charOffset {number}
+     * @returns {string[]}
+     */
+    getSpellingSuggestions(charOffset) {
+        return TextInput_get_spelling_suggestions(this.handle, charOffset);
+    }
+
     /**
      *
      * @param callback {(e: ITextEvent) => void}
@@ -1835,6 +2690,14 @@ export class TextEditElement extends Element {
         TextEdit_set_caret_by_char_offset(this.handle, charOffset);
     }
 
+    /**
+     * Insert elements at the caret, images and placeholders are represented by U+FFFC in text
+     * @param elements {TextElementUnit[]}
+     */
+    insertElements(elements) {
+        TextEdit_insert_elements(this.handle, elements);
+    }
+
     /**
      *
      * @returns {string}
@@ -1860,6 +2723,61 @@ export class TextEditElement extends Element {
     }
 
 
+    /**
+     * Highlight occurrences of the query, the first match is activated and scrolled into view
+     * @param query {string}
+     * @param options {FindOptions | undefined}
+     * @returns {[[number, number], [number, number]][]} (start, end) of matches
+     */
+    find(query, options) {
+        return TextEdit_find(this.handle, query, options);
+    }
+
+    /**
+     * Activate the match and scroll it into view
+     * @param index {number}
+     */
+    setActiveMatch(index) {
+        TextEdit_set_active_match(this.handle, index);
+    }
+
+    clearFind() {
+        TextEdit_clear_find(this.handle);
+    }
+
+    /**
+     * Decorate chars between start and end, the decoration moves with the chars when the text is edited
+     * @param decoration {DecorationRange}
+     */
+    addDecoration(decoration) {
+        TextEdit_add_decoration(this.handle, decoration);
+    }
+
+    /**
+     * Remove decorations of the group, all decorations are removed if group is not specified
+     * @param group {string | undefined}
+     */
+    removeDecorations(group) {
+        TextEdit_remove_decorations(this.handle, group);
+    }
+
+    /**
+     *
+     * @returns {DecorationRange[]}
+     */
+    get decorations() {
+        return TextEdit_get_decorations(this.handle);
+    }
+
+    /**
+     * Suggestions for the misspelled word at the char offset
+     * @param charOffset {number}
+     * @returns {string[]}
+     */
+    getSpellingSuggestions(charOffset) {
+        return TextEdit_get_spelling_suggestions(this.handle, charOffset);
+    }
+
     /**
      *
      * @param callback {(e: ITextEvent) => void}
@@ -2436,6 +3354,8 @@ if (globalThis.SystemTray_create) {
 }
 globalThis.Menu = Menu;
 globalThis.StandardMenuItem = StandardMenuItem;
+globalThis.CheckMenuItem = CheckMenuItem;
+globalThis.SubmenuItem = SubmenuItem;
 globalThis.Element = Element;
 globalThis.ContainerElement = ContainerElement;
 globalThis.ScrollElement = ScrollElement;
@@ -2449,6 +3369,8 @@ globalThis.CheckboxElement = CheckboxElement;
 globalThis.RadioElement = RadioElement;
 globalThis.RadioGroupElement = RadioGroupElement;
 globalThis.SelectElement = SelectElement;
+globalThis.ListViewElement = ListViewElement;
+globalThis.GridViewElement = GridViewElement;
 globalThis.Audio = Audio;
 globalThis.WebSocket = WebSocket;
 globalThis.Sqlite = Sqlite;
@@ -2483,6 +3405,8 @@ globalThis.KEY_MOD_SHIFT = 0x1 << 3;
  * @typedef {IEvent<TextDetail>} ITextEvent
  * @typedef {IEvent<TouchDetail>} ITouchEvent
  * @typedef {IEvent<ScrollDetail>} IScrollEvent
+ * @typedef {IEvent<AnimationDetail>} IAnimationEvent
+ * @typedef {IEvent<LinkClickDetail>} ILinkClickEvent
  * @typedef {IEvent<string>} IDroppedFileEvent
  * @typedef {IEvent<string>} IHoveredFileEvent
  */
\ No newline at end of file
@@ -1,7 +1,10 @@
 pub mod actor;
 pub mod css_actor;
+pub mod timing_function;
+pub mod transition;
 
 use crate::animation::actor::AnimationActor;
+use crate::animation::timing_function::TimingFunction;
 use crate::base::Callback;
 use crate::mrc::Mrc;
 use crate::style::transform::{
@@ -12,10 +15,11 @@ use crate::timer::{set_timeout, set_timeout_nanos, TimerHandle};
 use crate::window::WindowHandle;
 use log::debug;
 use ordered_float::OrderedFloat;
+use skia_safe::Color;
 use std::cell::RefCell;
 use std::collections::{BTreeMap, HashMap};
 use std::ops::Bound::{Excluded, Included};
-use std::time::{Instant, SystemTime};
+use std::time::{Duration, Instant, SystemTime};
 use yoga::StyleUnit;
 
 macro_rules! interpolate_values {
@@ -61,6 +65,19 @@ fn interpolate_f32(prev: &f32, next: &f32, position: f32) -> Option<f32> {
     Some(prev + delta)
 }
 
+fn interpolate_color(prev: &Color, next: &Color, position: f32) -> Option<Color> {
+    let channel = |p: u8, n: u8| {
+        let v = p as f32 + (n as f32 - p as f32) * position;
+        v.round().clamp(0.0, 255.0) as u8
+    };
+    Some(Color::from_argb(
+        channel(prev.a(), next.a()),
+        channel(prev.r(), next.r()),
+        channel(prev.g(), next.g()),
+        channel(prev.b(), next.b()),
+    ))
+}
+
 fn interpolate_style_unit(prev: &StyleUnit, next: &StyleUnit, position: f32) -> Option<StyleUnit> {
     //TODO use compute value?
     if let StyleUnit::Point(p) = prev {
@@ -189,6 +206,13 @@ fn interpolate(
         */
 
         Transform => interpolate_transform,
+        Opacity => interpolate_f32,
+        Color => interpolate_color,
+        BackgroundColor => interpolate_color,
+        BorderTopColor => interpolate_color,
+        BorderRightColor => interpolate_color,
+        BorderBottomColor => interpolate_color,
+        BorderLeftColor => interpolate_color,
     );
     None
 }
@@ -209,10 +233,15 @@ pub trait FrameController {
 pub struct AnimationState {
     actor: Box<dyn AnimationActor>,
     start_time: Instant,
+    delay: f32,
     duration: f32,
     iteration_count: f32,
     frame_controller: Box<dyn FrameController>,
     stopped: bool,
+    paused_elapsed: Option<f32>,
+    frame_requested: bool,
+    /// Actor has been notified of the stop, pending frames do nothing
+    actor_stopped: bool,
 }
 
 pub struct AnimationInstance {
@@ -265,7 +294,12 @@ impl Animation {
         style
     }
 
-    pub fn get_frame(&self, position: f32) -> Vec<FixedStyleProp> {
+    /// Styles at `position`, the timing function applies to each keyframe segment
+    pub fn get_frame(
+        &self,
+        position: f32,
+        timing_function: &TimingFunction,
+    ) -> Vec<FixedStyleProp> {
         //TODO support loop
         if position > 1.0 {
             return Vec::new();
@@ -280,12 +314,13 @@ impl Animation {
             let next = v.range((Excluded(p), Included(end))).next();
             if let Some((prev_position, prev_value)) = prev {
                 if let Some((next_position, next_value)) = next {
+                    let percent = (p.0 - prev_position.0) / (next_position.0 - prev_position.0);
                     if let Some(value) = interpolate(
-                        prev_position.0,
+                        0.0,
                         prev_value.clone(),
-                        next_position.0,
+                        1.0,
                         next_value.clone(),
-                        p.0,
+                        timing_function.evaluate(percent),
                     ) {
                         result.push(value);
                     }
@@ -308,51 +343,110 @@ impl AnimationInstance {
         let state = AnimationState {
             actor: Box::new(actor),
             start_time: Instant::now(),
+            delay: 0.0,
             duration,
             iteration_count,
             frame_controller,
             stopped: false,
+            paused_elapsed: None,
+            frame_requested: false,
+            actor_stopped: false,
         };
         Self {
             state: Mrc::new(state),
         }
     }
 
+    /// Delay in nanoseconds before the first iteration, negative value skips part of it
+    pub fn set_delay(&mut self, delay: f32) {
+        self.state.delay = delay;
+    }
+
     pub fn run(&mut self) {
         let mut state = self.state.clone();
+        self.state.frame_requested = true;
         self.state
             .frame_controller
             .request_next_frame(Box::new(move || {
                 // debug!("animation started:{}", t);
                 state.start_time = Instant::now();
+                if state.paused_elapsed.is_some() {
+                    state.paused_elapsed = Some(0.0);
+                }
                 Self::render_frame(state);
             }));
     }
 
+    pub fn pause(&mut self) {
+        if self.state.paused_elapsed.is_none() {
+            let elapsed = self.state.start_time.elapsed().as_nanos() as f32;
+            self.state.paused_elapsed = Some(elapsed);
+        }
+    }
+
+    pub fn resume(&mut self) {
+        if let Some(elapsed) = self.state.paused_elapsed.take() {
+            let paused_time = Duration::from_nanos(elapsed as u64);
+            self.state.start_time = Instant::now() - paused_time;
+            if !self.state.frame_requested {
+                Self::request_frame(self.state.clone());
+            }
+        }
+    }
+
+    /// Stop the animation and notify the actor right away instead of in the next frame
+    pub fn cancel(&mut self) {
+        if !self.state.actor_stopped {
+            self.state.stopped = true;
+            self.state.actor_stopped = true;
+            self.state.actor.stop();
+        }
+    }
+
     fn stop(&mut self) {
         // debug!("stopped");
+        if self.state.actor_stopped {
+            return;
+        }
         self.state.stopped = true;
+        // Paused or ended animation has no pending frame to notify the actor
+        if !self.state.frame_requested {
+            Self::request_frame(self.state.clone());
+        }
+    }
+
+    fn request_frame(mut state: Mrc<AnimationState>) {
+        let s = state.clone();
+        state.frame_requested = true;
+        state.frame_controller.request_next_frame(Box::new(|| {
+            Self::render_frame(s);
+        }))
     }
 
     fn render_frame(mut state: Mrc<AnimationState>) {
-        let elapsed = state.start_time.elapsed().as_nanos() as f32;
-        let position = elapsed / state.duration;
+        state.frame_requested = false;
+        if state.actor_stopped {
+            return;
+        }
+        let elapsed = match state.paused_elapsed {
+            Some(e) => e,
+            None => state.start_time.elapsed().as_nanos() as f32,
+        };
+        let position = (elapsed - state.delay) / state.duration;
         let mut is_ended = false;
-        if position >= state.iteration_count || state.stopped {
+        if state.stopped {
+            state.actor_stopped = true;
             state.actor.stop();
             is_ended = true;
+        } else if position >= state.iteration_count {
+            state.actor.end();
+            is_ended = true;
         } else {
-            state
-                .actor
-                .apply_animation(position - position as usize as f32, &mut is_ended);
+            state.actor.apply_position(position, &mut is_ended);
         };
-        if !is_ended {
-            let s = state.clone();
-            state.frame_controller.request_next_frame(Box::new(|| {
-                Self::render_frame(s);
-            }))
-        } else {
-            //TODO notify ended?
+        // Paused animation keeps its current frame until resumed
+        if !is_ended && state.paused_elapsed.is_none() {
+            Self::request_frame(state);
         }
     }
 }
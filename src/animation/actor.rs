@@ -4,5 +4,19 @@ pub enum AnimationAction {
 }
 pub trait AnimationActor {
     fn apply_animation(&mut self, progress: f32, stop: &mut bool);
+
+    /// `position` is the count of elapsed iterations, negative while delayed
+    fn apply_position(&mut self, position: f32, stop: &mut bool) {
+        if position >= 0.0 {
+            self.apply_animation(position.fract(), stop);
+        }
+    }
+
+    /// Called when all iterations are finished
+    fn end(&mut self) {
+        self.stop();
+    }
+
+    /// Called when the animation is cancelled
     fn stop(&mut self) {}
 }
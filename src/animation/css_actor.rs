@@ -1,33 +1,235 @@
 use crate::animation::actor::AnimationActor;
 use crate::animation::Animation;
 use crate::element::ElementWeak;
+use crate::event::{AnimationEndEvent, AnimationIterationEvent, AnimationStartEvent};
 use crate::ok_or_return;
+use crate::style::animation::AnimationParams;
+
+/// Animation event with the elapsed time in seconds
+#[derive(Debug, PartialEq)]
+enum CssAnimationEvent {
+    Start(f32),
+    Iteration(f32),
+    End(f32),
+}
 
 pub struct CssAnimationActor {
     animation: Animation,
+    params: AnimationParams,
     element: ElementWeak,
+    started: bool,
+    backwards_filled: bool,
+    iteration: u32,
 }
 
 impl CssAnimationActor {
-    pub fn new(animation: Animation, element: ElementWeak) -> Self {
-        Self { animation, element }
+    pub fn new(animation: Animation, params: AnimationParams, element: ElementWeak) -> Self {
+        Self {
+            animation,
+            params,
+            element,
+            started: false,
+            backwards_filled: false,
+            iteration: 0,
+        }
     }
-}
 
-impl AnimationActor for CssAnimationActor {
-    fn apply_animation(&mut self, position: f32, _stop: &mut bool) {
+    /// Keyframe progress of `iteration`, taking the direction into account
+    fn frame_progress(&self, iteration: u32, progress: f32) -> f32 {
+        if self.params.direction.is_reversed(iteration) {
+            1.0 - progress
+        } else {
+            progress
+        }
+    }
+
+    /// Iteration and progress of the frame kept after the animation ends
+    fn end_frame(&self) -> (u32, f32) {
+        // A whole iteration count ends at the end of the last iteration
+        let count = self.params.iteration_count;
+        if count > 0.0 && count.fract() == 0.0 {
+            (count as u32 - 1, 1.0)
+        } else {
+            (count as u32, count.fract())
+        }
+    }
+
+    fn apply_frame(&mut self, iteration: u32, progress: f32) {
+        let progress = self.frame_progress(iteration, progress);
         let mut el = ok_or_return!(self.element.upgrade());
         el.animation_style_props.clear();
-        let styles = self.animation.get_frame(position);
+        let styles = self
+            .animation
+            .get_frame(progress, &self.params.timing_function);
         for st in styles {
             el.animation_style_props.insert(st.key().clone(), st);
         }
         el.mark_style_dirty();
     }
 
-    fn stop(&mut self) {
+    fn clear_frame(&mut self) {
         let mut el = ok_or_return!(self.element.upgrade());
         el.animation_style_props.clear();
         el.mark_style_dirty();
     }
+
+    /// Seconds elapsed after `iterations` iterations
+    fn elapsed_time(&self, iterations: f32) -> f32 {
+        iterations * self.params.duration / 1000.0
+    }
+
+    fn start_event(&mut self) -> CssAnimationEvent {
+        self.started = true;
+        // Negative delay starts the animation partway through
+        CssAnimationEvent::Start((-self.params.delay / 1000.0).max(0.0))
+    }
+
+    /// Events fired when the animation reaches `position`
+    fn advance(&mut self, position: f32) -> Vec<CssAnimationEvent> {
+        let mut events = Vec::new();
+        let iteration = position as u32;
+        if !self.started {
+            events.push(self.start_event());
+        } else if iteration > self.iteration {
+            events.push(CssAnimationEvent::Iteration(
+                self.elapsed_time(iteration as f32),
+            ));
+        }
+        self.iteration = iteration;
+        events
+    }
+
+    /// Events fired when all iterations are finished
+    fn finish(&mut self) -> Vec<CssAnimationEvent> {
+        let mut events = Vec::new();
+        if !self.started {
+            events.push(self.start_event());
+        }
+        events.push(CssAnimationEvent::End(
+            self.elapsed_time(self.params.iteration_count),
+        ));
+        events
+    }
+
+    fn emit_events(&self, events: Vec<CssAnimationEvent>) {
+        let animation_name = self.params.name.clone();
+        for event in events {
+            match event {
+                CssAnimationEvent::Start(elapsed_time) => {
+                    self.element.emit(AnimationStartEvent {
+                        animation_name: animation_name.clone(),
+                        elapsed_time,
+                    });
+                }
+                CssAnimationEvent::Iteration(elapsed_time) => {
+                    self.element.emit(AnimationIterationEvent {
+                        animation_name: animation_name.clone(),
+                        elapsed_time,
+                    });
+                }
+                CssAnimationEvent::End(elapsed_time) => {
+                    self.element.emit(AnimationEndEvent {
+                        animation_name: animation_name.clone(),
+                        elapsed_time,
+                    });
+                }
+            }
+        }
+    }
+}
+
+impl AnimationActor for CssAnimationActor {
+    fn apply_animation(&mut self, position: f32, stop: &mut bool) {
+        self.apply_position(position, stop);
+    }
+
+    fn apply_position(&mut self, position: f32, _stop: &mut bool) {
+        if position < 0.0 {
+            if !self.backwards_filled && self.params.fill_mode.fills_backwards() {
+                self.backwards_filled = true;
+                self.apply_frame(0, 0.0);
+            }
+            return;
+        }
+        let events = self.advance(position);
+        self.emit_events(events);
+        self.apply_frame(self.iteration, position.fract());
+    }
+
+    fn end(&mut self) {
+        if self.params.fill_mode.fills_forwards() {
+            let (iteration, progress) = self.end_frame();
+            self.apply_frame(iteration, progress);
+        } else {
+            self.clear_frame();
+        }
+        let events = self.finish();
+        self.emit_events(events);
+    }
+
+    fn stop(&mut self) {
+        self.clear_frame();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::animation::css_actor::{CssAnimationActor, CssAnimationEvent};
+    use crate::animation::AnimationDef;
+    use crate::element::ElementWeak;
+    use crate::style::animation::{AnimationDirection, AnimationParams};
+
+    fn actor(params: AnimationParams) -> CssAnimationActor {
+        let animation = AnimationDef::new().build();
+        CssAnimationActor::new(animation, params, ElementWeak::invalid())
+    }
+
+    #[test]
+    fn test_direction() {
+        let mut params = AnimationParams::new();
+        params.direction = AnimationDirection::Alternate;
+        let alternate = actor(params.clone());
+        assert_eq!(0.25, alternate.frame_progress(0, 0.25));
+        assert_eq!(0.75, alternate.frame_progress(1, 0.25));
+
+        params.direction = AnimationDirection::AlternateReverse;
+        let alternate_reverse = actor(params.clone());
+        assert_eq!(0.75, alternate_reverse.frame_progress(0, 0.25));
+        assert_eq!(0.25, alternate_reverse.frame_progress(1, 0.25));
+    }
+
+    #[test]
+    fn test_end_frame() {
+        let mut params = AnimationParams::new();
+        params.iteration_count = 2.0;
+        params.direction = AnimationDirection::Alternate;
+        let a = actor(params.clone());
+        // The second iteration runs backwards and ends at the first keyframe
+        assert_eq!((1, 1.0), a.end_frame());
+        assert_eq!(0.0, a.frame_progress(1, 1.0));
+
+        params.iteration_count = 1.5;
+        assert_eq!((1, 0.5), actor(params).end_frame());
+    }
+
+    #[test]
+    fn test_events() {
+        let mut params = AnimationParams::new();
+        params.duration = 500.0;
+        params.iteration_count = 3.0;
+        let mut a = actor(params.clone());
+        assert_eq!(vec![CssAnimationEvent::Start(0.0)], a.advance(0.1));
+        assert!(a.advance(0.9).is_empty());
+        assert_eq!(vec![CssAnimationEvent::Iteration(0.5)], a.advance(1.2));
+        assert_eq!(vec![CssAnimationEvent::Iteration(1.0)], a.advance(2.0));
+        assert_eq!(vec![CssAnimationEvent::End(1.5)], a.finish());
+
+        // Negative delay skips part of the first iteration
+        params.delay = -200.0;
+        let mut a = actor(params);
+        assert_eq!(
+            vec![CssAnimationEvent::Start(0.2), CssAnimationEvent::End(1.5)],
+            a.finish()
+        );
+    }
 }
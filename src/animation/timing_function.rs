@@ -0,0 +1,155 @@
+use crate::style::PropValueParse;
+use bezier_rs::{Bezier, TValue};
+use std::str::FromStr;
+
+/// Easing curve mapping the progress of time to the progress of value
+#[derive(Clone, Debug, PartialEq)]
+pub enum TimingFunction {
+    Linear,
+    CubicBezier(f32, f32, f32, f32),
+    Steps(u32, StepPosition),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StepPosition {
+    JumpStart,
+    JumpEnd,
+    JumpNone,
+    JumpBoth,
+}
+
+impl TimingFunction {
+    pub fn ease() -> Self {
+        Self::CubicBezier(0.25, 0.1, 0.25, 1.0)
+    }
+
+    pub fn evaluate(&self, progress: f32) -> f32 {
+        let progress = progress.clamp(0.0, 1.0);
+        match self {
+            TimingFunction::Linear => progress,
+            TimingFunction::CubicBezier(x1, y1, x2, y2) => {
+                let curve = Bezier::from_cubic_coordinates(
+                    0.0, 0.0, *x1 as f64, *y1 as f64, *x2 as f64, *y2 as f64, 1.0, 1.0,
+                );
+                // x is monotonic since x1 and x2 are in [0, 1], find t of x by bisection
+                let x = progress as f64;
+                let (mut low, mut high) = (0.0, 1.0);
+                for _ in 0..32 {
+                    let mid = (low + high) / 2.0;
+                    if curve.evaluate(TValue::Parametric(mid)).x < x {
+                        low = mid;
+                    } else {
+                        high = mid;
+                    }
+                }
+                curve.evaluate(TValue::Parametric((low + high) / 2.0)).y as f32
+            }
+            TimingFunction::Steps(steps, position) => {
+                let mut step = (progress * *steps as f32).floor();
+                if matches!(position, StepPosition::JumpStart | StepPosition::JumpBoth) {
+                    step += 1.0;
+                }
+                let jumps = match position {
+                    StepPosition::JumpBoth => *steps + 1,
+                    StepPosition::JumpNone => *steps - 1,
+                    _ => *steps,
+                } as f32;
+                step.min(jumps) / jumps
+            }
+        }
+    }
+}
+
+impl Default for TimingFunction {
+    fn default() -> Self {
+        Self::ease()
+    }
+}
+
+impl PropValueParse for TimingFunction {
+    fn parse_prop_value(value: &str) -> Option<Self> {
+        let value = value.trim().to_lowercase();
+        match value.as_str() {
+            "linear" => return Some(Self::Linear),
+            "ease" => return Some(Self::ease()),
+            "ease-in" => return Some(Self::CubicBezier(0.42, 0.0, 1.0, 1.0)),
+            "ease-out" => return Some(Self::CubicBezier(0.0, 0.0, 0.58, 1.0)),
+            "ease-in-out" => return Some(Self::CubicBezier(0.42, 0.0, 0.58, 1.0)),
+            "step-start" => return Some(Self::Steps(1, StepPosition::JumpStart)),
+            "step-end" => return Some(Self::Steps(1, StepPosition::JumpEnd)),
+            _ => {}
+        }
+        if let Some(params) = value.strip_prefix("steps(") {
+            let mut params = params.strip_suffix(")")?.split(',').map(|p| p.trim());
+            let steps = u32::from_str(params.next()?).ok()?;
+            let position = match params.next() {
+                None | Some("end") | Some("jump-end") => StepPosition::JumpEnd,
+                Some("start") | Some("jump-start") => StepPosition::JumpStart,
+                Some("jump-none") => StepPosition::JumpNone,
+                Some("jump-both") => StepPosition::JumpBoth,
+                _ => return None,
+            };
+            let min_steps = if position == StepPosition::JumpNone {
+                2
+            } else {
+                1
+            };
+            if steps < min_steps || params.next().is_some() {
+                return None;
+            }
+            return Some(Self::Steps(steps, position));
+        }
+        let params = value.strip_prefix("cubic-bezier(")?.strip_suffix(")")?;
+        let params = params
+            .split(',')
+            .map(|p| f32::from_str(p.trim()).ok())
+            .collect::<Option<Vec<f32>>>()?;
+        match params.as_slice() {
+            [x1, y1, x2, y2] if (0.0..=1.0).contains(x1) && (0.0..=1.0).contains(x2) => {
+                Some(Self::CubicBezier(*x1, *y1, *x2, *y2))
+            }
+            _ => None,
+        }
+    }
+
+    fn to_style_string(&self) -> String {
+        match self {
+            TimingFunction::Linear => "linear".to_string(),
+            TimingFunction::CubicBezier(x1, y1, x2, y2) => {
+                format!("cubic-bezier({}, {}, {}, {})", x1, y1, x2, y2)
+            }
+            TimingFunction::Steps(steps, position) => {
+                let position = match position {
+                    StepPosition::JumpStart => "jump-start",
+                    StepPosition::JumpEnd => "jump-end",
+                    StepPosition::JumpNone => "jump-none",
+                    StepPosition::JumpBoth => "jump-both",
+                };
+                format!("steps({}, {})", steps, position)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::animation::timing_function::TimingFunction;
+    use crate::style::PropValueParse;
+
+    #[test]
+    fn test_timing_function() {
+        let linear = TimingFunction::parse_prop_value("linear").unwrap();
+        assert_eq!(0.3, linear.evaluate(0.3));
+        let ease_in_out = TimingFunction::parse_prop_value("ease-in-out").unwrap();
+        assert!((ease_in_out.evaluate(0.5) - 0.5).abs() < 0.001);
+        assert!(ease_in_out.evaluate(0.2) < 0.2);
+        assert!(TimingFunction::parse_prop_value("cubic-bezier(2, 0, 1, 1)").is_none());
+
+        let steps = TimingFunction::parse_prop_value("steps(4, jump-end)").unwrap();
+        assert_eq!(0.25, steps.evaluate(0.3));
+        assert_eq!(1.0, steps.evaluate(1.0));
+        let step_start = TimingFunction::parse_prop_value("step-start").unwrap();
+        assert_eq!(1.0, step_start.evaluate(0.1));
+        assert!(TimingFunction::parse_prop_value("steps(1, jump-none)").is_none());
+    }
+}
@@ -0,0 +1,147 @@
+use crate::animation::actor::AnimationActor;
+use crate::animation::timing_function::TimingFunction;
+use crate::animation::{interpolate, Animation, AnimationInstance, WindowAnimationController};
+use crate::element::{Element, ElementWeak};
+use crate::mrc::Mrc;
+use crate::ok_or_return;
+use crate::style::styles::Styles;
+use crate::style::transition::{TimeList, TimingFunctionList, TransitionProperty};
+use crate::style::{FixedStyleProp, ResolvedStyleProp, StylePropKey};
+
+/// Transition properties of an element, lists are matched by index like css does
+pub struct TransitionParams {
+    property: TransitionProperty,
+    duration: TimeList,
+    timing_function: TimingFunctionList,
+    delay: TimeList,
+}
+
+impl TransitionParams {
+    pub fn from_styles(styles: &Styles) -> Self {
+        let mut params = Self {
+            property: TransitionProperty::default(),
+            duration: TimeList::default(),
+            timing_function: TimingFunctionList::default(),
+            delay: TimeList::default(),
+        };
+        for key in [
+            StylePropKey::TransitionProperty,
+            StylePropKey::TransitionDuration,
+            StylePropKey::TransitionTimingFunction,
+            StylePropKey::TransitionDelay,
+        ] {
+            match styles.get(&key) {
+                Some(ResolvedStyleProp::TransitionProperty(v)) => params.property = v.clone(),
+                Some(ResolvedStyleProp::TransitionDuration(v)) => params.duration = v.clone(),
+                Some(ResolvedStyleProp::TransitionTimingFunction(v)) => {
+                    params.timing_function = v.clone()
+                }
+                Some(ResolvedStyleProp::TransitionDelay(v)) => params.delay = v.clone(),
+                _ => {}
+            }
+        }
+        params
+    }
+
+    /// (duration, delay, timing function) of the last transition matching `key`
+    pub fn get(&self, key: StylePropKey) -> Option<(f32, f32, TimingFunction)> {
+        let index = self.property.list.iter().rposition(|p| p.matches(key))?;
+        let duration = self.duration.0[index % self.duration.0.len()];
+        let delay = self.delay.0[index % self.delay.0.len()];
+        let timing_function = &self.timing_function.0[index % self.timing_function.0.len()];
+        Some((duration, delay, timing_function.clone()))
+    }
+}
+
+struct TransitionState {
+    value: ResolvedStyleProp,
+    finished: bool,
+}
+
+/// A running transition of one style property
+pub struct Transition {
+    state: Mrc<TransitionState>,
+    _instance: AnimationInstance,
+}
+
+impl Transition {
+    /// Start transition from `from` to `to`, None if the value is not animatable
+    pub fn start(
+        element: &Element,
+        from: ResolvedStyleProp,
+        to: ResolvedStyleProp,
+        duration: f32,
+        delay: f32,
+        timing_function: TimingFunction,
+    ) -> Option<Self> {
+        if duration <= 0.0 || delay + duration <= 0.0 || from == to {
+            return None;
+        }
+        let from_value = Animation::preprocess_style(from.to_unresolved());
+        let to_value = Animation::preprocess_style(to.to_unresolved());
+        interpolate(0.0, from_value.clone(), 1.0, to_value.clone(), 0.5)?;
+        let window = element.get_window()?;
+        let state = Mrc::new(TransitionState {
+            value: from,
+            finished: false,
+        });
+        let actor = TransitionActor {
+            from: from_value,
+            to: to_value,
+            duration,
+            delay,
+            timing_function,
+            state: state.clone(),
+            element: element.as_weak(),
+        };
+        let mut instance = AnimationInstance::new(
+            actor,
+            (delay + duration) * 1000000.0,
+            1.0,
+            Box::new(WindowAnimationController::new(window)),
+        );
+        instance.run();
+        Some(Self {
+            state,
+            _instance: instance,
+        })
+    }
+
+    pub fn value(&self) -> ResolvedStyleProp {
+        self.state.value.clone()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.state.finished
+    }
+}
+
+struct TransitionActor {
+    from: FixedStyleProp,
+    to: FixedStyleProp,
+    duration: f32,
+    delay: f32,
+    timing_function: TimingFunction,
+    state: Mrc<TransitionState>,
+    element: ElementWeak,
+}
+
+impl AnimationActor for TransitionActor {
+    fn apply_animation(&mut self, position: f32, _stop: &mut bool) {
+        // Negative delay starts the transition partway through
+        let elapsed = position * (self.delay + self.duration) - self.delay;
+        let progress = self.timing_function.evaluate(elapsed / self.duration);
+        let value = interpolate(0.0, self.from.clone(), 1.0, self.to.clone(), progress);
+        if let Some(v) = value.and_then(|v| v.to_resolved()) {
+            self.state.value = v;
+        }
+        let mut el = ok_or_return!(self.element.upgrade());
+        el.mark_style_dirty();
+    }
+
+    fn stop(&mut self) {
+        self.state.finished = true;
+        let mut el = ok_or_return!(self.element.upgrade());
+        el.mark_style_dirty();
+    }
+}
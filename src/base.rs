@@ -250,6 +250,8 @@ pub struct MouseDetail {
     pub window_y: f32,
     pub screen_x: f32,
     pub screen_y: f32,
+    /// Number of consecutive clicks at the same position, 2 for double-click
+    pub click_count: u32,
 }
 
 #[derive(Debug, Copy, Clone, Serialize)]
@@ -22,15 +22,16 @@ use crate::element::container::Container;
 use crate::element::image::Image;
 use crate::element::scroll::{Scroll, ScrollBarStrategy};
 use crate::event::{
+    AnimationEndEventListener, AnimationIterationEventListener, AnimationStartEventListener,
     BlurEventListener, BoundsChangeEvent, BoundsChangeEventListener, ClickEventListener,
     ContextMenuEventListener, DragOverEventListener, DragStartEventListener, DropEventListener,
     DroppedFileEventListener, Event, FocusEventListener, FocusShiftEventListener,
-    HoveredFileEventListener, KeyDownEventListener, KeyUpEventListener, MouseDownEvent,
-    MouseDownEventListener, MouseEnterEvent, MouseEnterEventListener, MouseLeaveEvent,
-    MouseLeaveEventListener, MouseMoveEventListener, MouseUpEventListener, MouseWheelEventListener,
-    ScrollEvent, ScrollEventListener, TextChangeEventListener, TextUpdateEventListener,
-    TouchCancelEventListener, TouchEndEventListener, TouchMoveEventListener, TouchStartEvent,
-    TouchStartEventListener,
+    HoveredFileEventListener, KeyDownEventListener, KeyUpEventListener, LinkClickEventListener,
+    MouseDownEvent, MouseDownEventListener, MouseEnterEvent, MouseEnterEventListener,
+    MouseLeaveEvent, MouseLeaveEventListener, MouseMoveEventListener, MouseUpEventListener,
+    MouseWheelEventListener, ScrollEvent, ScrollEventListener, TextChangeEventListener,
+    TextUpdateEventListener, TouchCancelEventListener, TouchEndEventListener,
+    TouchMoveEventListener, TouchStartEvent, TouchStartEventListener,
 };
 use crate::event_loop::create_event_loop_callback;
 use crate::mrc::Mrc;
@@ -50,24 +51,30 @@ pub mod common;
 pub mod container;
 mod edit_history;
 mod font_manager;
+pub mod grid_view;
 pub mod image;
 pub mod label;
+pub mod list_view;
 pub mod paragraph;
 pub mod radio;
 pub mod richtext;
 pub mod scroll;
 pub mod select;
+pub mod spell_checker;
 pub mod text;
 pub mod textedit;
 pub mod textinput;
 pub mod util;
 
 use crate as deft;
+use crate::animation::transition::{Transition, TransitionParams};
 use crate::computed::ComputedValue;
 use crate::element::body::Body;
 use crate::element::checkbox::Checkbox;
 use crate::element::common::scrollable::Scrollable;
+use crate::element::grid_view::GridView;
 use crate::element::label::Label;
+use crate::element::list_view::ListView;
 use crate::element::radio::{Radio, RadioGroup};
 use crate::element::richtext::RichText;
 use crate::element::select::Select;
@@ -143,6 +150,8 @@ pub fn init_base_components() {
     register_component::<RadioGroup>("radio-group");
     register_component::<RichText>("rich-text");
     register_component::<Select>("select");
+    register_component::<ListView>("list-view");
+    register_component::<GridView>("grid-view");
     register_component::<Container>("dialog");
     register_component::<Container>("dialog-title");
 }
@@ -414,6 +423,10 @@ impl Element {
             "boundschange" => BoundsChangeEventListener,
             "droppedfile" => DroppedFileEventListener,
             "hoveredfile" => HoveredFileEventListener,
+            "animationstart" => AnimationStartEventListener,
+            "animationiteration" => AnimationIterationEventListener,
+            "animationend" => AnimationEndEventListener,
+            "linkclick" => LinkClickEventListener,
         );
         if id.is_none() {
             if let Some(factory) = self.js_event_listener_factory.get_mut(&event_type) {
@@ -440,6 +453,16 @@ impl Element {
         });
     }
 
+    /// Capture the painted area of this element as png
+    #[js_func]
+    pub fn capture(&self) -> Option<Vec<u8>> {
+        let mut result = None;
+        self.with_window(|mut w| {
+            result = w.capture(Some(self.clone())).ok();
+        });
+        result
+    }
+
     #[js_func]
     pub fn set_tooltip(&mut self, tooltip: String) {
         self.tooltip = tooltip;
@@ -473,6 +496,25 @@ impl Element {
         }
     }
 
+    /// Scroll the element and its ancestors to make `rect` visible, `rect` is relative to the
+    /// content of the element
+    pub fn scroll_rect_into_view(&self, rect: &base::Rect) {
+        let mut rect = *rect;
+        let mut el = self.clone();
+        loop {
+            if el.scrollable.is_scrollable() {
+                el.scrollable.scroll_into_view(&rect);
+            }
+            let (scroll_left, scroll_top) = el.scrollable.scroll_offset();
+            let bounds = el.get_bounds();
+            rect = rect.translate(bounds.x - scroll_left, bounds.y - scroll_top);
+            el = match el.get_parent() {
+                Some(p) => p,
+                None => break,
+            };
+        }
+    }
+
     pub fn get_max_scroll_left(&self) -> f32 {
         let content_bounds = self.get_content_bounds();
         let width = content_bounds.width;
@@ -550,6 +592,8 @@ impl Element {
     fn set_parent_internal(&mut self, parent: ElementParent) {
         self.parent = parent;
         self.applied_style = Styles::new();
+        self.transition_targets = None;
+        self.transitions.clear();
         self.select_style_recurse();
         self.mark_style_dirty();
     }
@@ -816,6 +860,12 @@ impl Element {
         self.sync_style();
     }
 
+    /// Replace the inline styles with `styles`, e.g. when recycled elements are bound again
+    pub fn replace_style_props(&mut self, styles: Vec<FixedStyleProp>) {
+        self.style_list.clear();
+        self.set_style_props(styles);
+    }
+
     #[js_func]
     pub fn set_hover_style(&mut self, style: JsValue) {
         self.style_list.set_hover_style(style);
@@ -972,8 +1022,44 @@ impl Element {
         Styles::from_map(resolved)
     }
 
+    /// Start transitions for changed target values, and overlay values of running
+    /// transitions on `styles`
+    fn apply_transitions(&mut self, styles: &mut Styles) {
+        let targets = styles.clone();
+        // No transition for the initial style
+        if let Some(prev_targets) = self.transition_targets.take() {
+            let params = TransitionParams::from_styles(&targets);
+            let changed_targets =
+                targets.compute_changed_style(&prev_targets, |k| self.style.get_default_value(k));
+            for to in changed_targets {
+                let key = to.key();
+                // Animations take precedence over transitions
+                let transition = if self.animation_style_props.contains_key(&key) {
+                    None
+                } else {
+                    let from = self.style.get_resolved_value(key);
+                    params
+                        .get(key)
+                        .and_then(|(duration, delay, timing_function)| {
+                            Transition::start(self, from, to, duration, delay, timing_function)
+                        })
+                };
+                match transition {
+                    Some(t) => self.transitions.insert(key, t),
+                    None => self.transitions.remove(&key),
+                };
+            }
+        }
+        self.transition_targets = Some(targets);
+        self.transitions.retain(|_, t| !t.is_finished());
+        for t in self.transitions.values() {
+            styles.insert(t.value());
+        }
+    }
+
     pub fn apply_owned_style(&mut self, length_ctx: &LengthContext) -> bool {
-        let (styles, pseudo_element_styles) = self.compute_owned_style();
+        let (mut styles, pseudo_element_styles) = self.compute_owned_style();
+        self.apply_transitions(&mut styles);
 
         let changed_styles =
             styles.compute_changed_style(&self.applied_style, |k| self.style.get_default_value(k));
@@ -1048,17 +1134,37 @@ impl Element {
         self.emit_raw(event_type_id, Event::new(event));
     }
 
-    pub fn emit_raw(&self, event_type_id: TypeId, mut event: Event) {
+    pub fn emit_raw(&self, event_type_id: TypeId, event: Event) {
+        self.emit_raw_with_callback(event_type_id, event, |_| {});
+    }
+
+    /// Emit the event, `callback` is called with the context after the default behavior is
+    /// executed, e.g. to find out whether the event is prevented
+    pub fn emit_with_callback<T, F>(&self, event: T, callback: F)
+    where
+        T: ViewEvent + 'static,
+        F: FnOnce(&EventContext<ElementWeak>) + 'static,
+    {
+        self.emit_raw_with_callback(TypeId::of::<T>(), Event::new(event), callback);
+    }
+
+    fn emit_raw_with_callback<F: FnOnce(&EventContext<ElementWeak>) + 'static>(
+        &self,
+        event_type_id: TypeId,
+        mut event: Event,
+        callback: F,
+    ) {
         // log::debug!("emitting {:?}", event_type_id);
         let mut me = self.clone();
-        let callback = create_event_loop_callback(move || {
+        let event_loop_callback = create_event_loop_callback(move || {
             let mut ctx = EventContext::new(me.as_weak());
             me.handle_event(event_type_id, &mut event, &mut ctx);
             if !ctx.prevent_default {
                 me.handle_default_behavior(&mut event, &mut ctx);
             }
+            callback(&ctx);
         });
-        callback.call();
+        event_loop_callback.call();
     }
 
     pub fn create_event_emitter(&mut self) -> EventEmitter {
@@ -1356,6 +1462,9 @@ pub struct Element {
     event_registration: EventRegistration<ElementWeak>,
     pub style: StyleNode,
     pub(crate) animation_style_props: HashMap<StylePropKey, FixedStyleProp>,
+    /// Target values before overlaying transitions, None until style is applied first time
+    transition_targets: Option<Styles>,
+    transitions: HashMap<StylePropKey, Transition>,
     pub(crate) hover: bool,
     auto_focus: bool,
     dirty_flags: StyleDirtyFlags,
@@ -1410,6 +1519,8 @@ impl ElementData {
             event_registration: EventRegistration::new(),
             style: StyleNode::new(),
             animation_style_props: HashMap::new(),
+            transition_targets: None,
+            transitions: HashMap::new(),
             applied_style: Styles::new(),
             hover: false,
             element_type: ElementType::Inner,
@@ -1517,6 +1628,25 @@ pub trait ElementBackend: 'static {
         }
     }
 
+    /// Describe the element in the accessibility tree, e.g. role, name and states.
+    /// Returns false if the children should not be exposed to assistive technologies.
+    fn build_accessibility_node(&mut self, node: &mut accesskit::Node) -> bool {
+        if let Some(base) = self.get_base_mut() {
+            base.build_accessibility_node(node)
+        } else {
+            true
+        }
+    }
+
+    /// Handle an action requested by assistive technologies, returns true if handled.
+    fn do_accessibility_action(&mut self, request: &accesskit::ActionRequest) -> bool {
+        if let Some(base) = self.get_base_mut() {
+            base.do_accessibility_action(request)
+        } else {
+            false
+        }
+    }
+
     fn backend_type_id(&self) -> TypeId {
         self.type_id()
     }
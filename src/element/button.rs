@@ -5,6 +5,7 @@ use crate::element::{Element, ElementBackend, ElementWeak};
 use crate::event::Event;
 use crate::render::RenderFn;
 use crate::style::StylePropKey;
+use accesskit::{Action, Node, Role};
 use deft_macros::{element_backend, js_methods};
 
 #[element_backend]
@@ -51,4 +52,10 @@ impl ElementBackend for Button {
     fn handle_origin_bounds_change(&mut self, bounds: &Rect) {
         self.base.handle_origin_bounds_change(bounds)
     }
+
+    fn build_accessibility_node(&mut self, node: &mut Node) -> bool {
+        node.set_role(Role::Button);
+        node.add_action(Action::Click);
+        true
+    }
 }
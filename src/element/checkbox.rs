@@ -8,6 +8,7 @@ use crate::event::{ClickEvent, Event};
 use crate::ok_or_return;
 use crate::style::length::LengthOrPercent;
 use crate::style::{FixedStyleProp, ResolvedStyleProp, StylePropVal};
+use accesskit::{Action, Node, Role, Toggled};
 use deft_macros::{element_backend, event, js_methods};
 use std::collections::HashMap;
 use yoga::{Align, Display, FlexDirection};
@@ -142,4 +143,16 @@ impl ElementBackend for Checkbox {
             _ => self.base.on_attribute_changed(key, value),
         }
     }
+
+    fn build_accessibility_node(&mut self, node: &mut Node) -> bool {
+        node.set_role(Role::CheckBox);
+        node.set_label(self.get_label());
+        node.set_toggled(if self.checked {
+            Toggled::True
+        } else {
+            Toggled::False
+        });
+        node.add_action(Action::Click);
+        false
+    }
 }
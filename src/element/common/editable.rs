@@ -2,25 +2,33 @@ use crate as deft;
 use crate::app::AppEvent;
 use crate::base::{Callback, EventContext, Rect};
 use crate::canvas_util::CanvasHelper;
-use crate::element::edit_history::{EditHistory, EditOpType};
+use crate::element::edit_history::{EditHistory, EditOp, EditOpType};
+use crate::element::spell_checker::{find_misspelled_words, SpellChecker};
 use crate::element::util::is_form_event;
 use crate::element::{Element, ElementBackend, ElementWeak};
 use crate::event::{
-    BlurEvent, BoundsChangeEvent, CaretChangeEvent, Event, FocusEvent, KeyDownEvent,
-    KeyEventDetail, MouseDownEvent, MouseLeaveEvent, ScrollEvent, TextChangeEvent, TextInputEvent,
-    TextUpdateEvent, KEY_MOD_CTRL, KEY_MOD_SHIFT,
+    BlurEvent, BoundsChangeEvent, CaretChangeEvent, Event, FocusEvent, ImePreeditEvent,
+    KeyDownEvent, KeyEventDetail, MouseDownEvent, MouseLeaveEvent, ScrollEvent, TextChangeEvent,
+    TextInputEvent, TextUpdateEvent, KEY_MOD_CTRL, KEY_MOD_SHIFT,
 };
 use crate::event_loop::create_event_loop_proxy;
 use crate::js::{FromJsValue, ToJsValue};
 use crate::number::DeNan;
 use crate::render::RenderFn;
 use crate::string::StringUtils;
+use crate::style::text::WhiteSpace;
 use crate::style::{ResolvedStyleProp, StylePropKey};
-use crate::text::textbox::{TextBox, TextCoord, TextElement, TextUnit};
+use crate::text::textbox::decoration::{
+    DecorationKind, DecorationRange, PREEDIT_DECORATION_GROUP, SPELLCHECK_DECORATION_GROUP,
+};
+use crate::text::textbox::find::FindOptions;
+use crate::text::textbox::{
+    append_elements, elements_atom_count, TextBox, TextCoord, TextElement, TextUnit,
+};
 use crate::text::TextAlign;
 use crate::timer::TimerHandle;
 use crate::winit::dpi::{LogicalPosition, LogicalSize, Size};
-use crate::{ok_or_return, some_or_return, timer};
+use crate::{ok_or_return, some_or_break, some_or_return, timer};
 use deft_macros::{element_backend, js_methods};
 use quick_js::{JsValue, ValueError};
 use serde::{Deserialize, Serialize};
@@ -63,6 +71,13 @@ impl FromJsValue for InputType {
     }
 }
 
+/// Composition text of the input method, which is in the paragraph but not committed yet
+struct Preedit {
+    start: TextCoord,
+    char_count: usize,
+    caret_visible: bool,
+}
+
 #[element_backend]
 pub struct Editable {
     // base: Scroll,
@@ -74,6 +89,8 @@ pub struct Editable {
     focusing: bool,
     align: TextAlign,
     multiple_line: bool,
+    /// Spaces are always preserved, only wrapping of multiple line text is affected
+    white_space: WhiteSpace,
     element: ElementWeak,
     edit_history: EditHistory,
     rows: u32,
@@ -81,6 +98,8 @@ pub struct Editable {
     line_height: Option<f32>,
     auto_height: bool,
     layout_dirty: bool,
+    spell_checker: Option<Rc<dyn SpellChecker>>,
+    preedit: Option<Preedit>,
 }
 
 #[js_methods]
@@ -94,6 +113,7 @@ impl Editable {
     pub fn set_text(&mut self, text: String) {
         let old_text = self.get_text();
         if text != old_text {
+            self.preedit = None;
             self.paragraph.clear();
             let lines = text.split('\n').collect::<Vec<&str>>();
             for ln in lines {
@@ -101,10 +121,18 @@ impl Editable {
                 self.paragraph.add_line(Self::build_line(ln.to_string()));
             }
             self.update_caret_value(TextCoord::new((0, 0)), false);
+            self.update_spelling();
         }
         self.element.mark_dirty(true);
     }
 
+    /// Insert elements at the caret, the selection is replaced
+    #[js_func]
+    pub fn insert_elements(&mut self, elements: Vec<TextElement>) {
+        self.remove_preedit();
+        self.insert_lines(vec![elements], self.paragraph.get_caret(), true);
+    }
+
     #[js_func]
     pub fn set_placeholder(&mut self, placeholder: String) {
         self.placeholder.clear();
@@ -126,7 +154,8 @@ impl Editable {
     #[js_func]
     pub fn set_multiple_line(&mut self, multiple_line: bool) {
         self.multiple_line = multiple_line;
-        self.paragraph.set_text_wrap(multiple_line);
+        self.paragraph
+            .set_text_wrap(multiple_line && self.white_space.wraps());
         self.element.mark_dirty(true);
     }
 
@@ -169,6 +198,7 @@ impl Editable {
             }
         }
         self.input_type = input_type;
+        self.update_spelling();
     }
 
     #[js_func]
@@ -191,6 +221,67 @@ impl Editable {
         }
     }
 
+    /// Decorate chars between `start` and `end` of the decoration, the decoration moves with
+    /// the chars when the text is edited
+    #[js_func]
+    pub fn add_decoration(&mut self, decoration: DecorationRange) {
+        self.paragraph.add_decoration(decoration);
+    }
+
+    /// Remove decorations of `group`, all decorations are removed if `group` is not specified
+    #[js_func]
+    pub fn remove_decorations(&mut self, group: Option<String>) {
+        self.paragraph.remove_decorations(group.as_deref());
+    }
+
+    #[js_func]
+    pub fn get_decorations(&self) -> Vec<DecorationRange> {
+        self.paragraph.get_decorations().clone()
+    }
+
+    /// Highlight occurrences of `query`, the first match is activated and scrolled into view
+    #[js_func]
+    pub fn find(
+        &mut self,
+        query: String,
+        options: Option<FindOptions>,
+    ) -> Vec<(TextCoord, TextCoord)> {
+        let matches = self.paragraph.find(&query, options.unwrap_or_default());
+        self.set_active_match(0);
+        matches
+    }
+
+    /// Activate the match at `index` and scroll it into view
+    #[js_func]
+    pub fn set_active_match(&mut self, index: usize) {
+        let rect = some_or_return!(self.paragraph.set_active_match(index));
+        let element = ok_or_return!(self.element.upgrade());
+        element.scroll_rect_into_view(&rect);
+    }
+
+    #[js_func]
+    pub fn clear_find(&mut self) {
+        self.paragraph.clear_find();
+    }
+
+    /// Suggestions for the misspelled word at `char_offset`
+    #[js_func]
+    pub fn get_spelling_suggestions(&self, char_offset: usize) -> Vec<String> {
+        let checker = some_or_return!(self.spell_checker.clone(), Vec::new());
+        let text = self.paragraph.get_text();
+        find_misspelled_words(checker.as_ref(), &text)
+            .into_iter()
+            .find(|(start, end)| *start <= char_offset && char_offset <= *end)
+            .map(|(start, end)| checker.suggest(text.substring(start, end - start)))
+            .unwrap_or_default()
+    }
+
+    /// Misspelled words are underlined with squiggles, spellchecking is disabled if `checker` is None
+    pub fn set_spell_checker(&mut self, checker: Option<Rc<dyn SpellChecker>>) {
+        self.spell_checker = checker;
+        self.update_spelling();
+    }
+
     fn get_caret_pixels_position(&self) -> Option<Rect> {
         let element = self.element.upgrade_mut().ok()?;
         let (scroll_left, scroll_top) = element.scrollable.scroll_offset();
@@ -210,12 +301,13 @@ impl Editable {
         //TOOD use transformed position
         let el_offset = el.get_origin_bounds();
         let x = (el_offset.x + pos.x) as f64;
-        let y = (el_offset.y + pos.bottom()) as f64;
+        let y = (el_offset.y + pos.y) as f64;
+        // The candidate window is placed beside the caret without covering it
         win.window.set_ime_cursor_area(
             crate::winit::dpi::Position::Logical(LogicalPosition { x, y }),
             Size::Logical(LogicalSize {
-                width: 1.0,
-                height: 1.0,
+                width: pos.width as f64,
+                height: pos.height as f64,
             }),
         );
         Some(())
@@ -225,10 +317,6 @@ impl Editable {
         self.paragraph.move_caret(delta);
     }
 
-    fn move_caret_vertical(&mut self, is_up: bool) {
-        self.paragraph.move_caret_vertical(is_up);
-    }
-
     fn update_caret_value(&mut self, new_caret: TextCoord, is_kb_vertical: bool) {
         let old_caret = self.paragraph.get_caret();
         self.paragraph.update_caret_value(new_caret, is_kb_vertical);
@@ -276,7 +364,7 @@ impl Editable {
         let (scroll_left, scroll_top) = element.scrollable.scroll_offset();
 
         let caret = self.paragraph.get_caret();
-        let bounds = match self.paragraph.get_char_rect(caret) {
+        let bounds = match self.paragraph.get_caret_rect_at(caret) {
             None => return,
             Some(rect) => rect.translate(-scroll_left, -scroll_top),
         };
@@ -300,6 +388,7 @@ impl Editable {
     }
 
     fn handle_blur(&mut self) {
+        self.remove_preedit();
         self.focusing = false;
         self.caret_timer_handle = None;
         self.caret_visible.set(false);
@@ -352,7 +441,17 @@ impl Editable {
         self.paragraph.select_all();
     }
 
-    fn handle_key_down(&mut self, event: &KeyEventDetail) {
+    /// Returns true if the key is consumed
+    fn handle_key_down(&mut self, event: &KeyEventDetail) -> bool {
+        // Keys are handled by the input method while composing
+        if self.preedit.is_some() {
+            return true;
+        }
+        if let Some(nk) = &event.named_key {
+            if self.paragraph.on_navigation_key(nk, event.modifiers) {
+                return true;
+            }
+        }
         if event.modifiers == 0 {
             if let Some(nk) = &event.named_key {
                 match nk {
@@ -368,21 +467,10 @@ impl Editable {
                         self.handle_input("");
                     }
                     NamedKey::Enter => {
-                        if self.multiple_line {
-                            self.handle_input("\n");
+                        if !self.multiple_line {
+                            return false;
                         }
-                    }
-                    NamedKey::ArrowLeft => {
-                        self.move_caret(-1);
-                    }
-                    NamedKey::ArrowRight => {
-                        self.move_caret(1);
-                    }
-                    NamedKey::ArrowUp => {
-                        self.move_caret_vertical(true);
-                    }
-                    NamedKey::ArrowDown => {
-                        self.move_caret_vertical(false);
+                        self.handle_input("\n");
                     }
                     NamedKey::Space => {
                         self.handle_input(" ");
@@ -391,44 +479,54 @@ impl Editable {
                         //TODO use \t?
                         self.handle_input("   ");
                     }
-                    _ => {}
+                    _ => return false,
                 }
             } else if let Some(text) = &event.key_str {
                 self.handle_input(&text);
+            } else {
+                return false;
             }
         } else if event.modifiers == KEY_MOD_SHIFT {
-            if let Some(text) = &event.key_str {
-                self.handle_input(&text);
+            match &event.key_str {
+                Some(text) => self.handle_input(&text),
+                None => return false,
             }
         } else if event.modifiers == KEY_MOD_CTRL {
-            if let Some(text) = &event.key_str {
-                match text.as_str() {
-                    #[cfg(feature = "clipboard")]
-                    "c" => self.copy(),
-                    #[cfg(feature = "clipboard")]
-                    "x" => self.cut(),
-                    #[cfg(feature = "clipboard")]
-                    "v" => self.paste(),
-                    "a" => self.select_all(),
-                    "z" => {
-                        self.undo();
-                    }
-                    _ => {}
+            let text = some_or_return!(&event.key_str, false);
+            match text.as_str() {
+                #[cfg(feature = "clipboard")]
+                "c" => self.copy(),
+                #[cfg(feature = "clipboard")]
+                "x" => self.cut(),
+                #[cfg(feature = "clipboard")]
+                "v" => self.paste(),
+                "a" => self.select_all(),
+                "z" => {
+                    self.undo();
                 }
+                _ => return false,
             }
+        } else {
+            return false;
         }
+        true
     }
 
+    /// Revert the last recorded edit, the returned op is the inverse of the recorded one
     fn undo(&mut self) {
-        if let Some(op) = &self.edit_history.undo() {
-            match op.op {
-                EditOpType::Insert => {
-                    //TODO self.insert_text(op.content.as_str(), op.caret, false);
-                }
-                EditOpType::Delete => {
-                    //TODO self.base.select(op.caret, op.caret + op.content.chars_count());
-                    //TODO self.insert_text("", op.caret, false);
-                }
+        let op = some_or_return!(self.edit_history.undo());
+        self.remove_preedit();
+        let start = some_or_return!(self.paragraph.get_text_coord_by_char_offset(op.caret));
+        match op.op {
+            EditOpType::Insert => {
+                self.paragraph.unselect();
+                self.insert_text(&op.content, start, false);
+            }
+            EditOpType::Delete => {
+                let end_offset = op.caret + op.content.chars_count();
+                let end = some_or_return!(self.paragraph.get_text_coord_by_char_offset(end_offset));
+                self.paragraph.select(start, end);
+                self.insert_text("", start, false);
             }
         }
     }
@@ -459,91 +557,254 @@ impl Editable {
         }
     }
 
-    fn insert_text(&mut self, input: &str, mut caret: TextCoord, record_history: bool) {
+    fn insert_text(&mut self, input: &str, caret: TextCoord, record_history: bool) {
+        let lines = input
+            .split('\n')
+            .map(|ln| Self::build_line(ln.to_string()))
+            .collect();
+        self.insert_lines(lines, caret, record_history);
+    }
+
+    fn insert_lines(
+        &mut self,
+        input_lines: Vec<Vec<TextElement>>,
+        caret: TextCoord,
+        record_history: bool,
+    ) {
+        let first_line = match self.paragraph.get_selection() {
+            Some((start, _)) => start.0.min(caret.0),
+            None => caret.0,
+        };
+        self.edit_lines(input_lines, caret, record_history);
+        // Only the edited lines are checked again, squiggles of other lines are moved by edits
+        let last_line = self.paragraph.get_caret().0;
+        self.update_spelling_lines(first_line, last_line);
+
+        // emit text update
+        let text = self.paragraph.get_text().to_string();
+        self.element.emit(TextUpdateEvent {
+            value: text.clone(),
+        });
+
+        // emit text change
+        self.element.emit(TextChangeEvent { value: text });
+
+        self.element.mark_dirty(true);
+    }
+
+    /// Replace the selection with `input_lines` without emitting change events
+    fn edit_lines(
+        &mut self,
+        input_lines: Vec<Vec<TextElement>>,
+        mut caret: TextCoord,
+        record_history: bool,
+    ) {
         if let Some((start, end)) = self.paragraph.get_selection() {
-            if record_history {
-                // let text= self.paragraph.get_selection_text().unwrap();
-                //TODO self.edit_history.record_delete(begin, &text);
-            }
+            let delete_op = EditOp {
+                caret: self.paragraph.get_char_offset_by_text_coord(start),
+                op: EditOpType::Delete,
+                content: self.paragraph.get_selection_text().unwrap_or_default(),
+            };
 
-            if start.0 == end.0 {
-                let line_text = self.paragraph.get_line_text(start.0).unwrap();
-                let left = line_text.substring(0, start.1);
-                let right = line_text.substring(end.1, line_text.chars_count());
-                let new_text = format!("{}{}", left, right);
-                self.paragraph
-                    .update_line(caret.0, Self::build_line(new_text));
-            } else {
-                let first_line = self.paragraph.get_line_text(start.0).unwrap();
-                let left = first_line.substring(0, start.1).to_string();
-                let last_line = self.paragraph.get_line_text(end.0).unwrap();
-                let right = last_line
-                    .substring(end.1, last_line.chars_count())
-                    .to_string();
-                self.paragraph
-                    .update_line(start.0, Self::build_line(format!("{}{}", left, right)));
-                if end.0 > start.0 {
-                    for _ in start.0..end.0 {
-                        self.paragraph.delete_line(start.0 + 1);
-                    }
+            let mut new_line = self
+                .paragraph
+                .get_line_elements(start.0, 0, start.1)
+                .unwrap();
+            let end_atom_count = self.paragraph.get_line_atom_count(end.0).unwrap();
+            let right = self
+                .paragraph
+                .get_line_elements(end.0, end.1, end_atom_count)
+                .unwrap();
+            append_elements(&mut new_line, right);
+            self.paragraph.update_line(start.0, new_line);
+            if end.0 > start.0 {
+                for _ in start.0..end.0 {
+                    self.paragraph.delete_line(start.0 + 1);
                 }
             }
             self.paragraph.unselect();
             self.update_caret_value(start, false);
             caret = start;
+            self.apply_edit_op(delete_op, record_history);
         }
-        if !input.is_empty() {
-            if record_history {
-                //TODO self.edit_history.record_input(caret, input);
-            }
-            let line_text = self.paragraph.get_line_text(caret.0).unwrap();
-            let left_str = line_text.substring(0, caret.1);
-            let right_str = line_text.substring(caret.1, line_text.len() - caret.1);
-            let input_lines = input.split('\n').collect::<Vec<&str>>();
-            let new_caret = if input_lines.len() == 1 {
-                let new_text = format!("{}{}{}", left_str, input, right_str);
-                self.paragraph
-                    .update_line(caret.0, Self::build_line(new_text));
-                TextCoord(caret.0, caret.1 + input.chars_count())
+        let input_count = input_lines.len();
+        let has_input = input_count > 1
+            || input_lines
+                .first()
+                .map(|ln| elements_atom_count(ln) > 0)
+                .unwrap_or(false);
+        if has_input {
+            let input_op = EditOp {
+                caret: self.paragraph.get_char_offset_by_text_coord(caret),
+                op: EditOpType::Insert,
+                content: input_lines
+                    .iter()
+                    .map(|ln| ln.iter().map(|e| e.text()).collect::<String>())
+                    .collect::<Vec<String>>()
+                    .join("\n"),
+            };
+            let atom_count = self.paragraph.get_line_atom_count(caret.0).unwrap();
+            let mut first_line = self
+                .paragraph
+                .get_line_elements(caret.0, 0, caret.1)
+                .unwrap();
+            let right = self
+                .paragraph
+                .get_line_elements(caret.0, caret.1, atom_count)
+                .unwrap();
+            let mut input_lines = input_lines.into_iter();
+            append_elements(&mut first_line, input_lines.next().unwrap());
+            let new_caret = if input_count == 1 {
+                let new_col = elements_atom_count(&first_line);
+                append_elements(&mut first_line, right);
+                self.paragraph.update_line(caret.0, first_line);
+                TextCoord(caret.0, new_col)
             } else {
-                let first_line = format!("{}{}", left_str, unsafe { input_lines.get_unchecked(0) });
-                self.paragraph
-                    .insert_line(caret.0, Self::build_line(first_line));
-                if input_lines.len() > 2 {
-                    for i in 1..input_lines.len() - 1 {
-                        let line = unsafe { input_lines.get_unchecked(i).to_string() };
-                        self.paragraph
-                            .insert_line(caret.0 + i, Self::build_line(line));
-                    }
+                self.paragraph.insert_line(caret.0, first_line);
+                for i in 1..input_count - 1 {
+                    let line = input_lines.next().unwrap();
+                    self.paragraph.insert_line(caret.0 + i, line);
                 }
-                let last_line = format!(
-                    "{}{}",
-                    unsafe { input_lines.get_unchecked(input_lines.len() - 1) },
-                    right_str
-                );
+                let mut last_line = input_lines.next().unwrap();
+                let new_col = elements_atom_count(&last_line);
+                append_elements(&mut last_line, right);
                 self.paragraph
-                    .update_line(caret.0 + input_lines.len() - 1, Self::build_line(last_line));
-                TextCoord(
-                    caret.0 + input_lines.len() - 1,
-                    input_lines.last().unwrap().chars_count(),
-                )
+                    .update_line(caret.0 + input_count - 1, last_line);
+                TextCoord(caret.0 + input_count - 1, new_col)
             };
             //TODO maybe update caret twice?
             self.update_caret_value(new_caret, false);
+            self.apply_edit_op(input_op, record_history);
         }
+    }
 
-        // emit text update
-        let text = self.paragraph.get_text().to_string();
-        self.element.emit(TextUpdateEvent {
-            value: text.clone(),
+    /// Show the composition text at the caret with an underline, `cursor` is the byte range of
+    /// the cursor in `text`
+    fn set_preedit(&mut self, text: &str, cursor: Option<(usize, usize)>) {
+        self.remove_preedit();
+        if text.is_empty() {
+            return;
+        }
+        if self.paragraph.get_selection().is_some() {
+            // The selection is replaced by the composition
+            self.insert_text("", self.paragraph.get_caret(), true);
+        }
+        let start = self.paragraph.get_caret();
+        self.edit_lines(vec![Self::build_line(text.to_string())], start, false);
+
+        let char_count = text.chars_count();
+        let start_offset = self.paragraph.get_char_offset_by_text_coord(start);
+        let underline = DecorationRange::new(
+            start_offset,
+            start_offset + char_count,
+            DecorationKind::Underline,
+        )
+        .with_group(PREEDIT_DECORATION_GROUP);
+        self.paragraph.add_decoration(underline);
+
+        let caret_col = cursor
+            .and_then(|(_, end)| text.get(..end))
+            .map(|s| s.chars_count())
+            .unwrap_or(char_count);
+        self.update_caret_value(TextCoord(start.0, start.1 + caret_col), false);
+        self.preedit = Some(Preedit {
+            start,
+            char_count,
+            caret_visible: cursor.is_some(),
         });
+        self.element.mark_dirty(true);
+    }
 
-        // emit text change
-        self.element.emit(TextChangeEvent { value: text });
-
+    /// Remove the composition text, the caret is moved to where the composition starts
+    fn remove_preedit(&mut self) {
+        let preedit = some_or_return!(self.preedit.take());
+        self.paragraph
+            .remove_decorations(Some(PREEDIT_DECORATION_GROUP));
+        let end = TextCoord(preedit.start.0, preedit.start.1 + preedit.char_count);
+        self.paragraph.select(preedit.start, end);
+        self.edit_lines(vec![Self::build_line(String::new())], preedit.start, false);
         self.element.mark_dirty(true);
     }
 
+    /// Keep decorations on the same chars after the text is edited
+    fn apply_edit_op(&mut self, op: EditOp, record_history: bool) {
+        let decorations = self
+            .paragraph
+            .get_decorations()
+            .iter()
+            .filter_map(|d| {
+                let start = op.map_offset(d.start, true);
+                let end = op.map_offset(d.end, false);
+                if start < end {
+                    Some(DecorationRange {
+                        start,
+                        end,
+                        ..d.clone()
+                    })
+                } else {
+                    None
+                }
+            })
+            .collect();
+        self.paragraph.set_decorations(decorations);
+        if record_history {
+            match op.op {
+                EditOpType::Insert => self.edit_history.record_input(op.caret, &op.content),
+                EditOpType::Delete => self.edit_history.record_delete(op.caret, &op.content),
+            }
+        }
+    }
+
+    /// Replace squiggles of misspelled words
+    fn update_spelling(&mut self) {
+        self.paragraph
+            .remove_decorations(Some(SPELLCHECK_DECORATION_GROUP));
+        let line_count = self.paragraph.get_lines().len();
+        if line_count > 0 {
+            self.update_spelling_lines(0, line_count - 1);
+        }
+    }
+
+    /// Replace squiggles of misspelled words between `first_line` and `last_line`, both inclusive
+    fn update_spelling_lines(&mut self, first_line: usize, last_line: usize) {
+        let start = self
+            .paragraph
+            .get_char_offset_by_text_coord(TextCoord(first_line, 0));
+        let mut text = String::new();
+        for row in first_line..=last_line {
+            let line_text = some_or_break!(self.paragraph.get_line_text(row));
+            if row > first_line {
+                text.push('\n');
+            }
+            text.push_str(&line_text);
+        }
+        let end = start + text.chars_count();
+        // Words never cross lines, so the squiggles of the lines are inside the range
+        let decorations = self
+            .paragraph
+            .get_decorations()
+            .iter()
+            .filter(|d| {
+                !d.is_in_group(Some(SPELLCHECK_DECORATION_GROUP)) || d.end < start || d.start > end
+            })
+            .cloned()
+            .collect();
+        self.paragraph.set_decorations(decorations);
+        if self.input_type != InputType::Text {
+            return;
+        }
+        let checker = some_or_return!(self.spell_checker.clone());
+        for (word_start, word_end) in find_misspelled_words(checker.as_ref(), &text) {
+            let decoration = DecorationRange::new(
+                start + word_start,
+                start + word_end,
+                DecorationKind::Squiggle,
+            )
+            .with_group(SPELLCHECK_DECORATION_GROUP);
+            self.paragraph.add_decoration(decoration);
+        }
+    }
+
     fn handle_input(&mut self, input: &str) {
         //debug!("on input:{}", input);
         self.insert_text(input, self.paragraph.get_caret(), true);
@@ -552,13 +813,7 @@ impl Editable {
     pub fn build_line(text: String) -> Vec<TextElement> {
         let unit = TextElement::Text(TextUnit {
             text,
-            font_families: None,
-            font_size: None,
-            color: None,
-            text_decoration_line: None,
-            weight: None,
-            background_color: None,
-            style: None,
+            ..Default::default()
         });
         vec![unit]
     }
@@ -571,11 +826,11 @@ impl Editable {
         let padding_box_width = bounds.width.de_nan(f32::INFINITY) - border.1 - border.3;
         let padding_box_height = bounds.height.de_nan(f32::INFINITY) - border.0 - border.2;
 
-        let mut layout_width = padding_box_width;
+        // Single line text is not wrapped, the width is used to align it
+        let layout_width = padding_box_width;
         if !self.multiple_line {
             let content_height = padding_box_height;
             line_height = Some(content_height);
-            layout_width = f32::NAN;
         }
 
         self.placeholder.set_line_height(line_height);
@@ -611,7 +866,10 @@ impl Editable {
         } else if let Some(_e) = BlurEvent::cast(event) {
             self.handle_blur();
         } else if let Some(e) = TextInputEvent::cast(event) {
+            self.remove_preedit();
             self.insert_text(e.0.as_str(), self.paragraph.get_caret(), true);
+        } else if let Some(e) = ImePreeditEvent::cast(event) {
+            self.set_preedit(&e.text, e.cursor);
         } else if let Some(_e) = ScrollEvent::cast(event) {
             //TODO update later?
             let _ = self.update_ime();
@@ -624,7 +882,11 @@ impl Editable {
         }
     }
 
-    pub(crate) fn on_execute_default_behavior(&mut self, event: &mut Event) -> bool {
+    pub(crate) fn on_execute_default_behavior(
+        &mut self,
+        event: &mut Event,
+        ctx: &mut EventContext<ElementWeak>,
+    ) -> bool {
         #[cfg(feature = "clipboard")]
         if let Some(_e) = MouseDownEvent::cast(event) {
             if _e.0.button == 2 {
@@ -633,7 +895,10 @@ impl Editable {
             }
         }
         if let Some(e) = KeyDownEvent::cast(event) {
-            self.handle_key_down(&e.0);
+            // Consumed keys are not handled as shortcuts of the window
+            if self.handle_key_down(&e.0) {
+                ctx.prevent_default = true;
+            }
         }
         false
     }
@@ -715,6 +980,8 @@ impl ElementBackend for Editable {
         let mut placeholder = TextBox::new();
         paragraph.set_text_wrap(false);
         placeholder.set_text_wrap(false);
+        paragraph.set_white_space(WhiteSpace::PreWrap);
+        placeholder.set_white_space(WhiteSpace::PreWrap);
 
         //TODO support custom style
         placeholder.set_color(Color::from_rgb(80, 80, 80));
@@ -752,6 +1019,7 @@ impl ElementBackend for Editable {
             focusing: false,
             align: TextAlign::Left,
             multiple_line: false,
+            white_space: WhiteSpace::PreWrap,
             element: ele.as_weak(),
             edit_history: EditHistory::new(),
             rows: 5,
@@ -759,6 +1027,8 @@ impl ElementBackend for Editable {
             line_height: None,
             auto_height: true,
             layout_dirty: true,
+            spell_checker: None,
+            preedit: None,
         }
         .to_ref();
         inst.set_multiple_line(false);
@@ -821,6 +1091,51 @@ impl ElementBackend for Editable {
                 self.paragraph
                     .set_font_families(element.style.font_family.clone());
             }
+            StylePropKey::TextShadow => {
+                self.paragraph
+                    .set_text_shadow(element.style.text_shadow.clone());
+            }
+            StylePropKey::TextAlign => {
+                self.align = element.style.text_align;
+                self.paragraph.set_align(self.align);
+                self.placeholder.set_align(self.align);
+                self.element.mark_dirty(true);
+            }
+            StylePropKey::WhiteSpace => {
+                self.white_space = element.style.white_space;
+                self.set_multiple_line(self.multiple_line);
+            }
+            StylePropKey::WordBreak => {
+                self.paragraph.set_word_break(element.style.word_break);
+            }
+            StylePropKey::LetterSpacing => {
+                let letter_spacing = element.style.letter_spacing;
+                self.paragraph.set_letter_spacing(letter_spacing);
+                self.placeholder.set_letter_spacing(letter_spacing);
+                self.element.mark_dirty(true);
+            }
+            StylePropKey::TextTransform => {
+                let text_transform = element.style.text_transform;
+                self.paragraph.set_text_transform(text_transform);
+                self.placeholder.set_text_transform(text_transform);
+                self.element.mark_dirty(true);
+            }
+            StylePropKey::Direction => {
+                let direction = element.style.direction;
+                self.paragraph.set_direction(direction);
+                self.placeholder.set_direction(direction);
+                self.element.mark_dirty(true);
+            }
+            // The edited text is always shown entirely, only the placeholder is truncated
+            StylePropKey::TextOverflow => {
+                self.placeholder
+                    .set_text_overflow(element.style.text_overflow);
+                self.element.mark_dirty(true);
+            }
+            StylePropKey::LineClamp => {
+                self.placeholder.set_line_clamp(element.style.line_clamp);
+                self.element.mark_dirty(true);
+            }
             _ => {}
         }
     }
@@ -831,7 +1146,12 @@ impl ElementBackend for Editable {
         paint.set_color(element.style.color);
 
         let focusing = self.focusing;
-        let caret_visible = self.caret_visible.get();
+        let caret_visible = self.caret_visible.get()
+            && self
+                .preedit
+                .as_ref()
+                .map(|p| p.caret_visible)
+                .unwrap_or(true);
 
         let caret_pos = some_or_return!(self.get_caret_pixels_position(), RenderFn::empty());
         let text_render = if self.get_text().is_empty() {
@@ -902,10 +1222,13 @@ impl ElementBackend for Editable {
 #[cfg(test)]
 mod tests {
     use crate::element::common::editable::Editable;
+    use crate::element::spell_checker::SpellChecker;
     use crate::element::{Element, ElementBackend};
     use crate::string::StringUtils;
     use crate::text::textbox::TextCoord;
     use measure_time::print_time;
+    use std::cell::Cell;
+    use std::rc::Rc;
 
     #[test]
     fn test_performance() {
@@ -949,6 +1272,66 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_preedit() {
+        let mut el = Element::create(Editable::create);
+        let entry = el.get_backend_mut_as::<Editable>();
+        entry.set_text("ab".to_string());
+        entry.paragraph.update_caret_value(TextCoord(0, 1), false);
+
+        entry.set_preedit("你好", Some((3, 3)));
+        assert_eq!("a你好b", entry.get_text());
+        assert_eq!(TextCoord(0, 2), entry.paragraph.get_caret());
+        let decorations = entry.get_decorations();
+        assert_eq!((1, 3), (decorations[0].start, decorations[0].end));
+
+        entry.set_preedit("你", None);
+        assert_eq!("a你b", entry.get_text());
+        assert_eq!(TextCoord(0, 2), entry.paragraph.get_caret());
+
+        entry.set_preedit("", None);
+        assert_eq!("ab", entry.get_text());
+        assert_eq!(TextCoord(0, 1), entry.paragraph.get_caret());
+        assert!(entry.get_decorations().is_empty());
+    }
+
+    struct CountingChecker {
+        checked: Rc<Cell<usize>>,
+    }
+
+    impl SpellChecker for CountingChecker {
+        fn check(&self, word: &str) -> bool {
+            self.checked.set(self.checked.get() + 1);
+            word != "bad"
+        }
+    }
+
+    #[test]
+    fn test_spelling_of_edited_lines() {
+        let mut el = Element::create(Editable::create);
+        let entry = el.get_backend_mut_as::<Editable>();
+        let checked = Rc::new(Cell::new(0));
+        entry.set_text("good bad\ngood".to_string());
+        entry.set_spell_checker(Some(Rc::new(CountingChecker {
+            checked: checked.clone(),
+        })));
+        let squiggles = |entry: &Editable| {
+            entry
+                .get_decorations()
+                .iter()
+                .map(|d| (d.start, d.end))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(vec![(5, 8)], squiggles(entry));
+
+        checked.set(0);
+        let line = Editable::build_line(" bad".to_string());
+        entry.edit_lines(vec![line], TextCoord(1, 4), false);
+        entry.update_spelling_lines(1, 1);
+        assert_eq!(2, checked.get());
+        assert_eq!(vec![(5, 8), (14, 17)], squiggles(entry));
+    }
+
     //TODO error because of missing event loop
     // #[test]
     pub fn test_edit_history() {
@@ -12,6 +12,32 @@ pub struct EditOp {
     pub content: String,
 }
 
+impl EditOp {
+    /// Map a char offset before the operation to the offset after it. An offset at the caret
+    /// of insertion moves after the inserted content if `stick_after` is true.
+    pub fn map_offset(&self, offset: usize, stick_after: bool) -> usize {
+        let count = self.content.chars_count();
+        match self.op {
+            EditOpType::Insert => {
+                if offset > self.caret || (offset == self.caret && stick_after) {
+                    offset + count
+                } else {
+                    offset
+                }
+            }
+            EditOpType::Delete => {
+                if offset <= self.caret {
+                    offset
+                } else if offset >= self.caret + count {
+                    offset - count
+                } else {
+                    self.caret
+                }
+            }
+        }
+    }
+}
+
 pub struct EditHistory {
     max_history: usize,
     history: Vec<EditOp>,
@@ -118,3 +144,30 @@ impl EditHistory {
         self.history_ptr += 1;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::element::edit_history::{EditOp, EditOpType};
+
+    #[test]
+    fn test_map_offset() {
+        let insert = EditOp {
+            caret: 2,
+            op: EditOpType::Insert,
+            content: "abc".to_string(),
+        };
+        assert_eq!(insert.map_offset(1, true), 1);
+        assert_eq!(insert.map_offset(2, false), 2);
+        assert_eq!(insert.map_offset(2, true), 5);
+        assert_eq!(insert.map_offset(4, false), 7);
+
+        let delete = EditOp {
+            caret: 2,
+            op: EditOpType::Delete,
+            content: "abc".to_string(),
+        };
+        assert_eq!(delete.map_offset(2, true), 2);
+        assert_eq!(delete.map_offset(3, true), 2);
+        assert_eq!(delete.map_offset(6, false), 3);
+    }
+}
@@ -1,6 +1,7 @@
 use crate as deft;
 use crate::font::family::{FontFamilies, FontFamily};
-use crate::font::Font;
+use crate::font::{is_emoji, Font};
+use crate::some_or_continue;
 use crate::style::font::FontStyle;
 use crate::text::textbox::DEFAULT_FALLBACK_FONTS;
 use deft_macros::mrc_object;
@@ -13,13 +14,45 @@ use font_kit::source::SystemSource;
 use font_kit::sources::mem::MemSource as SystemSource;
 use skia_safe::font_style::Slant;
 use skia_safe::wrapper::NativeTransmutableWrapper;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use swash::text::{Codepoint, Script};
 use swash::{ObliqueAngle, Style, Weight};
 
 #[mrc_object]
 pub struct FontManager {
     source: SystemSource,
     cache: HashMap<FontCacheKey, Option<Font>>,
+    /// Fonts registered by app, family names are matched case-insensitively
+    registered: HashMap<String, Vec<Font>>,
+    /// System fonts found for chars missing in matched fonts, keyed by the script of chars and
+    /// the style the fonts are synthesized for
+    fallback_fonts: HashMap<(Script, StyleKey), Vec<Font>>,
+    /// Chars which no font of the style has a glyph for
+    missing_chars: HashSet<(char, StyleKey)>,
+    /// Families searched for fallback fonts with their normal style font to check the coverage
+    /// of chars, built on first use
+    fallback_index: Option<Vec<(String, Font)>>,
+}
+
+#[derive(Hash, PartialEq, Eq, Clone, Copy)]
+struct StyleKey {
+    weight: u16,
+    style: FontStyle,
+}
+
+impl StyleKey {
+    fn new(font_style: &skia_safe::FontStyle) -> Self {
+        let style = match font_style.slant() {
+            Slant::Upright => FontStyle::Normal,
+            Slant::Italic => FontStyle::Italic,
+            //TODO support angle
+            Slant::Oblique => FontStyle::Oblique,
+        };
+        Self {
+            weight: font_style.weight().unwrap() as u16,
+            style,
+        }
+    }
 }
 
 #[derive(Hash, PartialEq, Eq, Clone)]
@@ -51,10 +84,35 @@ impl FontManager {
         FontManagerData {
             source,
             cache: HashMap::new(),
+            registered: HashMap::new(),
+            fallback_fonts: HashMap::new(),
+            missing_chars: HashSet::new(),
+            fallback_index: None,
         }
         .to_ref()
     }
 
+    /// Register font under `family_name`, it takes precedence over system fonts of the same family
+    pub fn register_font(&mut self, family_name: &str, font: Font) {
+        let key = match self.find_registered_name(family_name) {
+            Some(name) => name,
+            None => family_name.to_string(),
+        };
+        self.registered.entry(key).or_default().push(font);
+        self.cache
+            .retain(|k, _| !k.family_name.eq_ignore_ascii_case(family_name));
+        // Chars missing before may be covered by the new font
+        self.missing_chars.clear();
+        self.fallback_index = None;
+    }
+
+    fn find_registered_name(&self, family_name: &str) -> Option<String> {
+        self.registered
+            .keys()
+            .find(|k| k.eq_ignore_ascii_case(family_name))
+            .cloned()
+    }
+
     pub fn match_best(
         &self,
         family_names: &[impl AsRef<str>],
@@ -73,51 +131,118 @@ impl FontManager {
         result
     }
 
+    /// Find a system font having a glyph for `ch`. Fonts found are cached by script and style, so
+    /// following chars of the same script are resolved without searching. Fonts with color glyphs
+    /// are preferred for emoji.
+    pub fn match_fallback(&self, ch: char, style: &skia_safe::FontStyle) -> Option<Font> {
+        let style_key = StyleKey::new(style);
+        let script = ch.script();
+        if let Some(fonts) = self.fallback_fonts.get(&(script, style_key)) {
+            if let Some(font) = fonts.iter().find(|f| f.has_glyph(ch)) {
+                return Some(font.clone());
+            }
+        }
+        if self.missing_chars.contains(&(ch, style_key)) {
+            return None;
+        }
+        let mut me = self.clone();
+        let index = me
+            .fallback_index
+            .get_or_insert_with(|| self.build_fallback_index())
+            .clone();
+        let emoji = is_emoji(ch);
+        let mut found: Option<Font> = None;
+        for (family, coverage) in index {
+            if !coverage.has_glyph(ch) {
+                continue;
+            }
+            let font = some_or_continue!(self.get_by_family_name(&family, style));
+            if !emoji || font.has_color_glyphs() {
+                found = Some(font);
+                break;
+            }
+            // Monochrome emoji are used only if there is no color one
+            found.get_or_insert(font);
+        }
+        match &found {
+            Some(font) => {
+                let fonts = me.fallback_fonts.entry((script, style_key)).or_default();
+                // Color fonts come first so that emoji are not taken by monochrome fonts
+                if font.has_color_glyphs() {
+                    fonts.insert(0, font.clone());
+                } else {
+                    fonts.push(font.clone());
+                }
+            }
+            None => {
+                me.missing_chars.insert((ch, style_key));
+            }
+        }
+        found
+    }
+
+    /// Load a normal style font of every family once, fonts registered by app come before
+    /// system fonts
+    fn build_fallback_index(&self) -> Vec<(String, Font)> {
+        let mut families = self.registered.keys().cloned().collect::<Vec<_>>();
+        families.extend(self.source.all_families().unwrap_or_default());
+        let style = skia_safe::FontStyle::normal();
+        families
+            .into_iter()
+            .filter_map(|family| {
+                let font = self.get_by_family_name(&family, &style)?;
+                Some((family, font))
+            })
+            .collect()
+    }
+
     pub fn all_font_families(&self) -> FontFamilies {
+        let mut list: Vec<FontFamily> = self
+            .registered
+            .keys()
+            .map(|name| FontFamily::new(name))
+            .collect();
         if let Ok(fonts) = self.source.all_families() {
-            let list = fonts.iter().map(|it| FontFamily::new(it)).collect();
-            FontFamilies::new(list)
-        } else {
-            FontFamilies::new(vec![])
+            list.extend(fonts.iter().map(|it| FontFamily::new(it)));
         }
+        FontFamilies::new(list)
     }
 
-    fn get_by_family_name(
+    pub fn get_by_family_name(
         &self,
         name: &str,
         expected_font_style: &skia_safe::FontStyle,
     ) -> Option<Font> {
-        let weight = expected_font_style.weight().unwrap() as u16;
-        let expected_style = match expected_font_style.slant() {
-            Slant::Upright => FontStyle::Normal,
-            Slant::Italic => FontStyle::Italic,
-            //TODO support angle
-            Slant::Oblique => FontStyle::Oblique,
-        };
+        let StyleKey { weight, style } = StyleKey::new(expected_font_style);
         let cache_key = FontCacheKey {
             family_name: name.to_string(),
             weight,
-            style: expected_style,
+            style,
         };
         let mut me = self.clone();
         me.cache
             .entry(cache_key)
             .or_insert_with(move || {
-                let family_name = Self::str_to_family_name(name);
-                let fh = self
-                    .source
-                    .select_family_by_generic_name(&family_name)
-                    .ok()?;
                 let expected_style = match expected_font_style.slant() {
                     Slant::Upright => Style::Normal,
                     Slant::Italic => Style::Italic,
                     //TODO support angle
                     Slant::Oblique => Style::Oblique(ObliqueAngle::default()),
                 };
-                let mut fonts = Vec::new();
-                for h in fh.fonts() {
-                    if let Some(font) = Self::load_font(h, name) {
-                        fonts.push(font);
+                let registered = self.find_registered_name(name);
+                let mut fonts = registered
+                    .and_then(|n| self.registered.get(&n).cloned())
+                    .unwrap_or_default();
+                if fonts.is_empty() {
+                    let family_name = Self::str_to_family_name(name);
+                    let fh = self
+                        .source
+                        .select_family_by_generic_name(&family_name)
+                        .ok()?;
+                    for h in fh.fonts() {
+                        if let Some(font) = Self::load_font(h, name) {
+                            fonts.push(font);
+                        }
                     }
                 }
                 let mut result = Self::filter_fonts_by_style(&fonts, expected_style);
@@ -133,7 +258,7 @@ impl FontManager {
     fn filter_fonts_by_style(fonts: &Vec<Font>, style: Style) -> Vec<&Font> {
         let mut result = Vec::with_capacity(fonts.len());
         for f in fonts {
-            if f.attributes().style() == style {
+            if f.style() == style {
                 result.push(f);
             }
         }
@@ -144,8 +269,7 @@ impl FontManager {
         let mut best_font = None;
         let mut best_weight_diff = i32::MAX;
         for f in fonts {
-            let attrs = f.attributes();
-            let weight_diff = (attrs.weight().0 as i32 - expected_weight as i32).abs();
+            let weight_diff = (f.weight().0 as i32 - expected_weight as i32).abs();
             if weight_diff < best_weight_diff {
                 best_font = Some(f);
                 best_weight_diff = weight_diff;
@@ -186,7 +310,8 @@ impl FontManager {
 
 #[cfg(test)]
 mod tests {
-    use crate::element::font_manager::FontManager;
+    use crate::element::font_manager::{FontManager, StyleKey};
+    use crate::font::{is_emoji, Font};
     use crate::text::textbox::DEFAULT_FALLBACK_FONTS;
 
     // #[test]
@@ -219,4 +344,59 @@ mod tests {
             println!("{}", font.attributes().weight().0);
         }
     }
+
+    #[test]
+    fn test_register_font() {
+        let mut fm = FontManager::new();
+        let data = include_bytes!("../../fonts/NotoSerif-Regular.ttf").to_vec();
+        let font = Font::from_bytes(data, 0, "Brand".to_string()).unwrap();
+        fm.register_font("Brand", font);
+        let fonts = fm.match_best(&["brand"], &skia_safe::FontStyle::normal());
+        assert_eq!("Brand", fonts[0].name());
+        let families = fm.all_font_families();
+        assert!(families.as_slice().iter().any(|f| f.name() == "Brand"));
+    }
+
+    #[test]
+    fn test_match_fallback() {
+        let mut fm = FontManager::new();
+        let data = include_bytes!("../../fonts/NotoSerif-Regular.ttf").to_vec();
+        let font = Font::from_bytes(data, 0, "Brand".to_string()).unwrap();
+        fm.register_font("Brand", font);
+        let style = skia_safe::FontStyle::normal();
+        // Registered fonts are searched before system fonts
+        let font = fm.match_fallback('A', &style).unwrap();
+        assert_eq!("Brand", font.name());
+        assert!(!font.has_color_glyphs());
+        // Unassigned char is not covered by any font
+        assert!(fm.match_fallback('\u{0378}', &style).is_none());
+        let style_key = StyleKey::new(&style);
+        assert!(fm.missing_chars.contains(&('\u{0378}', style_key)));
+        // Missing chars are cached per style
+        let bold = skia_safe::FontStyle::bold();
+        let bold_key = StyleKey::new(&bold);
+        assert!(!fm.missing_chars.contains(&('\u{0378}', bold_key)));
+        assert!(fm.match_fallback('\u{0378}', &bold).is_none());
+        assert!(fm.missing_chars.contains(&('\u{0378}', bold_key)));
+        // The index is built once and rebuilt after registering fonts
+        assert!(fm.fallback_index.is_some());
+        let data = include_bytes!("../../fonts/NotoSerif-Regular.ttf").to_vec();
+        let font = Font::from_bytes(data, 0, "Brand2".to_string()).unwrap();
+        fm.register_font("Brand2", font);
+        assert!(fm.fallback_index.is_none());
+        assert!(fm.missing_chars.is_empty());
+    }
+
+    #[test]
+    fn test_is_emoji() {
+        assert!(is_emoji('\u{1F600}'));
+        assert!(is_emoji('\u{2705}'));
+        assert!(is_emoji('\u{2B50}'));
+        // Text presentation by default
+        assert!(!is_emoji('\u{2764}'));
+        assert!(!is_emoji('\u{2318}'));
+        assert!(!is_emoji('\u{2B05}'));
+        assert!(!is_emoji('\u{1F100}'));
+        assert!(!is_emoji('A'));
+    }
 }
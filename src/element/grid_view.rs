@@ -0,0 +1,84 @@
+use crate as deft;
+use crate::element::list_view::{ItemFactory, ListView};
+use crate::element::{Element, ElementBackend, ScrollByOption};
+use deft_macros::{element_backend, js_methods};
+use quick_js::JsValue;
+
+/// Virtualized grid, every row of the underlying list view holds `columns` items
+#[element_backend]
+pub struct GridView {
+    base: ListView,
+}
+
+#[js_methods]
+impl GridView {
+    #[js_func]
+    pub fn set_columns(&mut self, columns: usize) {
+        self.base.set_columns(columns);
+    }
+
+    #[js_func]
+    pub fn get_columns(&self) -> usize {
+        self.base.get_columns()
+    }
+
+    #[js_func]
+    pub fn set_item_count(&mut self, count: usize) {
+        self.base.set_item_count(count);
+    }
+
+    #[js_func]
+    pub fn get_item_count(&self) -> usize {
+        self.base.get_item_count()
+    }
+
+    #[js_func]
+    pub fn set_item_factory(&mut self, create: JsValue, bind: JsValue) {
+        self.base.set_item_factory(create, bind);
+    }
+
+    pub fn set_factory<F: ItemFactory + 'static>(&mut self, factory: F) {
+        self.base.set_factory(factory);
+    }
+
+    #[js_func]
+    pub fn set_estimated_row_height(&mut self, height: f32) {
+        self.base.set_estimated_row_height(height);
+    }
+
+    #[js_func]
+    pub fn get_estimated_row_height(&self) -> f32 {
+        self.base.get_estimated_row_height()
+    }
+
+    #[js_func]
+    pub fn refresh(&mut self) {
+        self.base.refresh();
+    }
+
+    #[js_func]
+    pub fn scroll_to_item(&mut self, index: usize) {
+        self.base.scroll_to_item(index);
+    }
+
+    #[js_func]
+    pub fn scroll_by(&mut self, option: ScrollByOption) {
+        self.base.scroll_by(option);
+    }
+
+    #[js_func]
+    pub fn get_first_visible_item(&mut self) -> usize {
+        self.base.get_first_visible_item()
+    }
+}
+
+impl ElementBackend for GridView {
+    fn create(element: &mut Element) -> Self {
+        let base = ListView::create(element);
+        GridViewData { base }.to_ref()
+    }
+
+    fn get_base_mut(&mut self) -> Option<&mut dyn ElementBackend> {
+        Some(&mut self.base)
+    }
+}
@@ -3,21 +3,19 @@ use crate::base::Rect;
 use crate::element::{Element, ElementBackend, ElementWeak};
 use crate::event::TextUpdateEvent;
 use crate::mrc::Mrc;
-use crate::ok_or_return;
 use crate::render::RenderFn;
-use crate::style::StylePropKey;
-use crate::text::textbox::{TextBox, TextElement, TextUnit};
+use crate::style::{PropValueParse, StylePropKey};
+use crate::text::textbox::find::FindOptions;
+use crate::text::textbox::{TextBox, TextCoord, TextElement, TextUnit};
 use crate::text::TextAlign;
+use crate::{ok_or_return, some_or_return};
+use accesskit::{Node, Role};
 use deft_macros::{element_backend, js_methods};
 use yoga::Size;
 
+/// Parses a `text-align` value, falling back to `left` for invalid values
 pub fn parse_align(align: &str) -> TextAlign {
-    match align {
-        "left" => TextAlign::Left,
-        "right" => TextAlign::Right,
-        "center" => TextAlign::Center,
-        _ => TextAlign::Left,
-    }
+    TextAlign::parse_prop_value(align).unwrap_or(TextAlign::Left)
 }
 
 #[element_backend]
@@ -50,11 +48,54 @@ impl Label {
         }
     }
 
+    /// Set content with images and placeholders, which are represented by U+FFFC in text
+    #[js_func]
+    pub fn set_elements(&mut self, elements: Vec<TextElement>) {
+        let text = elements.iter().map(|e| e.text()).collect::<String>();
+        self.text = text.clone();
+        self.state.text_box.clear();
+        self.state.text_box.add_line(elements);
+        self.mark_dirty(true);
+
+        self.element.emit(TextUpdateEvent { value: text })
+    }
+
     #[js_func]
     pub fn get_text(&self) -> String {
         self.text.clone()
     }
 
+    /// Highlight occurrences of `query`, the first match is activated and scrolled into view
+    #[js_func]
+    pub fn find(
+        &mut self,
+        query: String,
+        options: Option<FindOptions>,
+    ) -> Vec<(TextCoord, TextCoord)> {
+        let matches = self
+            .state
+            .text_box
+            .find(&query, options.unwrap_or_default());
+        self.mark_dirty(false);
+        self.set_active_match(0);
+        matches
+    }
+
+    /// Activate the match at `index` and scroll it into view
+    #[js_func]
+    pub fn set_active_match(&mut self, index: usize) {
+        let rect = some_or_return!(self.state.text_box.set_active_match(index));
+        self.mark_dirty(false);
+        let element = ok_or_return!(self.element.upgrade());
+        element.scroll_rect_into_view(&rect);
+    }
+
+    #[js_func]
+    pub fn clear_find(&mut self) {
+        self.state.text_box.clear_find();
+        self.mark_dirty(false);
+    }
+
     fn mark_dirty(&mut self, layout_dirty: bool) {
         self.element.mark_dirty(layout_dirty);
     }
@@ -62,13 +103,7 @@ impl Label {
     fn build_text_unit(&self, text: String) -> TextUnit {
         TextUnit {
             text,
-            font_families: None,
-            font_size: None,
-            color: None,
-            text_decoration_line: None,
-            weight: None,
-            background_color: None,
-            style: None,
+            ..Default::default()
         }
     }
 }
@@ -139,6 +174,49 @@ impl ElementBackend for Label {
                 self.state.text_box.set_line_height(line_height);
                 self.mark_dirty(true);
             }
+            StylePropKey::TextShadow => {
+                let text_shadow = element.style.text_shadow.clone();
+                self.state.text_box.set_text_shadow(text_shadow);
+            }
+            StylePropKey::TextAlign => {
+                self.state.text_box.set_align(element.style.text_align);
+                self.mark_dirty(true);
+            }
+            StylePropKey::TextOverflow => {
+                let text_overflow = element.style.text_overflow;
+                self.state.text_box.set_text_overflow(text_overflow);
+                self.mark_dirty(true);
+            }
+            StylePropKey::WhiteSpace => {
+                let white_space = element.style.white_space;
+                self.state.text_box.set_white_space(white_space);
+                self.mark_dirty(true);
+            }
+            StylePropKey::WordBreak => {
+                let word_break = element.style.word_break;
+                self.state.text_box.set_word_break(word_break);
+                self.mark_dirty(true);
+            }
+            StylePropKey::LineClamp => {
+                let line_clamp = element.style.line_clamp;
+                self.state.text_box.set_line_clamp(line_clamp);
+                self.mark_dirty(true);
+            }
+            StylePropKey::LetterSpacing => {
+                let letter_spacing = element.style.letter_spacing;
+                self.state.text_box.set_letter_spacing(letter_spacing);
+                self.mark_dirty(true);
+            }
+            StylePropKey::TextTransform => {
+                let text_transform = element.style.text_transform;
+                self.state.text_box.set_text_transform(text_transform);
+                self.mark_dirty(true);
+            }
+            StylePropKey::Direction => {
+                let direction = element.style.direction;
+                self.state.text_box.set_direction(direction);
+                self.mark_dirty(true);
+            }
             _ => {}
         }
     }
@@ -157,6 +235,12 @@ impl ElementBackend for Label {
         self.state.layout_calculated = false;
     }
 
+    fn build_accessibility_node(&mut self, node: &mut Node) -> bool {
+        node.set_role(Role::Label);
+        node.set_value(self.text.clone());
+        false
+    }
+
     fn handle_origin_bounds_change(&mut self, bounds: &Rect) {
         if !self.state.layout_calculated {
             self.state.text_box.set_layout_width(bounds.width);
@@ -0,0 +1,722 @@
+use crate as deft;
+use crate::base::{Callback, Rect};
+use crate::element::container::Container;
+use crate::element::scroll::ScrollBarStrategy;
+use crate::element::{Element, ElementBackend, ElementWeak, ScrollByOption};
+use crate::event::ScrollEventListener;
+use crate::js::{FromJsValue, ToJsValue};
+use crate::number::DeNan;
+use crate::style::length::{Length, LengthOrPercent};
+use crate::style::{FixedStyleProp, StylePropVal};
+use crate::{ok_or_return, some_or_return};
+use deft_macros::{element_backend, js_methods};
+use log::error;
+use quick_js::JsValue;
+use std::mem;
+use yoga::FlexDirection;
+
+/// Rows materialized above and below the viewport
+const OVERSCAN_ROWS: usize = 3;
+const DEFAULT_ESTIMATED_ROW_HEIGHT: f32 = 24.0;
+
+/// Creates and binds the elements of the items of list views and grid views. Elements are
+/// recycled, so `bind` may be called many times on an element with different items.
+pub trait ItemFactory {
+    fn create(&mut self) -> Element;
+    fn bind(&mut self, element: &mut Element, index: usize);
+}
+
+/// Item factory backed by js functions, `create()` returns an element and `bind(element, index)`
+/// fills it with the content of the item
+pub struct JsItemFactory {
+    create: JsValue,
+    bind: JsValue,
+}
+
+impl JsItemFactory {
+    pub fn new(create: JsValue, bind: JsValue) -> Self {
+        Self { create, bind }
+    }
+}
+
+impl ItemFactory for JsItemFactory {
+    fn create(&mut self) -> Element {
+        let element = self
+            .create
+            .clone()
+            .call_as_function(vec![])
+            .map_err(|e| e.to_string())
+            .and_then(|v| Element::from_js_value(v).map_err(|e| e.to_string()));
+        match element {
+            Ok(element) => element,
+            Err(e) => {
+                error!("Error calling item factory: {}", e);
+                Element::create(Container::create)
+            }
+        }
+    }
+
+    fn bind(&mut self, element: &mut Element, index: usize) {
+        let element = ok_or_return!(element.clone().to_js_value());
+        let index = ok_or_return!(index.to_js_value());
+        if let Err(e) = self.bind.clone().call_as_function(vec![element, index]) {
+            error!("Error calling item binder: {}", e);
+        }
+    }
+}
+
+/// Heights of the rows, rows not measured yet take the average height of the measured rows
+struct RowHeights {
+    estimated: f32,
+    measured: Vec<Option<f32>>,
+    measured_sum: f32,
+    measured_count: usize,
+    /// Top of every row followed by the total height, rebuilt after heights changed
+    offsets: Vec<f32>,
+    offsets_dirty: bool,
+}
+
+impl RowHeights {
+    fn new(estimated: f32) -> Self {
+        Self {
+            estimated,
+            measured: Vec::new(),
+            measured_sum: 0.0,
+            measured_count: 0,
+            offsets: vec![0.0],
+            offsets_dirty: false,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.measured.len()
+    }
+
+    /// Change the number of rows, measured heights of the remaining rows are kept
+    fn set_len(&mut self, len: usize) {
+        if len == self.measured.len() {
+            return;
+        }
+        for height in self
+            .measured
+            .drain(len.min(self.measured.len())..)
+            .flatten()
+        {
+            self.measured_sum -= height;
+            self.measured_count -= 1;
+        }
+        self.measured.resize(len, None);
+        self.offsets_dirty = true;
+    }
+
+    fn set_estimated(&mut self, estimated: f32) {
+        self.estimated = estimated;
+        self.offsets_dirty = true;
+    }
+
+    fn clear(&mut self) {
+        self.measured.iter_mut().for_each(|h| *h = None);
+        self.measured_sum = 0.0;
+        self.measured_count = 0;
+        self.offsets_dirty = true;
+    }
+
+    /// Returns true if the height of the row changed
+    fn measure(&mut self, row: usize, height: f32) -> bool {
+        let old = some_or_return!(self.measured.get_mut(row), false);
+        if *old == Some(height) {
+            return false;
+        }
+        match old.replace(height) {
+            Some(old) => self.measured_sum -= old,
+            None => self.measured_count += 1,
+        }
+        self.measured_sum += height;
+        self.offsets_dirty = true;
+        true
+    }
+
+    fn get_default_height(&self) -> f32 {
+        if self.measured_count > 0 {
+            self.measured_sum / self.measured_count as f32
+        } else {
+            self.estimated
+        }
+    }
+
+    fn update_offsets(&mut self) {
+        if !self.offsets_dirty {
+            return;
+        }
+        let default_height = self.get_default_height();
+        let mut top = 0.0;
+        self.offsets.clear();
+        for height in &self.measured {
+            self.offsets.push(top);
+            top += height.unwrap_or(default_height);
+        }
+        self.offsets.push(top);
+        self.offsets_dirty = false;
+    }
+
+    /// Top of the row, `row` may be the row count to get the total height
+    fn get_offset(&mut self, row: usize) -> f32 {
+        self.update_offsets();
+        self.offsets[row.min(self.len())]
+    }
+
+    fn get_total_height(&mut self) -> f32 {
+        self.get_offset(self.len())
+    }
+
+    /// Returns the row at `y`, clamped to the last row
+    fn get_row_at(&mut self, y: f32) -> usize {
+        self.update_offsets();
+        let rows = &self.offsets[..self.len()];
+        rows.partition_point(|top| *top <= y).saturating_sub(1)
+    }
+}
+
+/// Materialized row, it's the element of the item in list views and a row of cells in grid views
+struct VisibleRow {
+    element: Element,
+    items: Vec<Element>,
+}
+
+/// Container of the rows, notifies the list view after the rows are laid out
+struct RowsContainer {
+    on_layout: Option<Box<dyn FnMut()>>,
+}
+
+impl ElementBackend for RowsContainer {
+    fn create(_element: &mut Element) -> Self {
+        Self { on_layout: None }
+    }
+
+    fn handle_origin_bounds_change(&mut self, _bounds: &Rect) {
+        if let Some(on_layout) = &mut self.on_layout {
+            on_layout();
+        }
+    }
+}
+
+/// Scrollable list of `item_count` items, only the items in the viewport are materialized by
+/// the item factory and their elements are recycled when scrolled out
+#[element_backend]
+pub struct ListView {
+    element: ElementWeak,
+    base: Container,
+    /// Holds a spacer for the rows above the viewport, the visible rows and a spacer for the rows
+    /// below the viewport
+    content: Element,
+    item_count: usize,
+    /// Items per row, only grid views have more than one column
+    columns: usize,
+    factory: Option<Box<dyn ItemFactory>>,
+    heights: RowHeights,
+    first_row: usize,
+    rows: Vec<VisibleRow>,
+    /// Elements of the items scrolled out, they are bound to other items later
+    recycled: Vec<Element>,
+    spacer_heights: (f32, f32),
+    viewport_height: f32,
+    /// Whether the layout of the visible rows is up to date
+    laid_out: bool,
+    updating: bool,
+    update_scheduled: bool,
+}
+
+#[js_methods]
+impl ListView {
+    #[js_func]
+    pub fn set_item_count(&mut self, count: usize) {
+        if self.item_count != count {
+            self.item_count = count;
+            self.update_rows(true);
+        }
+    }
+
+    #[js_func]
+    pub fn get_item_count(&self) -> usize {
+        self.item_count
+    }
+
+    /// Set the functions creating and binding the elements of items
+    #[js_func]
+    pub fn set_item_factory(&mut self, create: JsValue, bind: JsValue) {
+        self.set_factory(JsItemFactory::new(create, bind));
+    }
+
+    pub fn set_factory<F: ItemFactory + 'static>(&mut self, factory: F) {
+        self.remove_rows();
+        self.recycled.clear();
+        self.factory = Some(Box::new(factory));
+        self.heights.clear();
+        self.update_rows(false);
+    }
+
+    /// Height of the rows not measured yet
+    #[js_func]
+    pub fn set_estimated_row_height(&mut self, height: f32) {
+        self.heights.set_estimated(height);
+        self.update_rows(false);
+    }
+
+    #[js_func]
+    pub fn get_estimated_row_height(&self) -> f32 {
+        self.heights.estimated
+    }
+
+    /// Bind the visible items again after the data changed, measured heights are dropped
+    #[js_func]
+    pub fn refresh(&mut self) {
+        self.heights.clear();
+        self.update_rows(true);
+    }
+
+    /// Scroll the least distance to make the item visible
+    #[js_func]
+    pub fn scroll_to_item(&mut self, index: usize) {
+        let el = ok_or_return!(self.element.upgrade());
+        if index >= self.item_count {
+            return;
+        }
+        let row = index / self.columns;
+        let top = self.heights.get_offset(row);
+        let height = self.heights.get_offset(row + 1) - top;
+        el.scroll_rect_into_view(&Rect::new(0.0, top, 1.0, height));
+        self.update_rows(false);
+    }
+
+    /// Scroll by the offsets, the rows in the new viewport are materialized immediately
+    #[js_func]
+    pub fn scroll_by(&mut self, option: ScrollByOption) {
+        let mut el = ok_or_return!(self.element.upgrade());
+        el.scroll_by(option);
+        self.update_rows(false);
+    }
+
+    /// Returns the index of the first visible item
+    #[js_func]
+    pub fn get_first_visible_item(&mut self) -> usize {
+        let el = ok_or_return!(self.element.upgrade(), 0);
+        self.heights.get_row_at(el.get_scroll_top()) * self.columns
+    }
+
+    pub(crate) fn set_columns(&mut self, columns: usize) {
+        let columns = columns.max(1);
+        if self.columns != columns {
+            self.columns = columns;
+            self.remove_rows();
+            self.heights.clear();
+            self.update_rows(false);
+        }
+    }
+
+    pub(crate) fn get_columns(&self) -> usize {
+        self.columns
+    }
+
+    fn get_row_count(&self) -> usize {
+        if self.factory.is_none() {
+            return 0;
+        }
+        self.item_count.div_ceil(self.columns)
+    }
+
+    /// Materialize the rows in the viewport and recycle the others, the visible rows are bound
+    /// again if `rebind` is true
+    fn update_rows(&mut self, rebind: bool) {
+        if self.updating {
+            return;
+        }
+        let mut el = ok_or_return!(self.element.upgrade());
+        self.updating = true;
+        let row_count = self.get_row_count();
+        self.heights.set_len(row_count);
+
+        // Keep the first visible row in place when the heights above it changed
+        if let Some((row, delta)) = self.find_anchor(el.get_scroll_top()) {
+            let anchored_top = self.heights.get_offset(row) + delta;
+            if (anchored_top - el.get_scroll_top()).abs() >= 0.5 {
+                el.set_scroll_top(anchored_top);
+            }
+        }
+        let scroll_top = el.get_scroll_top();
+        let viewport_height = el.get_content_bounds().height.nan_to_zero();
+        let end = (self.heights.get_row_at(scroll_top + viewport_height) + 1 + OVERSCAN_ROWS)
+            .min(row_count);
+        let first = self
+            .heights
+            .get_row_at(scroll_top)
+            .saturating_sub(OVERSCAN_ROWS)
+            .min(end);
+
+        let old_range = (self.first_row, self.rows.len());
+        while self.first_row < first && !self.rows.is_empty() {
+            let row = self.rows.remove(0);
+            self.content.remove_child(1).unwrap();
+            self.recycle_row(row);
+            self.first_row += 1;
+        }
+        while self.first_row + self.rows.len() > end && !self.rows.is_empty() {
+            let row = self.rows.pop().unwrap();
+            self.content
+                .remove_child(self.rows.len() as u32 + 1)
+                .unwrap();
+            self.recycle_row(row);
+        }
+        if self.rows.is_empty() {
+            self.first_row = first;
+        }
+        if rebind {
+            let mut rows = mem::take(&mut self.rows);
+            for (i, row) in rows.iter_mut().enumerate() {
+                self.bind_row(row, self.first_row + i);
+            }
+            self.rows = rows;
+        }
+        while self.first_row > first {
+            self.first_row -= 1;
+            let row = self.build_row(self.first_row);
+            self.content.add_child(row.element.clone(), 1).unwrap();
+            self.rows.insert(0, row);
+        }
+        while self.first_row + self.rows.len() < end {
+            let row = self.build_row(self.first_row + self.rows.len());
+            let position = self.rows.len() as i32 + 1;
+            self.content
+                .add_child(row.element.clone(), position)
+                .unwrap();
+            self.rows.push(row);
+        }
+        if (self.first_row, self.rows.len()) != old_range {
+            self.laid_out = false;
+        }
+
+        let top = self.heights.get_offset(first);
+        let bottom = self.heights.get_total_height() - self.heights.get_offset(end);
+        if self.spacer_heights != (top, bottom) {
+            self.spacer_heights = (top, bottom);
+            let last = self.content.get_children().len() as u32 - 1;
+            self.content.remove_child(last).unwrap();
+            self.content.add_child(build_spacer(bottom), -1).unwrap();
+            self.content.remove_child(0).unwrap();
+            self.content.add_child(build_spacer(top), 0).unwrap();
+        }
+        self.updating = false;
+    }
+
+    /// Returns the first row in the viewport and the distance from its top to the top of the
+    /// viewport, rows are not anchored until laid out
+    fn find_anchor(&self, scroll_top: f32) -> Option<(usize, f32)> {
+        if !self.laid_out {
+            return None;
+        }
+        self.rows.iter().enumerate().find_map(|(i, row)| {
+            let bounds = row.element.get_bounds();
+            if bounds.bottom() > scroll_top {
+                Some((self.first_row + i, scroll_top - bounds.y))
+            } else {
+                None
+            }
+        })
+    }
+
+    fn build_row(&mut self, row: usize) -> VisibleRow {
+        let mut visible_row = if self.columns == 1 {
+            let item = self.take_item();
+            VisibleRow {
+                element: item.clone(),
+                items: vec![item],
+            }
+        } else {
+            let mut element = Element::create(Container::create);
+            element.tag = "grid-view-row".to_string();
+            element.set_style_props(vec![FixedStyleProp::FlexDirection(StylePropVal::Custom(
+                FlexDirection::Row,
+            ))]);
+            VisibleRow {
+                element,
+                items: Vec::new(),
+            }
+        };
+        self.bind_row(&mut visible_row, row);
+        visible_row
+    }
+
+    fn bind_row(&mut self, visible_row: &mut VisibleRow, row: usize) {
+        let start = row * self.columns;
+        let end = (start + self.columns).min(self.item_count);
+        if self.columns > 1 {
+            // The last row of grids may have less items
+            let count = end.saturating_sub(start);
+            while visible_row.items.len() > count {
+                let item = visible_row.items.pop().unwrap();
+                let mut cell = item.get_parent().unwrap();
+                cell.remove_child(0).unwrap();
+                visible_row
+                    .element
+                    .remove_child(visible_row.items.len() as u32)
+                    .unwrap();
+                self.recycled.push(item);
+            }
+            while visible_row.items.len() < count {
+                let item = self.take_item();
+                let cell = build_cell(item.clone(), self.columns);
+                visible_row.element.add_child(cell, -1).unwrap();
+                visible_row.items.push(item);
+            }
+        }
+        let factory = some_or_return!(self.factory.as_mut());
+        for (i, item) in visible_row.items.iter_mut().enumerate() {
+            factory.bind(item, start + i);
+        }
+    }
+
+    fn take_item(&mut self) -> Element {
+        if let Some(item) = self.recycled.pop() {
+            return item;
+        }
+        match self.factory.as_mut() {
+            Some(factory) => factory.create(),
+            None => Element::create(Container::create),
+        }
+    }
+
+    fn recycle_row(&mut self, row: VisibleRow) {
+        if self.columns > 1 {
+            for item in &row.items {
+                if let Some(mut cell) = item.get_parent() {
+                    cell.remove_child(0).unwrap();
+                }
+            }
+        }
+        self.recycled.extend(row.items);
+    }
+
+    /// Recycle all rows, e.g. when their structure changed
+    fn remove_rows(&mut self) {
+        while let Some(row) = self.rows.pop() {
+            self.content
+                .remove_child(self.rows.len() as u32 + 1)
+                .unwrap();
+            self.recycle_row(row);
+        }
+    }
+
+    fn on_rows_laid_out(&mut self) {
+        let el = ok_or_return!(self.element.upgrade());
+        self.laid_out = true;
+        let heights: Vec<f32> = self
+            .rows
+            .iter()
+            .map(|r| r.element.get_bounds().height)
+            .collect();
+        let mut changed = false;
+        for (i, height) in heights.into_iter().enumerate() {
+            changed |= self.heights.measure(self.first_row + i, height);
+        }
+        let viewport_height = el.get_content_bounds().height.nan_to_zero();
+        if changed || viewport_height != self.viewport_height {
+            self.viewport_height = viewport_height;
+            self.schedule_update();
+        }
+    }
+
+    /// Rows should not be changed during layout, so they are updated before the next frame
+    fn schedule_update(&mut self) {
+        if self.update_scheduled {
+            return;
+        }
+        let el = ok_or_return!(self.element.upgrade());
+        let window = some_or_return!(el.get_window());
+        let mut window = ok_or_return!(window.upgrade_mut());
+        self.update_scheduled = true;
+        let weak = self.as_weak();
+        window.request_next_frame_callback(Callback::new(move || {
+            let mut list_view = ok_or_return!(weak.upgrade());
+            list_view.update_scheduled = false;
+            list_view.update_rows(false);
+        }));
+    }
+}
+
+fn build_spacer(height: f32) -> Element {
+    let mut spacer = Element::create(Container::create);
+    spacer.set_style_props(vec![FixedStyleProp::Height(StylePropVal::Custom(
+        LengthOrPercent::Length(Length::PX(height)),
+    ))]);
+    spacer
+}
+
+fn build_cell(item: Element, columns: usize) -> Element {
+    let mut cell = Element::create(Container::create);
+    cell.tag = "grid-view-cell".to_string();
+    cell.set_style_props(vec![FixedStyleProp::Width(StylePropVal::Custom(
+        LengthOrPercent::Percent(100.0 / columns as f32),
+    ))]);
+    cell.add_child(item, 0).unwrap();
+    cell
+}
+
+impl ElementBackend for ListView {
+    fn create(element: &mut Element) -> Self {
+        let base = Container::create(element);
+        element.set_scroll_y(ScrollBarStrategy::Auto);
+        let mut content = Element::create(RowsContainer::create);
+        content.set_style_props(vec![FixedStyleProp::FlexShrink(StylePropVal::Custom(0.0))]);
+        content.add_child(build_spacer(0.0), 0).unwrap();
+        content.add_child(build_spacer(0.0), 1).unwrap();
+        element.add_child(content.clone(), 0).unwrap();
+
+        let mut list_view = ListViewData {
+            element: element.as_weak(),
+            base,
+            content: content.clone(),
+            item_count: 0,
+            columns: 1,
+            factory: None,
+            heights: RowHeights::new(DEFAULT_ESTIMATED_ROW_HEIGHT),
+            first_row: 0,
+            rows: Vec::new(),
+            recycled: Vec::new(),
+            spacer_heights: (0.0, 0.0),
+            viewport_height: 0.0,
+            laid_out: false,
+            updating: false,
+            update_scheduled: false,
+        }
+        .to_ref();
+        let weak = list_view.as_weak();
+        content.get_backend_mut_as::<RowsContainer>().on_layout = Some(Box::new(move || {
+            let mut list_view = ok_or_return!(weak.upgrade());
+            list_view.on_rows_laid_out();
+        }));
+        // Wheel, touch momentum and scroll_by all end up here
+        let weak = list_view.as_weak();
+        element.register_event_listener(ScrollEventListener::new(move |_e, ctx| {
+            let mut list_view = ok_or_return!(weak.upgrade());
+            if ctx.target == list_view.element {
+                list_view.update_rows(false);
+            }
+        }));
+        list_view
+    }
+
+    fn get_base_mut(&mut self) -> Option<&mut dyn ElementBackend> {
+        Some(&mut self.base)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::element::body::Body;
+    use crate::element::container::Container;
+    use crate::element::list_view::{ItemFactory, ListView, RowHeights};
+    use crate::element::{Element, ElementBackend, ScrollByOption};
+    use crate::ext::ext_window::WindowAttrs;
+    use crate::window::Window;
+    use quick_js::JsValue;
+
+    #[test]
+    fn test_row_heights() {
+        let mut heights = RowHeights::new(10.0);
+        heights.set_len(100);
+        assert_eq!(1000.0, heights.get_total_height());
+        assert_eq!(5, heights.get_row_at(55.0));
+        assert_eq!(99, heights.get_row_at(5000.0));
+
+        // Rows not measured take the average height of measured rows
+        assert!(heights.measure(0, 30.0));
+        assert!(heights.measure(1, 10.0));
+        assert!(!heights.measure(1, 10.0));
+        assert_eq!(2000.0, heights.get_total_height());
+        assert_eq!(30.0, heights.get_offset(1));
+        assert_eq!(0, heights.get_row_at(29.0));
+        assert_eq!(1, heights.get_row_at(30.0));
+        assert_eq!(2, heights.get_row_at(40.0));
+
+        heights.set_len(3);
+        assert_eq!(60.0, heights.get_total_height());
+        heights.clear();
+        assert_eq!(30.0, heights.get_total_height());
+    }
+
+    struct TestFactory;
+
+    impl ItemFactory for TestFactory {
+        fn create(&mut self) -> Element {
+            let mut element = Element::create(Container::create);
+            element.set_style(JsValue::String("height: 20px".to_string()));
+            element
+        }
+
+        fn bind(&mut self, element: &mut Element, index: usize) {
+            element.set_attribute("index".to_string(), index.to_string());
+        }
+    }
+
+    #[test]
+    fn test_materialize_visible_rows() {
+        let el = Element::create(ListView::create);
+        let mut list_view = el.get_backend_as::<ListView>().clone();
+        list_view.set_factory(TestFactory);
+        list_view.set_item_count(100_000);
+        // Not laid out yet, so only the first row and the rows of overscan are created
+        assert_eq!(4, list_view.rows.len());
+        assert_eq!(
+            Some("3".to_string()),
+            list_view.rows[3].element.get_attribute("index".to_string())
+        );
+
+        list_view.set_columns(3);
+        assert_eq!(4, list_view.rows.len());
+        assert_eq!(3, list_view.rows[0].items.len());
+        assert!(list_view.recycled.is_empty());
+        list_view.set_item_count(10);
+        assert_eq!(1, list_view.rows[3].items.len());
+        assert_eq!(2, list_view.recycled.len());
+    }
+
+    #[test]
+    fn test_scroll_recycles_rows() {
+        let handle = Window::create(WindowAttrs {
+            width: Some(100.0),
+            height: Some(100.0),
+            headless: Some(true),
+            ..Default::default()
+        })
+        .unwrap();
+        let mut body = Element::create(Body::create);
+        let mut el = Element::create(ListView::create);
+        el.set_style(JsValue::String("height: 100px".to_string()));
+        body.add_child(el.clone(), 0).unwrap();
+        handle.upgrade_mut().unwrap().set_body(body).unwrap();
+        let mut list_view = el.get_backend_as::<ListView>().clone();
+        list_view.set_factory(TestFactory);
+        list_view.set_item_count(1000);
+        // Rows are measured in the first frame and the viewport is filled in the next one
+        for _ in 0..3 {
+            handle.upgrade_mut().unwrap().update();
+        }
+        assert_eq!(0, list_view.first_row);
+        let eids = list_view
+            .rows
+            .iter()
+            .map(|r| r.element.get_eid())
+            .collect::<Vec<_>>();
+
+        list_view.scroll_by(ScrollByOption { x: 0.0, y: 400.0 });
+        assert_eq!(400.0, el.get_scroll_top());
+        // Row 20 is at the top of the viewport, 3 rows are kept above it
+        assert_eq!(17, list_view.first_row);
+        let new_eids = list_view
+            .rows
+            .iter()
+            .map(|r| r.element.get_eid())
+            .collect::<Vec<_>>();
+        assert!(eids.iter().all(|eid| new_eids.contains(eid)));
+        assert!(list_view.recycled.is_empty());
+    }
+}
@@ -19,6 +19,7 @@ use crate::render::RenderFn;
 use crate::string::StringUtils;
 use crate::style::color::parse_optional_color_str;
 use crate::style::font::FontStyle;
+use crate::style::text::{TextOverflow, TextTransform, WhiteSpace, WordBreak};
 use crate::style::{PropValueParse, StylePropKey};
 use crate::text::textbox::{TextCoord, TextUnit};
 use crate::text::{TextAlign, TextDecoration, TextStyle};
@@ -31,7 +32,7 @@ use skia_safe::wrapper::NativeTransmutableWrapper;
 use skia_safe::{Color, Paint};
 use std::any::Any;
 use std::str::FromStr;
-use yoga::{Context, MeasureMode, Node, NodeRef, Size};
+use yoga::{Context, Direction, MeasureMode, Node, NodeRef, Size};
 
 const ZERO_WIDTH_WHITESPACE: &str = "\u{200B}";
 
@@ -46,6 +47,14 @@ pub struct ParagraphParams {
     pub font_families: FontFamilies,
     pub font_weight: Weight,
     pub font_style: FontStyle,
+    pub white_space: WhiteSpace,
+    pub word_break: WordBreak,
+    pub text_overflow: TextOverflow,
+    /// Default letter spacing of text units
+    pub letter_spacing: f32,
+    pub text_transform: TextTransform,
+    /// Base direction of the paragraph
+    pub direction: Direction,
 }
 
 pub fn parse_optional_weight(value: Option<&String>) -> Option<Weight> {
@@ -1,15 +1,15 @@
 use crate::element::font_manager::FontManager;
 use crate::element::paragraph::ParagraphParams;
 use crate::element::text::simple_text_paragraph::{
-    chars_to_glyphs_vec, SimpleTextParagraph, TextBlock,
+    chars_to_glyphs_vec, InlineObject, SimpleTextParagraph, TextBlock,
 };
-use crate::font::Font;
-use crate::mrc::Mrc;
+use crate::font::{is_emoji_modifier, Font};
 use crate::some_or_continue;
 use crate::string::StringUtils;
 use crate::text::TextStyle;
 use log::warn;
-use std::collections::HashMap;
+
+pub const OBJECT_REPLACEMENT_CHARACTER: &str = "\u{FFFC}";
 
 thread_local! {
     pub static FONT_MANAGER: FontManager = FontManager::new();
@@ -20,7 +20,6 @@ pub struct SimpleParagraphBuilder {
     styles: Vec<TextStyle>,
     text_blocks: Vec<TextBlock>,
     font_manager: FontManager,
-    fallback_cache: Mrc<HashMap<char, Option<Font>>>,
 }
 
 impl SimpleParagraphBuilder {
@@ -33,7 +32,6 @@ impl SimpleParagraphBuilder {
             styles: vec![text_style],
             text_blocks: Vec::new(),
             font_manager: FONT_MANAGER.with(|fm| fm.clone()),
-            fallback_cache: Mrc::new(HashMap::new()),
         }
     }
 
@@ -58,6 +56,39 @@ impl SimpleParagraphBuilder {
         self.text_blocks.append(&mut text_blocks);
     }
 
+    /// Add an object taking one char, the font of current style is used to align it with text
+    pub fn add_inline_object(&mut self, object: InlineObject) {
+        let style = self.styles.last().unwrap().clone();
+        let font_families = style
+            .font_families()
+            .as_ref()
+            .unwrap_or(&self.paragraph_params.font_families);
+        let font_families_names = font_families
+            .as_slice()
+            .iter()
+            .map(|it| it.name())
+            .collect::<Vec<_>>();
+        let font = match self
+            .font_manager
+            .match_best(&font_families_names, style.font_style())
+            .into_iter()
+            .next()
+        {
+            Some(f) => f,
+            None => {
+                warn!("No matching font found for {:?}", &font_families_names);
+                return;
+            }
+        };
+        self.text_blocks.push(TextBlock {
+            text: OBJECT_REPLACEMENT_CHARACTER.to_string(),
+            style,
+            font,
+            inline_object: Some(object),
+            level: 0,
+        });
+    }
+
     fn resolve_font(
         &self,
         font_families_names: &Vec<&str>,
@@ -75,38 +106,23 @@ impl SimpleParagraphBuilder {
         let chars = text.chars().collect::<Vec<_>>();
         let (mut resolved_typefaces, _unresolved_count) = Self::do_resolve_font(&chars, &fonts);
         for i in 0..chars.len() {
-            if resolved_typefaces[i] != -1 {
+            let ch = chars[i];
+            // Joiners and selectors of sequences are shaped with the char before them
+            if i > 0 && is_emoji_modifier(ch) && resolved_typefaces[i - 1] != -1 {
+                resolved_typefaces[i] = resolved_typefaces[i - 1];
                 continue;
             }
-            let ch = chars[i];
-            if ch == '\n' {
+            if resolved_typefaces[i] != -1 || ch == '\n' {
                 continue;
             }
-            let cached_tf = match self.fallback_cache.get(&ch) {
-                Some(tf) => tf,
+            let tf = some_or_continue!(self.font_manager.match_fallback(ch, style.font_style()));
+            resolved_typefaces[i] = match fonts.iter().position(|f| *f == tf) {
+                Some(tf_idx) => tf_idx as i32,
                 None => {
-                    //TODO fix locale
-                    // let tf = self.font_collection.default_fallback_char(chars[i] as Unichar, style.font_style(), "en");
-                    let tf = None;
-                    self.fallback_cache.clone().insert(ch, tf);
-                    self.fallback_cache.get(&ch).unwrap()
+                    fonts.push(tf);
+                    (fonts.len() - 1) as i32
                 }
             };
-
-            let tf = some_or_continue!(cached_tf);
-            //TODO fix equal check
-            /*
-            for tf_idx in 0..fonts.len() {
-                if fonts[tf_idx] == tf {
-                    resolved_typefaces[i] = tf_idx as i32;
-                    break;
-                }
-            }
-             */
-            if resolved_typefaces[i] == -1 {
-                fonts.push(tf.clone());
-                resolved_typefaces[i] = (fonts.len() - 1) as i32;
-            }
         }
         for i in 0..resolved_typefaces.len() {
             if resolved_typefaces[i] == -1 {
@@ -128,13 +144,15 @@ impl SimpleParagraphBuilder {
                 text: text.substring(begin, end - begin).to_string(),
                 style: style.clone(),
                 font,
+                inline_object: None,
+                level: 0,
             });
         }
         text_blocks
     }
 
     pub fn build(self) -> SimpleTextParagraph {
-        SimpleTextParagraph::new(self.text_blocks, self.paragraph_params.line_height)
+        SimpleTextParagraph::new(self.text_blocks, &self.paragraph_params)
     }
 
     fn do_resolve_font(chars: &Vec<char>, fonts: &Vec<Font>) -> (Vec<i32>, usize) {
@@ -163,8 +181,11 @@ mod tests {
     use crate::element::paragraph::{ParagraphParams, ZERO_WIDTH_WHITESPACE};
     use crate::font::family::{FontFamilies, FontFamily};
     use crate::style::font::FontStyle;
+    use crate::style::text::{TextOverflow, TextTransform, WhiteSpace, WordBreak};
+    use crate::text::TextAlign;
     use measure_time::print_time;
     use skia_safe::font_style::Weight;
+    use yoga::Direction;
 
     #[test]
     fn test_performance() {
@@ -179,6 +200,12 @@ mod tests {
                 font_weight: Weight::NORMAL,
                 font_style: FontStyle::Normal,
                 mask_char: None,
+                white_space: WhiteSpace::Normal,
+                word_break: WordBreak::Normal,
+                text_overflow: TextOverflow::Clip,
+                letter_spacing: 0.0,
+                text_transform: TextTransform::None,
+                direction: Direction::LTR,
             };
             let mut pb = SimpleParagraphBuilder::new(&params);
             let str = include_str!("../../../Cargo.lock");
@@ -202,6 +229,12 @@ mod tests {
             font_weight: Weight::NORMAL,
             font_style: FontStyle::Normal,
             mask_char: None,
+            white_space: WhiteSpace::Normal,
+            word_break: WordBreak::Normal,
+            text_overflow: TextOverflow::Clip,
+            letter_spacing: 0.0,
+            text_transform: TextTransform::None,
+            direction: Direction::LTR,
         };
         let mut pb = SimpleParagraphBuilder::new(&params);
         pb.add_text(format!("{}{}", "12", ZERO_WIDTH_WHITESPACE));
@@ -217,4 +250,46 @@ mod tests {
         assert!(bounds1.left >= bounds0.right);
         assert!(paragraph.max_intrinsic_width() > 0.0);
     }
+
+    #[test]
+    fn test_bidi_layout() {
+        let params = ParagraphParams {
+            text_wrap: None,
+            line_height: None,
+            align: TextAlign::Start,
+            color: Default::default(),
+            font_size: 14.0,
+            font_families: FontFamilies::new(vec![FontFamily::new("monospace")]),
+            font_weight: Weight::NORMAL,
+            font_style: FontStyle::Normal,
+            mask_char: None,
+            white_space: WhiteSpace::Normal,
+            word_break: WordBreak::Normal,
+            text_overflow: TextOverflow::Clip,
+            letter_spacing: 0.0,
+            text_transform: TextTransform::None,
+            direction: Direction::RTL,
+        };
+        let mut pb = SimpleParagraphBuilder::new(&params);
+        pb.add_text(format!(
+            "{}{}",
+            "ab \u{05D0}\u{05D1}", ZERO_WIDTH_WHITESPACE
+        ));
+        let mut paragraph = pb.build();
+        paragraph.layout(200.0);
+        let layout = paragraph.layout.as_ref().unwrap();
+        assert!(!layout.is_char_rtl(0));
+        assert!(layout.is_char_rtl(3));
+        let a = layout.get_char_bounds(0).unwrap();
+        let b = layout.get_char_bounds(1).unwrap();
+        let alef = layout.get_char_bounds(3).unwrap();
+        let bet = layout.get_char_bounds(4).unwrap();
+        // The latin run keeps its order and starts the right-to-left line on the right
+        assert!(b.left >= a.right);
+        assert!((b.right - 200.0).abs() < 0.01);
+        assert!(a.left >= alef.right);
+        assert!(alef.left >= bet.right);
+        let a_center = (a.left + a.right) / 2.0;
+        assert_eq!(0, paragraph.get_char_offset_at_coordinate((a_center, 1.0)));
+    }
 }
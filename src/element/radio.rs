@@ -8,6 +8,7 @@ use crate::event::{ClickEvent, Event};
 use crate::style::length::LengthOrPercent;
 use crate::style::{FixedStyleProp, ResolvedStyleProp, StylePropVal};
 use crate::{ok_or_return, some_or_return};
+use accesskit::{Action, Node, Role, Toggled};
 use deft_macros::{element_backend, event, js_methods};
 use std::collections::HashMap;
 use yoga::{Align, Display, FlexDirection};
@@ -169,6 +170,18 @@ impl ElementBackend for Radio {
             _ => self.base.on_attribute_changed(key, value),
         }
     }
+
+    fn build_accessibility_node(&mut self, node: &mut Node) -> bool {
+        node.set_role(Role::RadioButton);
+        node.set_label(self.get_label());
+        node.set_toggled(if self.checked {
+            Toggled::True
+        } else {
+            Toggled::False
+        });
+        node.add_action(Action::Click);
+        false
+    }
 }
 
 #[element_backend]
@@ -189,4 +202,9 @@ impl ElementBackend for RadioGroup {
     fn get_base_mut(&mut self) -> Option<&mut dyn ElementBackend> {
         Some(&mut self.base)
     }
+
+    fn build_accessibility_node(&mut self, node: &mut Node) -> bool {
+        node.set_role(Role::RadioGroup);
+        true
+    }
 }
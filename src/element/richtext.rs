@@ -1,11 +1,13 @@
 use crate as deft;
-use crate::base::EventContext;
+use crate::base::{EventContext, Rect};
 use crate::element::{Element, ElementBackend, ElementWeak};
-use crate::event::Event;
-use crate::ok_or_return;
+use crate::event::{ClickEvent, Event, LinkClickEvent};
 use crate::render::RenderFn;
 use crate::style::StylePropKey;
+use crate::text::textbox::decoration::DecorationRange;
+use crate::text::textbox::find::FindOptions;
 use crate::text::textbox::{TextBox, TextCoord, TextElement};
+use crate::{ok_or_return, some_or_return};
 use deft_macros::{element_backend, js_methods};
 use yoga::Size;
 
@@ -52,11 +54,59 @@ impl RichText {
         self.text_box.get_text_coord_by_char_offset(caret)
     }
 
+    /// Bounds of the atom at (row, col) relative to the element, used to place widgets over placeholders
+    #[js_func]
+    pub fn get_char_rect(&mut self, row: usize, col: usize) -> Option<Rect> {
+        self.text_box.get_char_rect(TextCoord(row, col))
+    }
+
     #[js_func]
     pub fn get_selection_text(&self) -> Option<String> {
         self.text_box.get_selection_text()
     }
 
+    /// Decorate chars between `start` and `end` of the decoration, the offsets are not updated
+    /// when lines are inserted or deleted and [`RichText::clear`] removes all decorations
+    #[js_func]
+    pub fn add_decoration(&mut self, decoration: DecorationRange) {
+        self.text_box.add_decoration(decoration);
+    }
+
+    #[js_func]
+    pub fn remove_decorations(&mut self, group: Option<String>) {
+        self.text_box.remove_decorations(group.as_deref());
+    }
+
+    #[js_func]
+    pub fn get_decorations(&self) -> Vec<DecorationRange> {
+        self.text_box.get_decorations().clone()
+    }
+
+    /// Highlight occurrences of `query`, the first match is activated and scrolled into view
+    #[js_func]
+    pub fn find(
+        &mut self,
+        query: String,
+        options: Option<FindOptions>,
+    ) -> Vec<(TextCoord, TextCoord)> {
+        let matches = self.text_box.find(&query, options.unwrap_or_default());
+        self.set_active_match(0);
+        matches
+    }
+
+    /// Activate the match at `index` and scroll it into view
+    #[js_func]
+    pub fn set_active_match(&mut self, index: usize) {
+        let rect = some_or_return!(self.text_box.set_active_match(index));
+        let element = ok_or_return!(self.element.upgrade());
+        element.scroll_rect_into_view(&rect);
+    }
+
+    #[js_func]
+    pub fn clear_find(&mut self) {
+        self.text_box.clear_find();
+    }
+
     fn layout(&mut self, width: f32) {
         //TODO twice layout occurs here?
         self.text_box.set_layout_width(width);
@@ -128,6 +178,36 @@ impl ElementBackend for RichText {
             StylePropKey::LineHeight => {
                 self.text_box.set_line_height(element.style.line_height);
             }
+            StylePropKey::TextShadow => {
+                self.text_box
+                    .set_text_shadow(element.style.text_shadow.clone());
+            }
+            StylePropKey::TextAlign => {
+                self.text_box.set_align(element.style.text_align);
+            }
+            StylePropKey::TextOverflow => {
+                self.text_box.set_text_overflow(element.style.text_overflow);
+            }
+            StylePropKey::WhiteSpace => {
+                self.text_box.set_white_space(element.style.white_space);
+            }
+            StylePropKey::WordBreak => {
+                self.text_box.set_word_break(element.style.word_break);
+            }
+            StylePropKey::LineClamp => {
+                self.text_box.set_line_clamp(element.style.line_clamp);
+            }
+            StylePropKey::LetterSpacing => {
+                self.text_box
+                    .set_letter_spacing(element.style.letter_spacing);
+            }
+            StylePropKey::TextTransform => {
+                self.text_box
+                    .set_text_transform(element.style.text_transform);
+            }
+            StylePropKey::Direction => {
+                self.text_box.set_direction(element.style.direction);
+            }
             _ => {}
         }
     }
@@ -137,6 +217,12 @@ impl ElementBackend for RichText {
     }
 
     fn on_event(&mut self, event: &mut Event, ctx: &mut EventContext<ElementWeak>) {
+        if let Some(e) = ClickEvent::cast(event) {
+            let coord = (e.0.offset_x, e.0.offset_y);
+            if let Some(url) = self.text_box.get_link_by_pixel_coord(coord) {
+                self.element.emit(LinkClickEvent { url });
+            }
+        }
         self.text_box.on_event(&event, ctx, 0.0, 0.0);
     }
 }
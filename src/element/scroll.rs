@@ -9,6 +9,7 @@ use crate::js::FromJsValue;
 use crate::render::RenderFn;
 use crate::style::ResolvedStyleProp;
 use crate::{backend_as_api, ok_or_return};
+use accesskit::{Node, Role};
 use bezier_rs::{Bezier, TValue};
 use deft_macros::{element_backend, js_methods};
 use log::debug;
@@ -355,6 +356,11 @@ impl ElementBackend for Scroll {
         let element = ok_or_return!(self.element.upgrade());
         element.clone().scrollable.on_event(&event, ctx, &element);
     }
+
+    fn build_accessibility_node(&mut self, node: &mut Node) -> bool {
+        node.set_role(Role::ScrollView);
+        true
+    }
 }
 
 pub fn calculate_speed(distance: f32, duration: f32) -> f32 {
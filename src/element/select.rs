@@ -1,28 +1,44 @@
 use crate as deft;
-use crate::base::{EventContext, Rect};
+use crate::base::{Callback, EventContext, Rect};
 use crate::canvas_util::CanvasHelper;
 use crate::element::common::editable::Editable;
 use crate::element::common::image_object::ImageObject;
 use crate::element::container::Container;
 use crate::element::label::Label;
+use crate::element::list_view::{ItemFactory, ListView};
+use crate::element::textinput::TextInput;
 use crate::element::{Element, ElementBackend, ElementWeak};
-use crate::event::{ClickEvent, ClickEventListener, Event};
-use crate::mrc::Mrc;
+use crate::event::{
+    ClickEvent, ClickEventListener, Event, KeyDownEvent, KeyDownEventListener, KeyEventDetail,
+    TextChangeEventListener, KEY_MOD_ALT, KEY_MOD_CTRL, KEY_MOD_META,
+};
 use crate::render::RenderFn;
 use crate::style::length::{Length, LengthOrPercent};
 use crate::style::{FixedStyleProp, ResolvedStyleProp, StylePropKey, StylePropVal};
 use crate::text::textbox::TextBox;
 use crate::window::popup::Popup;
 use crate::{js_deserialize, js_serialize, ok_or_return, some_or_return};
+use accesskit::{Action, ActionData, ActionRequest, Node, Role};
 use deft_macros::{element_backend, event, js_methods};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::ops::DerefMut;
+use winit::keyboard::NamedKey;
+use yoga::Display;
+
+/// Rows visible in the option list at most, only these rows and a few around them are created
+const MAX_VISIBLE_ROWS: usize = 10;
+/// Height of the rows in the option list in `em`
+const ROW_HEIGHT_EM: f32 = 2.0;
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct SelectOption {
     value: String,
     label: String,
+    /// Consecutive options of the same group are shown under a header of the group
+    #[serde(default)]
+    group: Option<String>,
+    #[serde(default)]
+    disabled: bool,
 }
 
 js_serialize!(SelectOption);
@@ -31,45 +47,100 @@ js_deserialize!(SelectOption);
 #[event]
 pub struct ChangeEvent {}
 
+/// Row of the option list
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum OptionRow {
+    /// Header of the group of the option at the index
+    Group(usize),
+    Option(usize),
+}
+
 #[element_backend]
 pub struct Select {
     element_weak: ElementWeak,
     label: Label,
+    label_element: Element,
+    /// Selected options of multiple selects
+    chips: Element,
     placeholder: TextBox,
-    value: String,
+    values: Vec<String>,
+    multiple: bool,
     options: Vec<SelectOption>,
     select_img: ImageObject,
     options_style: Vec<FixedStyleProp>,
     option_style: Vec<FixedStyleProp>,
     option_hover_style: Vec<FixedStyleProp>,
+    option_group_style: Vec<FixedStyleProp>,
+    option_disabled_style: Vec<FixedStyleProp>,
+    option_selected_style: Vec<FixedStyleProp>,
+    option_active_style: Vec<FixedStyleProp>,
+    popup: Option<Popup>,
+    /// List view of the open popup and the input of the filter
+    list: Option<(ElementWeak, ElementWeak)>,
+    rows: Vec<OptionRow>,
+    /// Rows bound to the elements of the list view, by eid
+    bound_rows: HashMap<u32, usize>,
+    active: Option<usize>,
+    filter: String,
+    row_height: f32,
 }
 
 #[js_methods]
 impl Select {
     #[js_func]
     pub fn set_value(&mut self, value: String) {
-        if self.value != value {
-            let label = self
-                .options
-                .iter()
-                .find(|o| o.value == value)
-                .map(|it| &it.label)
-                .unwrap_or(&value)
-                .to_string();
-            self.label.set_text(label);
-            self.value = value;
+        let values = if value.is_empty() {
+            Vec::new()
+        } else {
+            vec![value]
+        };
+        self.update_values(values);
+    }
+
+    #[js_func]
+    pub fn get_value(&self) -> String {
+        self.values.first().cloned().unwrap_or_default()
+    }
+
+    #[js_func]
+    pub fn set_values(&mut self, values: Vec<String>) {
+        self.update_values(values);
+    }
+
+    #[js_func]
+    pub fn get_values(&self) -> Vec<String> {
+        self.values.clone()
+    }
+
+    /// Allow selecting multiple options, the selected options are shown as chips
+    #[js_func]
+    pub fn set_multiple(&mut self, multiple: bool) {
+        self.multiple = multiple;
+        if !multiple && self.values.len() > 1 {
+            self.values.truncate(1);
             self.element_weak.emit(ChangeEvent {});
         }
+        self.update_children();
+        self.update_label();
     }
 
     #[js_func]
-    pub fn get_value(&self) -> String {
-        self.value.clone()
+    pub fn is_multiple(&self) -> bool {
+        self.multiple
     }
 
     #[js_func]
     pub fn set_options(&mut self, options: Vec<SelectOption>) {
         self.options = options;
+        self.update_label();
+        if self.list.is_some() {
+            self.update_rows();
+            self.active = self
+                .active
+                .filter(|row| self.is_selectable(*row))
+                .or_else(|| self.find_selectable(0, 1));
+            self.render_rows();
+        }
     }
 
     #[js_func]
@@ -89,38 +160,422 @@ impl Select {
         self.placeholder.get_text()
     }
 
-    fn build_options_element<F: FnOnce(String) + Clone + 'static>(
-        &self,
-        value_setter: F,
-    ) -> Element {
+    fn update_values(&mut self, mut values: Vec<String>) {
+        if !self.multiple {
+            values.truncate(1);
+        }
+        if self.values != values {
+            self.values = values;
+            self.update_label();
+            self.element_weak.emit(ChangeEvent {});
+        }
+    }
+
+    fn get_option_label(&self, value: &str) -> String {
+        self.options
+            .iter()
+            .find(|o| o.value == value)
+            .map(|it| &it.label)
+            .map(|s| s.as_str())
+            .unwrap_or(value)
+            .to_string()
+    }
+
+    fn update_children(&mut self) {
+        let (label_display, chips_display) = if self.multiple {
+            (Display::None, Display::Flex)
+        } else {
+            (Display::Flex, Display::None)
+        };
+        self.label_element
+            .set_style_props(vec![FixedStyleProp::Display(StylePropVal::Custom(
+                label_display,
+            ))]);
+        self.chips
+            .set_style_props(vec![FixedStyleProp::Display(StylePropVal::Custom(
+                chips_display,
+            ))]);
+    }
+
+    fn update_label(&mut self) {
+        self.chips.remove_all_child();
+        if !self.multiple {
+            let label = self.get_option_label(&self.get_value());
+            self.label.set_text(label);
+            return;
+        }
+        self.label.set_text(String::new());
+        for value in self.values.clone() {
+            let chip = self.build_chip(value);
+            self.chips.add_child(chip, -1).unwrap();
+        }
+    }
+
+    fn build_chip(&self, value: String) -> Element {
+        let mut chip = Element::create(Container::create);
+        chip.tag = "select-chip".to_string();
+        let mut label_el = Element::create(Label::create);
+        label_el
+            .get_backend_mut_as::<Label>()
+            .set_text(self.get_option_label(&value));
+        chip.add_child(label_el, 0).unwrap();
+
+        let mut remove_el = Element::create(Label::create);
+        remove_el.tag = "select-chip-remove".to_string();
+        remove_el
+            .get_backend_mut_as::<Label>()
+            .set_text("×".to_string());
+        let weak = self.as_weak();
+        remove_el.register_event_listener(ClickEventListener::new(move |_e, ctx| {
+            // Removing a chip should not open the popup
+            ctx.propagation_cancelled = true;
+            let mut select = ok_or_return!(weak.upgrade());
+            let values = select
+                .values
+                .iter()
+                .filter(|v| *v != &value)
+                .cloned()
+                .collect();
+            select.update_values(values);
+        }));
+        chip.add_child(remove_el, 1).unwrap();
+        chip
+    }
+
+    fn open_popup(&mut self, filter: String) {
+        let el = ok_or_return!(self.element_weak.upgrade());
+        if el.is_disabled() || self.popup.is_some() {
+            return;
+        }
+        let w = some_or_return!(el.get_window());
+        let bounds = el.get_origin_bounds();
+        self.row_height = el.style.font_size * ROW_HEIGHT_EM;
+
         let mut wrapper = Element::create(Container::create);
         wrapper.set_style_props(self.options_style.clone());
-        for option in &self.options {
-            let mut label_el = Element::create(Label::create);
-            label_el.set_style_props(self.option_style.clone());
-            label_el.set_hover_styles(self.option_hover_style.clone());
-            let setter = value_setter.clone();
-            let value = option.clone();
-            label_el.register_event_listener(ClickEventListener::new(move |_e, _ctx| {
-                (setter.clone())(value.value.clone());
-            }));
-            let mut label = label_el.get_backend_as::<Label>().clone();
-            label.set_text(option.label.clone());
-            wrapper
-                .add_child(label_el, wrapper.children.len() as i32)
-                .unwrap();
-        }
-        wrapper
+        wrapper.set_style_props(vec![FixedStyleProp::MinWidth(StylePropVal::Custom(
+            LengthOrPercent::Length(Length::PX(bounds.width)),
+        ))]);
+        // Clicks in the popup would close it otherwise
+        wrapper.register_event_listener(ClickEventListener::new(|_e, ctx| {
+            ctx.propagation_cancelled = true;
+        }));
+        let weak = self.as_weak();
+        wrapper.register_event_listener(KeyDownEventListener::new(move |e, ctx| {
+            let mut select = ok_or_return!(weak.upgrade());
+            if select.handle_list_key(&e.0) {
+                ctx.prevent_default = true;
+                ctx.propagation_cancelled = true;
+            }
+        }));
+
+        let mut search = Element::create(TextInput::create);
+        search.tag = "text-input".to_string();
+        let search_input = search.get_backend_mut_as::<TextInput>();
+        search_input.set_text(filter.clone());
+        search_input.set_caret_by_char_offset(filter.chars().count());
+        let weak = self.as_weak();
+        search.register_event_listener(TextChangeEventListener::new(move |e, _ctx| {
+            let mut select = ok_or_return!(weak.upgrade());
+            select.set_filter(e.value.clone());
+        }));
+        wrapper.add_child(search.clone(), 0).unwrap();
+
+        let mut list = Element::create(ListView::create);
+        list.set_style_props(vec![FixedStyleProp::MaxHeight(StylePropVal::Custom(
+            LengthOrPercent::Length(Length::PX(self.row_height * MAX_VISIBLE_ROWS as f32)),
+        ))]);
+        let list_view = list.get_backend_mut_as::<ListView>();
+        list_view.set_estimated_row_height(self.row_height);
+        list_view.set_factory(OptionRowFactory {
+            select: self.as_weak(),
+        });
+        wrapper.add_child(list.clone(), 1).unwrap();
+
+        self.list = Some((list.as_weak(), search.as_weak()));
+        self.filter = filter;
+        self.update_rows();
+        self.active = self
+            .rows
+            .iter()
+            .position(|r| match r {
+                OptionRow::Option(i) => self.values.contains(&self.options[*i].value),
+                _ => false,
+            })
+            .or_else(|| self.find_selectable(0, 1));
+        self.render_rows();
+        let mut popup = Popup::new(wrapper, bounds, &w);
+        let weak = self.as_weak();
+        popup.set_close_callback(move || {
+            // e.g. closed by clicking outside of the popup
+            let mut select = ok_or_return!(weak.upgrade());
+            select.popup = None;
+            select.list = None;
+            select.bound_rows.clear();
+        });
+        self.popup = Some(popup);
+        search.focus();
+
+        // The list is not scrollable until it is laid out
+        let list_window = some_or_return!(list.get_window());
+        let mut list_window = ok_or_return!(list_window.upgrade_mut());
+        let weak = self.as_weak();
+        list_window.request_next_paint_callback(Callback::new(move || {
+            let mut select = ok_or_return!(weak.upgrade());
+            select.scroll_active_into_view();
+        }));
+    }
+
+    fn close_popup(&mut self) {
+        if let Some(popup) = self.popup.take() {
+            popup.close();
+        }
+        self.list = None;
+        self.bound_rows.clear();
+        if let Ok(mut el) = self.element_weak.upgrade() {
+            el.focus();
+        }
+    }
+
+    /// Append typed text to the filter of the open popup
+    fn append_filter(&mut self, text: &str) {
+        let filter = format!("{}{}", self.filter, text);
+        if let Some(mut search) = self.list.as_ref().and_then(|(_, s)| s.upgrade().ok()) {
+            let search_input = search.get_backend_mut_as::<TextInput>();
+            search_input.set_text(filter.clone());
+            search_input.set_caret_by_char_offset(filter.chars().count());
+        }
+        self.set_filter(filter);
+    }
+
+    fn set_filter(&mut self, filter: String) {
+        if self.filter == filter {
+            return;
+        }
+        self.filter = filter;
+        self.update_rows();
+        self.active = self.find_selectable(0, 1);
+        if let Some(mut list) = self.get_list() {
+            list.set_scroll_top(0.0);
+        }
+        self.render_rows();
     }
+
+    /// Collect the rows of the options matching the filter
+    fn update_rows(&mut self) {
+        let filter = self.filter.to_lowercase();
+        self.rows.clear();
+        let mut group = None;
+        for (i, option) in self.options.iter().enumerate() {
+            if !filter.is_empty() && !option.label.to_lowercase().contains(&filter) {
+                continue;
+            }
+            if option.group.is_some() && option.group.as_ref() != group {
+                self.rows.push(OptionRow::Group(i));
+            }
+            group = option.group.as_ref();
+            self.rows.push(OptionRow::Option(i));
+        }
+    }
+
+    fn is_selectable(&self, row: usize) -> bool {
+        match self.rows.get(row) {
+            Some(OptionRow::Option(i)) => !self.options[*i].disabled,
+            _ => false,
+        }
+    }
+
+    /// Find the first selectable row from `start` in the direction of `step`
+    fn find_selectable(&self, start: usize, step: isize) -> Option<usize> {
+        let mut row = start as isize;
+        while row >= 0 && (row as usize) < self.rows.len() {
+            if self.is_selectable(row as usize) {
+                return Some(row as usize);
+            }
+            row += step;
+        }
+        None
+    }
+
+    /// Move the active row by `delta` selectable rows, stopping at the first or last one
+    fn move_active(&mut self, delta: isize) {
+        let step = delta.signum();
+        let mut active = self.active;
+        let mut row = match self.active {
+            Some(row) => row as isize,
+            None if step > 0 => -1,
+            None => self.rows.len() as isize,
+        };
+        let mut remaining = delta.abs();
+        while remaining > 0 {
+            row += step;
+            if row < 0 || row as usize >= self.rows.len() {
+                break;
+            }
+            if self.is_selectable(row as usize) {
+                active = Some(row as usize);
+                remaining -= 1;
+            }
+        }
+        if active != self.active {
+            self.active = active;
+            self.render_rows();
+            self.scroll_active_into_view();
+        }
+    }
+
+    fn scroll_active_into_view(&mut self) {
+        let active = some_or_return!(self.active);
+        let mut list_view = some_or_return!(self.get_list_view());
+        list_view.scroll_to_item(active);
+    }
+
+    fn get_list(&self) -> Option<Element> {
+        self.list.as_ref().and_then(|(l, _)| l.upgrade().ok())
+    }
+
+    fn get_list_view(&self) -> Option<ListView> {
+        let list = self.get_list()?;
+        Some(list.get_backend_as::<ListView>().clone())
+    }
+
+    fn handle_list_key(&mut self, detail: &KeyEventDetail) -> bool {
+        match detail.named_key {
+            Some(NamedKey::ArrowDown) => self.move_active(1),
+            Some(NamedKey::ArrowUp) => self.move_active(-1),
+            Some(NamedKey::PageDown) => self.move_active(MAX_VISIBLE_ROWS as isize),
+            Some(NamedKey::PageUp) => self.move_active(-(MAX_VISIBLE_ROWS as isize)),
+            Some(NamedKey::Enter) => {
+                if let Some(active) = self.active {
+                    self.choose_row(active);
+                }
+            }
+            Some(NamedKey::Escape) => self.close_popup(),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Select the option of the row, the popup is kept open to select more options if multiple
+    fn choose_row(&mut self, row: usize) {
+        if !self.is_selectable(row) {
+            return;
+        }
+        let OptionRow::Option(index) = self.rows[row] else {
+            return;
+        };
+        let value = self.options[index].value.clone();
+        if !self.multiple {
+            self.set_value(value);
+            self.close_popup();
+            return;
+        }
+        let mut values = self.values.clone();
+        if let Some(pos) = values.iter().position(|v| v == &value) {
+            values.remove(pos);
+        } else {
+            values.push(value);
+        }
+        self.update_values(values);
+        self.active = Some(row);
+        self.render_rows();
+    }
+
+    /// Bind the rows to the elements of the list view, elements of the rows scrolled out are
+    /// reused by the list view
+    fn render_rows(&mut self) {
+        let mut list_view = some_or_return!(self.get_list_view());
+        if list_view.get_item_count() == self.rows.len() {
+            list_view.refresh();
+        } else {
+            list_view.set_item_count(self.rows.len());
+        }
+    }
+
+    fn bind_row(&mut self, element: &mut Element, row: usize) {
+        let option_row = some_or_return!(self.rows.get(row).copied());
+        self.bound_rows.insert(element.get_eid(), row);
+        let mut styles = Vec::new();
+        let mut hover_styles = Vec::new();
+        let text = match option_row {
+            OptionRow::Group(i) => {
+                styles.extend(self.option_group_style.clone());
+                self.options[i].group.clone().unwrap_or_default()
+            }
+            OptionRow::Option(i) => {
+                let option = &self.options[i];
+                styles.extend(self.option_style.clone());
+                if option.disabled {
+                    styles.extend(self.option_disabled_style.clone());
+                } else {
+                    hover_styles.extend(self.option_hover_style.clone());
+                }
+                if self.values.contains(&option.value) {
+                    styles.extend(self.option_selected_style.clone());
+                }
+                if self.active == Some(row) {
+                    styles.extend(self.option_active_style.clone());
+                }
+                option.label.clone()
+            }
+        };
+        styles.push(FixedStyleProp::Height(StylePropVal::Custom(
+            LengthOrPercent::Length(Length::PX(self.row_height)),
+        )));
+        element.replace_style_props(styles);
+        element.set_hover_styles(hover_styles);
+        element.get_backend_mut_as::<Label>().set_text(text);
+    }
+}
+
+/// Creates the labels of the rows of the option list
+struct OptionRowFactory {
+    select: SelectWeak,
+}
+
+impl ItemFactory for OptionRowFactory {
+    fn create(&mut self) -> Element {
+        let mut label_el = Element::create(Label::create);
+        let eid = label_el.get_eid();
+        let weak = self.select.clone();
+        label_el.register_event_listener(ClickEventListener::new(move |_e, _ctx| {
+            let mut select = ok_or_return!(weak.upgrade());
+            let row = some_or_return!(select.bound_rows.get(&eid).copied());
+            select.choose_row(row);
+        }));
+        label_el
+    }
+
+    fn bind(&mut self, element: &mut Element, index: usize) {
+        let mut select = ok_or_return!(self.select.upgrade());
+        select.bind_row(element, index);
+    }
+}
+
+/// Text typed without shortcut modifiers
+fn get_typed_text(detail: &KeyEventDetail) -> Option<&str> {
+    let text = detail.key_str.as_ref()?;
+    let shortcut_modifiers = KEY_MOD_CTRL | KEY_MOD_ALT | KEY_MOD_META;
+    if detail.modifiers & shortcut_modifiers != 0 || text.chars().any(|c| c.is_control()) {
+        return None;
+    }
+    Some(text)
 }
 
 impl ElementBackend for Select {
     fn create(element: &mut Element) -> Self {
         element.is_form_element = true;
+        element.set_focusable(true);
         element.register_js_event::<ChangeEvent>("change");
         let label_el = Element::create(Label::create);
         let label = label_el.get_backend_as::<Label>().clone();
         element.add_child(label_el.clone(), 0).unwrap();
+        let mut chips = Element::create(Container::create);
+        chips.tag = "select-chips".to_string();
+        chips.set_style_props(vec![FixedStyleProp::Display(StylePropVal::Custom(
+            Display::None,
+        ))]);
+        element.add_child(chips.clone(), 1).unwrap();
 
         let select_img = ImageObject::from_svg_bytes(include_bytes!("./select.svg"));
 
@@ -130,12 +585,26 @@ impl ElementBackend for Select {
             element_weak: element.as_weak(),
             placeholder,
             label,
-            value: "".to_string(),
+            label_element: label_el,
+            chips,
+            values: Vec::new(),
+            multiple: false,
             options: vec![],
             select_img,
             options_style: vec![],
             option_style: vec![],
             option_hover_style: vec![],
+            option_group_style: vec![],
+            option_disabled_style: vec![],
+            option_selected_style: vec![],
+            option_active_style: vec![],
+            popup: None,
+            list: None,
+            rows: Vec::new(),
+            bound_rows: HashMap::new(),
+            active: None,
+            filter: String::new(),
+            row_height: 0.0,
         }
         .to_ref()
     }
@@ -150,11 +619,12 @@ impl ElementBackend for Select {
         let y = (bounds.height - img_height) / 2.0;
         let x = bounds.width - img_width - y;
         let img = self.select_img.render();
-        let placeholder_renderer = if self.label.get_text().is_empty() {
-            Some(self.placeholder.render())
-        } else {
-            None
-        };
+        let placeholder_renderer =
+            if self.label.get_text().is_empty() && (!self.multiple || self.values.is_empty()) {
+                Some(self.placeholder.render())
+            } else {
+                None
+            };
         let (pt, _, _, pl) = el.get_padding();
         RenderFn::new(move |painter| {
             if let Some(pr) = placeholder_renderer {
@@ -168,40 +638,64 @@ impl ElementBackend for Select {
         })
     }
 
-    fn on_event(&mut self, event: &mut Event, _ctx: &mut EventContext<ElementWeak>) {
-        let el = ok_or_return!(self.element_weak.upgrade());
-        let w = some_or_return!(el.get_window());
-        let bounds = el.get_origin_bounds();
+    fn on_event(&mut self, event: &mut Event, ctx: &mut EventContext<ElementWeak>) {
         if let Some(_) = ClickEvent::cast(event) {
-            let mut popup: Mrc<Option<Popup>> = Mrc::new(None);
-            let mut popup_mrc = popup.clone();
-            let weak = self.as_weak();
-            let value_setter = move |v| {
-                let mut select = ok_or_return!(weak.upgrade());
-                select.set_value(v);
-                if let Some(p) = popup_mrc.deref_mut() {
-                    let _ = p.clone().close();
+            if self.popup.is_some() {
+                self.close_popup();
+            } else {
+                self.open_popup(String::new());
+            }
+        } else if let Some(e) = KeyDownEvent::cast(event) {
+            if ctx.target != self.element_weak {
+                return;
+            }
+            let detail = &e.0;
+            // Keys go to the open popup instead of opening another one
+            if self.popup.is_some() {
+                if self.handle_list_key(detail) {
+                    ctx.prevent_default = true;
+                } else if let Some(text) = get_typed_text(detail) {
+                    self.append_filter(text);
                 }
-            };
-            let mut options_el = self.build_options_element(value_setter);
-            options_el.set_style_props(vec![FixedStyleProp::MinWidth(StylePropVal::Custom(
-                LengthOrPercent::Length(Length::PX(bounds.width)),
-            ))]);
-            *popup = Some(Popup::new(options_el, bounds, &w));
+                return;
+            }
+            match detail.named_key {
+                Some(NamedKey::Enter)
+                | Some(NamedKey::Space)
+                | Some(NamedKey::ArrowDown)
+                | Some(NamedKey::ArrowUp) => self.open_popup(String::new()),
+                _ => {
+                    // Type-ahead, the typed text filters the options
+                    let text = some_or_return!(get_typed_text(detail));
+                    self.open_popup(text.to_string());
+                }
+            }
         }
     }
     fn accept_pseudo_element_styles(&mut self, styles: HashMap<String, Vec<ResolvedStyleProp>>) {
+        let to_unresolved = |styles: &Vec<ResolvedStyleProp>| -> Vec<FixedStyleProp> {
+            styles.iter().map(|it| it.to_unresolved()).collect()
+        };
         if let Some(styles) = styles.get("options") {
-            let styles: Vec<FixedStyleProp> = styles.iter().map(|it| it.to_unresolved()).collect();
-            self.options_style = styles;
+            self.options_style = to_unresolved(styles);
         }
         if let Some(styles) = styles.get("option") {
-            let styles: Vec<FixedStyleProp> = styles.iter().map(|it| it.to_unresolved()).collect();
-            self.option_style = styles;
+            self.option_style = to_unresolved(styles);
         }
         if let Some(styles) = styles.get("option-hover") {
-            let styles: Vec<FixedStyleProp> = styles.iter().map(|it| it.to_unresolved()).collect();
-            self.option_hover_style = styles;
+            self.option_hover_style = to_unresolved(styles);
+        }
+        if let Some(styles) = styles.get("option-group") {
+            self.option_group_style = to_unresolved(styles);
+        }
+        if let Some(styles) = styles.get("option-disabled") {
+            self.option_disabled_style = to_unresolved(styles);
+        }
+        if let Some(styles) = styles.get("option-selected") {
+            self.option_selected_style = to_unresolved(styles);
+        }
+        if let Some(styles) = styles.get("option-active") {
+            self.option_active_style = to_unresolved(styles);
         }
         if let Some(placeholder_styles) = styles.get("placeholder") {
             for style in placeholder_styles {
@@ -225,6 +719,40 @@ impl ElementBackend for Select {
         self.placeholder.layout();
     }
 
+    fn build_accessibility_node(&mut self, node: &mut Node) -> bool {
+        node.set_role(Role::ComboBox);
+        let labels: Vec<String> = self
+            .values
+            .iter()
+            .map(|v| self.get_option_label(v))
+            .collect();
+        node.set_value(labels.join(", "));
+        let placeholder = self.get_placeholder();
+        if !placeholder.is_empty() {
+            node.set_placeholder(placeholder);
+        }
+        node.add_action(Action::Click);
+        node.add_action(Action::SetValue);
+        false
+    }
+
+    fn do_accessibility_action(&mut self, request: &ActionRequest) -> bool {
+        match (request.action, &request.data) {
+            (Action::SetValue, Some(ActionData::Value(value))) => {
+                // Assistive technologies may pass either the value or the label of an option
+                let value = self
+                    .options
+                    .iter()
+                    .find(|o| o.value == value.as_ref() || o.label == value.as_ref())
+                    .map(|o| o.value.clone())
+                    .unwrap_or(value.to_string());
+                self.set_value(value);
+                true
+            }
+            _ => false,
+        }
+    }
+
     fn handle_style_changed(&mut self, key: StylePropKey) {
         let mut el = ok_or_return!(self.element_weak.upgrade());
         match key {
@@ -237,3 +765,147 @@ impl ElementBackend for Select {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::element::body::Body;
+    use crate::element::select::{OptionRow, Select, SelectOption};
+    use crate::element::Element;
+    use crate::ext::ext_window::WindowAttrs;
+    use crate::window::Window;
+
+    fn option(value: &str, group: Option<&str>, disabled: bool) -> SelectOption {
+        SelectOption {
+            value: value.to_string(),
+            label: value.to_string(),
+            group: group.map(|g| g.to_string()),
+            disabled,
+        }
+    }
+
+    #[test]
+    fn test_option_rows() {
+        let el = Element::create(Select::create);
+        let mut select = el.get_backend_as::<Select>().clone();
+        select.set_options(vec![
+            option("apple", Some("fruit"), false),
+            option("banana", Some("fruit"), true),
+            option("cherry", Some("fruit"), false),
+            option("carrot", Some("vegetable"), false),
+        ]);
+        select.update_rows();
+        assert_eq!(
+            vec![
+                OptionRow::Group(0),
+                OptionRow::Option(0),
+                OptionRow::Option(1),
+                OptionRow::Option(2),
+                OptionRow::Group(3),
+                OptionRow::Option(3),
+            ],
+            select.rows
+        );
+        assert_eq!(Some(1), select.find_selectable(0, 1));
+        // The disabled banana is skipped
+        select.active = Some(1);
+        select.move_active(1);
+        assert_eq!(Some(3), select.active);
+        select.move_active(10);
+        assert_eq!(Some(5), select.active);
+
+        select.filter = "CA".to_string();
+        select.update_rows();
+        assert_eq!(vec![OptionRow::Group(3), OptionRow::Option(3)], select.rows);
+    }
+
+    #[test]
+    fn test_multiple_values() {
+        let el = Element::create(Select::create);
+        let mut select = el.get_backend_as::<Select>().clone();
+        select.set_options(vec![option("a", None, false), option("b", None, false)]);
+        select.set_values(vec!["a".to_string(), "b".to_string()]);
+        assert_eq!(vec!["a".to_string()], select.get_values());
+        select.set_multiple(true);
+        select.set_values(vec!["a".to_string(), "b".to_string()]);
+        assert_eq!("a", select.get_value());
+        assert_eq!(2, select.chips.get_children().len());
+        select.set_multiple(false);
+        assert_eq!(vec!["a".to_string()], select.get_values());
+        assert_eq!(0, select.chips.get_children().len());
+    }
+
+    #[test]
+    fn test_popup_rows() {
+        let handle = Window::create(WindowAttrs {
+            width: Some(100.0),
+            height: Some(100.0),
+            headless: Some(true),
+            ..Default::default()
+        })
+        .unwrap();
+        let mut body = Element::create(Body::create);
+        let el = Element::create(Select::create);
+        body.add_child(el.clone(), 0).unwrap();
+        handle.upgrade_mut().unwrap().set_body(body).unwrap();
+
+        let mut select = el.get_backend_as::<Select>().clone();
+        let options = (0..50)
+            .map(|i| option(&i.to_string(), None, false))
+            .collect();
+        select.set_options(options);
+        select.open_popup(String::new());
+        let popup = select.popup.clone();
+        assert!(popup.is_some());
+        select.open_popup(String::new());
+        assert!(popup == select.popup);
+
+        // Row elements are bound again instead of being recreated
+        let row_eids = |select: &Select| {
+            let list = select.get_list().unwrap();
+            let children = list.get_children()[0].get_children();
+            // Spacers are the first and the last children
+            children[1..children.len() - 1]
+                .iter()
+                .map(|e| e.get_eid())
+                .collect::<Vec<_>>()
+        };
+        let eids = row_eids(&select);
+        select.move_active(1);
+        assert_eq!(Some(1), select.active);
+        assert_eq!(eids, row_eids(&select));
+
+        select.set_options(vec![option("a", None, false)]);
+        assert_eq!(Some(0), select.active);
+        assert_eq!(1, select.get_list_view().unwrap().get_item_count());
+        select.close_popup();
+        assert!(select.popup.is_none());
+        assert!(select.bound_rows.is_empty());
+    }
+
+    #[test]
+    fn test_popup_closed_directly() {
+        let handle = Window::create(WindowAttrs {
+            width: Some(100.0),
+            height: Some(100.0),
+            headless: Some(true),
+            ..Default::default()
+        })
+        .unwrap();
+        let mut body = Element::create(Body::create);
+        let el = Element::create(Select::create);
+        body.add_child(el.clone(), 0).unwrap();
+        handle.upgrade_mut().unwrap().set_body(body).unwrap();
+
+        let mut select = el.get_backend_as::<Select>().clone();
+        select.set_options(vec![option("a", None, false), option("b", None, false)]);
+        select.open_popup(String::new());
+        // e.g. the popup window is closed by clicking outside of it
+        select.popup.clone().unwrap().close();
+        assert!(select.popup.is_none());
+        assert!(select.list.is_none());
+        assert!(select.bound_rows.is_empty());
+        // The next click opens the popup again
+        select.open_popup(String::new());
+        assert!(select.popup.is_some());
+    }
+}
@@ -0,0 +1,132 @@
+use std::collections::HashSet;
+use unicode_segmentation::UnicodeSegmentation;
+
+const MAX_SUGGESTION_DISTANCE: usize = 2;
+const MAX_SUGGESTIONS: usize = 5;
+
+/// Checks the spelling of words in editable fields, misspelled words are underlined with squiggles
+pub trait SpellChecker {
+    fn check(&self, word: &str) -> bool;
+
+    /// Replacements of a misspelled word, the most likely first
+    fn suggest(&self, _word: &str) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+/// Spell checker backed by a list of words, such as the `.dic` file of a hunspell dictionary
+pub struct WordListSpellChecker {
+    words: HashSet<String>,
+}
+
+impl WordListSpellChecker {
+    pub fn new<I, S>(words: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let words = words.into_iter().map(|w| w.as_ref().to_string()).collect();
+        Self { words }
+    }
+
+    /// Load words of a hunspell `.dic` file, affix flags after `/` are ignored
+    pub fn from_dic(content: &str) -> Self {
+        let words = content.lines().enumerate().filter_map(|(i, ln)| {
+            let ln = ln.trim();
+            // The first line is the approximate word count
+            if i == 0 && ln.parse::<usize>().is_ok() {
+                return None;
+            }
+            let word = ln.split('/').next()?.trim();
+            if word.is_empty() {
+                None
+            } else {
+                Some(word)
+            }
+        });
+        Self::new(words)
+    }
+
+    pub fn add_word(&mut self, word: &str) {
+        self.words.insert(word.to_string());
+    }
+}
+
+impl SpellChecker for WordListSpellChecker {
+    fn check(&self, word: &str) -> bool {
+        self.words.contains(word) || self.words.contains(&word.to_lowercase())
+    }
+
+    fn suggest(&self, word: &str) -> Vec<String> {
+        let word = word.to_lowercase().chars().collect::<Vec<char>>();
+        let mut candidates = Vec::new();
+        for w in &self.words {
+            let chars = w.to_lowercase().chars().collect::<Vec<char>>();
+            if chars.len().abs_diff(word.len()) > MAX_SUGGESTION_DISTANCE {
+                continue;
+            }
+            let distance = edit_distance(&word, &chars);
+            if distance <= MAX_SUGGESTION_DISTANCE {
+                candidates.push((distance, w));
+            }
+        }
+        candidates.sort();
+        candidates
+            .into_iter()
+            .take(MAX_SUGGESTIONS)
+            .map(|(_, w)| w.to_string())
+            .collect()
+    }
+}
+
+/// Char ranges of the misspelled words in `text`
+pub fn find_misspelled_words(checker: &dyn SpellChecker, text: &str) -> Vec<(usize, usize)> {
+    let mut result = Vec::new();
+    let mut char_offset = 0;
+    for word in text.split_word_bounds() {
+        let char_count = word.chars().count();
+        if is_checkable_word(word) && !checker.check(word) {
+            result.push((char_offset, char_offset + char_count));
+        }
+        char_offset += char_count;
+    }
+    result
+}
+
+/// Words with digits are usually identifiers or codes rather than natural language
+fn is_checkable_word(word: &str) -> bool {
+    word.chars().any(|c| c.is_alphabetic()) && !word.chars().any(|c| c.is_numeric())
+}
+
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let mut prev = (0..=b.len()).collect::<Vec<usize>>();
+    let mut current = vec![0; b.len() + 1];
+    for (i, ca) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == cb { 0 } else { 1 };
+            current[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut current);
+    }
+    prev[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::element::spell_checker::{
+        find_misspelled_words, SpellChecker, WordListSpellChecker,
+    };
+
+    #[test]
+    fn test_word_list_spell_checker() {
+        let checker = WordListSpellChecker::from_dic("3\nhello\nworld/MS\nword/S\n");
+        assert!(checker.check("Hello"));
+        assert!(!checker.check("wrold"));
+        assert_eq!(
+            find_misspelled_words(&checker, "hello wrold\nworld 42x"),
+            vec![(6, 11)]
+        );
+        assert_eq!(checker.suggest("wrold"), vec!["word", "world"]);
+    }
+}
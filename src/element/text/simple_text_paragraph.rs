@@ -1,27 +1,40 @@
+use crate::element::common::image_object::ImageObject;
+use crate::element::paragraph::ParagraphParams;
 use crate::element::text::rasterize_cache::RasterizeCache;
-use crate::font::Font;
+use crate::font::{is_emoji_modifier, Font};
 use crate::number::DeNan;
 use crate::paint::Painter;
 use crate::some_or_return;
 use crate::string::StringUtils;
 use crate::style::color::ColorHelper;
-use crate::text::{calculate_line_char_count, TextStyle};
+use crate::style::text::{TextOverflow, WordBreak};
+use crate::text::{TextAlign, TextDecoration, TextDecorationStyle, TextStyle};
 use libc::memcpy;
 use log::warn;
+use skia_safe::canvas::SaveLayerRec;
 use skia_safe::{
-    scalar, AlphaType, Bitmap, Color, ColorType, FilterMode, ImageInfo, Paint, Point, Rect,
-    SamplingOptions,
+    image_filters, scalar, AlphaType, Bitmap, Color, ColorType, FilterMode, ImageInfo, Paint,
+    PaintStyle, Path, PathEffect, Point, Rect, SamplingOptions,
 };
+use std::cell::RefCell;
 use std::ffi::c_void;
 use std::ptr::slice_from_raw_parts_mut;
 use std::sync::Arc;
 use swash::scale::image::Content;
+use swash::shape::{Direction as ShapeDirection, ShapeContext};
+use swash::text::{Codepoint, Script};
 use swash::GlyphId;
+use unicode_bidi::{BidiInfo, Level};
+use yoga::Direction;
 
 thread_local! {
     static RASTERIZE_CACHE: RasterizeCache = RasterizeCache::new();
+    static SHAPE_CONTEXT: RefCell<ShapeContext> = RefCell::new(ShapeContext::new());
 }
 
+const ZERO_WIDTH_SPACE: char = '\u{200B}';
+const ELLIPSIS: &str = "\u{2026}";
+
 struct LineUnit {
     block: TextBlock,
     x: f32,
@@ -44,34 +57,42 @@ impl BoundsWithOffset {
 
 impl LineUnit {
     fn get_inner_layout_bounds(&self, compact: bool) -> Vec<BoundsWithOffset> {
-        let glyph_ids = str_to_glyphs_vec(&self.block.font, self.block.text.as_str());
-        let mut bounds = Vec::with_capacity(glyph_ids.len());
-        let mut widths = Vec::with_capacity(glyph_ids.len());
-        let mut result = Vec::with_capacity(glyph_ids.len());
-        unsafe {
-            bounds.set_len(glyph_ids.len());
-            widths.set_len(glyph_ids.len());
+        if let Some(obj) = &self.block.inline_object {
+            let (ascent, descent) = self.block.ascent_descent(None);
+            let bounds = Rect::new(0.0, -ascent, obj.width, descent);
+            return vec![BoundsWithOffset::new(0.0, bounds)];
         }
-        get_fixed_widths_bounds(
-            &self.block.font,
-            &glyph_ids,
-            &mut widths,
-            &mut bounds,
-            Some(&self.block.style.foreground()),
-            self.block.style.font_size(),
-        );
-        let mut x = 0.0;
+        // Raster bounds of shaped glyphs do not map to chars, so they are never compact
+        let compact = compact && !self.block.needs_shaping();
+        let (widths, bounds) = if compact {
+            self.block.measure_with_bounds()
+        } else {
+            (self.block.measure(), Vec::new())
+        };
         let metrics = self.block.font.metrics();
-        for i in 0..glyph_ids.len() {
-            if !compact {
-                bounds[i].left = 0.0;
-                bounds[i].right = widths[i];
-                bounds[i].bottom =
-                    metrics.descent / metrics.units_per_em as f32 * self.block.style.font_size();
-                bounds[i].top =
-                    -metrics.ascent / metrics.units_per_em as f32 * self.block.style.font_size();
-            }
-            result.push(BoundsWithOffset::new(x, bounds[i]));
+        let metrics_scale = self.block.style.font_size() / metrics.units_per_em as f32;
+        let total_width = widths.iter().sum::<f32>();
+        let mut result = Vec::with_capacity(widths.len());
+        let mut x = 0.0;
+        for i in 0..widths.len() {
+            let mut b = if compact {
+                bounds[i]
+            } else {
+                Rect::new(
+                    0.0,
+                    -metrics.ascent * metrics_scale,
+                    widths[i],
+                    metrics.descent * metrics_scale,
+                )
+            };
+            b.offset((0.0, self.block.style.baseline_shift()));
+            // Chars of right-to-left blocks are placed from right to left
+            let char_x = if self.block.is_rtl() {
+                total_width - x - widths[i]
+            } else {
+                x
+            };
+            result.push(BoundsWithOffset::new(char_x, b));
             x += widths[i];
         }
         result
@@ -84,43 +105,34 @@ impl LineUnit {
         range: Option<(usize, usize)>,
         paint: Option<&Paint>,
     ) {
+        if let Some(obj) = &self.block.inline_object {
+            let (start, end) = range.unwrap_or((0, 1));
+            if start < end {
+                let (ascent, _) = self.block.ascent_descent(None);
+                obj.paint(painter, Point::new(origin.x, origin.y - ascent));
+            }
+            return;
+        }
+        let origin = Point::new(origin.x, origin.y + self.block.style.baseline_shift());
         let font = &self.block.font;
         let font_size = self.block.style.font_size();
         let foreground = self.block.style.foreground();
         let paint = paint.unwrap_or(&foreground);
-        let mut glyphs = str_to_glyphs_vec(&font, self.block.text.as_str());
-        let mut layout_bounds = self
-            .get_inner_layout_bounds(false)
-            .iter()
-            .map(|b| Point::new(b.x, 0.0))
-            .collect::<Vec<_>>();
-        let mut offset = 0;
-        let (mut range_start, mut range_end) = range.unwrap_or((0, glyphs.len()));
-        for i in 0..glyphs.len() {
-            if glyphs[i] != 0 {
-                glyphs[offset] = glyphs[i];
-                layout_bounds[offset] = layout_bounds[i];
-                offset += 1;
-            } else {
-                if i < range_start {
-                    range_start -= 1;
-                }
-                if i < range_end {
-                    range_end -= 1;
-                }
-            }
-        }
+        let (_, glyphs) = self.block.shape();
+        let (range_start, range_end) = range.unwrap_or((0, usize::MAX));
 
         let scale = painter.context.scale_factor;
         let canvas = painter.canvas;
         canvas.save();
         canvas.scale((1.0 / scale, 1.0 / scale));
         let color = paint.color();
-        for idx in range_start..range_end {
-            let lb = layout_bounds[idx];
-            let glyph = glyphs[idx];
-            let rasterized_img =
-                RASTERIZE_CACHE.with(move |cache| cache.get_image(&font, glyph, font_size * scale));
+        for glyph in &glyphs {
+            if glyph.char_index < range_start || glyph.char_index >= range_end {
+                continue;
+            }
+            let glyph_id = glyph.id;
+            let rasterized_img = RASTERIZE_CACHE
+                .with(move |cache| cache.get_image(&font, glyph_id, font_size * scale));
             if let Some(img) = rasterized_img {
                 if let Some(bmp) = Self::swash_to_bitmap(&img, color) {
                     let x = img.placement.left;
@@ -130,8 +142,8 @@ impl LineUnit {
                     canvas.draw_image_with_sampling_options(
                         bmp.as_image(),
                         (
-                            (origin.x + lb.x) * scale + x as f32,
-                            origin.y * scale - y as f32,
+                            (origin.x + glyph.x) * scale + x as f32,
+                            (origin.y + glyph.y) * scale - y as f32,
                         ),
                         options,
                         None,
@@ -142,6 +154,80 @@ impl LineUnit {
         canvas.restore();
     }
 
+    fn paint_decorations(&self, painter: &Painter, origin: Point) {
+        let style = &self.block.style;
+        let decoration = style.decoration_type();
+        if decoration.is_empty() || self.block.inline_object.is_some() {
+            return;
+        }
+        let metrics = self.block.font.metrics();
+        let scale = style.font_size() / metrics.units_per_em as f32;
+        let thickness = style.decoration_thickness().unwrap_or_else(|| {
+            if metrics.stroke_size > 0.0 {
+                metrics.stroke_size * scale
+            } else {
+                style.font_size() / 14.0
+            }
+        });
+        let baseline = origin.y + style.baseline_shift();
+        let mut positions = Vec::new();
+        if decoration.contains(TextDecoration::UNDERLINE) {
+            let offset = if metrics.underline_offset != 0.0 {
+                -metrics.underline_offset * scale
+            } else {
+                metrics.descent * scale / 2.0
+            };
+            positions.push(baseline + offset + thickness / 2.0);
+        }
+        if decoration.contains(TextDecoration::OVERLINE) {
+            positions.push(baseline - metrics.ascent * scale + thickness / 2.0);
+        }
+        if decoration.contains(TextDecoration::LINE_THROUGH) {
+            let offset = if metrics.strikeout_offset != 0.0 {
+                metrics.strikeout_offset * scale
+            } else {
+                metrics.x_height * scale / 2.0
+            };
+            positions.push(baseline - offset + thickness / 2.0);
+        }
+        let width = self.block.measure().iter().sum::<f32>();
+        let (left, right) = (origin.x, origin.x + width);
+        let mut paint = Paint::default();
+        paint.set_anti_alias(true);
+        paint.set_style(PaintStyle::Stroke);
+        paint.set_stroke_width(thickness);
+        paint.set_color(
+            style
+                .decoration_color()
+                .unwrap_or_else(|| style.foreground().color()),
+        );
+        let canvas = painter.canvas;
+        for y in positions {
+            match style.decoration_style() {
+                TextDecorationStyle::Solid => {
+                    canvas.draw_line((left, y), (right, y), &paint);
+                }
+                TextDecorationStyle::Double => {
+                    canvas.draw_line((left, y - thickness), (right, y - thickness), &paint);
+                    canvas.draw_line((left, y + thickness), (right, y + thickness), &paint);
+                }
+                TextDecorationStyle::Dotted | TextDecorationStyle::Dashed => {
+                    let dash = if style.decoration_style() == TextDecorationStyle::Dotted {
+                        thickness
+                    } else {
+                        thickness * 3.0
+                    };
+                    let mut dash_paint = paint.clone();
+                    dash_paint.set_path_effect(PathEffect::dash(&[dash, thickness * 2.0], 0.0));
+                    canvas.draw_line((left, y), (right, y), &dash_paint);
+                }
+                TextDecorationStyle::Wavy => {
+                    draw_wavy_line(painter, (left, right), y, thickness, &paint);
+                }
+            }
+        }
+    }
+
     fn swash_to_bitmap(img: &swash::scale::image::Image, color: Color) -> Option<Bitmap> {
         let width = img.placement.width;
         let height = img.placement.height;
@@ -191,9 +277,13 @@ impl LineUnit {
 
 struct TextLine {
     units: Vec<LineUnit>,
+    /// Painted after the units when the line is truncated, it takes no chars
+    ellipsis: Option<LineUnit>,
     line_number: usize,
     y: f32,
     baseline: f32,
+    /// Max distance between the baseline and the bottom of units
+    descent: f32,
     height: f32,
     char_offset: usize,
 }
@@ -202,18 +292,37 @@ impl TextLine {
     pub fn new(line_number: usize, char_offset: usize) -> Self {
         Self {
             units: Vec::new(),
+            ellipsis: None,
             line_number,
             baseline: 0.0,
+            descent: 0.0,
             height: 0.0,
             y: 0.0,
             char_offset,
         }
     }
+
+    fn update_metrics(&mut self, (ascent, descent): (f32, f32)) {
+        self.baseline = f32::max(self.baseline, ascent);
+        self.descent = f32::max(self.descent, descent);
+        self.height = f32::max(self.height, self.baseline + self.descent);
+    }
 }
 
 pub struct SimpleTextParagraph {
     text: String,
     line_height: Option<f32>,
+    align: TextAlign,
+    /// Whether the base direction is right-to-left
+    rtl: bool,
+    wrap: bool,
+    preserve_newlines: bool,
+    word_break: WordBreak,
+    ellipsis: bool,
+    /// Lines after `max_lines` are hidden
+    max_lines: Option<usize>,
+    /// Whether content after the paragraph is hidden, the last line ends with an ellipsis if so
+    has_hidden_content: bool,
     text_blocks: Vec<TextBlock>,
     pub(crate) layout: Option<Arc<TextLayout>>,
 }
@@ -233,16 +342,46 @@ impl TextLayout {
             let y = ln.y + ln.baseline;
             for unit in &ln.units {
                 let tb = &unit.block;
-                if tb.style.foreground().color().is_transparent() {
+                if tb.inline_object.is_none() && tb.style.foreground().color().is_transparent() {
                     continue;
                 }
                 let x = unit.x;
                 unit.paint(painter, Point::new(x, y), None, None);
+                unit.paint_decorations(painter, Point::new(x, y));
+            }
+            if let Some(ellipsis) = &ln.ellipsis {
+                ellipsis.paint(painter, Point::new(ellipsis.x, y), None, None);
             }
         }
         canvas.restore();
     }
 
+    /// Paint the blurred shadow of glyphs only, the text itself is not painted
+    pub fn paint_shadow(
+        &self,
+        painter: &Painter,
+        p: Point,
+        offset: (f32, f32),
+        blur_sigma: f32,
+        color: Color,
+    ) {
+        let filter = image_filters::drop_shadow_only(
+            offset,
+            (blur_sigma, blur_sigma),
+            color,
+            None,
+            None,
+            None,
+        );
+        let mut paint = Paint::default();
+        paint.set_image_filter(filter);
+        painter
+            .canvas
+            .save_layer(&SaveLayerRec::default().paint(&paint));
+        self.paint(painter, p);
+        painter.canvas.restore();
+    }
+
     fn get_unit_at_char_offset(&self, char_offset: usize) -> Option<(&TextLine, &LineUnit)> {
         for ln in self.lines.iter().rev() {
             if ln.char_offset > char_offset {
@@ -263,14 +402,24 @@ impl TextLayout {
         let (ln, unit) = self.get_unit_at_char_offset(char_offset)?;
         let char_offset = char_offset - unit.char_offset;
         let unit_origin = (unit.x, ln.y + ln.baseline);
+        // Chars hidden by ellipsis have no bounds
         let bounds = unit.get_inner_layout_bounds(false);
         Some(
-            bounds[char_offset]
+            bounds
+                .get(char_offset)?
                 .bounds_with_offset()
                 .with_offset(unit_origin),
         )
     }
 
+    /// Whether the char at `char_offset` is placed from right to left
+    pub fn is_char_rtl(&self, char_offset: usize) -> bool {
+        match self.get_unit_at_char_offset(char_offset) {
+            Some((_, unit)) => unit.block.is_rtl(),
+            None => false,
+        }
+    }
+
     pub fn paint_chars(
         &self,
         painter: &Painter,
@@ -281,8 +430,11 @@ impl TextLayout {
         while start < end {
             if let Some((ln, unit)) = self.get_unit_at_char_offset(start) {
                 let unit_start = start - unit.char_offset;
-                let paint_char_count =
-                    usize::min(unit.block.text.chars_count() - unit_start, end - start);
+                let unit_char_count = unit.block.text.chars_count();
+                if unit_start >= unit_char_count {
+                    return;
+                }
+                let paint_char_count = usize::min(unit_char_count - unit_start, end - start);
                 unit.paint(
                     painter,
                     Point::new(unit.x, ln.y + ln.baseline),
@@ -296,6 +448,28 @@ impl TextLayout {
         }
     }
 
+    /// Bounds of the chars between `start` and `end`, visually adjacent chars are merged
+    pub fn get_range_rects(&self, start: usize, end: usize) -> Vec<Rect> {
+        let mut rects: Vec<Rect> = Vec::new();
+        for i in start..end {
+            let rect = match self.get_char_bounds(i) {
+                Some(r) => r,
+                None => continue,
+            };
+            if let Some(last) = rects.last_mut() {
+                let same_line = rect.top < last.bottom && rect.bottom > last.top;
+                let adjacent =
+                    (rect.left - last.right).abs() < 0.5 || (last.left - rect.right).abs() < 0.5;
+                if same_line && adjacent {
+                    last.join(rect);
+                    continue;
+                }
+            }
+            rects.push(rect);
+        }
+        rects
+    }
+
     pub fn paint_selection(
         &self,
         painter: &Painter,
@@ -334,119 +508,542 @@ pub struct TextBlock {
     pub text: String,
     pub style: TextStyle,
     pub font: Font,
+    /// Object taking the place of `text`, which is a single object replacement character
+    pub inline_object: Option<InlineObject>,
+    /// Bidi embedding level, text of odd levels is right-to-left
+    pub level: u8,
+}
+
+/// Glyph placed by shaping, `x` is relative to the left edge of the block and `y` to the baseline
+struct ShapedGlyph {
+    id: GlyphId,
+    x: f32,
+    y: f32,
+    /// Index of the first char of the cluster the glyph belongs to
+    char_index: usize,
+}
+
+/// Glyph cluster in logical order
+struct ShapedCluster {
+    char_index: usize,
+    advance: f32,
+    glyphs: Vec<swash::shape::cluster::Glyph>,
+}
+
+impl TextBlock {
+    /// Returns (ascent, descent) of the block, both are distances from the baseline
+    fn ascent_descent(&self, line_height: Option<f32>) -> (f32, f32) {
+        let font_metrics = self.font.metrics();
+        let metrics_scale = self.style.font_size() / font_metrics.units_per_em as f32;
+        let font_ascent = font_metrics.ascent * metrics_scale;
+        let font_descent = font_metrics.descent * metrics_scale;
+        if let Some(obj) = &self.inline_object {
+            let ascent = match obj.align {
+                InlineObjectAlign::Baseline => obj.height - obj.baseline_offset,
+                InlineObjectAlign::Middle => (font_ascent - font_descent + obj.height) / 2.0,
+                InlineObjectAlign::Top => font_ascent,
+                InlineObjectAlign::Bottom => obj.height - font_descent,
+            };
+            return (ascent, obj.height - ascent);
+        }
+        let text_height = font_ascent + font_descent + font_metrics.leading * metrics_scale;
+        //TODO fix leading?
+        let text_base_line = font_ascent;
+        let line_height = line_height.unwrap_or(text_height);
+        let line_space = line_height - text_height;
+        let ascent = text_base_line + line_space / 2.0;
+        let baseline_shift = self.style.baseline_shift();
+        (
+            ascent - baseline_shift,
+            line_height - ascent + baseline_shift,
+        )
+    }
+
+    fn is_rtl(&self) -> bool {
+        self.level % 2 == 1
+    }
+
+    /// Right-to-left text, complex scripts and emoji sequences are shaped by swash, glyphs of
+    /// other text are mapped from chars directly
+    fn needs_shaping(&self) -> bool {
+        self.inline_object.is_none()
+            && (self.is_rtl()
+                || self.text.chars().any(|c| {
+                    // Pairs of regional indicators are flags
+                    c.script().is_complex()
+                        || is_emoji_modifier(c)
+                        || ('\u{1F1E6}'..='\u{1F1FF}').contains(&c)
+                }))
+    }
+
+    /// Block of chars in `start..end` with the same style
+    fn sub_block(&self, start: usize, end: usize) -> TextBlock {
+        TextBlock {
+            text: self.text.substring(start, end - start).to_string(),
+            style: self.style.clone(),
+            font: self.font.clone(),
+            inline_object: self.inline_object.clone(),
+            level: self.level,
+        }
+    }
+
+    /// Returns the advance widths of chars
+    fn measure(&self) -> Vec<f32> {
+        if self.needs_shaping() {
+            self.shape().0
+        } else {
+            self.measure_with_bounds().0
+        }
+    }
+
+    /// Returns the advance widths of chars and glyphs in visual order. The advance of a
+    /// cluster is taken by its first char, or split between the components of ligatures.
+    fn shape(&self) -> (Vec<f32>, Vec<ShapedGlyph>) {
+        if !self.needs_shaping() {
+            let glyphs = str_to_glyphs_vec(&self.font, &self.text);
+            let widths = self.measure_with_bounds().0;
+            let mut shaped_glyphs = Vec::with_capacity(glyphs.len());
+            let mut x = 0.0;
+            for (i, id) in glyphs.into_iter().enumerate() {
+                if id != 0 {
+                    shaped_glyphs.push(ShapedGlyph {
+                        id,
+                        x,
+                        y: 0.0,
+                        char_index: i,
+                    });
+                }
+                x += widths[i];
+            }
+            return (widths, shaped_glyphs);
+        }
+        let clusters = self.shape_clusters();
+        let mut widths = vec![0.0; self.text.chars_count()];
+        for (cluster, components) in &clusters {
+            if components.is_empty() {
+                widths[cluster.char_index] += cluster.advance;
+            } else {
+                let advance = cluster.advance / components.len() as f32;
+                for char_index in components {
+                    widths[*char_index] += advance;
+                }
+            }
+        }
+        let mut glyphs = Vec::new();
+        let mut x = 0.0;
+        let mut place_cluster = |cluster: &ShapedCluster| {
+            // Glyphs in a cluster are in logical order even if the text is right-to-left
+            let mut pen = x;
+            for g in &cluster.glyphs {
+                glyphs.push(ShapedGlyph {
+                    id: g.id,
+                    x: pen + g.x,
+                    y: -g.y,
+                    char_index: cluster.char_index,
+                });
+                pen += g.advance;
+            }
+            x += cluster.advance;
+        };
+        if self.is_rtl() {
+            clusters.iter().rev().for_each(|(c, _)| place_cluster(c));
+        } else {
+            clusters.iter().for_each(|(c, _)| place_cluster(c));
+        }
+        (widths, glyphs)
+    }
+
+    /// Shape the text with swash, returns clusters and the char indices of ligature components
+    fn shape_clusters(&self) -> Vec<(ShapedCluster, Vec<usize>)> {
+        let script = self
+            .text
+            .chars()
+            .map(|c| c.script())
+            .find(|s| is_real_script(*s))
+            .unwrap_or(Script::Latin);
+        let direction = if self.is_rtl() {
+            ShapeDirection::RightToLeft
+        } else {
+            ShapeDirection::LeftToRight
+        };
+        let letter_spacing = self.style.letter_spacing();
+        let word_spacing = self.style.word_spacing();
+        let mut char_indices = vec![0; self.text.len() + 1];
+        for (i, (byte_offset, _)) in self.text.char_indices().enumerate() {
+            char_indices[byte_offset] = i;
+        }
+        let mut clusters = Vec::new();
+        SHAPE_CONTEXT.with(|context| {
+            let mut context = context.borrow_mut();
+            let mut shaper = context
+                .builder(self.font.as_ref())
+                .script(script)
+                .direction(direction)
+                .size(self.style.font_size())
+                .build();
+            shaper.add_str(&self.text);
+            shaper.shape_with(|cluster| {
+                let source = cluster.source.to_range();
+                let mut advance = cluster.advance();
+                if !cluster.is_empty() {
+                    advance += letter_spacing;
+                }
+                if self.text[source.clone()].contains(|c| c == ' ' || c == '\u{00A0}') {
+                    advance += word_spacing;
+                }
+                let components = cluster
+                    .components
+                    .iter()
+                    .map(|c| char_indices[c.start as usize])
+                    .collect::<Vec<_>>();
+                clusters.push((
+                    ShapedCluster {
+                        char_index: char_indices[source.start],
+                        advance,
+                        glyphs: cluster.glyphs.to_vec(),
+                    },
+                    components,
+                ));
+            });
+        });
+        clusters
+    }
+
+    /// Returns the advance widths including spacing and the raster bounds of chars
+    fn measure_with_bounds(&self) -> (Vec<f32>, Vec<Rect>) {
+        if let Some(obj) = &self.inline_object {
+            return (
+                vec![obj.width],
+                vec![Rect::new(0.0, -obj.height, obj.width, 0.0)],
+            );
+        }
+        let glyphs = str_to_glyphs_vec(&self.font, &self.text);
+        let char_count = glyphs.len();
+        let mut widths = Vec::with_capacity(char_count);
+        let mut bounds = Vec::with_capacity(char_count);
+        unsafe {
+            widths.set_len(char_count);
+            bounds.set_len(char_count);
+        }
+        get_fixed_widths_bounds(
+            &self.font,
+            &glyphs,
+            &mut widths,
+            &mut bounds,
+            Some(&self.style.foreground()),
+            self.style.font_size(),
+        );
+        let letter_spacing = self.style.letter_spacing();
+        let word_spacing = self.style.word_spacing();
+        if letter_spacing != 0.0 || word_spacing != 0.0 {
+            for (i, ch) in self.text.chars().enumerate() {
+                if glyphs[i] == 0 {
+                    continue;
+                }
+                widths[i] += letter_spacing;
+                if ch == ' ' || ch == '\u{00A0}' {
+                    widths[i] += word_spacing;
+                }
+            }
+        }
+        (widths, bounds)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InlineObjectAlign {
+    /// Bottom of the object is `baseline_offset` above the baseline
+    Baseline,
+    /// Middle of the object is aligned with the middle of text
+    Middle,
+    /// Top of the object is aligned with the ascent of text
+    Top,
+    /// Bottom of the object is aligned with the descent of text
+    Bottom,
+}
+
+impl InlineObjectAlign {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "baseline" => Some(Self::Baseline),
+            "middle" => Some(Self::Middle),
+            "top" => Some(Self::Top),
+            "bottom" => Some(Self::Bottom),
+            _ => None,
+        }
+    }
+}
+
+/// Box flowing with text, such as inline images and placeholders of widgets
+#[derive(Clone)]
+pub struct InlineObject {
+    pub width: f32,
+    pub height: f32,
+    pub align: InlineObjectAlign,
+    pub baseline_offset: f32,
+    /// Image painted in the box, nothing is painted for placeholders
+    pub image: Option<ImageObject>,
+}
+
+impl InlineObject {
+    fn paint(&self, painter: &Painter, top_left: Point) {
+        let mut image = match &self.image {
+            Some(img) => img.clone(),
+            None => return,
+        };
+        image.set_container_size((self.width, self.height));
+        let renderer = image.render();
+        let canvas = painter.canvas;
+        canvas.save();
+        canvas.translate(top_left);
+        renderer.run(painter);
+        canvas.restore();
+    }
 }
 
 impl SimpleTextParagraph {
-    pub fn new(text_blocks: Vec<TextBlock>, line_height: Option<f32>) -> Self {
+    pub fn new(text_blocks: Vec<TextBlock>, params: &ParagraphParams) -> Self {
         let mut text = String::new();
         for text_block in &text_blocks {
             text.push_str(text_block.text.as_str());
         }
+        let rtl = params.direction == Direction::RTL;
+        let text_blocks = split_runs(text_blocks, &text, rtl);
 
         Self {
             text,
-            line_height,
+            line_height: params.line_height,
+            align: params.align,
+            rtl,
+            wrap: params.text_wrap != Some(false) && params.white_space.wraps(),
+            preserve_newlines: params.white_space.preserves_spaces(),
+            word_break: params.word_break,
+            ellipsis: params.text_overflow == TextOverflow::Ellipsis,
+            max_lines: None,
+            has_hidden_content: false,
             text_blocks,
             layout: None,
         }
     }
 
-    pub fn layout(&mut self, mut available_width: f32) {
-        available_width = available_width.de_nan(f32::INFINITY);
-
-        let mut left = 0.0;
-        let mut top = 0.0;
-        let mut char_offset = 0;
-        let mut max_intrinsic_width = 0.0;
+    /// Limit the count of lines, returns true if the layout should be recalculated
+    pub fn set_max_lines(&mut self, max_lines: Option<usize>, has_hidden_content: bool) -> bool {
+        let has_hidden_content = max_lines.is_some() && has_hidden_content;
+        if self.max_lines == max_lines && self.has_hidden_content == has_hidden_content {
+            return false;
+        }
+        self.max_lines = max_lines;
+        self.has_hidden_content = has_hidden_content;
+        true
+    }
 
-        let mut lines = Vec::new();
-        let mut current_line = TextLine::new(0, 0);
+    pub fn layout(&mut self, available_width: f32) {
+        let available_width = available_width.de_nan(f32::INFINITY);
 
+        let mut chars = Vec::new();
+        let mut widths = Vec::new();
+        let mut block_ranges = Vec::with_capacity(self.text_blocks.len());
         for tb in &self.text_blocks {
-            let glyphs = str_to_glyphs_vec(&tb.font, &tb.text);
-            let char_count = glyphs.len();
-            if char_count == 0 {
-                continue;
-            }
-            let mut widths = Vec::with_capacity(char_count);
-            let mut x_pos = Vec::with_capacity(char_count + 1);
-            let mut bounds = Vec::with_capacity(char_count);
-            unsafe {
-                widths.set_len(char_count);
-                bounds.set_len(char_count);
-                x_pos.set_len(char_count + 1);
+            let begin = chars.len();
+            chars.extend(tb.text.chars());
+            widths.append(&mut tb.measure());
+            block_ranges.push((begin, chars.len()));
+        }
+        let mut x_pos = Vec::with_capacity(widths.len() + 1);
+        x_pos.push(0.0);
+        for i in 0..widths.len() {
+            x_pos.push(x_pos[i] + widths[i]);
+        }
+
+        let wrap_width = if self.wrap {
+            available_width
+        } else {
+            f32::INFINITY
+        };
+        let mut ranges = break_lines(
+            &chars,
+            &x_pos,
+            wrap_width,
+            self.word_break,
+            self.preserve_newlines,
+        );
+        let lines_count = ranges.len();
+        let mut truncated = false;
+        if let Some(max_lines) = self.max_lines {
+            if ranges.len() > max_lines {
+                ranges.truncate(max_lines);
+                truncated = true;
+            } else if ranges.len() == max_lines {
+                truncated = self.has_hidden_content;
             }
-            get_fixed_widths_bounds(
-                &tb.font,
-                &glyphs,
-                &mut widths,
-                &mut bounds,
-                Some(&tb.style.foreground()),
-                tb.style.font_size(),
-            );
-            x_pos[0] = 0.0;
-            for i in 0..char_count {
-                if glyphs[i] == 0 {
-                    widths[i] = 0.0;
+        }
+
+        let mut top = 0.0;
+        let mut max_intrinsic_width = 0.0;
+        let mut lines = Vec::with_capacity(ranges.len());
+        for (line_number, &(start, line_end)) in ranges.iter().enumerate() {
+            let is_last = line_number == ranges.len() - 1;
+            let mut current_line = TextLine::new(line_number, start);
+            current_line.y = top;
+
+            let mut end = line_end;
+            let mut ellipsis = None;
+            let overflow = x_pos[trim_hanging(&chars, start, end)] - x_pos[start] > available_width;
+            if self.ellipsis && end > start && (overflow || (is_last && truncated)) {
+                let block_idx = block_ranges
+                    .iter()
+                    .position(|(_, block_end)| *block_end >= end)
+                    .unwrap_or(self.text_blocks.len() - 1);
+                let block = Self::build_ellipsis(&self.text_blocks[block_idx]);
+                let ellipsis_width = block.measure().iter().sum::<f32>();
+                while end > start && x_pos[end] - x_pos[start] + ellipsis_width > available_width {
+                    end -= 1;
                 }
-                x_pos[i + 1] = x_pos[i] + widths[i];
+                end = trim_hanging(&chars, start, end);
+                ellipsis = Some((block, ellipsis_width));
             }
+            let ellipsis_width = ellipsis.as_ref().map(|(_, w)| *w).unwrap_or(0.0);
 
-            let font_metrics = tb.font.metrics();
-            let metrics_scale = tb.style.font_size() / font_metrics.units_per_em as f32;
-            let mut consumed_char_count = 0;
-            while consumed_char_count < char_count {
-                let mut cc = calculate_line_char_count(
-                    &x_pos[consumed_char_count..],
-                    available_width - left,
-                );
-                if cc == 0 && left == 0.0 {
-                    cc = 1;
+            // Spaces at the end of line are not aligned
+            let content_end = trim_hanging(&chars, start, end);
+            let content_width = x_pos[content_end] - x_pos[start] + ellipsis_width;
+            let free_space = if available_width.is_finite() {
+                f32::max(available_width - content_width, 0.0)
+            } else {
+                0.0
+            };
+            // Start and end of lines depend on the base direction
+            let start_offset = if self.rtl { free_space } else { 0.0 };
+            let (offset, extra_word_spacing) = match self.align {
+                TextAlign::Left => (0.0, 0.0),
+                TextAlign::Right => (free_space, 0.0),
+                TextAlign::Start => (start_offset, 0.0),
+                TextAlign::End => (free_space - start_offset, 0.0),
+                TextAlign::Center => (free_space / 2.0, 0.0),
+                TextAlign::Justify => {
+                    // The last line and lines ending with newline are not justified
+                    let ends_paragraph = line_number == lines_count - 1
+                        || (self.preserve_newlines && chars[line_end - 1] == '\n');
+                    let spaces = count_word_separators(&chars[start..content_end]);
+                    if ends_paragraph || ellipsis.is_some() || spaces == 0 {
+                        (start_offset, 0.0)
+                    } else {
+                        (0.0, free_space / spaces as f32)
+                    }
                 }
-                if cc == 0 {
-                    let next_line_number = current_line.line_number + 1;
-                    left = 0.0;
-                    top += current_line.height;
-                    lines.push(current_line);
-                    current_line = TextLine::new(next_line_number, char_offset);
-                    current_line.y = top;
-                    continue;
+            };
+
+            // Units in logical order, spaces at the end of line take the base level
+            let base_level = if self.rtl { 1 } else { 0 };
+            let mut unit_widths = Vec::new();
+            for (tb, &(block_start, block_end)) in self.text_blocks.iter().zip(&block_ranges) {
+                for (range_start, range_end) in [(start, content_end), (content_end, end)] {
+                    let unit_start = usize::max(range_start, block_start);
+                    let unit_end = usize::min(range_end, block_end);
+                    if unit_start >= unit_end {
+                        continue;
+                    }
+                    let mut block = tb.sub_block(unit_start - block_start, unit_end - block_start);
+                    if range_start == content_end {
+                        block.level = base_level;
+                    }
+                    if extra_word_spacing != 0.0 {
+                        let word_spacing = block.style.word_spacing();
+                        block
+                            .style
+                            .set_word_spacing(word_spacing + extra_word_spacing);
+                    }
+                    let spaces = count_word_separators(&chars[unit_start..unit_end]);
+                    unit_widths.push(
+                        x_pos[unit_end] - x_pos[unit_start] + spaces as f32 * extra_word_spacing,
+                    );
+                    current_line.units.push(LineUnit {
+                        block,
+                        x: 0.0,
+                        char_offset: unit_start,
+                    });
+                    current_line.update_metrics(tb.ascent_descent(self.line_height));
                 }
-                current_line.units.push(LineUnit {
-                    block: TextBlock {
-                        text: tb.text.substring(consumed_char_count, cc).to_string(),
-                        style: tb.style.clone(),
-                        font: tb.font.clone(),
-                    },
-                    x: left,
-                    char_offset,
+            }
+
+            // Ellipsis is placed at the end of line, which is the left side of right-to-left
+            // lines, and so are the spaces at the end of line
+            let mut x = if self.rtl {
+                let hanging_width = current_line
+                    .units
+                    .iter()
+                    .zip(&unit_widths)
+                    .filter(|(u, _)| u.char_offset >= content_end)
+                    .map(|(_, w)| *w)
+                    .sum::<f32>();
+                offset + ellipsis_width - hanging_width
+            } else {
+                offset
+            };
+            let levels = current_line
+                .units
+                .iter()
+                .map(|u| Level::from(u.block.level))
+                .collect::<Vec<_>>();
+            for idx in BidiInfo::reorder_visual(&levels) {
+                current_line.units[idx].x = x;
+                x += unit_widths[idx];
+            }
+            if let Some((block, _)) = ellipsis {
+                let ellipsis_x = if self.rtl { offset } else { x };
+                current_line.update_metrics(block.ascent_descent(self.line_height));
+                current_line.ellipsis = Some(LineUnit {
+                    block,
+                    x: ellipsis_x,
+                    char_offset: end,
                 });
-                char_offset += cc;
-                left += x_pos[consumed_char_count + cc - 1] - x_pos[consumed_char_count]
-                    + widths[consumed_char_count + cc - 1];
-                consumed_char_count += cc;
-                let text_height =
-                    (font_metrics.ascent + font_metrics.descent + font_metrics.leading)
-                        * metrics_scale;
-                //TODO fix leading?
-                let text_base_line = font_metrics.ascent * metrics_scale /* + font_metrics.leading */;
-                let line_height = self.line_height.unwrap_or(text_height);
-                let line_space = line_height - text_height;
-                current_line.baseline =
-                    f32::max(current_line.baseline, text_base_line + line_space / 2.0);
-                current_line.height = f32::max(current_line.height, line_height);
-                max_intrinsic_width = f32::max(max_intrinsic_width, left);
             }
+            max_intrinsic_width = f32::max(
+                max_intrinsic_width,
+                x_pos[end] - x_pos[start] + ellipsis_width,
+            );
+            top += current_line.height;
+            lines.push(current_line);
         }
-        let height = current_line.y + current_line.height;
-        lines.push(current_line);
         self.layout = Some(Arc::new(TextLayout {
             max_intrinsic_width,
-            height,
+            height: top,
             lines,
         }));
     }
 
+    /// Ellipsis with the style of `block`, dots are used if the font has no ellipsis char
+    fn build_ellipsis(block: &TextBlock) -> TextBlock {
+        let ellipsis_char = ELLIPSIS.chars().next().unwrap();
+        let text = if block.font.glyph_for_char(ellipsis_char).unwrap_or(0) != 0 {
+            ELLIPSIS
+        } else {
+            "..."
+        };
+        TextBlock {
+            text: text.to_string(),
+            style: block.style.clone(),
+            font: block.font.clone(),
+            inline_object: None,
+            level: block.level,
+        }
+    }
+
+    pub fn line_count(&self) -> usize {
+        match &self.layout {
+            Some(layout) => layout.lines.len(),
+            None => 0,
+        }
+    }
+
+    /// Char offsets where the visual lines start
+    pub fn get_line_start_offsets(&self) -> Vec<usize> {
+        match &self.layout {
+            Some(layout) => layout.lines.iter().map(|ln| ln.char_offset).collect(),
+            None => Vec::new(),
+        }
+    }
+
     pub fn height(&self) -> f32 {
         match &self.layout {
             Some(layout) => layout.height,
@@ -461,6 +1058,8 @@ impl SimpleTextParagraph {
         }
     }
 
+    /// Returns the offset of the char under `coord` or the nearest char on the line. Later chars
+    /// win ties so that the zero width space at the end of paragraph can be hit.
     pub fn get_char_offset_at_coordinate(&self, coord: (f32, f32)) -> usize {
         let (x, y) = coord;
         if y < 0.0 {
@@ -468,29 +1067,29 @@ impl SimpleTextParagraph {
         }
         let layout = some_or_return!(self.layout.as_ref(), 0);
         for ln in layout.lines.iter().rev() {
-            if ln.y > coord.1 {
+            if ln.y > y {
                 continue;
             }
-            if x < 0.0 {
-                return ln.char_offset;
-            }
-            for unit in ln.units.iter().rev() {
-                if unit.x > coord.0 {
-                    continue;
-                }
-                let inner_x = x - unit.x;
-                let inner_bounds = unit.get_inner_layout_bounds(false);
-                let inner_bounds_len = inner_bounds.len();
-                for b in 0..inner_bounds_len {
-                    if inner_bounds[b].bounds_with_offset().right > inner_x {
-                        return unit.char_offset + b;
+            let mut char_offset = ln.char_offset;
+            let mut min_distance = f32::INFINITY;
+            for unit in &ln.units {
+                for (i, b) in unit.get_inner_layout_bounds(false).iter().enumerate() {
+                    let bounds = b.bounds_with_offset();
+                    let (left, right) = (unit.x + bounds.left, unit.x + bounds.right);
+                    let distance = if x < left {
+                        left - x
+                    } else {
+                        f32::max(x - right, 0.0)
+                    };
+                    if distance <= min_distance {
+                        min_distance = distance;
+                        char_offset = unit.char_offset + i;
                     }
                 }
-                return unit.char_offset;
             }
-            return ln.char_offset;
+            return char_offset;
         }
-        return 0;
+        0
     }
 
     pub fn get_text(&self) -> &str {
@@ -510,6 +1109,205 @@ impl SimpleTextParagraph {
     }
 }
 
+/// Returns char ranges of lines, `x_pos` contains the start position of each char and
+/// the end position of the last char. Words wider than the line are broken between chars.
+fn break_lines(
+    chars: &[char],
+    x_pos: &[f32],
+    available_width: f32,
+    word_break: WordBreak,
+    preserve_newlines: bool,
+) -> Vec<(usize, usize)> {
+    let mut ranges = Vec::new();
+    let mut line_start = 0;
+    let mut break_pos = None;
+    let mut i = 0;
+    while i < chars.len() {
+        if i > line_start && is_break_opportunity(chars[i - 1], chars[i], word_break) {
+            break_pos = Some(i);
+        }
+        if preserve_newlines && chars[i] == '\n' {
+            ranges.push((line_start, i + 1));
+            line_start = i + 1;
+            break_pos = None;
+            i += 1;
+            continue;
+        }
+        // Spaces at the end of line are allowed to overflow
+        let overflow = x_pos[i + 1] - x_pos[line_start] > available_width;
+        if overflow && i > line_start && !is_hanging(chars[i]) {
+            let end = break_pos.unwrap_or(i);
+            ranges.push((line_start, end));
+            line_start = end;
+            break_pos = None;
+            i = end;
+            continue;
+        }
+        i += 1;
+    }
+    if line_start < chars.len() || ranges.is_empty() {
+        ranges.push((line_start, chars.len()));
+    }
+    ranges
+}
+
+fn is_break_opportunity(prev: char, next: char, word_break: WordBreak) -> bool {
+    if word_break == WordBreak::BreakAll || prev == '-' || is_hanging(prev) {
+        return true;
+    }
+    // Inline objects are atoms
+    if prev == '\u{FFFC}' || next == '\u{FFFC}' {
+        return true;
+    }
+    word_break == WordBreak::Normal
+        && (is_cjk(prev) || is_cjk(next))
+        && !is_closing_punctuation(next)
+}
+
+fn is_hanging(ch: char) -> bool {
+    ch.is_whitespace() || ch == ZERO_WIDTH_SPACE
+}
+
+/// Returns the end of chars in `start..end` without hanging chars at the end
+fn trim_hanging(chars: &[char], start: usize, mut end: usize) -> usize {
+    while end > start && is_hanging(chars[end - 1]) {
+        end -= 1;
+    }
+    end
+}
+
+/// Split blocks into runs of the same bidi level and script, so that each run is shaped
+/// with one direction and script
+fn split_runs(text_blocks: Vec<TextBlock>, text: &str, rtl: bool) -> Vec<TextBlock> {
+    let base_level = if rtl { Level::rtl() } else { Level::ltr() };
+    let bidi_info = BidiInfo::new(text, Some(base_level));
+    let levels = text
+        .char_indices()
+        .map(|(i, _)| bidi_info.levels[i].number())
+        .collect::<Vec<_>>();
+    let mut runs = Vec::with_capacity(text_blocks.len());
+    let mut offset = 0;
+    for mut tb in text_blocks {
+        let chars = tb.text.chars().collect::<Vec<_>>();
+        let block_levels = &levels[offset..offset + chars.len()];
+        offset += chars.len();
+        if chars.is_empty() || tb.inline_object.is_some() {
+            tb.level = block_levels.first().copied().unwrap_or(base_level.number());
+            runs.push(tb);
+            continue;
+        }
+        let mut run_start = 0;
+        let mut script = None;
+        for i in 0..chars.len() {
+            let char_script = Some(chars[i].script()).filter(|s| is_real_script(*s));
+            let script_changed = script.is_some() && char_script.is_some() && script != char_script;
+            if block_levels[i] != block_levels[run_start] || script_changed {
+                let mut run = tb.sub_block(run_start, i);
+                run.level = block_levels[run_start];
+                runs.push(run);
+                run_start = i;
+                script = None;
+            }
+            script = char_script.or(script);
+        }
+        if run_start == 0 {
+            tb.level = block_levels[0];
+            runs.push(tb);
+        } else {
+            let mut run = tb.sub_block(run_start, chars.len());
+            run.level = block_levels[run_start];
+            runs.push(run);
+        }
+    }
+    runs
+}
+
+/// Common and inherited chars take the script of chars around them
+fn is_real_script(script: Script) -> bool {
+    !matches!(script, Script::Common | Script::Inherited | Script::Unknown)
+}
+
+fn is_cjk(ch: char) -> bool {
+    matches!(ch as u32,
+        0x2E80..=0x2FFF
+        | 0x3040..=0x30FF
+        | 0x3400..=0x4DBF
+        | 0x4E00..=0x9FFF
+        | 0xAC00..=0xD7AF
+        | 0xF900..=0xFAFF
+        | 0x20000..=0x2FFFF)
+}
+
+fn is_closing_punctuation(ch: char) -> bool {
+    matches!(
+        ch,
+        ',' | '.'
+            | ';'
+            | ':'
+            | '?'
+            | '!'
+            | ')'
+            | ']'
+            | '}'
+            | '，'
+            | '。'
+            | '、'
+            | '；'
+            | '：'
+            | '？'
+            | '！'
+            | '）'
+            | '」'
+            | '』'
+            | '】'
+            | '》'
+            | '〉'
+    )
+}
+
+fn count_word_separators(chars: &[char]) -> usize {
+    chars
+        .iter()
+        .filter(|c| **c == ' ' || **c == '\u{00A0}')
+        .count()
+}
+
+/// Draw a wave along `y` between the horizontal bounds, the wave is clipped to the bounds
+pub fn draw_wavy_line(
+    painter: &Painter,
+    (left, right): (f32, f32),
+    y: f32,
+    thickness: f32,
+    paint: &Paint,
+) {
+    let amplitude = thickness * 1.5;
+    let half_wave = thickness * 3.0;
+    let mut path = Path::new();
+    path.move_to((left, y));
+    let mut x = left;
+    let mut up = true;
+    while x < right {
+        let control_y = if up { y - amplitude } else { y + amplitude };
+        path.quad_to((x + half_wave / 2.0, control_y), (x + half_wave, y));
+        x += half_wave;
+        up = !up;
+    }
+    let canvas = painter.canvas;
+    canvas.save();
+    canvas.clip_rect(
+        &Rect::new(
+            left,
+            y - amplitude - thickness,
+            right,
+            y + amplitude + thickness,
+        ),
+        None,
+        None,
+    );
+    canvas.draw_path(&path, paint);
+    canvas.restore();
+}
+
 pub fn get_fixed_widths_bounds(
     font: &Font,
     glyphs: &[GlyphId],
@@ -1,12 +1,18 @@
 use crate as deft;
 use crate::base::EventContext;
 use crate::element::common::editable::Editable;
+use crate::element::spell_checker::SpellChecker;
 use crate::element::{Element, ElementBackend, ElementWeak};
 use crate::event::Event;
 use crate::style::length::{Length, LengthOrPercent};
 use crate::style::{FixedStyleProp, ResolvedStyleProp, StylePropVal};
+use crate::text::textbox::decoration::DecorationRange;
+use crate::text::textbox::find::FindOptions;
+use crate::text::textbox::{TextCoord, TextElement};
+use accesskit::{Action, ActionData, ActionRequest, Node, Role};
 use deft_macros::{element_backend, js_methods};
 use std::collections::HashMap;
+use std::rc::Rc;
 
 #[element_backend]
 pub struct TextEdit {
@@ -27,6 +33,11 @@ impl TextEdit {
         self.editable.set_text(text);
     }
 
+    #[js_func]
+    pub fn insert_elements(&mut self, elements: Vec<TextElement>) {
+        self.editable.insert_elements(elements);
+    }
+
     #[js_func]
     pub fn set_placeholder(&mut self, placeholder: String) {
         self.editable.set_placeholder(placeholder);
@@ -46,6 +57,50 @@ impl TextEdit {
     pub fn set_caret_by_char_offset(&mut self, char_offset: usize) {
         self.editable.set_caret_by_char_offset(char_offset);
     }
+
+    /// Highlight occurrences of `query`, the first match is activated and scrolled into view
+    #[js_func]
+    pub fn find(
+        &mut self,
+        query: String,
+        options: Option<FindOptions>,
+    ) -> Vec<(TextCoord, TextCoord)> {
+        self.editable.find(query, options)
+    }
+
+    #[js_func]
+    pub fn set_active_match(&mut self, index: usize) {
+        self.editable.set_active_match(index);
+    }
+
+    #[js_func]
+    pub fn clear_find(&mut self) {
+        self.editable.clear_find();
+    }
+
+    #[js_func]
+    pub fn add_decoration(&mut self, decoration: DecorationRange) {
+        self.editable.add_decoration(decoration);
+    }
+
+    #[js_func]
+    pub fn remove_decorations(&mut self, group: Option<String>) {
+        self.editable.remove_decorations(group);
+    }
+
+    #[js_func]
+    pub fn get_decorations(&self) -> Vec<DecorationRange> {
+        self.editable.get_decorations()
+    }
+
+    #[js_func]
+    pub fn get_spelling_suggestions(&self, char_offset: usize) -> Vec<String> {
+        self.editable.get_spelling_suggestions(char_offset)
+    }
+
+    pub fn set_spell_checker(&mut self, checker: Option<Rc<dyn SpellChecker>>) {
+        self.editable.set_spell_checker(checker);
+    }
 }
 
 impl ElementBackend for TextEdit {
@@ -94,7 +149,7 @@ impl ElementBackend for TextEdit {
         ctx: &mut EventContext<ElementWeak>,
     ) -> bool {
         if ctx.target == self.element {
-            return self.editable.on_execute_default_behavior(event);
+            return self.editable.on_execute_default_behavior(event, ctx);
         }
         false
     }
@@ -102,4 +157,25 @@ impl ElementBackend for TextEdit {
     fn accept_pseudo_element_styles(&mut self, styles: HashMap<String, Vec<ResolvedStyleProp>>) {
         self.editable.accept_pseudo_element_styles(styles);
     }
+
+    fn build_accessibility_node(&mut self, node: &mut Node) -> bool {
+        node.set_role(Role::MultilineTextInput);
+        node.set_value(self.get_text());
+        let placeholder = self.get_placeholder();
+        if !placeholder.is_empty() {
+            node.set_placeholder(placeholder);
+        }
+        node.add_action(Action::SetValue);
+        false
+    }
+
+    fn do_accessibility_action(&mut self, request: &ActionRequest) -> bool {
+        match (request.action, &request.data) {
+            (Action::SetValue, Some(ActionData::Value(value))) => {
+                self.set_text(value.to_string());
+                true
+            }
+            _ => false,
+        }
+    }
 }
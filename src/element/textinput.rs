@@ -1,13 +1,17 @@
 use crate as deft;
 use crate::base::EventContext;
 use crate::element::common::editable::{Editable, InputType};
+use crate::element::spell_checker::SpellChecker;
 use crate::element::{Element, ElementBackend, ElementWeak};
 use crate::event::Event;
 use crate::ok_or_return;
 use crate::style::length::LengthOrPercent;
 use crate::style::{FixedStyleProp, ResolvedStyleProp, StylePropVal};
+use crate::text::textbox::decoration::DecorationRange;
+use accesskit::{Action, ActionData, ActionRequest, Node, Role};
 use deft_macros::{element_backend, js_methods};
 use std::collections::HashMap;
+use std::rc::Rc;
 use yoga::FlexDirection;
 
 #[element_backend]
@@ -53,6 +57,35 @@ impl TextInput {
     pub fn get_type(&self) -> InputType {
         self.editable.get_type()
     }
+
+    #[js_func]
+    pub fn set_caret_by_char_offset(&mut self, char_offset: usize) {
+        self.editable.set_caret_by_char_offset(char_offset);
+    }
+
+    #[js_func]
+    pub fn add_decoration(&mut self, decoration: DecorationRange) {
+        self.editable.add_decoration(decoration);
+    }
+
+    #[js_func]
+    pub fn remove_decorations(&mut self, group: Option<String>) {
+        self.editable.remove_decorations(group);
+    }
+
+    #[js_func]
+    pub fn get_decorations(&self) -> Vec<DecorationRange> {
+        self.editable.get_decorations()
+    }
+
+    #[js_func]
+    pub fn get_spelling_suggestions(&self, char_offset: usize) -> Vec<String> {
+        self.editable.get_spelling_suggestions(char_offset)
+    }
+
+    pub fn set_spell_checker(&mut self, checker: Option<Rc<dyn SpellChecker>>) {
+        self.editable.set_spell_checker(checker);
+    }
 }
 
 impl ElementBackend for TextInput {
@@ -105,7 +138,7 @@ impl ElementBackend for TextInput {
         ctx: &mut EventContext<ElementWeak>,
     ) -> bool {
         if ctx.target == self.element {
-            return self.editable.on_execute_default_behavior(event);
+            return self.editable.on_execute_default_behavior(event, ctx);
         }
         false
     }
@@ -113,4 +146,31 @@ impl ElementBackend for TextInput {
     fn accept_pseudo_element_styles(&mut self, styles: HashMap<String, Vec<ResolvedStyleProp>>) {
         self.editable.accept_pseudo_element_styles(styles);
     }
+
+    fn build_accessibility_node(&mut self, node: &mut Node) -> bool {
+        match self.editable.get_type() {
+            InputType::Text => {
+                node.set_role(Role::TextInput);
+                node.set_value(self.get_text());
+            }
+            // Never expose the content of a password
+            InputType::Password => node.set_role(Role::PasswordInput),
+        }
+        let placeholder = self.get_placeholder();
+        if !placeholder.is_empty() {
+            node.set_placeholder(placeholder);
+        }
+        node.add_action(Action::SetValue);
+        false
+    }
+
+    fn do_accessibility_action(&mut self, request: &ActionRequest) -> bool {
+        match (request.action, &request.data) {
+            (Action::SetValue, Some(ActionData::Value(value))) => {
+                self.set_text(value.to_string());
+                true
+            }
+            _ => false,
+        }
+    }
 }
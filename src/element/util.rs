@@ -1,6 +1,7 @@
 use crate::element::Element;
 use crate::event::{
-    ClickEvent, Event, KeyDownEvent, KeyUpEvent, MouseDownEvent, MouseUpEvent, TextInputEvent,
+    ClickEvent, Event, ImePreeditEvent, KeyDownEvent, KeyUpEvent, MouseDownEvent, MouseUpEvent,
+    TextInputEvent,
 };
 
 pub fn is_form_event(event: &Event) -> bool {
@@ -8,6 +9,7 @@ pub fn is_form_event(event: &Event) -> bool {
         || MouseDownEvent::is(event)
         || MouseUpEvent::is(event)
         || TextInputEvent::is(event)
+        || ImePreeditEvent::is(event)
         || KeyDownEvent::is(event)
         || KeyUpEvent::is(event)
 }
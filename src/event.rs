@@ -74,18 +74,26 @@ pub fn build_modifier(state: &ModifiersState) -> u32 {
 }
 
 pub fn str_to_named_key(key: &str) -> Option<NamedKey> {
-    macro_rules! str_to_named {
-        ($key: expr => $($name: ident,)*) => {
-            match $key {
-                $(
-                    stringify!($name) => Some(NamedKey::$name),
-                )*
-                _ => None,
-            }
-        };
+    find_named_key(|name| name == key).map(|(_, k)| k)
+}
+
+/// Finds a named key by its name ignoring case, e.g. `pageup`, returning its canonical name
+pub fn find_named_key_ignore_case(key: &str) -> Option<(&'static str, NamedKey)> {
+    find_named_key(|name| name.eq_ignore_ascii_case(key))
+}
+
+fn find_named_key(matches: impl Fn(&str) -> bool) -> Option<(&'static str, NamedKey)> {
+    macro_rules! find_named {
+        ($($name: ident,)*) => {{
+            $(
+                if matches(stringify!($name)) {
+                    return Some((stringify!($name), NamedKey::$name));
+                }
+            )*
+            None
+        }};
     }
-    str_to_named!(
-        key =>
+    find_named!(
         Alt,
         AltGraph,
         CapsLock,
@@ -720,6 +728,15 @@ pub fn named_key_to_str(key: &NamedKey) -> &'static str {
 #[event]
 pub struct TextInputEvent(pub String);
 
+/// Composition text of the input method, which is not committed yet. An empty text ends
+/// the composition.
+#[event]
+pub struct ImePreeditEvent {
+    pub text: String,
+    /// Byte range of the cursor in `text`, the cursor is hidden if None
+    pub cursor: Option<(usize, usize)>,
+}
+
 #[event]
 pub struct ClickEvent(pub MouseDetail);
 
@@ -805,6 +822,32 @@ pub struct ScrollEvent {
     pub scroll_left: f32,
 }
 
+#[event]
+pub struct AnimationStartEvent {
+    pub animation_name: String,
+    /// Seconds the animation had been running, excluding delay
+    pub elapsed_time: f32,
+}
+
+#[event]
+pub struct AnimationIterationEvent {
+    pub animation_name: String,
+    pub elapsed_time: f32,
+}
+
+#[event]
+pub struct AnimationEndEvent {
+    pub animation_name: String,
+    pub elapsed_time: f32,
+}
+
+/// Emitted by rich text elements when a text unit with a link is clicked, labels and
+/// editable fields ignore links
+#[event]
+pub struct LinkClickEvent {
+    pub url: String,
+}
+
 #[event]
 pub struct DragStartEvent;
 
@@ -6,7 +6,7 @@ use crate::window::WindowHandle;
 use deft_macros::js_methods;
 use native_dialog::FileDialog;
 use quick_js::JsValue;
-use raw_window_handle::{HasRawWindowHandle, RawWindowHandle};
+use raw_window_handle::RawWindowHandle;
 use serde::{Deserialize, Serialize};
 use std::thread;
 
@@ -37,7 +37,7 @@ pub type ElementId = i32;
 
 pub type WindowId = i32;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct WindowAttrs {
     pub width: Option<f32>,
@@ -53,6 +53,7 @@ pub struct WindowAttrs {
     pub minimizable: Option<bool>,
     pub maximizable: Option<bool>,
     pub closable: Option<bool>,
+    pub headless: Option<bool>,
 }
 
 js_deserialize!(WindowAttrs);
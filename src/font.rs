@@ -11,7 +11,103 @@ use std::path::Path;
 use swash::scale::image::Image;
 use swash::scale::{Render, ScaleContext, Source, StrikeWith};
 use swash::zeno::{Angle, Format, Transform};
-use swash::{Attributes, CacheKey, Charmap, FontRef, GlyphId, Metrics, Style, Weight};
+use swash::{
+    tag_from_bytes, Attributes, CacheKey, Charmap, FontRef, GlyphId, Metrics, Style, Weight,
+};
+
+/// Whether `ch` is presented as emoji by default, i.e. has the Emoji_Presentation property
+pub fn is_emoji(ch: char) -> bool {
+    matches!(
+        ch as u32,
+        0x231A..=0x231B
+            | 0x23E9..=0x23EC
+            | 0x23F0
+            | 0x23F3
+            | 0x25FD..=0x25FE
+            | 0x2614..=0x2615
+            | 0x2648..=0x2653
+            | 0x267F
+            | 0x2693
+            | 0x26A1
+            | 0x26AA..=0x26AB
+            | 0x26BD..=0x26BE
+            | 0x26C4..=0x26C5
+            | 0x26CE
+            | 0x26D4
+            | 0x26EA
+            | 0x26F2..=0x26F3
+            | 0x26F5
+            | 0x26FA
+            | 0x26FD
+            | 0x2705
+            | 0x270A..=0x270B
+            | 0x2728
+            | 0x274C
+            | 0x274E
+            | 0x2753..=0x2755
+            | 0x2757
+            | 0x2795..=0x2797
+            | 0x27B0
+            | 0x27BF
+            | 0x2B1B..=0x2B1C
+            | 0x2B50
+            | 0x2B55
+            | 0x1F004
+            | 0x1F0CF
+            | 0x1F18E
+            | 0x1F191..=0x1F19A
+            | 0x1F1E6..=0x1F1FF
+            | 0x1F201
+            | 0x1F21A
+            | 0x1F22F
+            | 0x1F232..=0x1F236
+            | 0x1F238..=0x1F23A
+            | 0x1F250..=0x1F251
+            | 0x1F300..=0x1F320
+            | 0x1F32D..=0x1F335
+            | 0x1F337..=0x1F37C
+            | 0x1F37E..=0x1F393
+            | 0x1F3A0..=0x1F3CA
+            | 0x1F3CF..=0x1F3D3
+            | 0x1F3E0..=0x1F3F0
+            | 0x1F3F4
+            | 0x1F3F8..=0x1F43E
+            | 0x1F440
+            | 0x1F442..=0x1F4FC
+            | 0x1F4FF..=0x1F53D
+            | 0x1F54B..=0x1F54E
+            | 0x1F550..=0x1F567
+            | 0x1F57A
+            | 0x1F595..=0x1F596
+            | 0x1F5A4
+            | 0x1F5FB..=0x1F64F
+            | 0x1F680..=0x1F6C5
+            | 0x1F6CC
+            | 0x1F6D0..=0x1F6D2
+            | 0x1F6D5..=0x1F6D7
+            | 0x1F6DC..=0x1F6DF
+            | 0x1F6EB..=0x1F6EC
+            | 0x1F6F4..=0x1F6FC
+            | 0x1F7E0..=0x1F7EB
+            | 0x1F7F0
+            | 0x1F90C..=0x1F93A
+            | 0x1F93C..=0x1F945
+            | 0x1F947..=0x1F9FF
+            | 0x1FA70..=0x1FA7C
+            | 0x1FA80..=0x1FA88
+            | 0x1FA90..=0x1FABD
+            | 0x1FABF..=0x1FAC5
+            | 0x1FACE..=0x1FADB
+            | 0x1FAE0..=0x1FAE8
+            | 0x1FAF0..=0x1FAF8
+    )
+}
+
+/// Whether `ch` combines with the emoji before it, such as joiners, variation selectors
+/// and skin tone modifiers
+pub fn is_emoji_modifier(ch: char) -> bool {
+    matches!(ch as u32, 0x200D | 0x20E3 | 0xFE0E | 0xFE0F | 0x1F3FB..=0x1F3FF | 0xE0020..=0xE007F)
+}
 
 enum FontContent {
     Mmap(Mmap),
@@ -88,6 +184,15 @@ impl Font {
         .to_ref()
     }
 
+    /// Weight used for font matching, may differ from the weight of font file if synthesized
+    pub fn weight(&self) -> Weight {
+        self.weight
+    }
+
+    pub fn style(&self) -> Style {
+        self.style
+    }
+
     // As a convenience, you may want to forward some methods.
     pub fn attributes(&self) -> Attributes {
         self.as_ref().attributes()
@@ -115,6 +220,18 @@ impl Font {
         Some(self.charmap().map(c))
     }
 
+    pub fn has_glyph(&self, c: char) -> bool {
+        self.charmap().map(c) != 0
+    }
+
+    /// Whether the font has color glyphs, which are COLR outlines or CBDT and sbix bitmaps
+    pub fn has_color_glyphs(&self) -> bool {
+        let font = self.as_ref();
+        [b"COLR", b"CBDT", b"sbix"]
+            .iter()
+            .any(|tag| font.table(tag_from_bytes(tag)).is_some())
+    }
+
     pub fn rasterize_glyph(&self, glyph_id: GlyphId, font_size: f32) -> Option<Image> {
         let mut context = ScaleContext::new();
         let mut scaler = context
@@ -125,9 +242,9 @@ impl Font {
         let mut render = Render::new(&[
             // Color outline with the first palette
             Source::ColorOutline(0),
-            Source::Bitmap(StrikeWith::BestFit),
-            // Color bitmap with best fit selection mode
+            // Color bitmap with best fit selection mode, which is scaled to the font size
             Source::ColorBitmap(StrikeWith::BestFit),
+            Source::Bitmap(StrikeWith::BestFit),
             // Standard scalable outline
             Source::Outline,
         ]);
@@ -1,3 +1,4 @@
+use crate::event_loop::create_event_loop_fn_mut;
 use anyhow::Error;
 use image::{DynamicImage, EncodableLayout, ImageReader};
 use libc::memcpy;
@@ -8,36 +9,89 @@ use std::cell::RefCell;
 use std::collections::HashMap;
 use std::ffi::c_void;
 use std::rc::{Rc, Weak};
+use std::thread;
 
 thread_local! {
     pub static IMG_MANAGER: ImgManager = ImgManager::new();
 }
 
+type LoadCallback = Box<dyn FnOnce(Option<Image>)>;
+
 pub struct ImgManager {
     cache: RefCell<HashMap<String, Weak<Image>>>,
+    /// Callbacks waiting for images being loaded in background, keyed by path
+    pending: RefCell<HashMap<String, Vec<LoadCallback>>>,
 }
 
 impl ImgManager {
     pub fn new() -> Self {
         Self {
             cache: RefCell::new(HashMap::new()),
+            pending: RefCell::new(HashMap::new()),
         }
     }
     pub fn load_img(&self, path: &str) -> Result<Image, Error> {
-        if let Some(cached_img) = self.cache.borrow().get(path) {
-            if let Some(img) = cached_img.upgrade() {
-                let i: &Image = img.borrow();
-                let ii = i.clone();
-                return Ok(ii);
-            }
+        if let Some(img) = self.get_cached(path) {
+            return Ok(img);
         }
-        let img = ImageReader::open(path)?.decode()?;
+        let img = decode_img(path)?;
         let sk_img = dyn_image_to_skia_image(&img);
-        let rc = Rc::new(sk_img.clone());
+        self.put_cache(path, &sk_img);
+        Ok(sk_img)
+    }
+
+    /// Load image in background and call `callback` in the event loop with it, or None if it
+    /// fails to load. Cached images are passed immediately and requests of a path being loaded
+    /// share the load.
+    pub fn load_img_async<F: FnOnce(Option<Image>) + 'static>(&self, path: &str, callback: F) {
+        if let Some(img) = self.get_cached(path) {
+            callback(Some(img));
+            return;
+        }
+        let mut pending = self.pending.borrow_mut();
+        if let Some(callbacks) = pending.get_mut(path) {
+            callbacks.push(Box::new(callback));
+            return;
+        }
+        pending.insert(path.to_string(), vec![Box::new(callback)]);
+        drop(pending);
+
+        let key = path.to_string();
+        let mut on_loaded = create_event_loop_fn_mut(move |img: Result<DynamicImage, Error>| {
+            let img = match img {
+                Ok(img) => Some(dyn_image_to_skia_image(&img)),
+                Err(err) => {
+                    error!("failed to load image:{:?}", err);
+                    None
+                }
+            };
+            let callbacks = IMG_MANAGER.with(|im| {
+                if let Some(img) = &img {
+                    im.put_cache(&key, img);
+                }
+                im.pending.borrow_mut().remove(&key)
+            });
+            for callback in callbacks.unwrap_or_default() {
+                callback(img.clone());
+            }
+        });
+        let path = path.to_string();
+        thread::spawn(move || {
+            on_loaded.call(decode_img(&path));
+        });
+    }
+
+    fn get_cached(&self, path: &str) -> Option<Image> {
+        let img = self.cache.borrow().get(path)?.upgrade()?;
+        let i: &Image = img.borrow();
+        Some(i.clone())
+    }
+
+    fn put_cache(&self, path: &str, img: &Image) {
+        let rc = Rc::new(img.clone());
         self.cache
             .borrow_mut()
             .insert(path.to_string(), Rc::downgrade(&rc));
-        Ok(sk_img)
     }
 
     pub fn get_img(&self, src: &str) -> Option<Image> {
@@ -52,6 +106,10 @@ impl ImgManager {
     }
 }
 
+fn decode_img(path: &str) -> Result<DynamicImage, Error> {
+    Ok(ImageReader::open(path)?.decode()?)
+}
+
 pub fn dyn_image_to_skia_image(src: &DynamicImage) -> Image {
     let width = src.width() as i32;
     let height = src.height() as i32;
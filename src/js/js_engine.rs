@@ -11,8 +11,10 @@ use crate::app::App;
 use crate::console::Console;
 use crate::element::button::Button;
 use crate::element::checkbox::Checkbox;
+use crate::element::grid_view::GridView;
 use crate::element::image::Image;
 use crate::element::label::Label;
+use crate::element::list_view::ListView;
 use crate::element::radio::Radio;
 use crate::element::richtext::RichText;
 use crate::element::scroll::Scroll;
@@ -46,10 +48,11 @@ use crate::ext::ext_worker::{SharedModuleLoader, Worker, WorkerInitParams};
 use crate::js::js_binding::{JsCallError, JsFunc};
 use crate::js::js_runtime::{JsContext, PromiseResolver};
 use crate::js::ToJsCallResult;
-use crate::menu::{Menu, StandardMenuItem};
+use crate::menu::{CheckMenuItem, Menu, StandardMenuItem, SubmenuItem};
 use crate::mrc::Mrc;
 use crate::stylesheet::{stylesheet_add, stylesheet_remove, stylesheet_update};
 use crate::typeface::typeface_create;
+use crate::window::driver::WindowDriver;
 use crate::window::page::Page;
 use crate::window::popup::Popup;
 use crate::window::{Window, WindowHandle, WindowType};
@@ -127,6 +130,8 @@ impl JsEngine {
 
         engine.add_global_functions(Menu::create_js_apis());
         engine.add_global_functions(StandardMenuItem::create_js_apis());
+        engine.add_global_functions(CheckMenuItem::create_js_apis());
+        engine.add_global_functions(SubmenuItem::create_js_apis());
 
         engine.add_global_functions(Page::create_js_apis());
         engine.add_global_functions(ExtConsole::create_js_apis());
@@ -141,6 +146,8 @@ impl JsEngine {
         engine.add_global_functions(Label::create_js_apis());
         engine.add_global_functions(Image::create_js_apis());
         engine.add_global_functions(Select::create_js_apis());
+        engine.add_global_functions(ListView::create_js_apis());
+        engine.add_global_functions(GridView::create_js_apis());
         #[cfg(feature = "sqlite")]
         engine.add_global_functions(crate::ext::ext_sqlite::SqliteConn::create_js_apis());
         #[cfg(feature = "tray")]
@@ -168,6 +175,7 @@ impl JsEngine {
         engine.add_global_functions(crate::ext::ext_fetch::fetch::create_js_apis());
 
         engine.add_global_functions(Window::create_js_apis());
+        engine.add_global_functions(WindowDriver::create_js_apis());
         engine.add_global_func(timer_set_timeout::new());
         engine.add_global_func(timer_clear_timeout::new());
         engine.add_global_func(timer_set_interval::new());
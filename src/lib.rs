@@ -33,6 +33,7 @@ pub mod number;
 pub mod performance;
 pub mod renderer;
 pub mod resource_table;
+pub mod snapshot;
 pub mod string;
 pub mod time;
 pub mod timer;
@@ -1,13 +1,29 @@
+pub mod accelerator;
+pub mod menubar;
+
 use crate as deft;
+use crate::base::Rect;
 use crate::element::container::Container;
+use crate::element::image::Image;
 use crate::element::label::Label;
-use crate::element::{Element, ElementBackend, ElementType};
-use crate::event::ClickEventListener;
+use crate::element::{Element, ElementBackend, ElementType, ElementWeak};
+use crate::event::{
+    ClickEventListener, KeyDownEventListener, KeyEventDetail, MouseEnterEventListener,
+    KEY_MOD_SHIFT,
+};
 use crate::js_value;
+use crate::menu::accelerator::Accelerator;
 use crate::mrc::Mrc;
+use crate::style::length::LengthOrPercent;
+use crate::style::{FixedStyleProp, StylePropVal};
+use crate::window::popup::Popup;
+use crate::window::WindowHandle;
+use crate::{ok_or_return, some_or_return};
 use deft_macros::{js_methods, mrc_object};
 use log::error;
 use quick_js::JsValue;
+use std::rc::Rc;
+use winit::keyboard::NamedKey;
 
 #[mrc_object]
 pub struct Menu {
@@ -31,16 +47,106 @@ impl Menu {
         self.items.push(MenuItem::Standard(standard_item));
     }
 
+    #[js_func]
+    pub fn add_check_item(&mut self, check_item: CheckMenuItem) {
+        self.items.push(MenuItem::Check(check_item));
+    }
+
+    #[js_func]
+    pub fn add_submenu(&mut self, submenu_item: SubmenuItem) {
+        self.items.push(MenuItem::Submenu(submenu_item));
+    }
+
     #[js_func]
     pub fn add_separator(&mut self) {
         self.items.push(MenuItem::Separator);
     }
+
+    pub fn get_items(&self) -> &Vec<MenuItem> {
+        &self.items
+    }
+
+    /// Run the action of the item at `index`, check items are toggled and radio items uncheck
+    /// the other items of the same group
+    pub fn activate(&mut self, index: usize) {
+        let item = some_or_return!(self.items.get(index).cloned());
+        if item.is_disabled() {
+            return;
+        }
+        match item {
+            MenuItem::Standard(s) => {
+                let mut onclick = s.onclick.clone();
+                if let Some(onclick) = &mut *onclick {
+                    onclick();
+                }
+            }
+            MenuItem::Check(mut c) => {
+                if let Some(group) = c.radio_group.clone() {
+                    if c.checked {
+                        return;
+                    }
+                    for it in &mut self.items {
+                        if let MenuItem::Check(other) = it {
+                            if other.radio_group.as_ref() == Some(&group) {
+                                other.checked = false;
+                            }
+                        }
+                    }
+                    c.checked = true;
+                } else {
+                    c.checked = !c.checked;
+                }
+                let checked = c.checked;
+                let mut onchange = c.onchange.clone();
+                if let Some(onchange) = &mut *onchange {
+                    onchange(checked);
+                }
+            }
+            MenuItem::Separator | MenuItem::Submenu(_) => {}
+        }
+    }
+
+    /// Like [`Menu::activate`], but items referring to a command execute the command of
+    /// `window`
+    pub fn activate_in(&mut self, index: usize, window: &WindowHandle) {
+        let item = some_or_return!(self.items.get(index).cloned());
+        match item.command() {
+            Some(command) if !item.is_disabled() => {
+                if let Ok(window) = window.upgrade_mut() {
+                    window.execute_command(command.to_string());
+                }
+            }
+            _ => self.activate(index),
+        }
+    }
+
+    /// Accelerators of the items in this menu and its submenus with the actions to trigger,
+    /// items referring to commands are skipped
+    pub fn collect_accelerators(&self) -> Vec<(Accelerator, Rc<dyn Fn()>)> {
+        let mut result: Vec<(Accelerator, Rc<dyn Fn()>)> = Vec::new();
+        for (i, item) in self.items.iter().enumerate() {
+            if let MenuItem::Submenu(s) = item {
+                result.extend(s.menu.collect_accelerators());
+            } else if item.command().is_some() {
+                continue;
+            } else if let Some(accelerator) = item.accelerator() {
+                let menu = self.clone();
+                let action = Rc::new(move || menu.clone().activate(i));
+                result.push((accelerator.clone(), action));
+            }
+        }
+        result
+    }
 }
 
 #[mrc_object]
 pub struct StandardMenuItem {
     pub disabled: bool,
     pub label: String,
+    pub icon: Option<String>,
+    pub accelerator: Option<Accelerator>,
+    /// Name of the command executed instead of `onclick`
+    pub command: Option<String>,
     pub onclick: Mrc<Option<Box<dyn FnMut()>>>,
 }
 
@@ -52,6 +158,9 @@ impl StandardMenuItem {
         StandardMenuItemData {
             disabled: false,
             label: label.to_string(),
+            icon: None,
+            accelerator: None,
+            command: None,
             onclick: Mrc::new(Some(Box::new(callback))),
         }
         .to_ref()
@@ -62,6 +171,9 @@ impl StandardMenuItem {
         StandardMenuItemData {
             disabled: false,
             label,
+            icon: None,
+            accelerator: None,
+            command: None,
             onclick: Mrc::new(Some(Box::new(move || {
                 if let Err(e) = callback.clone().call_as_function(vec![]) {
                     error!("Error calling callback: {}", e);
@@ -80,43 +192,684 @@ impl StandardMenuItem {
     pub fn get_disabled(&self) -> bool {
         self.disabled
     }
+
+    /// Path of the image shown before the label
+    #[js_func]
+    pub fn set_icon(&mut self, icon: Option<String>) {
+        self.icon = icon;
+    }
+
+    /// Shortcut like `Ctrl+S`, which is shown after the label
+    #[js_func]
+    pub fn set_accelerator(&mut self, accelerator: Option<String>) {
+        self.accelerator = parse_optional_accelerator(accelerator);
+    }
+
+    /// Execute the command registered by
+    /// [`Window::register_command`](crate::window::Window::register_command) when the item is
+    /// activated, the key chords bound to the command are shown after the label
+    #[js_func]
+    pub fn set_command(&mut self, command: Option<String>) {
+        self.command = command;
+    }
+}
+
+/// Menu item with a check mark, items with the same radio group are checked exclusively
+#[mrc_object]
+pub struct CheckMenuItem {
+    pub disabled: bool,
+    pub label: String,
+    pub icon: Option<String>,
+    pub accelerator: Option<Accelerator>,
+    pub checked: bool,
+    pub radio_group: Option<String>,
+    pub onchange: Mrc<Option<Box<dyn FnMut(bool)>>>,
+}
+
+js_value!(CheckMenuItem);
+
+#[js_methods]
+impl CheckMenuItem {
+    pub fn new<F: FnMut(bool) + 'static>(
+        label: &str,
+        radio_group: Option<String>,
+        callback: F,
+    ) -> Self {
+        CheckMenuItemData {
+            disabled: false,
+            label: label.to_string(),
+            icon: None,
+            accelerator: None,
+            checked: false,
+            radio_group,
+            onchange: Mrc::new(Some(Box::new(callback))),
+        }
+        .to_ref()
+    }
+
+    #[js_func]
+    pub fn js_new(label: String, radio_group: Option<String>, callback: JsValue) -> Self {
+        Self::new(&label, radio_group, move |checked| {
+            let args = vec![JsValue::Bool(checked)];
+            if let Err(e) = callback.clone().call_as_function(args) {
+                error!("Error calling callback: {}", e);
+            }
+        })
+    }
+
+    #[js_func]
+    pub fn set_checked(&mut self, checked: bool) {
+        self.checked = checked;
+    }
+
+    #[js_func]
+    pub fn get_checked(&self) -> bool {
+        self.checked
+    }
+
+    #[js_func]
+    pub fn set_disabled(&mut self, disabled: bool) {
+        self.disabled = disabled;
+    }
+
+    #[js_func]
+    pub fn get_disabled(&self) -> bool {
+        self.disabled
+    }
+
+    #[js_func]
+    pub fn set_icon(&mut self, icon: Option<String>) {
+        self.icon = icon;
+    }
+
+    #[js_func]
+    pub fn set_accelerator(&mut self, accelerator: Option<String>) {
+        self.accelerator = parse_optional_accelerator(accelerator);
+    }
+}
+
+/// Menu item which opens a cascading popup of `menu`
+#[mrc_object]
+pub struct SubmenuItem {
+    pub disabled: bool,
+    pub label: String,
+    pub icon: Option<String>,
+    pub menu: Menu,
+}
+
+js_value!(SubmenuItem);
+
+#[js_methods]
+impl SubmenuItem {
+    #[js_func]
+    pub fn new(label: String, menu: Menu) -> Self {
+        SubmenuItemData {
+            disabled: false,
+            label,
+            icon: None,
+            menu,
+        }
+        .to_ref()
+    }
+
+    #[js_func]
+    pub fn set_disabled(&mut self, disabled: bool) {
+        self.disabled = disabled;
+    }
+
+    #[js_func]
+    pub fn get_disabled(&self) -> bool {
+        self.disabled
+    }
+
+    #[js_func]
+    pub fn set_icon(&mut self, icon: Option<String>) {
+        self.icon = icon;
+    }
 }
 
 #[derive(Clone)]
 pub enum MenuItem {
     Separator,
     Standard(StandardMenuItem),
+    Check(CheckMenuItem),
+    Submenu(SubmenuItem),
 }
 
-pub fn build_menu_elements(menu: Menu) -> Element {
+impl MenuItem {
+    /// Separators are never enabled
+    pub fn is_disabled(&self) -> bool {
+        match self {
+            MenuItem::Separator => true,
+            MenuItem::Standard(s) => s.disabled,
+            MenuItem::Check(c) => c.disabled,
+            MenuItem::Submenu(s) => s.disabled,
+        }
+    }
+
+    pub fn label(&self) -> Option<&str> {
+        match self {
+            MenuItem::Separator => None,
+            MenuItem::Standard(s) => Some(&s.label),
+            MenuItem::Check(c) => Some(&c.label),
+            MenuItem::Submenu(s) => Some(&s.label),
+        }
+    }
+
+    pub fn accelerator(&self) -> Option<&Accelerator> {
+        match self {
+            MenuItem::Standard(s) => s.accelerator.as_ref(),
+            MenuItem::Check(c) => c.accelerator.as_ref(),
+            MenuItem::Separator | MenuItem::Submenu(_) => None,
+        }
+    }
+
+    pub fn command(&self) -> Option<&str> {
+        match self {
+            MenuItem::Standard(s) => s.command.as_deref(),
+            MenuItem::Separator | MenuItem::Check(_) | MenuItem::Submenu(_) => None,
+        }
+    }
+
+    fn icon(&self) -> Option<&str> {
+        match self {
+            MenuItem::Separator => None,
+            MenuItem::Standard(s) => s.icon.as_deref(),
+            MenuItem::Check(c) => c.icon.as_deref(),
+            MenuItem::Submenu(s) => s.icon.as_deref(),
+        }
+    }
+}
+
+fn parse_optional_accelerator(accelerator: Option<String>) -> Option<Accelerator> {
+    let accelerator = accelerator?;
+    let result = Accelerator::parse(&accelerator);
+    if result.is_none() {
+        error!("Invalid accelerator: {}", accelerator);
+    }
+    result
+}
+
+/// Split the mnemonic marked by `&` from a label, e.g. `&Save` is shown as `Save` and activated
+/// by `s`. The first char is the mnemonic if none is marked, `&&` is a literal `&`.
+pub fn parse_mnemonic(label: &str) -> (String, Option<char>) {
+    let mut text = String::new();
+    let mut mnemonic = None;
+    let mut chars = label.chars();
+    while let Some(c) = chars.next() {
+        if c != '&' {
+            text.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('&') => text.push('&'),
+            Some(m) => {
+                if mnemonic.is_none() {
+                    mnemonic = m.to_lowercase().next();
+                }
+                text.push(m);
+            }
+            None => {}
+        }
+    }
+    let mnemonic = mnemonic.or_else(|| text.chars().next()?.to_lowercase().next());
+    (text, mnemonic)
+}
+
+/// Build the elements of the items, `window` resolves the key chords of items referring to
+/// commands
+pub fn build_menu_elements(menu: Menu, window: &WindowHandle) -> Element {
     let mut root = Element::create(Container::create);
     root.tag = "menu".to_string();
     root.set_element_type(ElementType::Widget);
     for it in menu.items.clone() {
-        match it {
-            MenuItem::Separator => {
-                let mut e = Element::create(Container::create);
-                e.tag = "menu-item-separator".to_string();
-                e.set_element_type(ElementType::Widget);
-                root.add_child(e, -1).unwrap();
+        let accelerator = match it.command() {
+            Some(command) => window.upgrade_mut().ok().and_then(|w| {
+                let bindings = w.get_command_bindings(command.to_string());
+                bindings.into_iter().next()
+            }),
+            None => it.accelerator().map(|a| a.to_string()),
+        };
+        root.add_child(build_menu_item_element(&it, accelerator), -1)
+            .unwrap();
+    }
+    root
+}
+
+fn build_menu_item_element(item: &MenuItem, accelerator: Option<String>) -> Element {
+    let tag = match item {
+        MenuItem::Separator => {
+            let mut e = Element::create(Container::create);
+            e.tag = "menu-item-separator".to_string();
+            e.set_element_type(ElementType::Widget);
+            return e;
+        }
+        MenuItem::Standard(_) => "menu-item-standard",
+        MenuItem::Check(c) if c.radio_group.is_some() => "menu-item-radio",
+        MenuItem::Check(_) => "menu-item-checkbox",
+        MenuItem::Submenu(_) => "menu-item-submenu",
+    };
+    let mut e = Element::create(Container::create);
+    e.is_form_element = true;
+    e.tag = tag.to_string();
+    e.set_element_type(ElementType::Widget);
+    e.set_disabled(item.is_disabled());
+
+    let mut icon = Element::create(Container::create);
+    icon.tag = "menu-item-icon".to_string();
+    let check_mark: Option<&[u8]> = match item {
+        MenuItem::Check(c) if c.checked && c.radio_group.is_some() => {
+            Some(include_bytes!("element/selected.svg"))
+        }
+        MenuItem::Check(c) if c.checked => Some(include_bytes!("element/checked.svg")),
+        _ => None,
+    };
+    let mut img = Element::create(Image::create);
+    img.set_style_props(vec![
+        FixedStyleProp::Width(StylePropVal::Custom(LengthOrPercent::Percent(100.0))),
+        FixedStyleProp::Height(StylePropVal::Custom(LengthOrPercent::Percent(100.0))),
+    ]);
+    if let Some(svg) = check_mark {
+        e.set_attribute("checked".to_string(), "".to_string());
+        img.get_backend_mut_as::<Image>().set_src_svg_raw(svg);
+        icon.add_child(img, -1).unwrap();
+    } else if let Some(src) = item.icon() {
+        img.get_backend_mut_as::<Image>().set_src(src.to_string());
+        icon.add_child(img, -1).unwrap();
+    }
+    e.add_child(icon, -1).unwrap();
+
+    let (text, _) = parse_mnemonic(item.label().unwrap_or_default());
+    let mut label = Element::create(Label::create);
+    label.tag = "menu-item-label".to_string();
+    label.get_backend_mut_as::<Label>().set_text(text);
+    e.add_child(label, -1).unwrap();
+
+    if let Some(accelerator) = accelerator {
+        let mut label = Element::create(Label::create);
+        label.tag = "menu-item-accelerator".to_string();
+        label.get_backend_mut_as::<Label>().set_text(accelerator);
+        e.add_child(label, -1).unwrap();
+    }
+    if let MenuItem::Submenu(_) = item {
+        let mut arrow = Element::create(Label::create);
+        arrow.tag = "menu-item-arrow".to_string();
+        arrow
+            .get_backend_mut_as::<Label>()
+            .set_text("›".to_string());
+        e.add_child(arrow, -1).unwrap();
+    }
+    e
+}
+
+/// A popup of a menu, which handles hovering, clicking and keyboard navigation of the items
+/// and opens submenus as cascading popups
+#[mrc_object]
+pub struct MenuView {
+    menu: Menu,
+    owner: WindowHandle,
+    /// Position of the popup in the owner window
+    origin: (f32, f32),
+    element: ElementWeak,
+    items: Vec<ElementWeak>,
+    active: Option<usize>,
+    popup: Option<Popup>,
+    submenu: Option<(usize, MenuView)>,
+    parent: Option<MenuViewWeak>,
+    close_callback: Option<Rc<dyn Fn()>>,
+    /// Called with -1 or 1 when left or right is pressed and there is no submenu to move to
+    navigate_callback: Option<Rc<dyn Fn(isize)>>,
+}
+
+impl MenuView {
+    /// Show `menu` below `target`, which is relative to the owner window
+    pub fn popup(menu: Menu, owner: &WindowHandle, target: Rect) -> MenuView {
+        Self::open(menu, owner, target, None)
+    }
+
+    fn open(menu: Menu, owner: &WindowHandle, target: Rect, parent: Option<MenuViewWeak>) -> Self {
+        let mut root = build_menu_elements(menu.clone(), owner);
+        root.set_focusable(true);
+        let mut view = MenuViewData {
+            menu,
+            owner: owner.clone(),
+            origin: (target.x, target.bottom()),
+            element: root.as_weak(),
+            items: root.get_children().iter().map(|c| c.as_weak()).collect(),
+            active: None,
+            popup: None,
+            submenu: None,
+            parent,
+            close_callback: None,
+            navigate_callback: None,
+        }
+        .to_ref();
+        for (i, mut item) in root.get_children().into_iter().enumerate() {
+            let mut v = view.clone();
+            item.register_event_listener(MouseEnterEventListener::new(move |_, _| {
+                v.set_active(Some(i));
+                if let Some(MenuItem::Submenu(_)) = v.menu.items.get(i) {
+                    v.open_submenu(i, false);
+                } else {
+                    v.close_submenu();
+                }
+            }));
+            let mut v = view.clone();
+            item.register_event_listener(ClickEventListener::new(move |_, _| {
+                v.activate_item(i);
+            }));
+        }
+        let mut v = view.clone();
+        root.register_event_listener(KeyDownEventListener::new(move |e, ctx| {
+            if v.on_key_down(&e.0) {
+                ctx.prevent_default = true;
+                ctx.propagation_cancelled = true;
             }
-            MenuItem::Standard(s) => {
-                let mut e = Element::create(Label::create);
-                e.is_form_element = true;
-                e.tag = "menu-item-standard".to_string();
-                e.set_element_type(ElementType::Widget);
-                e.set_disabled(s.disabled);
-                let label = e.get_backend_mut_as::<Label>();
-                label.set_text(s.label.to_string());
-                let mut onclick = s.onclick.clone();
-                e.register_event_listener(ClickEventListener::new(move |_, _| {
-                    if let Some(onclick) = &mut *onclick {
-                        onclick();
-                    }
-                }));
-                root.add_child(e, -1).unwrap();
+        }));
+        let owner = owner.upgrade_mut();
+        if let Ok(owner) = owner {
+            let mut popup = owner.popup(root.clone(), target);
+            // e.g. closed by clicking outside of the popup
+            let weak = view.as_weak();
+            popup.set_close_callback(move || {
+                if let Ok(mut view) = weak.upgrade() {
+                    view.close();
+                }
+            });
+            view.popup = Some(popup);
+        }
+        root.focus();
+        view
+    }
+
+    /// Close the popup and the popups of its submenus
+    pub fn close(&mut self) {
+        if let Some((_, mut submenu)) = self.submenu.take() {
+            submenu.close();
+        }
+        if let Some(popup) = self.popup.take() {
+            popup.close();
+        }
+        if let Some(callback) = self.close_callback.take() {
+            callback();
+        }
+    }
+
+    pub fn set_close_callback<F: Fn() + 'static>(&mut self, callback: F) {
+        self.close_callback = Some(Rc::new(callback));
+    }
+
+    pub fn set_navigate_callback<F: Fn(isize) + 'static>(&mut self, callback: F) {
+        self.navigate_callback = Some(Rc::new(callback));
+    }
+
+    /// Close the whole chain of popups from the root menu
+    pub fn close_all(&mut self) {
+        self.get_root().close();
+    }
+
+    fn get_root(&self) -> MenuView {
+        let mut root = self.clone();
+        while let Some(parent) = root.parent.as_ref().and_then(|p| p.upgrade().ok()) {
+            root = parent;
+        }
+        root
+    }
+
+    fn navigate(&self, delta: isize) {
+        let root = self.get_root();
+        if let Some(callback) = root.navigate_callback.clone() {
+            callback(delta);
+        }
+    }
+
+    fn set_active(&mut self, index: Option<usize>) {
+        if let Some(i) = index {
+            if self.menu.items.get(i).map(|it| it.is_disabled()) != Some(false) {
+                return;
             }
         }
+        if let Some(mut el) = self.active.and_then(|i| self.get_item_element(i)) {
+            el.remove_attribute("active".to_string());
+        }
+        self.active = index;
+        if let Some(mut el) = index.and_then(|i| self.get_item_element(i)) {
+            el.set_attribute("active".to_string(), "".to_string());
+        }
+    }
+
+    fn get_item_element(&self, index: usize) -> Option<Element> {
+        self.items.get(index)?.upgrade().ok()
+    }
+
+    fn activate_item(&mut self, index: usize) {
+        let item = some_or_return!(self.menu.items.get(index).cloned());
+        if item.is_disabled() {
+            return;
+        }
+        if let MenuItem::Submenu(_) = item {
+            self.set_active(Some(index));
+            self.open_submenu(index, true);
+        } else {
+            self.menu.activate_in(index, &self.owner);
+            self.close_all();
+        }
+    }
+
+    /// Open the submenu at `index` on the right of the item, the first item of the submenu is
+    /// activated if `focus` is true
+    fn open_submenu(&mut self, index: usize, focus: bool) {
+        if let Some((i, submenu)) = &mut self.submenu {
+            if *i == index {
+                if focus {
+                    submenu.focus_first_item();
+                }
+                return;
+            }
+        }
+        self.close_submenu();
+        let menu = match self.menu.items.get(index) {
+            Some(MenuItem::Submenu(s)) => s.menu.clone(),
+            _ => return,
+        };
+        let item = some_or_return!(self.get_item_element(index));
+        let bounds = item.get_origin_bounds();
+        // The negative width makes the popup placed on the left of the item if there is no
+        // room on the right
+        let target = Rect::new(
+            self.origin.0 + bounds.right(),
+            self.origin.1 + bounds.y,
+            -bounds.width,
+            0.0,
+        );
+        let mut submenu = Self::open(menu, &self.owner, target, Some(self.as_weak()));
+        // The popup of a submenu may be closed on its own, e.g. by clicking outside of it
+        let weak = self.as_weak();
+        let submenu_weak = submenu.as_weak();
+        submenu.set_close_callback(move || {
+            let mut parent = ok_or_return!(weak.upgrade());
+            let closed = ok_or_return!(submenu_weak.upgrade());
+            if parent.submenu.as_ref().map(|(_, s)| s) == Some(&closed) {
+                parent.submenu = None;
+            }
+        });
+        if focus {
+            submenu.focus_first_item();
+        } else if let Ok(mut el) = self.element.upgrade() {
+            el.focus();
+        }
+        self.submenu = Some((index, submenu));
+    }
+
+    fn close_submenu(&mut self) {
+        let (_, mut submenu) = some_or_return!(self.submenu.take());
+        submenu.close();
+        if let Ok(mut el) = self.element.upgrade() {
+            el.focus();
+        }
+    }
+
+    pub fn focus_first_item(&mut self) {
+        if let Ok(mut el) = self.element.upgrade() {
+            el.focus();
+        }
+        if self.active.is_none() {
+            self.move_active(1);
+        }
+    }
+
+    /// Activate the next enabled item in the direction of `delta`, wrapping around the ends
+    fn move_active(&mut self, delta: isize) {
+        let count = self.menu.items.len() as isize;
+        let mut index = match self.active {
+            Some(i) => i as isize,
+            None if delta > 0 => -1,
+            None => count,
+        };
+        for _ in 0..count {
+            index = (index + delta).rem_euclid(count);
+            if !self.menu.items[index as usize].is_disabled() {
+                self.set_active(Some(index as usize));
+                return;
+            }
+        }
+    }
+
+    fn on_key_down(&mut self, detail: &KeyEventDetail) -> bool {
+        match detail.named_key {
+            Some(NamedKey::ArrowDown) => self.move_active(1),
+            Some(NamedKey::ArrowUp) => self.move_active(-1),
+            Some(NamedKey::ArrowRight) => match self.active {
+                Some(active) if matches!(self.menu.items[active], MenuItem::Submenu(_)) => {
+                    self.open_submenu(active, true);
+                }
+                _ => self.navigate(1),
+            },
+            Some(NamedKey::ArrowLeft) | Some(NamedKey::Escape) => {
+                let parent = self.parent.as_ref().and_then(|p| p.upgrade().ok());
+                if let Some(mut parent) = parent {
+                    parent.close_submenu();
+                } else if detail.named_key == Some(NamedKey::Escape) {
+                    self.close();
+                } else {
+                    self.navigate(-1);
+                }
+            }
+            Some(NamedKey::Enter) | Some(NamedKey::Space) => {
+                let active = some_or_return!(self.active, false);
+                self.activate_item(active);
+            }
+            _ => {
+                if detail.modifiers & !KEY_MOD_SHIFT != 0 {
+                    return false;
+                }
+                let key = some_or_return!(detail.key_str.as_ref(), false);
+                return self.on_mnemonic(key);
+            }
+        }
+        true
+    }
+
+    /// Activate the item whose mnemonic is `key`, the items are only focused in turn if
+    /// several items share the mnemonic
+    fn on_mnemonic(&mut self, key: &str) -> bool {
+        let key = some_or_return!(key.to_lowercase().chars().next(), false);
+        let matched = self
+            .menu
+            .items
+            .iter()
+            .enumerate()
+            .filter(|(_, it)| !it.is_disabled())
+            .filter(|(_, it)| parse_mnemonic(it.label().unwrap_or_default()).1 == Some(key))
+            .map(|(i, _)| i)
+            .collect::<Vec<usize>>();
+        match matched.len() {
+            0 => false,
+            1 => {
+                self.set_active(Some(matched[0]));
+                self.activate_item(matched[0]);
+                true
+            }
+            _ => {
+                let next = matched
+                    .iter()
+                    .find(|i| Some(**i) > self.active)
+                    .unwrap_or(&matched[0]);
+                self.set_active(Some(*next));
+                true
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::base::Rect;
+    use crate::element::body::Body;
+    use crate::element::Element;
+    use crate::ext::ext_window::WindowAttrs;
+    use crate::menu::{
+        parse_mnemonic, CheckMenuItem, Menu, MenuItem, MenuView, StandardMenuItem, SubmenuItem,
+    };
+    use crate::window::Window;
+
+    #[test]
+    fn test_parse_mnemonic() {
+        assert_eq!(
+            ("Save As".to_string(), Some('a')),
+            parse_mnemonic("Save &As")
+        );
+        assert_eq!(
+            ("Fish & Chips".to_string(), Some('f')),
+            parse_mnemonic("Fish && Chips")
+        );
+        assert_eq!((String::new(), None), parse_mnemonic(""));
+    }
+
+    #[test]
+    fn test_radio_items() {
+        let mut menu = Menu::new();
+        let small = CheckMenuItem::new("Small", Some("size".to_string()), |_| {});
+        let large = CheckMenuItem::new("Large", Some("size".to_string()), |_| {});
+        let wrap = CheckMenuItem::new("Wrap", None, |_| {});
+        menu.add_check_item(small.clone());
+        menu.add_check_item(large.clone());
+        menu.add_item(MenuItem::Separator);
+        menu.add_check_item(wrap.clone());
+
+        menu.activate(0);
+        menu.activate(1);
+        assert!(!small.checked);
+        assert!(large.checked);
+        menu.activate(3);
+        assert!(wrap.checked);
+        menu.activate(3);
+        assert!(!wrap.checked);
+    }
+
+    #[test]
+    fn test_submenu_popup_closed() {
+        let handle = Window::create(WindowAttrs {
+            width: Some(100.0),
+            height: Some(100.0),
+            headless: Some(true),
+            ..Default::default()
+        })
+        .unwrap();
+        let body = Element::create(Body::create);
+        handle.upgrade_mut().unwrap().set_body(body).unwrap();
+        let mut edit = Menu::new();
+        edit.add_standard_item(StandardMenuItem::new("Copy", || {}));
+        let mut menu = Menu::new();
+        menu.add_submenu(SubmenuItem::new("Edit".to_string(), edit));
+        let mut view = MenuView::popup(menu, &handle, Rect::new(0.0, 0.0, 1.0, 1.0));
+        view.open_submenu(0, false);
+        let submenu = view.submenu.as_ref().unwrap().1.clone();
+
+        // e.g. the popup window of the submenu is closed by clicking outside of it
+        submenu.popup.as_ref().unwrap().close();
+        assert!(view.submenu.is_none());
+        view.open_submenu(0, false);
+        assert!(view.submenu.as_ref().is_some_and(|(_, s)| *s != submenu));
     }
-    root
 }
@@ -0,0 +1,112 @@
+use crate::event::{
+    find_named_key_ignore_case, KeyEventDetail, KEY_MOD_ALT, KEY_MOD_CTRL, KEY_MOD_META,
+    KEY_MOD_SHIFT,
+};
+use std::fmt::{Display, Formatter};
+
+/// Modifier of shortcuts written as `CmdOrCtrl`
+#[cfg(target_os = "macos")]
+const PRIMARY_MOD: u32 = KEY_MOD_META;
+#[cfg(not(target_os = "macos"))]
+const PRIMARY_MOD: u32 = KEY_MOD_CTRL;
+
+#[cfg(target_os = "macos")]
+const META_NAME: &str = "Cmd";
+#[cfg(not(target_os = "macos"))]
+const META_NAME: &str = "Meta";
+
+/// Keyboard shortcut of a menu item, e.g. `Ctrl+S` or `Ctrl+Shift+F5`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Accelerator {
+    pub modifiers: u32,
+    /// Name of a named key like `F5`, or a lowercase char
+    pub key: String,
+}
+
+impl Accelerator {
+    pub fn parse(value: &str) -> Option<Self> {
+        let mut modifiers = 0;
+        let mut key = None;
+        for part in value.split('+').map(|p| p.trim()) {
+            if key.is_some() {
+                return None;
+            }
+            match part.to_lowercase().as_str() {
+                "ctrl" | "control" => modifiers |= KEY_MOD_CTRL,
+                "alt" | "option" => modifiers |= KEY_MOD_ALT,
+                "shift" => modifiers |= KEY_MOD_SHIFT,
+                "meta" | "cmd" | "command" | "super" => modifiers |= KEY_MOD_META,
+                "cmdorctrl" | "commandorcontrol" => modifiers |= PRIMARY_MOD,
+                _ => key = Some(parse_key(part)?),
+            }
+        }
+        Some(Self {
+            modifiers,
+            key: key?,
+        })
+    }
+
+    pub fn matches(&self, detail: &KeyEventDetail) -> bool {
+        detail.modifiers == self.modifiers
+            && detail
+                .key
+                .as_ref()
+                .map(|k| k.to_lowercase() == self.key.to_lowercase())
+                .unwrap_or(false)
+    }
+}
+
+impl Display for Accelerator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let modifier_names = [
+            (KEY_MOD_CTRL, "Ctrl"),
+            (KEY_MOD_ALT, "Alt"),
+            (KEY_MOD_SHIFT, "Shift"),
+            (KEY_MOD_META, META_NAME),
+        ];
+        for (modifier, name) in modifier_names {
+            if self.modifiers & modifier != 0 {
+                write!(f, "{}+", name)?;
+            }
+        }
+        if self.key.chars().count() == 1 {
+            write!(f, "{}", self.key.to_uppercase())
+        } else {
+            write!(f, "{}", self.key)
+        }
+    }
+}
+
+fn parse_key(key: &str) -> Option<String> {
+    let mut chars = key.chars();
+    let first = chars.next()?;
+    if chars.next().is_none() {
+        return Some(first.to_lowercase().to_string());
+    }
+    if key.eq_ignore_ascii_case("plus") {
+        return Some("+".to_string());
+    }
+    // Named keys are case-insensitive, e.g. `enter`, `Enter` or `PAGEUP`
+    find_named_key_ignore_case(key).map(|(name, _)| name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::event::{KEY_MOD_CTRL, KEY_MOD_SHIFT};
+    use crate::menu::accelerator::Accelerator;
+
+    #[test]
+    fn test_parse_accelerator() {
+        let accelerator = Accelerator::parse("Ctrl+Shift+s").unwrap();
+        assert_eq!(KEY_MOD_CTRL | KEY_MOD_SHIFT, accelerator.modifiers);
+        assert_eq!("s", accelerator.key);
+        assert_eq!("Ctrl+Shift+S", accelerator.to_string());
+        assert_eq!("F5", Accelerator::parse("f5").unwrap().key);
+        assert_eq!("Escape", Accelerator::parse("Escape").unwrap().key);
+        assert_eq!("PageUp", Accelerator::parse("pageup").unwrap().key);
+        assert_eq!("PageUp", Accelerator::parse("PAGEUP").unwrap().key);
+        assert_eq!("ArrowDown", Accelerator::parse("arrowdown").unwrap().key);
+        assert!(Accelerator::parse("Ctrl+S+X").is_none());
+        assert!(Accelerator::parse("Ctrl+").is_none());
+    }
+}
@@ -0,0 +1,198 @@
+use crate as deft;
+use crate::element::container::Container;
+use crate::element::label::Label;
+use crate::element::{Element, ElementBackend, ElementType, ElementWeak};
+use crate::event::{ClickEventListener, MouseEnterEventListener};
+use crate::menu::{parse_mnemonic, Menu, MenuItem, MenuView};
+use crate::window::WindowHandle;
+use crate::{ok_or_return, some_or_return};
+use deft_macros::mrc_object;
+use std::cell::RefCell;
+
+/// Shows menu bars with the menu of the platform, e.g. the global menu bar of macOS. The items
+/// are triggered by [`Menu::activate_in`].
+pub trait MenuBarHandler {
+    /// Returns false to fall back to the menu bar rendered in the window
+    fn set_menubar(&mut self, window: &WindowHandle, menu: Option<&Menu>) -> bool;
+}
+
+thread_local! {
+    static MENUBAR_HANDLER: RefCell<Option<Box<dyn MenuBarHandler>>> = RefCell::new(None);
+}
+
+pub fn set_menubar_handler(handler: Option<Box<dyn MenuBarHandler>>) {
+    MENUBAR_HANDLER.with_borrow_mut(|h| *h = handler);
+}
+
+pub(crate) fn set_native_menubar(window: &WindowHandle, menu: Option<&Menu>) -> bool {
+    MENUBAR_HANDLER.with_borrow_mut(|h| match h {
+        Some(h) => h.set_menubar(window, menu),
+        None => false,
+    })
+}
+
+/// Menu bar rendered in the window, the submenus of the top level items are opened as popups
+#[mrc_object]
+pub struct MenuBar {
+    menu: Menu,
+    owner: WindowHandle,
+    element: ElementWeak,
+    items: Vec<ElementWeak>,
+    opened: Option<(usize, MenuView)>,
+}
+
+impl MenuBar {
+    pub fn new(menu: Menu, owner: &WindowHandle) -> (MenuBar, Element) {
+        let mut root = Element::create(Container::create);
+        root.tag = "menubar".to_string();
+        root.set_element_type(ElementType::Widget);
+        for item in menu.items.iter() {
+            let mut e = Element::create(Label::create);
+            e.is_form_element = true;
+            e.tag = "menubar-item".to_string();
+            e.set_element_type(ElementType::Widget);
+            e.set_disabled(item.is_disabled());
+            let (text, _) = parse_mnemonic(item.label().unwrap_or_default());
+            e.get_backend_mut_as::<Label>().set_text(text);
+            root.add_child(e, -1).unwrap();
+        }
+        let bar = MenuBarData {
+            menu,
+            owner: owner.clone(),
+            element: root.as_weak(),
+            items: root.get_children().iter().map(|c| c.as_weak()).collect(),
+            opened: None,
+        }
+        .to_ref();
+        for (i, mut item) in root.get_children().into_iter().enumerate() {
+            let mut b = bar.clone();
+            item.register_event_listener(ClickEventListener::new(move |_, _| {
+                b.activate_item(i, false);
+            }));
+            let mut b = bar.clone();
+            item.register_event_listener(MouseEnterEventListener::new(move |_, _| {
+                // Menus are switched by hovering only if a menu is open
+                if b.opened.as_ref().map(|(index, _)| *index != i) == Some(true) {
+                    b.activate_item(i, false);
+                }
+            }));
+        }
+        (bar, root)
+    }
+
+    /// Open the submenu of the top level item at `index`, standard and check items are
+    /// triggered directly. The first item of the submenu is activated if `focus` is true.
+    pub fn activate_item(&mut self, index: usize, focus: bool) {
+        self.close_menu();
+        let item = some_or_return!(self.menu.items.get(index).cloned());
+        if item.is_disabled() {
+            return;
+        }
+        let submenu = match item {
+            MenuItem::Submenu(s) => s.menu.clone(),
+            _ => {
+                self.menu.activate_in(index, &self.owner);
+                return;
+            }
+        };
+        let mut item_el = some_or_return!(self.items.get(index).and_then(|e| e.upgrade().ok()));
+        let mut view = MenuView::popup(submenu, &self.owner, item_el.get_origin_bounds());
+        let weak = self.as_weak();
+        view.set_close_callback(move || {
+            let mut bar = ok_or_return!(weak.upgrade());
+            bar.on_menu_closed(index);
+        });
+        let weak = self.as_weak();
+        view.set_navigate_callback(move |delta| {
+            let mut bar = ok_or_return!(weak.upgrade());
+            bar.navigate(index, delta);
+        });
+        if focus {
+            view.focus_first_item();
+        }
+        item_el.set_attribute("active".to_string(), "".to_string());
+        self.opened = Some((index, view));
+    }
+
+    pub fn close_menu(&mut self) {
+        if let Some((_, mut view)) = self.opened.take() {
+            view.close();
+        }
+    }
+
+    /// Open the item whose mnemonic is `key`, e.g. `f` for `&File`
+    pub fn open_by_mnemonic(&mut self, key: &str) -> bool {
+        let key = some_or_return!(key.to_lowercase().chars().next(), false);
+        let index = self.menu.items.iter().position(|it| {
+            !it.is_disabled() && parse_mnemonic(it.label().unwrap_or_default()).1 == Some(key)
+        });
+        let index = some_or_return!(index, false);
+        self.activate_item(index, true);
+        true
+    }
+
+    fn on_menu_closed(&mut self, index: usize) {
+        if self.opened.as_ref().map(|(i, _)| *i) == Some(index) {
+            self.opened = None;
+        }
+        if let Some(mut el) = self.items.get(index).and_then(|e| e.upgrade().ok()) {
+            el.remove_attribute("active".to_string());
+        }
+    }
+
+    /// Open the next enabled submenu in the direction of `delta`
+    fn navigate(&mut self, from: usize, delta: isize) {
+        let count = self.menu.items.len() as isize;
+        let mut index = from as isize;
+        for _ in 0..count {
+            index = (index + delta).rem_euclid(count);
+            let item = &self.menu.items[index as usize];
+            if !item.is_disabled() && matches!(item, MenuItem::Submenu(_)) {
+                self.activate_item(index as usize, true);
+                return;
+            }
+        }
+    }
+
+    pub fn get_element(&self) -> Option<Element> {
+        self.element.upgrade().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::element::body::Body;
+    use crate::element::Element;
+    use crate::ext::ext_window::WindowAttrs;
+    use crate::menu::menubar::MenuBar;
+    use crate::menu::{Menu, StandardMenuItem, SubmenuItem};
+    use crate::window::Window;
+
+    #[test]
+    fn test_menu_popup_closed() {
+        let handle = Window::create(WindowAttrs {
+            width: Some(100.0),
+            height: Some(100.0),
+            headless: Some(true),
+            ..Default::default()
+        })
+        .unwrap();
+        let mut edit = Menu::new();
+        edit.add_standard_item(StandardMenuItem::new("Copy", || {}));
+        let mut menu = Menu::new();
+        menu.add_submenu(SubmenuItem::new("Edit".to_string(), edit));
+        let (mut bar, root) = MenuBar::new(menu, &handle);
+        let mut body = Element::create(Body::create);
+        body.add_child(root, 0).unwrap();
+        handle.upgrade_mut().unwrap().set_body(body).unwrap();
+
+        bar.activate_item(0, false);
+        let item = bar.items[0].upgrade().unwrap();
+        assert!(item.get_attribute("active".to_string()).is_some());
+        // e.g. the popup window is closed by clicking outside of it
+        let view = bar.opened.as_ref().unwrap().1.clone();
+        view.popup.as_ref().unwrap().close();
+        assert!(bar.opened.is_none());
+        assert!(item.get_attribute("active".to_string()).is_none());
+    }
+}
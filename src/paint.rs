@@ -1,12 +1,14 @@
 use crate::base::{Id, IdKey, Rect};
 use crate::element::Element;
 use crate::render::layout_tree::LayoutTree;
-use crate::render::paint_object::{ElementPO, LayerPO};
+use crate::render::paint_object::{BoxShadowPO, ElementPO, InsetShadowPO, LayerPO, OuterShadowPO};
 use crate::render::RenderFn;
 use crate::renderer::CpuRenderer;
+use crate::style::background::BackgroundLayer;
+use crate::style::shadow::{shadows_outset, ComputedShadow};
 use crate::{some_or_continue, some_or_return};
 use skia_safe::Canvas;
-use skia_safe::{scalar, Color, Image, Matrix, Path, PathOp, Point, Vector};
+use skia_safe::{scalar, Color, Matrix, Path, PathOp, Point, Vector};
 use skia_window::layer::Layer;
 use std::cell::Cell;
 use std::cmp::Ordering;
@@ -88,9 +90,12 @@ pub struct ElementObjectData {
     // pub layer_y: f32,
     pub border_color: [Color; 4],
     pub renderer: Box<dyn FnMut() -> RenderFn>,
-    pub background_image: Option<Image>,
+    pub background_layers: Vec<BackgroundLayer>,
     pub background_color: Color,
     pub border_width: (f32, f32, f32, f32),
+    pub box_shadow: Vec<ComputedShadow>,
+    pub text_shadow: Vec<ComputedShadow>,
+    pub color: Color,
     pub width: f32,
     pub height: f32,
     pub layer_object_idx: Option<usize>,
@@ -98,6 +103,62 @@ pub struct ElementObjectData {
     pub element: Element,
 }
 
+impl ElementObjectData {
+    /// Bounds in layer, including the box shadow and the text shadow outside the element
+    pub fn paint_bounds(&self) -> Rect {
+        let (bt, br, bb, bl) = shadows_outset(&self.box_shadow);
+        let (tt, tr, tb, tl) = shadows_outset(&self.text_shadow);
+        let (top, right, bottom, left) = (bt.max(tt), br.max(tr), bb.max(tb), bl.max(tl));
+        Rect::from_xywh(
+            self.layer_coord.0 - left,
+            self.layer_coord.1 - top,
+            self.width + left + right,
+            self.height + top + bottom,
+        )
+    }
+
+    fn build_shadow_objects(&mut self) -> (Option<OuterShadowPO>, Option<InsetShadowPO>) {
+        if self.box_shadow.is_empty() {
+            return (None, None);
+        }
+        let (bt, br, bb, bl) = self.border_width;
+        let border_path = self.element.get_border_path_mut();
+        let mut outer_shadows = Vec::new();
+        let mut inset_shadows = Vec::new();
+        // The first shadow is on the top
+        for s in self.box_shadow.iter().rev() {
+            let sp = s.spread;
+            let (insets, shadows) = if s.inset {
+                ([bt + sp, br + sp, bb + sp, bl + sp], &mut inset_shadows)
+            } else {
+                ([-sp; 4], &mut outer_shadows)
+            };
+            let path = some_or_continue!(border_path.build_inset_path(insets));
+            shadows.push(BoxShadowPO {
+                path,
+                offset: (s.offset_x, s.offset_y),
+                blur_sigma: s.blur_sigma(),
+                color: s.color_or(self.color),
+            });
+        }
+        let outer_shadow = match border_path.get_box_path() {
+            Some(p) if !outer_shadows.is_empty() => Some(OuterShadowPO {
+                border_box_path: p.clone(),
+                shadows: outer_shadows,
+            }),
+            _ => None,
+        };
+        let inset_shadow = match border_path.build_padding_box_path() {
+            Some(p) if !inset_shadows.is_empty() => Some(InsetShadowPO {
+                padding_box_path: p,
+                shadows: inset_shadows,
+            }),
+            _ => None,
+        };
+        (outer_shadow, inset_shadow)
+    }
+}
+
 #[derive(Clone)]
 pub struct ElementRO {
     pub element_object_idx: usize,
@@ -116,6 +177,7 @@ pub struct LayerObjectData {
     pub surface_bounds: Rect,
     pub visible_bounds: Rect,
     pub clip_rect: Option<Rect>,
+    pub opacity: f32,
 }
 
 #[derive(Clone)]
@@ -247,9 +309,12 @@ impl RenderTree {
             renderer: Box::new(move || {
                 RenderFn::merge(vec![el.scrollable.render(), el.get_backend_mut().render()])
             }),
-            background_image: element.style.background_image.clone(),
+            background_layers: element.style.get_background_layers(),
             background_color: element.style.background_color,
             border_width: element.get_border_width(),
+            box_shadow: element.style.box_shadow.clone(),
+            text_shadow: element.style.text_shadow.clone(),
+            color: element.style.color,
             width: bounds.width,
             height: bounds.height,
 
@@ -427,6 +492,7 @@ impl RenderTree {
                 surface_bounds: Rect::default(),
                 visible_bounds: Rect::default(),
                 clip_rect: Some(clip_rect),
+                opacity: 1.0,
             };
             self.layout_tree.layer_objects.push(layer_object_data);
             let children_layer_object = LayerRO {
@@ -488,6 +554,7 @@ impl RenderTree {
                 surface_bounds: Rect::default(),
                 visible_bounds: Rect::default(),
                 clip_rect: None,
+                opacity: element.style.opacity,
             };
             self.layout_tree.layer_objects.push(layer_object_data);
             let obj = self.create_normal_render_object(
@@ -536,9 +603,12 @@ impl RenderTree {
         // let mut border_path = element.create_border_path();
         let element_data = &mut self.element_objects[element_object_idx];
         element_data.border_color = element.style.border_color;
-        element_data.background_image = element.style.background_image.clone();
+        element_data.background_layers = element.style.get_background_layers();
         element_data.background_color = element.style.background_color;
         element_data.border_width = element.get_border_width();
+        element_data.box_shadow = element.style.box_shadow.clone();
+        element_data.text_shadow = element.style.text_shadow.clone();
+        element_data.color = element.style.color;
         element_data.coord = (bounds.x, bounds.y);
         element_data.layer_object_idx = Some(layer_object_idx);
         element_data.layer_coord = (layer_x, layer_y);
@@ -559,17 +629,26 @@ impl RenderTree {
 
     pub fn invalid_element(&mut self, element: &Element) {
         let render_object_idx = some_or_return!(element.render_object_idx);
-        let eo = some_or_return!(self.element_objects.get(render_object_idx));
-        let bounds = Rect::from_xywh(eo.layer_coord.0, eo.layer_coord.1, eo.width, eo.height);
+        let eo = some_or_return!(self.element_objects.get_mut(render_object_idx));
+        let bounds = eo.paint_bounds();
+        // Shadows may be changed since last paint
+        eo.box_shadow = element.style.box_shadow.clone();
+        eo.text_shadow = element.style.text_shadow.clone();
+        let new_bounds = eo.paint_bounds();
         let layer_idx = some_or_return!(eo.layer_object_idx);
         let lo = &mut self.layout_tree.layer_objects[layer_idx];
         lo.invalid(&bounds);
+        lo.invalid(&new_bounds);
     }
 
     fn need_create_root_layer(element: &Element) -> bool {
         if element.style.transform.is_some() {
             return true;
         }
+        // Translucent subtree is composited as a whole, opaque ones stay in parent layer
+        if element.style.opacity < 1.0 {
+            return true;
+        }
         let pos_type = element.style.yoga_node._yn.get_position_type();
         pos_type == PositionType::Absolute || pos_type == PositionType::Relative
     }
@@ -611,15 +690,16 @@ impl RenderTree {
             self.build_paint_normal_nodes(&mut eod.children.clone(), viewport, invalid_rects);
         let eo = &mut self.element_objects[eod.element_object_idx];
 
-        let need_paint = invalid_rects.has_intersects(&Rect::from_xywh(
-            eo.layer_coord.0,
-            eo.layer_coord.1,
-            eo.width,
-            eo.height,
-        ));
+        let need_paint = invalid_rects.has_intersects(&eo.paint_bounds());
+        let (outer_shadow, inset_shadow) = eo.build_shadow_objects();
         let border_path_mut = eo.element.get_border_path_mut();
         let border_path = border_path_mut.get_paths().clone();
         let border_box_path = border_path_mut.get_box_path().clone().unwrap();
+        let padding_box_path = if eo.background_layers.is_empty() {
+            None
+        } else {
+            border_path_mut.build_padding_box_path()
+        };
         let epo = ElementPO {
             coord: eo.coord,
             children,
@@ -632,7 +712,8 @@ impl RenderTree {
             } else {
                 None
             },
-            background_image: eo.background_image.clone(),
+            background_layers: eo.background_layers.clone(),
+            padding_box_path,
             background_color: eo.background_color,
             border_width: eo.border_width,
             width: eo.width,
@@ -640,6 +721,9 @@ impl RenderTree {
             element_id: eo.element_id,
             need_paint,
             focused: eo.element.is_focused(),
+            outer_shadow,
+            inset_shadow,
+            text_shadow_outset: shadows_outset(&eo.text_shadow),
         };
         epo
     }
@@ -770,7 +854,7 @@ impl RenderTree {
             lo.visible_bounds = visible_bounds.clone();
             invalid_area.build(visible_bounds.clone())
         };
-        let normal_nodes =
+        let mut normal_nodes =
             self.build_paint_normal_nodes(&lod.normal_nodes, viewport, &invalid_rects);
         let mut layers = Vec::new();
         for lo in &lod.layer_nodes {
@@ -778,6 +862,11 @@ impl RenderTree {
         }
 
         let lo = &mut self.layout_tree.layer_objects[lod.layer_object_idx];
+        // Shadow of root element is out of the layer, so it's painted when compositing the layer
+        let outer_shadow = match lo.key.layer_type {
+            RenderLayerType::Root => normal_nodes.first_mut().and_then(|e| e.outer_shadow.take()),
+            RenderLayerType::Children => None,
+        };
         let lpo = LayerPO {
             matrix: lo.matrix.clone(),
             total_matrix: lo.total_matrix.clone(),
@@ -791,6 +880,8 @@ impl RenderTree {
             surface_bounds: lo.surface_bounds.clone(),
             invalid_rects,
             clip_rect: lo.clip_rect.clone(),
+            opacity: lo.opacity,
+            outer_shadow,
         };
         lo.invalid_area = InvalidArea::None;
         lpo
@@ -1006,10 +1097,14 @@ impl MatrixCalculator {
 #[cfg(test)]
 pub mod tests {
     use crate::base::Rect;
-    use crate::paint::InvalidArea;
+    use crate::element::container::Container;
+    use crate::element::Element;
+    use crate::paint::{ElementObjectData, InvalidArea};
+    use crate::render::RenderFn;
+    use crate::style::shadow::ComputedShadow;
     use log::debug;
     use measure_time::print_time;
-    use skia_safe::{Matrix, Path, Vector};
+    use skia_safe::{Color, Matrix, Path, Vector};
 
     #[test]
     pub fn test_visible() {
@@ -1070,4 +1165,40 @@ pub mod tests {
             }
         }
     }
+
+    #[test]
+    pub fn test_paint_bounds() {
+        let shadow = |offset_x: f32, blur: f32| ComputedShadow {
+            offset_x,
+            offset_y: 0.0,
+            blur,
+            spread: 0.0,
+            color: None,
+            inset: false,
+        };
+        let element = Element::create(Container::create);
+        let mut eo = ElementObjectData {
+            coord: (5.0, 5.0),
+            layer_coord: (5.0, 5.0),
+            children_viewport: None,
+            border_color: [Color::TRANSPARENT; 4],
+            renderer: Box::new(RenderFn::empty),
+            background_layers: Vec::new(),
+            background_color: Color::TRANSPARENT,
+            border_width: (0.0, 0.0, 0.0, 0.0),
+            box_shadow: Vec::new(),
+            text_shadow: vec![shadow(4.0, 2.0)],
+            color: Color::BLACK,
+            width: 10.0,
+            height: 10.0,
+            layer_object_idx: None,
+            element_id: element.get_eid(),
+            element,
+        };
+        // The blur reaches 3px, the offset moves the shadow 4px to the right
+        assert_eq!(Rect::from_xywh(5.0, 2.0, 17.0, 16.0), eo.paint_bounds());
+
+        eo.box_shadow = vec![shadow(-6.0, 0.0)];
+        assert_eq!(Rect::from_xywh(-1.0, 2.0, 23.0, 16.0), eo.paint_bounds());
+    }
 }
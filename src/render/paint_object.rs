@@ -2,11 +2,95 @@ use crate::base;
 use crate::border::tiny_path_to_skia_path;
 use crate::paint::{InvalidRects, RenderLayerKey};
 use crate::render::RenderFn;
+use crate::style::background::BackgroundLayer;
 use crate::style::color::ColorHelper;
 use skia_safe::PaintStyle::{Fill, Stroke};
-use skia_safe::{Canvas, Color, Image, Matrix, Paint, Rect};
+use skia_safe::{BlurStyle, Canvas, ClipOp, Color, MaskFilter, Matrix, Paint, PathFillType, Rect};
 use tiny_skia::Path;
 
+pub struct BoxShadowPO {
+    /// Shadow shape before offset, i.e. the border box with spread applied
+    pub path: Path,
+    pub offset: (f32, f32),
+    pub blur_sigma: f32,
+    pub color: Color,
+}
+
+impl BoxShadowPO {
+    fn create_paint(&self) -> Paint {
+        let mut paint = Paint::default();
+        paint.set_style(Fill);
+        paint.set_anti_alias(true);
+        paint.set_color(self.color);
+        if self.blur_sigma > 0.0 {
+            paint.set_mask_filter(MaskFilter::blur(BlurStyle::Normal, self.blur_sigma, false));
+        }
+        paint
+    }
+}
+
+/// Shadows painted outside the border box of an element
+pub struct OuterShadowPO {
+    pub border_box_path: Path,
+    /// In painting order, i.e. the reverse of declared order
+    pub shadows: Vec<BoxShadowPO>,
+}
+
+impl OuterShadowPO {
+    /// Bounds of the element together with its shadows
+    pub fn bounds(&self) -> Rect {
+        let mut bounds = *tiny_path_to_skia_path(&self.border_box_path).bounds();
+        for s in &self.shadows {
+            let blur_extent = s.blur_sigma * 3.0;
+            let shadow_bounds = tiny_path_to_skia_path(&s.path)
+                .bounds()
+                .with_offset(s.offset)
+                .with_outset((blur_extent, blur_extent));
+            bounds.join(shadow_bounds);
+        }
+        bounds
+    }
+
+    pub fn draw(&self, canvas: &Canvas) {
+        canvas.save();
+        canvas.clip_path(
+            &tiny_path_to_skia_path(&self.border_box_path),
+            ClipOp::Difference,
+            true,
+        );
+        for s in &self.shadows {
+            let path = tiny_path_to_skia_path(&s.path).with_offset(s.offset);
+            canvas.draw_path(&path, &s.create_paint());
+        }
+        canvas.restore();
+    }
+}
+
+/// Shadows painted inside the padding box of an element
+pub struct InsetShadowPO {
+    pub padding_box_path: Path,
+    /// In painting order, `path` of shadow is the hole of the shadow
+    pub shadows: Vec<BoxShadowPO>,
+}
+
+impl InsetShadowPO {
+    pub fn draw(&self, canvas: &Canvas) {
+        let padding_box_path = tiny_path_to_skia_path(&self.padding_box_path);
+        canvas.save();
+        canvas.clip_path(&padding_box_path, ClipOp::Intersect, true);
+        let bounds = padding_box_path.bounds();
+        for s in &self.shadows {
+            let (ox, oy) = s.offset;
+            let outset = s.blur_sigma * 3.0 + ox.abs().max(oy.abs());
+            let mut path = tiny_path_to_skia_path(&s.path).with_offset(s.offset);
+            path.add_rect(bounds.with_outset((outset, outset)), None);
+            path.set_fill_type(PathFillType::EvenOdd);
+            canvas.draw_path(&path, &s.create_paint());
+        }
+        canvas.restore();
+    }
+}
+
 pub struct ElementPO {
     pub coord: (f32, f32),
     pub children: Vec<ElementPO>,
@@ -17,7 +101,9 @@ pub struct ElementPO {
     // pub layer_y: f32,
     pub border_color: [Color; 4],
     pub render_fn: Option<RenderFn>,
-    pub background_image: Option<Image>,
+    pub background_layers: Vec<BackgroundLayer>,
+    /// Clip of background layers, only built when there are layers
+    pub padding_box_path: Option<Path>,
     pub background_color: Color,
     pub border_width: (f32, f32, f32, f32),
     pub width: f32,
@@ -25,24 +111,57 @@ pub struct ElementPO {
     pub element_id: u32,
     pub need_paint: bool,
     pub focused: bool,
+    pub outer_shadow: Option<OuterShadowPO>,
+    pub inset_shadow: Option<InsetShadowPO>,
+    /// Extent of text shadows outside the border box, (top, right, bottom, left)
+    pub text_shadow_outset: (f32, f32, f32, f32),
 }
 
 impl ElementPO {
+    /// Clip of the paint of element itself, which is the border box grown by the text shadows
+    pub fn paint_clip_path(&self) -> skia_safe::Path {
+        let path = tiny_path_to_skia_path(&self.border_box_path);
+        let (top, right, bottom, left) = self.text_shadow_outset;
+        if top <= 0.0 && right <= 0.0 && bottom <= 0.0 && left <= 0.0 {
+            return path;
+        }
+        let bounds = path.bounds();
+        let rect = Rect::from_ltrb(
+            bounds.left - left,
+            bounds.top - top,
+            bounds.right + right,
+            bounds.bottom + bottom,
+        );
+        skia_safe::Path::rect(rect, None)
+    }
+
     pub fn draw_background(&self, canvas: &Canvas) {
         // let pi = some_or_return!(&self.paint_info);
-        if let Some(img) = &self.background_image {
-            canvas.draw_image(img, (0.0, 0.0), Some(&Paint::default()));
-        } else if !self.background_color.is_transparent() {
+        let (bd_top, bd_right, bd_bottom, bd_left) = self.border_width;
+        let rect = Rect::new(
+            bd_left,
+            bd_top,
+            self.width - bd_right,
+            self.height - bd_bottom,
+        );
+        if !self.background_color.is_transparent() {
             let mut paint = Paint::default();
-            let (bd_top, bd_right, bd_bottom, bd_left) = self.border_width;
-            let width = self.width;
-            let height = self.height;
-            let rect = Rect::new(bd_left, bd_top, width - bd_right, height - bd_bottom);
-
             paint.set_color(self.background_color);
             paint.set_style(Fill);
             canvas.draw_rect(&rect, &paint);
         }
+        if self.background_layers.is_empty() {
+            return;
+        }
+        canvas.save();
+        if let Some(path) = &self.padding_box_path {
+            canvas.clip_path(&tiny_path_to_skia_path(path), ClipOp::Intersect, true);
+        }
+        // The first layer is painted on the top
+        for layer in self.background_layers.iter().rev() {
+            layer.draw(canvas, &rect);
+        }
+        canvas.restore();
     }
 
     pub fn draw_border(&mut self, canvas: &Canvas) {
@@ -86,4 +205,7 @@ pub struct LayerPO {
     pub surface_bounds: base::Rect,
     pub visible_bounds: base::Rect,
     pub clip_rect: Option<base::Rect>,
+    pub opacity: f32,
+    /// Outer shadow of the root element, which is painted outside of the layer
+    pub outer_shadow: Option<OuterShadowPO>,
 }
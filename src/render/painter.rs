@@ -71,14 +71,21 @@ impl ElementPainter {
         }
         canvas.save();
         canvas.translate(epo.coord);
+        if epo.need_paint {
+            if let Some(outer_shadow) = &epo.outer_shadow {
+                outer_shadow.draw(canvas);
+            }
+            // Text shadows may overflow the border box, children are still clipped by it
+            canvas.save();
+            canvas.clip_path(&epo.paint_clip_path(), ClipOp::Intersect, false);
+            self.draw_element_paint_object(painter, epo);
+            canvas.restore();
+        }
         canvas.clip_path(
             &tiny_path_to_skia_path(&epo.border_box_path),
             ClipOp::Intersect,
             false,
         );
-        if epo.need_paint {
-            self.draw_element_paint_object(painter, epo);
-        }
         for e in &mut epo.children {
             self.draw_element_object_recurse(painter, e, context);
         }
@@ -146,6 +153,12 @@ impl ElementPainter {
         if surface_width <= 0 || surface_height <= 0 {
             return;
         }
+        // Blend the layer together with its sublayers, so overlapped content doesn't show through
+        let translucent = layer.opacity < 1.0;
+        if translucent {
+            // Unbounded, sublayers may overflow the bounds of this layer
+            root_canvas.save_layer_alpha_f(None, layer.opacity);
+        }
         {
             let mut graphic_layer = if is_root {
                 LayerState {
@@ -198,6 +211,9 @@ impl ElementPainter {
             root_canvas.save();
             let old_total_matrix = root_canvas.local_to_device();
             root_canvas.concat(&layer.total_matrix);
+            if let Some(outer_shadow) = &layer.outer_shadow {
+                outer_shadow.draw(root_canvas);
+            }
             if let Some(clip_rect) = &layer.clip_rect {
                 root_canvas.clip_rect(&clip_rect.to_skia_rect(), ClipOp::Intersect, false);
             } else {
@@ -218,6 +234,9 @@ impl ElementPainter {
             self.draw_layer(painter, context, l, layer_state_map, false);
         }
         root_canvas.restore();
+        if translucent {
+            root_canvas.restore();
+        }
     }
 
     fn get_graphic_layer(
@@ -295,6 +314,9 @@ impl ElementPainter {
         painter.canvas.session(move |canvas| {
             // draw background and border
             node.draw_background(&canvas);
+            if let Some(inset_shadow) = &node.inset_shadow {
+                inset_shadow.draw(&canvas);
+            }
             node.draw_border(&canvas);
 
             // draw padding box and content box
@@ -1,6 +1,6 @@
-use skia_safe::{surfaces, Canvas, Surface};
+use skia_safe::{surfaces, Canvas, Image, Surface};
+use skia_window::layer::ILayer;
 
-//TODO remove
 pub struct CpuRenderer {
     surface: Surface,
 }
@@ -20,6 +20,16 @@ impl CpuRenderer {
     }
 }
 
+impl ILayer for CpuRenderer {
+    fn canvas(&mut self) -> &Canvas {
+        self.surface.canvas()
+    }
+
+    fn as_image(&mut self) -> Image {
+        self.surface.image_snapshot()
+    }
+}
+
 /*
 pub fn test_border(canvas: &Canvas) {
     print_time!("draw border time");
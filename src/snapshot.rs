@@ -0,0 +1,157 @@
+use crate::error::{DeftError, DeftResult};
+use image::{ImageFormat, RgbaImage};
+use log::warn;
+use skia_safe::images::raster_from_data;
+use skia_safe::{AlphaType, ColorType, Data, IRect, Image, ImageInfo};
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::{env, fs};
+
+/// Max difference of a color channel which is still treated as the same color
+pub const DEFAULT_CHANNEL_TOLERANCE: u8 = 2;
+
+/// Encode the pixels of `image` in `bounds` (the whole image if not specified) as png.
+pub fn encode_png(image: &Image, bounds: Option<IRect>) -> DeftResult<Vec<u8>> {
+    let full = IRect::from_wh(image.width(), image.height());
+    let bounds = match bounds {
+        None => full,
+        Some(b) => IRect::intersect(&b, &full).ok_or(DeftError::InvalidParameter)?,
+    };
+    let (width, height) = (bounds.width(), bounds.height());
+    let info = ImageInfo::new(
+        (width, height),
+        ColorType::RGBA8888,
+        AlphaType::Unpremul,
+        None,
+    );
+    let row_bytes = width as usize * 4;
+    let mut pixels = vec![0u8; row_bytes * height as usize];
+    if !image.read_pixels(
+        &info,
+        &mut pixels,
+        row_bytes,
+        (bounds.left, bounds.top),
+        skia_safe::image::CachingHint::Allow,
+    ) {
+        return Err(DeftError::Internal("failed to read pixels".to_string()));
+    }
+    let rgba = RgbaImage::from_raw(width as u32, height as u32, pixels)
+        .ok_or(DeftError::InvalidState)?;
+    let mut result = Cursor::new(Vec::new());
+    rgba.write_to(&mut result, ImageFormat::Png)
+        .map_err(|e| DeftError::Internal(e.to_string()))?;
+    Ok(result.into_inner())
+}
+
+/// Decode png bytes to a raster image, e.g. to draw a captured frame.
+pub fn decode_png(png: &[u8]) -> DeftResult<Image> {
+    let rgba = image::load_from_memory_with_format(png, ImageFormat::Png)
+        .map_err(|e| DeftError::Internal(e.to_string()))?
+        .into_rgba8();
+    let (width, height) = rgba.dimensions();
+    let info = ImageInfo::new(
+        (width as i32, height as i32),
+        ColorType::RGBA8888,
+        AlphaType::Unpremul,
+        None,
+    );
+    let data = Data::new_copy(rgba.as_raw());
+    raster_from_data(&info, data, width as usize * 4).ok_or(DeftError::InvalidState)
+}
+
+/// Count the pixels which differ more than `tolerance` in any channel.
+///
+/// Images with different sizes are treated as totally different.
+pub fn compare_png(actual: &[u8], expected: &[u8], tolerance: u8) -> DeftResult<usize> {
+    let load = |png: &[u8]| {
+        image::load_from_memory_with_format(png, ImageFormat::Png)
+            .map(|img| img.into_rgba8())
+            .map_err(|e| DeftError::Internal(e.to_string()))
+    };
+    let actual = load(actual)?;
+    let expected = load(expected)?;
+    if actual.dimensions() != expected.dimensions() {
+        let (w, h) = actual.dimensions();
+        let (ew, eh) = expected.dimensions();
+        return Ok((w * h).max(ew * eh) as usize);
+    }
+    let diff = actual
+        .pixels()
+        .zip(expected.pixels())
+        .filter(|(a, e)| {
+            a.0.iter()
+                .zip(e.0.iter())
+                .any(|(a, e)| a.abs_diff(*e) > tolerance)
+        })
+        .count();
+    Ok(diff)
+}
+
+/// Compare `png` with the golden file at `path`.
+///
+/// The golden file is written when it doesn't exist or env `DEFT_UPDATE_SNAPSHOTS` is set.
+/// On mismatch, the actual result is saved beside it as `<name>.actual.png`.
+pub fn match_snapshot<P: AsRef<Path>>(path: P, png: &[u8]) -> DeftResult<()> {
+    let path = path.as_ref();
+    let update = env::var("DEFT_UPDATE_SNAPSHOTS").unwrap_or("0".to_string()) != "0";
+    if update || !path.exists() {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| DeftError::Internal(e.to_string()))?;
+        }
+        warn!("writing snapshot: {}", path.display());
+        return fs::write(path, png).map_err(|e| DeftError::Internal(e.to_string()));
+    }
+    let expected = fs::read(path).map_err(|e| DeftError::Internal(e.to_string()))?;
+    let diff = compare_png(png, &expected, DEFAULT_CHANNEL_TOLERANCE)?;
+    if diff == 0 {
+        return Ok(());
+    }
+    let actual_path = actual_path(path);
+    let _ = fs::write(&actual_path, png);
+    Err(DeftError::Internal(format!(
+        "snapshot mismatch: {} pixels differ from {}, actual result saved to {}",
+        diff,
+        path.display(),
+        actual_path.display()
+    )))
+}
+
+/// Panic if `png` doesn't match the golden file at `path`, see [`match_snapshot`].
+pub fn assert_snapshot<P: AsRef<Path>>(path: P, png: &[u8]) {
+    if let Err(e) = match_snapshot(path, png) {
+        panic!("{}", e);
+    }
+}
+
+fn actual_path(path: &Path) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    path.with_file_name(format!("{}.actual.png", stem))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::renderer::CpuRenderer;
+    use crate::snapshot::{compare_png, encode_png};
+    use skia_safe::{Color, IRect, Paint, Rect};
+
+    #[test]
+    fn test_encode_and_compare() {
+        let mut renderer = CpuRenderer::new(20, 10);
+        let canvas = renderer.canvas();
+        canvas.clear(Color::WHITE);
+        let mut paint = Paint::default();
+        paint.set_color(Color::RED);
+        canvas.draw_rect(Rect::from_xywh(0.0, 0.0, 10.0, 10.0), &paint);
+        let image = renderer.surface().image_snapshot();
+
+        let full = encode_png(&image, None).unwrap();
+        let left = encode_png(&image, Some(IRect::from_xywh(0, 0, 10, 10))).unwrap();
+        let right = encode_png(&image, Some(IRect::from_xywh(10, 0, 10, 10))).unwrap();
+        assert_eq!(0, compare_png(&full, &full, 0).unwrap());
+        assert_eq!(100, compare_png(&left, &right, 2).unwrap());
+        assert_eq!(200, compare_png(&full, &left, 2).unwrap());
+    }
+}
@@ -1,4 +1,5 @@
 pub mod animation;
+pub mod background;
 pub mod border;
 pub mod border_path;
 pub mod color;
@@ -6,16 +7,21 @@ pub mod css_manager;
 pub mod flex;
 pub mod font;
 pub mod length;
+pub mod media;
 mod node_item;
 pub mod overflow;
 mod select;
+pub mod shadow;
 pub mod style_vars;
 pub mod styles;
+pub mod text;
 pub mod transform;
+pub mod transition;
 pub mod var_expr;
 
 use crate as deft;
 use crate::animation::css_actor::CssAnimationActor;
+use crate::animation::timing_function::TimingFunction;
 use crate::animation::ANIMATIONS;
 use crate::animation::{AnimationInstance, WindowAnimationController};
 use crate::base::Rect;
@@ -23,22 +29,35 @@ use crate::element::scroll::ScrollBarStrategy;
 use crate::element::ElementWeak;
 use crate::event_loop::create_event_loop_callback;
 use crate::font::family::FontFamilies;
+use crate::img_manager::IMG_MANAGER;
 use crate::mrc::{Mrc, MrcWeak};
 use crate::number::DeNan;
-use crate::style::animation::AnimationParams;
+use crate::style::animation::{
+    AnimationDirection, AnimationFillMode, AnimationParams, AnimationPlayState, AnimationTime,
+};
+use crate::style::background::{
+    build_background_layers, BackgroundImage, BackgroundImageValue, BackgroundLayer,
+    BackgroundPosition, BackgroundRepeat, BackgroundSize, BackgroundSource,
+};
 use crate::style::font::{FontStyle, LineHeightVal};
 use crate::style::length::{Length, LengthContext, LengthOrPercent};
 use crate::style::node_item::NodeItem;
 use crate::style::overflow::Overflow;
+use crate::style::shadow::{BoxShadow, ComputedShadow, TextShadow};
 use crate::style::style_vars::StyleVars;
+use crate::style::text::{
+    LetterSpacing, LineClamp, TextOverflow, TextTransform, WhiteSpace, WordBreak,
+};
 use crate::style::transform::StyleTransform;
+use crate::style::transition::{TimeList, TimingFunctionList, TransitionProperty};
 use crate::style_list::ParsedStyleProp;
+use crate::text::TextAlign;
 use crate::{ok_or_return, some_or_return};
 use anyhow::{anyhow, Error};
 use deft_macros::mrc_object;
 use quick_js::JsValue;
 use skia_safe::font_style::Weight;
-use skia_safe::{Color, Image, Matrix};
+use skia_safe::{Color, Matrix};
 use std::collections::HashMap;
 use std::ops::{Deref, DerefMut};
 use std::str::FromStr;
@@ -195,6 +214,14 @@ macro_rules! define_style_props {
                     )*
                 }
             }
+            pub fn to_resolved(&self) -> Option<ResolvedStyleProp> {
+                match self {
+                    $(
+                        Self::$name(StylePropVal::Custom(v)) => Some(ResolvedStyleProp::$name(v.clone())),
+                    )*
+                    _ => None,
+                }
+            }
             pub fn unset(&self) -> Self {
                 match self {
                     $(
@@ -250,11 +277,25 @@ macro_rules! define_style_props {
 define_style_props!(
     Color => Color, Color;
     BackgroundColor => Color, Color;
+    BackgroundImage => BackgroundImage, BackgroundImage;
+    BackgroundSize => BackgroundSize, BackgroundSize;
+    BackgroundPosition => BackgroundPosition, BackgroundPosition;
+    BackgroundRepeat => BackgroundRepeat, BackgroundRepeat;
+    Opacity => f32, f32;
+    BoxShadow => BoxShadow, BoxShadow;
+    TextShadow => TextShadow, TextShadow;
     FontSize        => Length, f32;
     FontFamily      => FontFamilies, FontFamilies;
     FontWeight      => Weight, Weight;
     FontStyle       => FontStyle, Style;
     LineHeight      => LineHeightVal, f32;
+    TextAlign       => TextAlign, TextAlign;
+    TextOverflow    => TextOverflow, TextOverflow;
+    WhiteSpace      => WhiteSpace, WhiteSpace;
+    WordBreak       => WordBreak, WordBreak;
+    LineClamp       => LineClamp, LineClamp;
+    LetterSpacing   => LetterSpacing, f32;
+    TextTransform   => TextTransform, TextTransform;
 
     BorderTopWidth => LengthOrPercent, f32;
     BorderRightWidth => LengthOrPercent, f32;
@@ -314,8 +355,17 @@ define_style_props!(
 
     Transform => StyleTransform, StyleTransform;
     AnimationName => String, String;
-    AnimationDuration => f32, f32;
+    AnimationDuration => AnimationTime, AnimationTime;
     AnimationIterationCount => f32, f32;
+    AnimationTimingFunction => TimingFunction, TimingFunction;
+    AnimationDelay => AnimationTime, AnimationTime;
+    AnimationDirection => AnimationDirection, AnimationDirection;
+    AnimationFillMode => AnimationFillMode, AnimationFillMode;
+    AnimationPlayState => AnimationPlayState, AnimationPlayState;
+    TransitionProperty => TransitionProperty, TransitionProperty;
+    TransitionDuration => TimeList, TimeList;
+    TransitionTimingFunction => TimingFunctionList, TimingFunctionList;
+    TransitionDelay => TimeList, TimeList;
 );
 
 pub fn parse_box_prop(str: &str, default: &str) -> (String, String, String, String) {
@@ -336,6 +386,27 @@ pub fn parse_box_prop(str: &str, default: &str) -> (String, String, String, Stri
     )
 }
 
+/// Split by `sep` outside of parentheses, e.g. `rgb(0, 0, 0)` is kept as one part.
+pub fn split_top_level(value: &str, sep: char) -> Vec<&str> {
+    let mut result = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in value.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            c if c == sep && depth == 0 => {
+                result.push(value[start..i].trim());
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    result.push(value[start..].trim());
+    result.retain(|s| !s.is_empty());
+    result
+}
+
 #[derive(PartialEq, Clone)]
 pub struct YogaNode {
     node: Mrc<Node>,
@@ -374,8 +445,14 @@ pub struct StyleNode {
     // (inherited, computed)
     pub border_radius: [f32; 4],
     pub border_color: [Color; 4],
-    pub background_image: Option<Image>,
+    pub background_image: Vec<Option<BackgroundSource>>,
+    pub background_size: BackgroundSize,
+    pub background_position: BackgroundPosition,
+    pub background_repeat: BackgroundRepeat,
     pub transform: Option<StyleTransform>,
+    pub opacity: f32,
+    pub box_shadow: Vec<ComputedShadow>,
+    pub text_shadow: Vec<ComputedShadow>,
     animation_params: AnimationParams,
     animation_instance: Option<AnimationInstance>,
     pub on_changed: Option<Box<dyn FnMut(StylePropKey)>>,
@@ -387,6 +464,14 @@ pub struct StyleNode {
     pub font_family: FontFamilies,
     pub font_weight: Weight,
     pub font_style: FontStyle,
+    pub text_align: TextAlign,
+    pub text_overflow: TextOverflow,
+    pub white_space: WhiteSpace,
+    pub word_break: WordBreak,
+    pub line_clamp: LineClamp,
+    pub letter_spacing: f32,
+    pub text_transform: TextTransform,
+    pub direction: Direction,
 }
 
 impl StyleNode {
@@ -399,8 +484,14 @@ impl StyleNode {
             children: Vec::new(),
             border_radius: [0.0, 0.0, 0.0, 0.0],
             border_color: [transparent, transparent, transparent, transparent],
-            background_image: None,
+            background_image: Vec::new(),
+            background_size: BackgroundSize::default(),
+            background_position: BackgroundPosition::default(),
+            background_repeat: BackgroundRepeat::default(),
             transform: None,
+            opacity: 1.0,
+            box_shadow: Vec::new(),
+            text_shadow: Vec::new(),
             animation_instance: None,
             animation_params: AnimationParams::new(),
             on_changed: None,
@@ -412,11 +503,28 @@ impl StyleNode {
             font_family: FontFamilies::default(),
             font_weight: Weight::NORMAL,
             font_style: FontStyle::Normal,
+            text_align: TextAlign::Left,
+            text_overflow: TextOverflow::Clip,
+            white_space: WhiteSpace::PreWrap,
+            word_break: WordBreak::Normal,
+            line_clamp: LineClamp::None,
+            letter_spacing: 0.0,
+            text_transform: TextTransform::None,
+            direction: Direction::LTR,
         };
         inner.yoga_node.position_type = PositionType::Static;
         inner.to_ref()
     }
 
+    pub fn get_background_layers(&self) -> Vec<BackgroundLayer> {
+        build_background_layers(
+            &self.background_image,
+            &self.background_size,
+            &self.background_position,
+            &self.background_repeat,
+        )
+    }
+
     pub fn has_shadow(&self) -> bool {
         self.yoga_node.has_shadow()
     }
@@ -472,11 +580,30 @@ impl StyleNode {
         match key {
             StylePropKey::Color => ResolvedStyleProp::Color(Color::BLACK),
             StylePropKey::BackgroundColor => ResolvedStyleProp::BackgroundColor(Color::TRANSPARENT),
+            StylePropKey::BackgroundImage => {
+                ResolvedStyleProp::BackgroundImage(BackgroundImage::default())
+            }
+            StylePropKey::BackgroundSize => {
+                ResolvedStyleProp::BackgroundSize(BackgroundSize::default())
+            }
+            StylePropKey::BackgroundPosition => {
+                ResolvedStyleProp::BackgroundPosition(BackgroundPosition::default())
+            }
+            StylePropKey::BackgroundRepeat => {
+                ResolvedStyleProp::BackgroundRepeat(BackgroundRepeat::default())
+            }
             StylePropKey::FontSize => ResolvedStyleProp::FontSize(Length::PX(12.0)),
             StylePropKey::FontFamily => ResolvedStyleProp::FontFamily(FontFamilies::default()),
             StylePropKey::FontWeight => ResolvedStyleProp::FontWeight(Weight::NORMAL),
             StylePropKey::FontStyle => ResolvedStyleProp::FontStyle(FontStyle::Normal),
             StylePropKey::LineHeight => ResolvedStyleProp::LineHeight(LineHeightVal::Normal),
+            StylePropKey::TextAlign => ResolvedStyleProp::TextAlign(TextAlign::Left),
+            StylePropKey::TextOverflow => ResolvedStyleProp::TextOverflow(TextOverflow::Clip),
+            StylePropKey::WhiteSpace => ResolvedStyleProp::WhiteSpace(WhiteSpace::PreWrap),
+            StylePropKey::WordBreak => ResolvedStyleProp::WordBreak(WordBreak::Normal),
+            StylePropKey::LineClamp => ResolvedStyleProp::LineClamp(LineClamp::None),
+            StylePropKey::LetterSpacing => ResolvedStyleProp::LetterSpacing(LetterSpacing::Normal),
+            StylePropKey::TextTransform => ResolvedStyleProp::TextTransform(TextTransform::None),
             StylePropKey::BorderTopWidth => ResolvedStyleProp::BorderTopWidth(default_border_width),
             StylePropKey::BorderRightWidth => {
                 ResolvedStyleProp::BorderRightWidth(default_border_width)
@@ -564,11 +691,41 @@ impl StyleNode {
                 ResolvedStyleProp::BorderBottomLeftRadius(Length::PX(0.0))
             }
             StylePropKey::Transform => ResolvedStyleProp::Transform(StyleTransform::empty()),
+            StylePropKey::Opacity => ResolvedStyleProp::Opacity(1.0),
+            StylePropKey::BoxShadow => ResolvedStyleProp::BoxShadow(BoxShadow::default()),
+            StylePropKey::TextShadow => ResolvedStyleProp::TextShadow(TextShadow::default()),
             StylePropKey::AnimationName => ResolvedStyleProp::AnimationName("".to_string()),
-            StylePropKey::AnimationDuration => ResolvedStyleProp::AnimationDuration(0.0),
+            StylePropKey::AnimationDuration => {
+                ResolvedStyleProp::AnimationDuration(AnimationTime(0.0))
+            }
             StylePropKey::AnimationIterationCount => {
                 ResolvedStyleProp::AnimationIterationCount(1.0)
             }
+            StylePropKey::AnimationTimingFunction => {
+                ResolvedStyleProp::AnimationTimingFunction(TimingFunction::Linear)
+            }
+            StylePropKey::AnimationDelay => ResolvedStyleProp::AnimationDelay(AnimationTime(0.0)),
+            StylePropKey::AnimationDirection => {
+                ResolvedStyleProp::AnimationDirection(AnimationDirection::Normal)
+            }
+            StylePropKey::AnimationFillMode => {
+                ResolvedStyleProp::AnimationFillMode(AnimationFillMode::None)
+            }
+            StylePropKey::AnimationPlayState => {
+                ResolvedStyleProp::AnimationPlayState(AnimationPlayState::Running)
+            }
+            StylePropKey::TransitionProperty => {
+                ResolvedStyleProp::TransitionProperty(TransitionProperty::default())
+            }
+            StylePropKey::TransitionDuration => {
+                ResolvedStyleProp::TransitionDuration(TimeList::default())
+            }
+            StylePropKey::TransitionTimingFunction => {
+                ResolvedStyleProp::TransitionTimingFunction(TimingFunctionList::default())
+            }
+            StylePropKey::TransitionDelay => {
+                ResolvedStyleProp::TransitionDelay(TimeList::default())
+            }
 
             StylePropKey::JustifyContent => ResolvedStyleProp::JustifyContent(Justify::FlexStart),
             StylePropKey::FlexDirection => ResolvedStyleProp::FlexDirection(FlexDirection::Column),
@@ -604,6 +761,35 @@ impl StyleNode {
                 self.background_color = value;
                 need_layout = false;
             }
+            ResolvedStyleProp::BackgroundImage(value) => {
+                self.background_image = value.layers.iter().map(|l| l.load(length_ctx)).collect();
+                self.load_background_images(&value);
+                need_layout = false;
+            }
+            ResolvedStyleProp::BackgroundSize(value) => {
+                self.background_size = value.compute(length_ctx);
+                need_layout = false;
+            }
+            ResolvedStyleProp::BackgroundPosition(value) => {
+                self.background_position = value.compute(length_ctx);
+                need_layout = false;
+            }
+            ResolvedStyleProp::BackgroundRepeat(value) => {
+                self.background_repeat = value;
+                need_layout = false;
+            }
+            ResolvedStyleProp::Opacity(value) => {
+                self.opacity = value.clamp(0.0, 1.0);
+                need_layout = false;
+            }
+            ResolvedStyleProp::BoxShadow(value) => {
+                self.box_shadow = value.0.iter().map(|s| s.compute(&length_ctx)).collect();
+                need_layout = false;
+            }
+            ResolvedStyleProp::TextShadow(value) => {
+                self.text_shadow = value.0.iter().map(|s| s.compute(&length_ctx)).collect();
+                need_layout = false;
+            }
             ResolvedStyleProp::FontSize(_) => {
                 //Do nothing
                 change_notified = true;
@@ -621,6 +807,27 @@ impl StyleNode {
             ResolvedStyleProp::LineHeight(value) => {
                 self.line_height = value.to_px(length_ctx);
             }
+            ResolvedStyleProp::TextAlign(value) => {
+                self.text_align = value;
+            }
+            ResolvedStyleProp::TextOverflow(value) => {
+                self.text_overflow = value;
+            }
+            ResolvedStyleProp::WhiteSpace(value) => {
+                self.white_space = value;
+            }
+            ResolvedStyleProp::WordBreak(value) => {
+                self.word_break = value;
+            }
+            ResolvedStyleProp::LineClamp(value) => {
+                self.line_clamp = value;
+            }
+            ResolvedStyleProp::LetterSpacing(value) => {
+                self.letter_spacing = value.to_px(length_ctx);
+            }
+            ResolvedStyleProp::TextTransform(value) => {
+                self.text_transform = value;
+            }
             ResolvedStyleProp::BorderTopWidth(value) => {
                 self.set_border_width(&value, &vec![0], length_ctx);
             }
@@ -699,7 +906,10 @@ impl StyleNode {
             ResolvedStyleProp::FlexGrow(value) => self.yoga_node.flex_grow = value,
             ResolvedStyleProp::FlexShrink(value) => self.yoga_node.flex_shrink = value,
             ResolvedStyleProp::AlignSelf(value) => self.yoga_node.align_self = value,
-            ResolvedStyleProp::Direction(value) => self.yoga_node.direction = value,
+            ResolvedStyleProp::Direction(value) => {
+                self.direction = value;
+                self.yoga_node.direction = value;
+            }
             ResolvedStyleProp::Position(value) => self.yoga_node.position_type = value,
             ResolvedStyleProp::Top(value) => {
                 self.yoga_node.top = value.to_style_unit(&length_ctx);
@@ -757,8 +967,7 @@ impl StyleNode {
             }
             ResolvedStyleProp::AnimationDuration(value) => {
                 need_layout = false;
-                let duration = value;
-                self.animation_params.duration = duration;
+                self.animation_params.duration = value.0;
                 self.update_animation();
             }
             ResolvedStyleProp::AnimationIterationCount(value) => {
@@ -767,6 +976,44 @@ impl StyleNode {
                 self.animation_params.iteration_count = ic;
                 self.update_animation();
             }
+            ResolvedStyleProp::AnimationTimingFunction(value) => {
+                need_layout = false;
+                self.animation_params.timing_function = value;
+                self.update_animation();
+            }
+            ResolvedStyleProp::AnimationDelay(value) => {
+                need_layout = false;
+                self.animation_params.delay = value.0;
+                self.update_animation();
+            }
+            ResolvedStyleProp::AnimationDirection(value) => {
+                need_layout = false;
+                self.animation_params.direction = value;
+                self.update_animation();
+            }
+            ResolvedStyleProp::AnimationFillMode(value) => {
+                need_layout = false;
+                self.animation_params.fill_mode = value;
+                self.update_animation();
+            }
+            ResolvedStyleProp::AnimationPlayState(value) => {
+                need_layout = false;
+                // Pausing keeps the progress, so the running instance is not recreated
+                if let Some(instance) = &mut self.animation_instance {
+                    match value {
+                        AnimationPlayState::Running => instance.resume(),
+                        AnimationPlayState::Paused => instance.pause(),
+                    }
+                }
+                self.animation_params.play_state = value;
+            }
+            ResolvedStyleProp::TransitionProperty(_)
+            | ResolvedStyleProp::TransitionDuration(_)
+            | ResolvedStyleProp::TransitionTimingFunction(_)
+            | ResolvedStyleProp::TransitionDelay(_) => {
+                // Transitions are started by element when target values change
+                need_layout = false;
+            }
 
             // container node style
             ResolvedStyleProp::JustifyContent(value) => {
@@ -800,36 +1047,81 @@ impl StyleNode {
         (repaint, need_layout)
     }
 
+    /// Load url images of `value` in background and repaint once they arrive. Images failed to
+    /// load are loaded again the next time the style is resolved.
+    fn load_background_images(&mut self, value: &BackgroundImage) {
+        for (i, layer) in value.layers.iter().enumerate() {
+            let BackgroundImageValue::Url(src) = layer else {
+                continue;
+            };
+            let me = self.as_weak();
+            let expected = ResolvedStyleProp::BackgroundImage(value.clone());
+            IMG_MANAGER.with(|im| {
+                im.load_img_async(src, move |img| {
+                    let mut me = ok_or_return!(me.upgrade());
+                    // The background may be replaced while loading
+                    if me.resolved_style_props.get(&StylePropKey::BackgroundImage)
+                        != Some(&expected)
+                    {
+                        return;
+                    }
+                    match img {
+                        Some(img) => {
+                            me.background_image[i] = Some(BackgroundSource::Image(img));
+                            me.element.mark_dirty(false);
+                        }
+                        None => {
+                            me.resolved_style_props
+                                .remove(&StylePropKey::BackgroundImage);
+                        }
+                    }
+                });
+            });
+        }
+    }
+
     fn update_animation(&mut self) {
         let mut me = self.clone();
         let task = create_event_loop_callback(move || {
-            let p = &me.animation_params;
-            me.animation_instance =
-                if p.name.is_empty() || p.duration <= 0.0 || p.iteration_count <= 0.0 {
-                    None
-                } else {
-                    let element = ok_or_return!(me.element.upgrade());
-                    let window = some_or_return!(element.get_window());
-                    ANIMATIONS.with_borrow(|m| {
-                        let ani = m.get(&p.name)?.preprocess();
-                        let frame_controller = WindowAnimationController::new(window);
-                        let duration = p.duration * 1000000.0;
-                        let iteration_count = p.iteration_count;
-                        let actor = CssAnimationActor::new(ani, element.as_weak());
-                        let mut ani_instance = AnimationInstance::new(
-                            actor,
-                            duration,
-                            iteration_count,
-                            Box::new(frame_controller),
-                        );
-                        ani_instance.run();
-                        Some(ani_instance)
-                    })
-                };
+            me.restart_animation();
         });
         task.call();
     }
 
+    /// Replace the running animation with one built from the current params
+    fn restart_animation(&mut self) {
+        // The old actor clears the animated styles when stopped, so it must be stopped before the
+        // new animation applies its first frame
+        if let Some(mut old) = self.animation_instance.take() {
+            old.cancel();
+        }
+        let p = self.animation_params.clone();
+        if p.name.is_empty() || p.duration <= 0.0 || p.iteration_count <= 0.0 {
+            return;
+        }
+        let element = ok_or_return!(self.element.upgrade());
+        let window = some_or_return!(element.get_window());
+        self.animation_instance = ANIMATIONS.with_borrow(|m| {
+            let ani = m.get(&p.name)?.preprocess();
+            let frame_controller = WindowAnimationController::new(window);
+            let duration = p.duration * 1000000.0;
+            let iteration_count = p.iteration_count;
+            let actor = CssAnimationActor::new(ani, p.clone(), element.as_weak());
+            let mut ani_instance = AnimationInstance::new(
+                actor,
+                duration,
+                iteration_count,
+                Box::new(frame_controller),
+            );
+            ani_instance.set_delay(p.delay * 1000000.0);
+            if p.play_state == AnimationPlayState::Paused {
+                ani_instance.pause();
+            }
+            ani_instance.run();
+            Some(ani_instance)
+        });
+    }
+
     pub fn get_parent(&self) -> Option<StyleNode> {
         if let Some(p) = &self.parent {
             if let Ok(sn) = p.upgrade() {
@@ -976,3 +1268,65 @@ fn create_matrix(values: [f32; 6]) -> Matrix {
         scale_x, skew_x, trans_x, skew_y, scale_y, trans_y, 0.0, 0.0, 1.0,
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::animation::{AnimationDef, ANIMATIONS};
+    use crate::element::body::Body;
+    use crate::element::container::Container;
+    use crate::element::Element;
+    use crate::ext::ext_window::WindowAttrs;
+    use crate::style::animation::{AnimationFillMode, AnimationPlayState};
+    use crate::style::{FixedStyleProp, StylePropKey, StylePropVal};
+    use crate::window::driver::WindowDriver;
+    use crate::window::Window;
+
+    #[test]
+    fn test_replace_delayed_animation() {
+        let handle = Window::create(WindowAttrs {
+            width: Some(100.0),
+            height: Some(100.0),
+            headless: Some(true),
+            ..Default::default()
+        })
+        .unwrap();
+        let mut body = Element::create(Body::create);
+        let mut el = Element::create(Container::create);
+        body.add_child(el.clone(), 0).unwrap();
+        handle.upgrade_mut().unwrap().set_body(body).unwrap();
+        ANIMATIONS.with_borrow_mut(|m| {
+            for (name, opacity) in [("fade-a", 0.2), ("fade-b", 0.6)] {
+                let frame = |v| vec![FixedStyleProp::Opacity(StylePropVal::Custom(v))];
+                let ani = AnimationDef::new()
+                    .key_frame(0.0, frame(opacity))
+                    .key_frame(1.0, frame(1.0));
+                m.insert(name.to_string(), ani.build());
+            }
+        });
+        let driver = WindowDriver::new(handle);
+        let opacity = |el: &Element| {
+            el.animation_style_props
+                .get(&StylePropKey::Opacity)
+                .cloned()
+        };
+        let filled = |v| Some(FixedStyleProp::Opacity(StylePropVal::Custom(v)));
+
+        let params = &mut el.style.animation_params;
+        params.name = "fade-a".to_string();
+        params.duration = 1000.0;
+        params.delay = 60000.0;
+        params.fill_mode = AnimationFillMode::Backwards;
+        // Paused animation has no pending frame, so stopping it requests a new one
+        params.play_state = AnimationPlayState::Paused;
+        el.style.restart_animation();
+        driver.flush().unwrap();
+        assert_eq!(filled(0.2), opacity(&el));
+
+        // The replaced animation must not clear the backwards fill of the new one
+        el.style.animation_params.name = "fade-b".to_string();
+        el.style.restart_animation();
+        driver.flush().unwrap();
+        driver.flush().unwrap();
+        assert_eq!(filled(0.6), opacity(&el));
+    }
+}
@@ -1,7 +1,133 @@
+use crate::animation::timing_function::TimingFunction;
+use crate::style::transition::parse_time;
+use crate::style::PropValueParse;
+
+/// Time in milliseconds, accepts css times like `200ms` or `0.2s`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AnimationTime(pub f32);
+
+impl PropValueParse for AnimationTime {
+    fn parse_prop_value(value: &str) -> Option<Self> {
+        parse_time(value).map(Self)
+    }
+    fn to_style_string(&self) -> String {
+        format!("{}ms", self.0)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum AnimationDirection {
+    Normal,
+    Reverse,
+    Alternate,
+    AlternateReverse,
+}
+
+impl AnimationDirection {
+    /// Whether the iteration at `index` runs backwards
+    pub fn is_reversed(&self, index: u32) -> bool {
+        match self {
+            AnimationDirection::Normal => false,
+            AnimationDirection::Reverse => true,
+            AnimationDirection::Alternate => index % 2 == 1,
+            AnimationDirection::AlternateReverse => index % 2 == 0,
+        }
+    }
+}
+
+impl PropValueParse for AnimationDirection {
+    fn parse_prop_value(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "normal" => Some(Self::Normal),
+            "reverse" => Some(Self::Reverse),
+            "alternate" => Some(Self::Alternate),
+            "alternate-reverse" => Some(Self::AlternateReverse),
+            _ => None,
+        }
+    }
+    fn to_style_string(&self) -> String {
+        match self {
+            AnimationDirection::Normal => "normal",
+            AnimationDirection::Reverse => "reverse",
+            AnimationDirection::Alternate => "alternate",
+            AnimationDirection::AlternateReverse => "alternate-reverse",
+        }
+        .to_owned()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum AnimationFillMode {
+    None,
+    Forwards,
+    Backwards,
+    Both,
+}
+
+impl AnimationFillMode {
+    pub fn fills_backwards(&self) -> bool {
+        matches!(self, AnimationFillMode::Backwards | AnimationFillMode::Both)
+    }
+
+    pub fn fills_forwards(&self) -> bool {
+        matches!(self, AnimationFillMode::Forwards | AnimationFillMode::Both)
+    }
+}
+
+impl PropValueParse for AnimationFillMode {
+    fn parse_prop_value(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "none" => Some(Self::None),
+            "forwards" => Some(Self::Forwards),
+            "backwards" => Some(Self::Backwards),
+            "both" => Some(Self::Both),
+            _ => None,
+        }
+    }
+    fn to_style_string(&self) -> String {
+        match self {
+            AnimationFillMode::None => "none",
+            AnimationFillMode::Forwards => "forwards",
+            AnimationFillMode::Backwards => "backwards",
+            AnimationFillMode::Both => "both",
+        }
+        .to_owned()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum AnimationPlayState {
+    Running,
+    Paused,
+}
+
+impl PropValueParse for AnimationPlayState {
+    fn parse_prop_value(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "running" => Some(Self::Running),
+            "paused" => Some(Self::Paused),
+            _ => None,
+        }
+    }
+    fn to_style_string(&self) -> String {
+        match self {
+            AnimationPlayState::Running => "running",
+            AnimationPlayState::Paused => "paused",
+        }
+        .to_owned()
+    }
+}
+
+#[derive(Clone)]
 pub struct AnimationParams {
     pub name: String,
     pub duration: f32,
     pub iteration_count: f32,
+    pub timing_function: TimingFunction,
+    pub delay: f32,
+    pub direction: AnimationDirection,
+    pub fill_mode: AnimationFillMode,
+    pub play_state: AnimationPlayState,
 }
 
 impl AnimationParams {
@@ -10,6 +136,49 @@ impl AnimationParams {
             name: "".to_string(),
             duration: 0.0,
             iteration_count: 1.0,
+            // Unlike transitions, animations default to linear
+            timing_function: TimingFunction::Linear,
+            delay: 0.0,
+            direction: AnimationDirection::Normal,
+            fill_mode: AnimationFillMode::None,
+            play_state: AnimationPlayState::Running,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::animation::timing_function::TimingFunction;
+    use crate::style::animation::{
+        AnimationDirection, AnimationFillMode, AnimationParams, AnimationPlayState, AnimationTime,
+    };
+    use crate::style::PropValueParse;
+
+    #[test]
+    fn test_parse_animation_props() {
+        assert_eq!(
+            TimingFunction::Linear,
+            AnimationParams::new().timing_function
+        );
+        assert_eq!(200.0, AnimationTime::parse_prop_value("200ms").unwrap().0);
+        assert_eq!(200.0, AnimationTime::parse_prop_value("0.2s").unwrap().0);
+        assert_eq!(-50.0, AnimationTime::parse_prop_value("-50").unwrap().0);
+        assert!(AnimationTime::parse_prop_value("fast").is_none());
+
+        let direction = AnimationDirection::parse_prop_value("Alternate-Reverse").unwrap();
+        assert_eq!(AnimationDirection::AlternateReverse, direction);
+        assert!(direction.is_reversed(0));
+        assert!(!direction.is_reversed(1));
+        assert!(AnimationDirection::parse_prop_value("backwards").is_none());
+
+        let fill_mode = AnimationFillMode::parse_prop_value("backwards").unwrap();
+        assert!(fill_mode.fills_backwards());
+        assert!(!fill_mode.fills_forwards());
+        let both = AnimationFillMode::parse_prop_value("both").unwrap();
+        assert!(both.fills_backwards() && both.fills_forwards());
+
+        let paused = AnimationPlayState::parse_prop_value("paused").unwrap();
+        assert_eq!(AnimationPlayState::Paused, paused);
+        assert_eq!("paused", paused.to_style_string());
+    }
+}
@@ -0,0 +1,1108 @@
+use crate::style::color::parse_color;
+use crate::style::length::{parse_percent, Length, LengthContext, LengthOrPercent};
+use crate::style::{split_top_level, PropValueParse};
+use skia_safe::{
+    Canvas, Color, FilterMode, Image, Matrix, Paint, PictureRecorder, Point, Rect, SamplingOptions,
+    Shader, TileMode,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RepeatMode {
+    Repeat,
+    NoRepeat,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum BackgroundSizeValue {
+    Cover,
+    Contain,
+    /// (width, height), `auto` keeps the aspect ratio of image
+    Size(LengthOrPercent, LengthOrPercent),
+}
+
+/// (x, y), percent is relative to the room left by the tile
+pub type BackgroundPositionValue = (LengthOrPercent, LengthOrPercent);
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum StopPosition {
+    Length(Length),
+    Percent(f32),
+    /// Degrees, only for conic gradients
+    Angle(f32),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColorStop {
+    pub color: Color,
+    pub position: Option<StopPosition>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum LinearDirection {
+    /// Degrees, 0 points to top and goes clockwise
+    Angle(f32),
+    /// `to <corner>`, x is -1 for left and 1 for right, y is -1 for top and 1 for bottom
+    Corner(f32, f32),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RadialShape {
+    Circle,
+    Ellipse,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum RadialSize {
+    ClosestSide,
+    ClosestCorner,
+    FarthestSide,
+    FarthestCorner,
+    Explicit(LengthOrPercent, LengthOrPercent),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum GradientKind {
+    Linear(LinearDirection),
+    Radial {
+        shape: RadialShape,
+        size: RadialSize,
+        center: BackgroundPositionValue,
+    },
+    Conic {
+        from: f32,
+        center: BackgroundPositionValue,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Gradient {
+    pub kind: GradientKind,
+    pub stops: Vec<ColorStop>,
+    pub repeating: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum BackgroundImageValue {
+    None,
+    Url(String),
+    Gradient(Gradient),
+}
+
+/// A loaded background image
+#[derive(Clone)]
+pub enum BackgroundSource {
+    Image(Image),
+    Gradient(Gradient),
+}
+
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct BackgroundImage {
+    pub layers: Vec<BackgroundImageValue>,
+    text: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BackgroundSize(pub Vec<BackgroundSizeValue>);
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BackgroundPosition(pub Vec<BackgroundPositionValue>);
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BackgroundRepeat(pub Vec<(RepeatMode, RepeatMode)>);
+
+impl Default for BackgroundSize {
+    fn default() -> Self {
+        Self(vec![BackgroundSizeValue::Size(
+            LengthOrPercent::Auto,
+            LengthOrPercent::Auto,
+        )])
+    }
+}
+
+impl Default for BackgroundPosition {
+    fn default() -> Self {
+        Self(vec![(
+            LengthOrPercent::Percent(0.0),
+            LengthOrPercent::Percent(0.0),
+        )])
+    }
+}
+
+impl Default for BackgroundRepeat {
+    fn default() -> Self {
+        Self(vec![(RepeatMode::Repeat, RepeatMode::Repeat)])
+    }
+}
+
+impl BackgroundSize {
+    pub fn compute(&self, ctx: &LengthContext) -> Self {
+        let list = self
+            .0
+            .iter()
+            .map(|s| match s {
+                BackgroundSizeValue::Size(w, h) => BackgroundSizeValue::Size(
+                    compute_length_or_percent(w, ctx),
+                    compute_length_or_percent(h, ctx),
+                ),
+                s => s.clone(),
+            })
+            .collect();
+        Self(list)
+    }
+}
+
+impl BackgroundPosition {
+    pub fn compute(&self, ctx: &LengthContext) -> Self {
+        let list = self
+            .0
+            .iter()
+            .map(|(x, y)| {
+                (
+                    compute_length_or_percent(x, ctx),
+                    compute_length_or_percent(y, ctx),
+                )
+            })
+            .collect();
+        Self(list)
+    }
+}
+
+/// Parse angle to degrees
+fn parse_angle(value: &str) -> Option<f32> {
+    let value = value.trim().to_lowercase();
+    let (num, scale) = if let Some(v) = value.strip_suffix("deg") {
+        (v, 1.0)
+    } else if let Some(v) = value.strip_suffix("grad") {
+        (v, 0.9)
+    } else if let Some(v) = value.strip_suffix("rad") {
+        (v, 180.0 / std::f32::consts::PI)
+    } else if let Some(v) = value.strip_suffix("turn") {
+        (v, 360.0)
+    } else {
+        return None;
+    };
+    num.trim().parse::<f32>().ok().map(|v| v * scale)
+}
+
+fn parse_length_or_percent(value: &str) -> Option<LengthOrPercent> {
+    if let Some(p) = parse_percent(value) {
+        Some(LengthOrPercent::Percent(p))
+    } else {
+        Length::from_str(value).map(LengthOrPercent::Length)
+    }
+}
+
+fn compute_length_or_percent(value: &LengthOrPercent, ctx: &LengthContext) -> LengthOrPercent {
+    match value {
+        LengthOrPercent::Length(l) => LengthOrPercent::Length(Length::PX(l.to_px(ctx))),
+        v => *v,
+    }
+}
+
+/// Resolve a computed length or percent against `reference`, None for auto
+fn resolve_length_or_percent(value: &LengthOrPercent, reference: f32) -> Option<f32> {
+    match value {
+        LengthOrPercent::Length(l) => Some(l.to_px(&LengthContext::default())),
+        LengthOrPercent::Percent(p) => Some(reference * p / 100.0),
+        LengthOrPercent::Undefined | LengthOrPercent::Auto => None,
+    }
+}
+
+fn parse_position(tokens: &[&str]) -> Option<BackgroundPositionValue> {
+    fn keyword(token: &str) -> Option<(LengthOrPercent, Option<bool>)> {
+        // (value, Some(true) for horizontal only keyword, Some(false) for vertical only)
+        let (percent, horizontal) = match token.to_lowercase().as_str() {
+            "left" => (0.0, Some(true)),
+            "right" => (100.0, Some(true)),
+            "top" => (0.0, Some(false)),
+            "bottom" => (100.0, Some(false)),
+            "center" => (50.0, None),
+            _ => return parse_length_or_percent(token).map(|v| (v, None)),
+        };
+        Some((LengthOrPercent::Percent(percent), horizontal))
+    }
+    let center = LengthOrPercent::Percent(50.0);
+    match tokens {
+        [v] => {
+            let (v, horizontal) = keyword(v)?;
+            if horizontal == Some(false) {
+                Some((center, v))
+            } else {
+                Some((v, center))
+            }
+        }
+        [first, second] => {
+            let (first, first_horizontal) = keyword(first)?;
+            let (second, second_horizontal) = keyword(second)?;
+            if first_horizontal == Some(false) || second_horizontal == Some(true) {
+                Some((second, first))
+            } else {
+                Some((first, second))
+            }
+        }
+        _ => None,
+    }
+}
+
+fn parse_color_stops(value: &str) -> Option<Vec<ColorStop>> {
+    let tokens = split_top_level(value, ' ');
+    let (color, positions) = tokens.split_first()?;
+    let color = parse_color(color)?;
+    if positions.is_empty() {
+        return Some(vec![ColorStop {
+            color,
+            position: None,
+        }]);
+    }
+    if positions.len() > 2 {
+        return None;
+    }
+    positions
+        .iter()
+        .map(|p| {
+            let position = if let Some(v) = parse_percent(p) {
+                StopPosition::Percent(v)
+            } else if let Some(v) = parse_angle(p) {
+                StopPosition::Angle(v)
+            } else {
+                StopPosition::Length(Length::from_str(p)?)
+            };
+            Some(ColorStop {
+                color,
+                position: Some(position),
+            })
+        })
+        .collect()
+}
+
+fn parse_linear_direction(value: &str) -> Option<LinearDirection> {
+    if let Some(angle) = parse_angle(value) {
+        return Some(LinearDirection::Angle(angle));
+    }
+    let mut tokens = value.split_whitespace();
+    if !tokens.next()?.eq_ignore_ascii_case("to") {
+        return None;
+    }
+    let (mut x, mut y) = (0.0, 0.0);
+    for side in tokens {
+        match side.to_lowercase().as_str() {
+            "left" if x == 0.0 => x = -1.0,
+            "right" if x == 0.0 => x = 1.0,
+            "top" if y == 0.0 => y = -1.0,
+            "bottom" if y == 0.0 => y = 1.0,
+            _ => return None,
+        }
+    }
+    if x == 0.0 && y == 0.0 {
+        None
+    } else if x == 0.0 {
+        Some(LinearDirection::Angle(if y < 0.0 { 0.0 } else { 180.0 }))
+    } else if y == 0.0 {
+        Some(LinearDirection::Angle(if x > 0.0 { 90.0 } else { 270.0 }))
+    } else {
+        Some(LinearDirection::Corner(x, y))
+    }
+}
+
+fn parse_radial_prelude(value: &str) -> Option<GradientKind> {
+    let tokens = split_top_level(value, ' ');
+    let at = tokens
+        .iter()
+        .position(|t| t.eq_ignore_ascii_case("at"))
+        .unwrap_or(tokens.len());
+    let center = if at < tokens.len() {
+        parse_position(&tokens[at + 1..])?
+    } else {
+        (
+            LengthOrPercent::Percent(50.0),
+            LengthOrPercent::Percent(50.0),
+        )
+    };
+    let mut shape = None;
+    let mut size = None;
+    let mut lengths = Vec::new();
+    for token in &tokens[..at] {
+        match token.to_lowercase().as_str() {
+            "circle" if shape.is_none() => shape = Some(RadialShape::Circle),
+            "ellipse" if shape.is_none() => shape = Some(RadialShape::Ellipse),
+            "closest-side" if size.is_none() => size = Some(RadialSize::ClosestSide),
+            "closest-corner" if size.is_none() => size = Some(RadialSize::ClosestCorner),
+            "farthest-side" if size.is_none() => size = Some(RadialSize::FarthestSide),
+            "farthest-corner" if size.is_none() => size = Some(RadialSize::FarthestCorner),
+            _ => lengths.push(parse_length_or_percent(token)?),
+        }
+    }
+    if size.is_some() && !lengths.is_empty() {
+        return None;
+    }
+    let shape = match (shape, lengths.len()) {
+        (Some(RadialShape::Circle), 2) | (_, 3..) => return None,
+        (Some(shape), _) => shape,
+        (None, 1) => RadialShape::Circle,
+        (None, _) => RadialShape::Ellipse,
+    };
+    let size = match lengths.as_slice() {
+        [] => size.unwrap_or(RadialSize::FarthestCorner),
+        [r] => RadialSize::Explicit(*r, *r),
+        [rx, ry] => RadialSize::Explicit(*rx, *ry),
+        _ => return None,
+    };
+    Some(GradientKind::Radial {
+        shape,
+        size,
+        center,
+    })
+}
+
+fn parse_conic_prelude(value: &str) -> Option<GradientKind> {
+    let tokens = split_top_level(value, ' ');
+    let mut from = 0.0;
+    let mut center = (
+        LengthOrPercent::Percent(50.0),
+        LengthOrPercent::Percent(50.0),
+    );
+    let mut rest = tokens.as_slice();
+    if rest
+        .first()
+        .map_or(false, |t| t.eq_ignore_ascii_case("from"))
+    {
+        from = parse_angle(rest.get(1)?)?;
+        rest = &rest[2..];
+    }
+    if let Some((at, position)) = rest.split_first() {
+        if !at.eq_ignore_ascii_case("at") {
+            return None;
+        }
+        center = parse_position(position)?;
+    }
+    Some(GradientKind::Conic { from, center })
+}
+
+/// Resolve stop positions to fractions of the gradient line, `resolve` returns None for
+/// unsupported units.
+fn resolve_stop_positions<F: Fn(&StopPosition) -> Option<f32>>(
+    stops: &[ColorStop],
+    resolve: F,
+) -> Vec<f32> {
+    let mut positions: Vec<Option<f32>> = stops
+        .iter()
+        .map(|s| s.position.as_ref().and_then(&resolve))
+        .collect();
+    let last = positions.len() - 1;
+    positions[0] = positions[0].or(Some(0.0));
+    positions[last] = positions[last].or(Some(1.0));
+    // A position smaller than the one before it is clamped
+    let mut max = f32::MIN;
+    for p in positions.iter_mut().flatten() {
+        max = max.max(*p);
+        *p = max;
+    }
+    // Stops without position are spread evenly between their neighbours
+    let mut result = Vec::with_capacity(positions.len());
+    let mut prev = 0;
+    for i in 1..positions.len() {
+        if let Some(end) = positions[i] {
+            let start = positions[prev].unwrap();
+            for j in prev..i {
+                result.push(start + (end - start) * (j - prev) as f32 / (i - prev) as f32);
+            }
+            prev = i;
+        }
+    }
+    result.push(positions[last].unwrap());
+    result
+}
+
+impl Gradient {
+    pub fn parse(value: &str) -> Option<Self> {
+        let (name, args) = value.trim().strip_suffix(')')?.split_once('(')?;
+        let name = name.trim().to_lowercase();
+        let (repeating, name) = match name.strip_prefix("repeating-") {
+            Some(n) => (true, n),
+            None => (false, name.as_str()),
+        };
+        let mut parts = split_top_level(args, ',');
+        let prelude = match name {
+            "linear-gradient" => parse_linear_direction(parts.first()?).map(GradientKind::Linear),
+            "radial-gradient" => parse_radial_prelude(parts.first()?),
+            "conic-gradient" => parse_conic_prelude(parts.first()?),
+            _ => return None,
+        };
+        let kind = match prelude {
+            Some(kind) => {
+                parts.remove(0);
+                kind
+            }
+            None => match name {
+                "linear-gradient" => GradientKind::Linear(LinearDirection::Angle(180.0)),
+                "radial-gradient" => parse_radial_prelude("")?,
+                _ => parse_conic_prelude("")?,
+            },
+        };
+        let mut stops = Vec::new();
+        for part in parts {
+            stops.append(&mut parse_color_stops(part)?);
+        }
+        if stops.len() < 2 {
+            return None;
+        }
+        Some(Self {
+            kind,
+            stops,
+            repeating,
+        })
+    }
+
+    fn compute(&self, ctx: &LengthContext) -> Self {
+        let compute_center = |(x, y): &BackgroundPositionValue| {
+            (
+                compute_length_or_percent(x, ctx),
+                compute_length_or_percent(y, ctx),
+            )
+        };
+        let kind = match &self.kind {
+            GradientKind::Linear(d) => GradientKind::Linear(d.clone()),
+            GradientKind::Radial {
+                shape,
+                size,
+                center,
+            } => GradientKind::Radial {
+                shape: *shape,
+                size: match size {
+                    RadialSize::Explicit(rx, ry) => RadialSize::Explicit(
+                        compute_length_or_percent(rx, ctx),
+                        compute_length_or_percent(ry, ctx),
+                    ),
+                    s => s.clone(),
+                },
+                center: compute_center(center),
+            },
+            GradientKind::Conic { from, center } => GradientKind::Conic {
+                from: *from,
+                center: compute_center(center),
+            },
+        };
+        let stops = self
+            .stops
+            .iter()
+            .map(|s| ColorStop {
+                color: s.color,
+                position: match &s.position {
+                    Some(StopPosition::Length(l)) => {
+                        Some(StopPosition::Length(Length::PX(l.to_px(ctx))))
+                    }
+                    p => p.clone(),
+                },
+            })
+            .collect();
+        Self {
+            kind,
+            stops,
+            repeating: self.repeating,
+        }
+    }
+
+    /// Create shader filling a box of `width` x `height` at origin
+    pub fn to_shader(&self, width: f32, height: f32) -> Option<Shader> {
+        let colors: Vec<Color> = self.stops.iter().map(|s| s.color).collect();
+        let mode = if self.repeating {
+            TileMode::Repeat
+        } else {
+            TileMode::Clamp
+        };
+        // Gradient line is mapped to [first stop, last stop] so that repeating works
+        let normalize = |positions: Vec<f32>| {
+            let first = positions[0];
+            let span = (positions[positions.len() - 1] - first).max(0.0001);
+            let normalized: Vec<f32> = positions.iter().map(|p| (p - first) / span).collect();
+            (first, first + span, normalized)
+        };
+        match &self.kind {
+            GradientKind::Linear(direction) => {
+                let (dx, dy) = match direction {
+                    LinearDirection::Angle(angle) => {
+                        let rad = angle.to_radians();
+                        (rad.sin(), -rad.cos())
+                    }
+                    LinearDirection::Corner(x, y) => {
+                        // Perpendicular to the diagonal between the two neighbouring corners
+                        let (vx, vy) = (x * height, y * width);
+                        let len = (vx * vx + vy * vy).sqrt().max(f32::EPSILON);
+                        (vx / len, vy / len)
+                    }
+                };
+                let length = (width * dx).abs() + (height * dy).abs();
+                let positions = resolve_stop_positions(&self.stops, |p| match p {
+                    StopPosition::Percent(v) => Some(v / 100.0),
+                    StopPosition::Length(l) => {
+                        Some(l.to_px(&LengthContext::default()) / length.max(f32::EPSILON))
+                    }
+                    StopPosition::Angle(_) => None,
+                });
+                let (start, end, positions) = normalize(positions);
+                let origin = Point::new(
+                    width / 2.0 - dx * length / 2.0,
+                    height / 2.0 - dy * length / 2.0,
+                );
+                let p0 = origin + Point::new(dx, dy) * (length * start);
+                let p1 = origin + Point::new(dx, dy) * (length * end);
+                Shader::linear_gradient(
+                    (p0, p1),
+                    colors.as_slice(),
+                    positions.as_slice(),
+                    mode,
+                    None,
+                    None,
+                )
+            }
+            GradientKind::Radial {
+                shape,
+                size,
+                center,
+            } => {
+                let cx = resolve_length_or_percent(&center.0, width).unwrap_or(0.0);
+                let cy = resolve_length_or_percent(&center.1, height).unwrap_or(0.0);
+                let sides_x = [cx.abs(), (width - cx).abs()];
+                let sides_y = [cy.abs(), (height - cy).abs()];
+                let closest = (sides_x[0].min(sides_x[1]), sides_y[0].min(sides_y[1]));
+                let farthest = (sides_x[0].max(sides_x[1]), sides_y[0].max(sides_y[1]));
+                let (rx, ry) = match (size, shape) {
+                    (RadialSize::ClosestSide, RadialShape::Circle) => {
+                        let r = closest.0.min(closest.1);
+                        (r, r)
+                    }
+                    (RadialSize::FarthestSide, RadialShape::Circle) => {
+                        let r = farthest.0.max(farthest.1);
+                        (r, r)
+                    }
+                    (RadialSize::ClosestCorner, RadialShape::Circle) => {
+                        let r = closest.0.hypot(closest.1);
+                        (r, r)
+                    }
+                    (RadialSize::FarthestCorner, RadialShape::Circle) => {
+                        let r = farthest.0.hypot(farthest.1);
+                        (r, r)
+                    }
+                    (RadialSize::ClosestSide, RadialShape::Ellipse) => closest,
+                    (RadialSize::FarthestSide, RadialShape::Ellipse) => farthest,
+                    (RadialSize::ClosestCorner, RadialShape::Ellipse) => {
+                        (closest.0 * 2f32.sqrt(), closest.1 * 2f32.sqrt())
+                    }
+                    (RadialSize::FarthestCorner, RadialShape::Ellipse) => {
+                        (farthest.0 * 2f32.sqrt(), farthest.1 * 2f32.sqrt())
+                    }
+                    (RadialSize::Explicit(rx, ry), _) => (
+                        resolve_length_or_percent(rx, width).unwrap_or(0.0),
+                        resolve_length_or_percent(ry, height).unwrap_or(0.0),
+                    ),
+                };
+                let rx = rx.max(f32::EPSILON);
+                let positions = resolve_stop_positions(&self.stops, |p| match p {
+                    StopPosition::Percent(v) => Some((v / 100.0).max(0.0)),
+                    StopPosition::Length(l) => {
+                        Some((l.to_px(&LengthContext::default()) / rx).max(0.0))
+                    }
+                    StopPosition::Angle(_) => None,
+                });
+                let (start, end, positions) = normalize(positions);
+                // Ellipse is a circle scaled vertically
+                let mut matrix = Matrix::new_identity();
+                matrix.set_scale((1.0, ry / rx), Point::new(cx, cy));
+                Shader::two_point_conical_gradient(
+                    (cx, cy),
+                    rx * start,
+                    (cx, cy),
+                    rx * end,
+                    colors.as_slice(),
+                    positions.as_slice(),
+                    mode,
+                    None,
+                    &matrix,
+                )
+            }
+            GradientKind::Conic { from, center } => {
+                let cx = resolve_length_or_percent(&center.0, width).unwrap_or(0.0);
+                let cy = resolve_length_or_percent(&center.1, height).unwrap_or(0.0);
+                let positions = resolve_stop_positions(&self.stops, |p| match p {
+                    StopPosition::Percent(v) => Some(v / 100.0),
+                    StopPosition::Angle(v) => Some(v / 360.0),
+                    StopPosition::Length(_) => None,
+                });
+                let (start, end, positions) = normalize(positions);
+                // Skia sweeps from 3 o'clock while css starts from 12 o'clock
+                let matrix = Matrix::rotate_deg_pivot(from - 90.0, (cx, cy));
+                Shader::sweep_gradient(
+                    (cx, cy),
+                    colors.as_slice(),
+                    positions.as_slice(),
+                    mode,
+                    (start * 360.0, end * 360.0),
+                    None,
+                    &matrix,
+                )
+            }
+        }
+    }
+}
+
+impl BackgroundImageValue {
+    fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        if value.eq_ignore_ascii_case("none") {
+            Some(Self::None)
+        } else if let Some(url) = value.strip_prefix("url(") {
+            let url = url.strip_suffix(')')?.trim();
+            let url = url.trim_matches(|c| c == '"' || c == '\'');
+            Some(Self::Url(url.to_string()))
+        } else {
+            Gradient::parse(value).map(Self::Gradient)
+        }
+    }
+
+    /// Compute lengths, None if nothing should be painted. Url images are loaded in background
+    /// by the style node, so they are None until loaded.
+    pub fn load(&self, ctx: &LengthContext) -> Option<BackgroundSource> {
+        match self {
+            BackgroundImageValue::None | BackgroundImageValue::Url(_) => None,
+            BackgroundImageValue::Gradient(g) => Some(BackgroundSource::Gradient(g.compute(ctx))),
+        }
+    }
+}
+
+impl PropValueParse for BackgroundImage {
+    fn parse_prop_value(value: &str) -> Option<Self> {
+        let layers = split_top_level(value, ',')
+            .into_iter()
+            .map(BackgroundImageValue::parse)
+            .collect::<Option<Vec<_>>>()?;
+        Some(Self {
+            layers,
+            text: value.trim().to_string(),
+        })
+    }
+    fn to_style_string(&self) -> String {
+        if self.layers.is_empty() {
+            return "none".to_string();
+        }
+        self.text.clone()
+    }
+}
+
+impl PropValueParse for BackgroundSize {
+    fn parse_prop_value(value: &str) -> Option<Self> {
+        let list = split_top_level(value, ',')
+            .into_iter()
+            .map(|layer| {
+                let tokens = split_top_level(layer, ' ');
+                let parse = |t: &str| match LengthOrPercent::parse_prop_value(t)? {
+                    LengthOrPercent::Undefined => None,
+                    v => Some(v),
+                };
+                match tokens.as_slice() {
+                    [t] if t.eq_ignore_ascii_case("cover") => Some(BackgroundSizeValue::Cover),
+                    [t] if t.eq_ignore_ascii_case("contain") => Some(BackgroundSizeValue::Contain),
+                    [w] => Some(BackgroundSizeValue::Size(parse(w)?, LengthOrPercent::Auto)),
+                    [w, h] => Some(BackgroundSizeValue::Size(parse(w)?, parse(h)?)),
+                    _ => None,
+                }
+            })
+            .collect::<Option<Vec<_>>>()?;
+        if list.is_empty() {
+            return None;
+        }
+        Some(Self(list))
+    }
+    fn to_style_string(&self) -> String {
+        let list: Vec<String> = self
+            .0
+            .iter()
+            .map(|s| match s {
+                BackgroundSizeValue::Cover => "cover".to_string(),
+                BackgroundSizeValue::Contain => "contain".to_string(),
+                BackgroundSizeValue::Size(w, h) => {
+                    format!("{} {}", w.to_style_string(), h.to_style_string())
+                }
+            })
+            .collect();
+        list.join(", ")
+    }
+}
+
+impl PropValueParse for BackgroundPosition {
+    fn parse_prop_value(value: &str) -> Option<Self> {
+        let list = split_top_level(value, ',')
+            .into_iter()
+            .map(|layer| parse_position(&split_top_level(layer, ' ')))
+            .collect::<Option<Vec<_>>>()?;
+        if list.is_empty() {
+            return None;
+        }
+        Some(Self(list))
+    }
+    fn to_style_string(&self) -> String {
+        let list: Vec<String> = self
+            .0
+            .iter()
+            .map(|(x, y)| format!("{} {}", x.to_style_string(), y.to_style_string()))
+            .collect();
+        list.join(", ")
+    }
+}
+
+fn parse_repeat_mode(value: &str) -> Option<RepeatMode> {
+    match value.to_lowercase().as_str() {
+        // space and round are treated as repeat
+        "repeat" | "space" | "round" => Some(RepeatMode::Repeat),
+        "no-repeat" => Some(RepeatMode::NoRepeat),
+        _ => None,
+    }
+}
+
+impl PropValueParse for BackgroundRepeat {
+    fn parse_prop_value(value: &str) -> Option<Self> {
+        let list = split_top_level(value, ',')
+            .into_iter()
+            .map(|layer| match split_top_level(layer, ' ').as_slice() {
+                [t] if t.eq_ignore_ascii_case("repeat-x") => {
+                    Some((RepeatMode::Repeat, RepeatMode::NoRepeat))
+                }
+                [t] if t.eq_ignore_ascii_case("repeat-y") => {
+                    Some((RepeatMode::NoRepeat, RepeatMode::Repeat))
+                }
+                [t] => {
+                    let mode = parse_repeat_mode(t)?;
+                    Some((mode, mode))
+                }
+                [x, y] => Some((parse_repeat_mode(x)?, parse_repeat_mode(y)?)),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()?;
+        if list.is_empty() {
+            return None;
+        }
+        Some(Self(list))
+    }
+    fn to_style_string(&self) -> String {
+        let to_str = |m: &RepeatMode| match m {
+            RepeatMode::Repeat => "repeat",
+            RepeatMode::NoRepeat => "no-repeat",
+        };
+        let list: Vec<String> = self
+            .0
+            .iter()
+            .map(|(x, y)| format!("{} {}", to_str(x), to_str(y)))
+            .collect();
+        list.join(", ")
+    }
+}
+
+/// Expand `background` shorthand to longhand properties
+pub fn expand_background(value: &str) -> Vec<(&'static str, String)> {
+    let keys = [
+        "BackgroundImage",
+        "BackgroundPosition",
+        "BackgroundSize",
+        "BackgroundRepeat",
+        "BackgroundColor",
+    ];
+    let value = value.trim();
+    if value.eq_ignore_ascii_case("inherit") || value.eq_ignore_ascii_case("unset") {
+        return keys.iter().map(|k| (*k, value.to_string())).collect();
+    }
+    let mut images = Vec::new();
+    let mut positions = Vec::new();
+    let mut sizes = Vec::new();
+    let mut repeats = Vec::new();
+    let mut color = "transparent".to_string();
+    for layer in split_top_level(value, ',') {
+        // Separate `position/size` into tokens
+        let mut spaced = String::new();
+        let mut depth = 0;
+        for c in layer.chars() {
+            match c {
+                '(' => depth += 1,
+                ')' => depth -= 1,
+                '/' if depth == 0 => {
+                    spaced.push_str(" / ");
+                    continue;
+                }
+                _ => {}
+            }
+            spaced.push(c);
+        }
+        let mut image = "none";
+        let mut position = Vec::new();
+        let mut size = Vec::new();
+        let mut repeat = Vec::new();
+        let mut in_size = false;
+        for token in split_top_level(&spaced, ' ') {
+            if token == "/" {
+                in_size = true;
+            } else if BackgroundImageValue::parse(token).is_some() {
+                image = token;
+            } else if token.eq_ignore_ascii_case("repeat-x")
+                || token.eq_ignore_ascii_case("repeat-y")
+                || parse_repeat_mode(token).is_some()
+            {
+                repeat.push(token);
+            } else if in_size {
+                size.push(token);
+            } else if parse_position(&[token]).is_some() {
+                position.push(token);
+            } else {
+                color = token.to_string();
+            }
+        }
+        images.push(image.to_string());
+        positions.push(if position.is_empty() {
+            "0% 0%".to_string()
+        } else {
+            position.join(" ")
+        });
+        sizes.push(if size.is_empty() {
+            "auto".to_string()
+        } else {
+            size.join(" ")
+        });
+        repeats.push(if repeat.is_empty() {
+            "repeat".to_string()
+        } else {
+            repeat.join(" ")
+        });
+    }
+    let values = [
+        images.join(", "),
+        positions.join(", "),
+        sizes.join(", "),
+        repeats.join(", "),
+        color,
+    ];
+    keys.into_iter().zip(values).collect()
+}
+
+/// One background layer ready to paint
+#[derive(Clone)]
+pub struct BackgroundLayer {
+    pub source: BackgroundSource,
+    pub size: BackgroundSizeValue,
+    pub position: BackgroundPositionValue,
+    pub repeat: (RepeatMode, RepeatMode),
+}
+
+/// Build painting layers, the first one is on the top. Lists shorter than images are repeated.
+pub fn build_background_layers(
+    images: &[Option<BackgroundSource>],
+    size: &BackgroundSize,
+    position: &BackgroundPosition,
+    repeat: &BackgroundRepeat,
+) -> Vec<BackgroundLayer> {
+    let mut result = Vec::new();
+    for (i, img) in images.iter().enumerate() {
+        if let Some(source) = img {
+            result.push(BackgroundLayer {
+                source: source.clone(),
+                size: size.0[i % size.0.len()].clone(),
+                position: position.0[i % position.0.len()],
+                repeat: repeat.0[i % repeat.0.len()],
+            });
+        }
+    }
+    result
+}
+
+impl BackgroundLayer {
+    fn tile_size(&self, area_width: f32, area_height: f32) -> (f32, f32) {
+        let intrinsic = match &self.source {
+            BackgroundSource::Image(img) if img.width() > 0 && img.height() > 0 => {
+                Some((img.width() as f32, img.height() as f32))
+            }
+            _ => None,
+        };
+        match (&self.size, intrinsic) {
+            (BackgroundSizeValue::Cover, Some((w, h))) => {
+                let scale = (area_width / w).max(area_height / h);
+                (w * scale, h * scale)
+            }
+            (BackgroundSizeValue::Contain, Some((w, h))) => {
+                let scale = (area_width / w).min(area_height / h);
+                (w * scale, h * scale)
+            }
+            (BackgroundSizeValue::Cover | BackgroundSizeValue::Contain, None) => {
+                (area_width, area_height)
+            }
+            (BackgroundSizeValue::Size(w, h), intrinsic) => {
+                let w = resolve_length_or_percent(w, area_width);
+                let h = resolve_length_or_percent(h, area_height);
+                match (w, h, intrinsic) {
+                    (Some(w), Some(h), _) => (w, h),
+                    (Some(w), None, Some((iw, ih))) => (w, w * ih / iw),
+                    (None, Some(h), Some((iw, ih))) => (h * iw / ih, h),
+                    (None, None, Some(size)) => size,
+                    (w, h, None) => (w.unwrap_or(area_width), h.unwrap_or(area_height)),
+                }
+            }
+        }
+    }
+
+    /// Paint the layer into `area`, which is the padding box
+    pub fn draw(&self, canvas: &Canvas, area: &Rect) {
+        let (tile_width, tile_height) = self.tile_size(area.width(), area.height());
+        if tile_width <= 0.0 || tile_height <= 0.0 {
+            return;
+        }
+        let x = area.left
+            + resolve_length_or_percent(&self.position.0, area.width() - tile_width).unwrap_or(0.0);
+        let y = area.top
+            + resolve_length_or_percent(&self.position.1, area.height() - tile_height)
+                .unwrap_or(0.0);
+        let tile_mode = |m: RepeatMode| match m {
+            RepeatMode::Repeat => TileMode::Repeat,
+            RepeatMode::NoRepeat => TileMode::Decal,
+        };
+        let tile_modes = (tile_mode(self.repeat.0), tile_mode(self.repeat.1));
+        let shader = match &self.source {
+            BackgroundSource::Image(img) => {
+                let mut matrix = Matrix::translate((x, y));
+                matrix.pre_scale(
+                    (
+                        tile_width / img.width() as f32,
+                        tile_height / img.height() as f32,
+                    ),
+                    None,
+                );
+                img.to_shader(
+                    tile_modes,
+                    SamplingOptions::from(FilterMode::Linear),
+                    &matrix,
+                )
+            }
+            BackgroundSource::Gradient(gradient) => {
+                let tile = Rect::from_wh(tile_width, tile_height);
+                let mut recorder = PictureRecorder::new();
+                let tile_canvas = recorder.begin_recording(tile, None);
+                if let Some(shader) = gradient.to_shader(tile_width, tile_height) {
+                    let mut paint = Paint::default();
+                    paint.set_shader(shader);
+                    tile_canvas.draw_rect(tile, &paint);
+                }
+                recorder.finish_recording_as_picture(None).map(|picture| {
+                    let matrix = Matrix::translate((x, y));
+                    picture.to_shader(tile_modes, FilterMode::Linear, &matrix, &tile)
+                })
+            }
+        };
+        if let Some(shader) = shader {
+            let mut paint = Paint::default();
+            paint.set_anti_alias(true);
+            paint.set_shader(shader);
+            canvas.draw_rect(area, &paint);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::style::background::{
+        expand_background, BackgroundImage, BackgroundImageValue, BackgroundPosition,
+        BackgroundRepeat, BackgroundSize, BackgroundSizeValue, GradientKind, LinearDirection,
+        RadialShape, RadialSize, RepeatMode, StopPosition,
+    };
+    use crate::style::length::{Length, LengthOrPercent};
+    use crate::style::PropValueParse;
+    use skia_safe::Color;
+
+    #[test]
+    fn test_parse_background_image() {
+        let bg = BackgroundImage::parse_prop_value(
+            "url(\"a.png\"), linear-gradient(to right, red, rgb(0, 0, 255) 50%), radial-gradient(circle at top, red, blue)",
+        )
+        .unwrap();
+        assert_eq!(3, bg.layers.len());
+        assert_eq!(BackgroundImageValue::Url("a.png".to_string()), bg.layers[0]);
+        let BackgroundImageValue::Gradient(linear) = &bg.layers[1] else {
+            panic!("not a gradient");
+        };
+        assert_eq!(
+            GradientKind::Linear(LinearDirection::Angle(90.0)),
+            linear.kind
+        );
+        assert_eq!(Color::BLUE, linear.stops[1].color);
+        assert_eq!(Some(StopPosition::Percent(50.0)), linear.stops[1].position);
+        let BackgroundImageValue::Gradient(radial) = &bg.layers[2] else {
+            panic!("not a gradient");
+        };
+        let GradientKind::Radial {
+            shape,
+            size,
+            center,
+        } = &radial.kind
+        else {
+            panic!("not a radial gradient");
+        };
+        assert_eq!(RadialShape::Circle, *shape);
+        assert_eq!(RadialSize::FarthestCorner, *size);
+        assert_eq!(
+            (
+                LengthOrPercent::Percent(50.0),
+                LengthOrPercent::Percent(0.0)
+            ),
+            *center
+        );
+
+        let BackgroundImageValue::Gradient(conic) = BackgroundImageValue::parse(
+            "repeating-conic-gradient(from 45deg, red 0deg 10deg, blue 20deg)",
+        )
+        .unwrap() else {
+            panic!("not a gradient");
+        };
+        assert!(conic.repeating);
+        assert_eq!(3, conic.stops.len());
+        assert!(BackgroundImage::parse_prop_value("linear-gradient(red)").is_none());
+    }
+
+    #[test]
+    fn test_parse_background_layout() {
+        let size = BackgroundSize::parse_prop_value("cover, 10px").unwrap();
+        assert_eq!(BackgroundSizeValue::Cover, size.0[0]);
+        assert_eq!(
+            BackgroundSizeValue::Size(
+                LengthOrPercent::Length(Length::PX(10.0)),
+                LengthOrPercent::Auto
+            ),
+            size.0[1]
+        );
+        let position = BackgroundPosition::parse_prop_value("bottom right, center").unwrap();
+        assert_eq!(
+            (
+                LengthOrPercent::Percent(100.0),
+                LengthOrPercent::Percent(100.0)
+            ),
+            position.0[0]
+        );
+        assert_eq!(
+            (
+                LengthOrPercent::Percent(50.0),
+                LengthOrPercent::Percent(50.0)
+            ),
+            position.0[1]
+        );
+        let repeat = BackgroundRepeat::parse_prop_value("repeat-x").unwrap();
+        assert_eq!((RepeatMode::Repeat, RepeatMode::NoRepeat), repeat.0[0]);
+    }
+
+    #[test]
+    fn test_expand_background() {
+        let list = expand_background("url(a/b.png) center/cover no-repeat, #fff");
+        assert_eq!(
+            ("BackgroundImage", "url(a/b.png), none".to_string()),
+            list[0]
+        );
+        assert_eq!(("BackgroundPosition", "center, 0% 0%".to_string()), list[1]);
+        assert_eq!(("BackgroundSize", "cover, auto".to_string()), list[2]);
+        assert_eq!(
+            ("BackgroundRepeat", "no-repeat, repeat".to_string()),
+            list[3]
+        );
+        assert_eq!(("BackgroundColor", "#fff".to_string()), list[4]);
+    }
+}
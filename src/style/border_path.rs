@@ -1,5 +1,5 @@
 use crate::render::cssborder::{build_border_paths, build_rect_with_radius};
-use tiny_skia::Path;
+use tiny_skia::{Path, Transform};
 
 pub struct BorderPath {
     box_width: f32,
@@ -46,6 +46,37 @@ impl BorderPath {
         self.path.as_ref().unwrap()
     }
 
+    /// Path of the border box shrunk by `insets`(top, right, bottom, left), negative insets grow it.
+    ///
+    /// Corner radii shrink or grow along with the box, so shadows follow the rounded border.
+    pub fn build_inset_path(&self, insets: [f32; 4]) -> Option<Path> {
+        let [top, right, bottom, left] = insets;
+        let adjacent = [
+            top.max(left),
+            top.max(right),
+            bottom.max(right),
+            bottom.max(left),
+        ];
+        let mut radius = [0.0; 4];
+        for i in 0..4 {
+            if self.radius[i] > 0.0 {
+                radius[i] = (self.radius[i] - adjacent[i]).max(0.0);
+            }
+        }
+        let width = self.box_width - left - right;
+        let height = self.box_height - top - bottom;
+        if width <= 0.0 || height <= 0.0 {
+            return None;
+        }
+        let path = build_rect_with_radius(radius, width, height)?;
+        path.transform(Transform::from_translate(left, top))
+    }
+
+    /// Path of the padding box, i.e. the inner edge of the border
+    pub fn build_padding_box_path(&self) -> Option<Path> {
+        self.build_inset_path(self.widths)
+    }
+
     fn has_border(&self) -> bool {
         self.widths.iter().any(|&w| w != 0.0)
     }
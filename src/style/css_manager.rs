@@ -1,9 +1,18 @@
+use crate::animation::{AnimationDef, ANIMATIONS};
 use crate::base::{Id, IdKey};
+use crate::element::paragraph::simple_paragraph_builder::FONT_MANAGER;
 use crate::element::Element;
+use crate::some_or_continue;
+use crate::style::media::{MediaEnv, MediaQueryList};
 use crate::style::select::{Selector, Selectors};
+use crate::style::split_top_level;
+use crate::style_list::ParsedStyleProp;
+use crate::typeface::load_font_file;
 use anyhow::{anyhow, Error};
-use simplecss::StyleSheet;
+use log::warn;
+use simplecss::{DeclarationTokenizer, StyleSheet};
 use std::collections::HashMap;
+use std::str::FromStr;
 
 thread_local! {
     static STYLESHEET_ID_KEY: IdKey = IdKey::new();
@@ -14,12 +23,23 @@ pub struct CSS {
     rules: Vec<CSSRule>,
     declared_classes: Vec<String>,
     declared_attrs: Vec<String>,
+    media_list: Vec<MediaQueryList>,
+    /// Names of `@keyframes` registered by this stylesheet
+    animation_names: Vec<String>,
 }
 
 pub struct CSSRule {
     selector: Selector,
     declarations: String,
     id: Id<CSS>,
+    /// Index of the `@media` condition in media list of the stylesheet
+    media: Option<usize>,
+}
+
+/// Top level statement of stylesheet, `prelude { block }` or `prelude;`
+struct CssStatement<'a> {
+    prelude: &'a str,
+    block: Option<&'a str>,
 }
 
 pub struct CssManager {
@@ -40,6 +60,8 @@ impl CssManager {
             rules: Vec::new(),
             declared_classes: Vec::new(),
             declared_attrs: Vec::new(),
+            media_list: Vec::new(),
+            animation_names: Vec::new(),
         };
         Self::update_css(&mut css, stylesheet_source)?;
         self.stylesheets.push(css);
@@ -57,7 +79,27 @@ impl CssManager {
     }
 
     pub fn remove(&mut self, id: &Id<CSS>) {
-        self.stylesheets.retain(|css| css.id != *id);
+        self.stylesheets.retain_mut(|css| {
+            if css.id == *id {
+                Self::remove_animations(css);
+                false
+            } else {
+                true
+            }
+        });
+    }
+
+    pub fn has_media_rules(&self) -> bool {
+        self.stylesheets.iter().any(|ss| !ss.media_list.is_empty())
+    }
+
+    /// Evaluate all `@media` conditions, changed result means styles should be selected again
+    pub fn eval_media(&self, env: &MediaEnv) -> Vec<bool> {
+        let mut result = Vec::new();
+        for ss in &self.stylesheets {
+            result.extend(ss.media_list.iter().map(|m| m.matches(env)));
+        }
+        result
     }
 
     pub fn contains_class(&self, clazz: &str) -> bool {
@@ -86,8 +128,25 @@ impl CssManager {
         let mut list = Vec::new();
         let mut pm = HashMap::new();
         let mut rules = Vec::new();
+        let media_env = if self.has_media_rules() {
+            element.get_window().and_then(|w| {
+                let env = w.upgrade_mut().ok()?.get_media_env();
+                Some(env)
+            })
+        } else {
+            None
+        };
         for css in &self.stylesheets {
+            let media_matches: Vec<bool> = match &media_env {
+                Some(env) => css.media_list.iter().map(|m| m.matches(env)).collect(),
+                None => Vec::new(),
+            };
             for rule in &css.rules {
+                if let Some(m) = rule.media {
+                    if !media_matches.get(m).copied().unwrap_or(false) {
+                        continue;
+                    }
+                }
                 if rule.selector.matches(element) {
                     rules.push(rule);
                 }
@@ -115,7 +174,44 @@ impl CssManager {
         css.declared_classes.clear();
         css.declared_attrs.clear();
         css.rules.clear();
-        let stylesheet = StyleSheet::parse(&stylesheet_source);
+        css.media_list.clear();
+        Self::remove_animations(css);
+        let source = strip_comments(stylesheet_source);
+        let mut plain_rules = String::new();
+        for statement in split_statements(&source) {
+            let prelude = statement.prelude;
+            if !prelude.starts_with('@') {
+                if let Some(block) = statement.block {
+                    plain_rules.push_str(&format!("{} {{{}}}\n", prelude, block));
+                }
+                continue;
+            }
+            let (name, params) = prelude[1..]
+                .split_once(|c: char| c.is_whitespace())
+                .unwrap_or((&prelude[1..], ""));
+            let block = statement.block.unwrap_or("");
+            match name.to_lowercase().as_str() {
+                "media" => {
+                    // Keep source order of rules with the same specificity
+                    Self::add_rules(css, &plain_rules, None)?;
+                    plain_rules.clear();
+                    let media = css.media_list.len();
+                    css.media_list.push(MediaQueryList::parse(params));
+                    Self::add_rules(css, block, Some(media))?;
+                }
+                "keyframes" => {
+                    let name = params.trim().trim_matches(|c| c == '"' || c == '\'');
+                    Self::add_keyframes(css, name, block);
+                }
+                "font-face" => load_font_face(block),
+                _ => warn!("The @{} rule is not supported. Skipped.", name),
+            }
+        }
+        Self::add_rules(css, &plain_rules, None)
+    }
+
+    fn add_rules(css: &mut CSS, source: &str, media: Option<usize>) -> Result<(), Error> {
+        let stylesheet = StyleSheet::parse(source);
         for rule in &stylesheet.rules {
             let selectors = rule.selector.source().to_string();
             let mut declarations = Vec::new();
@@ -133,16 +229,178 @@ impl CssManager {
                     selector,
                     declarations: declarations.join(";"),
                     id: css.id,
+                    media,
                 };
                 css.rules.push(rule);
             }
         }
         Ok(())
     }
+
+    fn add_keyframes(css: &mut CSS, name: &str, block: &str) {
+        let mut ad = AnimationDef::new();
+        for statement in split_statements(block) {
+            let block = some_or_continue!(statement.block);
+            let declarations = DeclarationTokenizer::from(block)
+                .map(|d| (d.name, d.value))
+                .collect();
+            let (parsed_styles, _) = ParsedStyleProp::parse_all(declarations);
+            let mut styles = Vec::new();
+            for item in parsed_styles {
+                styles.append(&mut item.fixed());
+            }
+            for selector in split_top_level(statement.prelude, ',') {
+                let position = match selector.to_lowercase().as_str() {
+                    "from" => 0.0,
+                    "to" => 1.0,
+                    s => match s.strip_suffix('%').and_then(|p| f32::from_str(p).ok()) {
+                        Some(p) => p / 100.0,
+                        None => {
+                            warn!("invalid keyframe selector: {}", selector);
+                            continue;
+                        }
+                    },
+                };
+                ad = ad.key_frame(position, styles.clone());
+            }
+        }
+        ANIMATIONS.with_borrow_mut(|m| m.insert(name.to_string(), ad.build()));
+        css.animation_names.push(name.to_string());
+    }
+
+    fn remove_animations(css: &mut CSS) {
+        ANIMATIONS.with_borrow_mut(|m| {
+            for name in css.animation_names.drain(..) {
+                m.remove(&name);
+            }
+        });
+    }
+}
+
+/// Load the first available source of `@font-face`, weight and style are read from the font file
+fn load_font_face(block: &str) {
+    let mut family = None;
+    let mut src = None;
+    for decl in DeclarationTokenizer::from(block) {
+        match decl.name.to_lowercase().as_str() {
+            "font-family" => family = Some(decl.value.trim_matches(|c| c == '"' || c == '\'')),
+            "src" => src = Some(decl.value),
+            _ => {}
+        }
+    }
+    let (family, src) = match (family, src) {
+        (Some(family), Some(src)) => (family, src),
+        _ => {
+            warn!("font-family and src are required in @font-face");
+            return;
+        }
+    };
+    for source in split_top_level(src, ',') {
+        let url = some_or_continue!(source.strip_prefix("url("));
+        let url = some_or_continue!(url.split_once(')')).0.trim();
+        let path = url.trim_matches(|c| c == '"' || c == '\'');
+        if let Some(font) = load_font_file(path, family) {
+            let mut fm = FONT_MANAGER.with(|fm| fm.clone());
+            fm.register_font(family, font);
+            return;
+        }
+    }
+    warn!("failed to load font face: {}", family);
+}
+
+/// Remove comments, comment markers inside strings are kept
+fn strip_comments(source: &str) -> String {
+    let mut result = String::with_capacity(source.len());
+    let mut chars = source.chars().peekable();
+    let mut quote = None;
+    while let Some(c) = chars.next() {
+        match quote {
+            Some(q) => {
+                result.push(c);
+                if c == '\\' {
+                    if let Some(escaped) = chars.next() {
+                        result.push(escaped);
+                    }
+                } else if c == q {
+                    quote = None;
+                }
+            }
+            None if c == '/' && chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut prev = ' ';
+                while let Some(c) = chars.next() {
+                    if prev == '*' && c == '/' {
+                        break;
+                    }
+                    prev = c;
+                }
+                result.push(' ');
+            }
+            None => {
+                if c == '"' || c == '\'' {
+                    quote = Some(c);
+                }
+                result.push(c);
+            }
+        }
+    }
+    result
+}
+
+/// Split stylesheet without comments into top level statements, nested blocks are kept as is
+fn split_statements(source: &str) -> Vec<CssStatement> {
+    let mut result = Vec::new();
+    let mut depth = 0;
+    let mut quote = None;
+    let mut escaped = false;
+    let mut start = 0;
+    let mut block_start = 0;
+    for (i, c) in source.char_indices() {
+        if let Some(q) = quote {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '"' | '\'' => quote = Some(c),
+            '{' => {
+                if depth == 0 {
+                    block_start = i;
+                }
+                depth += 1;
+            }
+            '}' if depth > 0 => {
+                depth -= 1;
+                if depth == 0 {
+                    result.push(CssStatement {
+                        prelude: source[start..block_start].trim(),
+                        block: Some(&source[block_start + 1..i]),
+                    });
+                    start = i + 1;
+                }
+            }
+            ';' if depth == 0 => {
+                result.push(CssStatement {
+                    prelude: source[start..i].trim(),
+                    block: None,
+                });
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    result.retain(|s| !s.prelude.is_empty() || s.block.is_some());
+    result
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::animation::ANIMATIONS;
     use crate::element::button::Button;
     use crate::element::container::Container;
     use crate::element::{Element, ElementBackend};
@@ -162,4 +420,25 @@ mod tests {
         assert_eq!(1, containers_styles.len());
         assert_eq!(1, button_styles.len());
     }
+
+    #[test]
+    fn test_at_rules() {
+        let mut manager = CssManager::new();
+        let source = r#"
+            /* { not a block } */
+            @keyframes fade { from { opacity: 0 } 50%, to { opacity: 1 } }
+            @media (max-width: 100px) { button { color: red } }
+            button { color: blue }
+        "#;
+        let id = manager.add(source).unwrap();
+        assert!(ANIMATIONS.with_borrow(|m| m.contains_key("fade")));
+        let mut button = Element::create(Button::create);
+        button.set_tag("button".to_string());
+        // Media conditions never match elements without window
+        let (button_styles, _) = manager.match_styles(&button);
+        assert_eq!(1, button_styles.len());
+        assert!(button_styles[0].contains("blue"));
+        manager.remove(&id);
+        assert!(!ANIMATIONS.with_borrow(|m| m.contains_key("fade")));
+    }
 }
@@ -0,0 +1,242 @@
+use crate::style::length::{Length, LengthContext};
+use crate::style::split_top_level;
+use std::str::FromStr;
+
+/// Font size of the root element without style, used by relative lengths in media queries
+const DEFAULT_FONT_SIZE: f32 = 12.0;
+
+/// Window state media queries are evaluated against
+#[derive(Clone, Debug, PartialEq)]
+pub struct MediaEnv {
+    /// Logical width of the viewport
+    pub width: f32,
+    /// Logical height of the viewport
+    pub height: f32,
+    pub dark: bool,
+    /// Device pixels per css pixel
+    pub resolution: f32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Comparison {
+    Lt,
+    Le,
+    Eq,
+    Ge,
+    Gt,
+}
+
+impl Comparison {
+    fn parse(op: &str) -> Option<Self> {
+        match op {
+            "<" => Some(Self::Lt),
+            "<=" => Some(Self::Le),
+            "=" => Some(Self::Eq),
+            ">=" => Some(Self::Ge),
+            ">" => Some(Self::Gt),
+            _ => None,
+        }
+    }
+
+    /// Comparison with swapped operands, `a < b` is `b > a`
+    fn flip(self) -> Self {
+        match self {
+            Self::Lt => Self::Gt,
+            Self::Le => Self::Ge,
+            Self::Eq => Self::Eq,
+            Self::Ge => Self::Le,
+            Self::Gt => Self::Lt,
+        }
+    }
+
+    fn test(self, actual: f32, expected: f32) -> bool {
+        match self {
+            Self::Lt => actual < expected,
+            Self::Le => actual <= expected,
+            Self::Eq => actual == expected,
+            Self::Ge => actual >= expected,
+            Self::Gt => actual > expected,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum MediaFeature {
+    Width(Comparison, Length),
+    Height(Comparison, Length),
+    Landscape(bool),
+    Dark(bool),
+    /// Resolution in dppx
+    Resolution(Comparison, f32),
+    /// Unsupported feature, never matches
+    Unknown,
+}
+
+impl MediaFeature {
+    /// Parse feature without parentheses
+    fn parse(feature: &str) -> Self {
+        Self::parse_feature(feature).unwrap_or(Self::Unknown)
+    }
+
+    fn parse_feature(feature: &str) -> Option<Self> {
+        if let Some((name, value)) = feature.split_once(':') {
+            let name = name.trim();
+            let value = value.trim();
+            let (cmp, name) = if let Some(n) = name.strip_prefix("min-") {
+                (Comparison::Ge, n)
+            } else if let Some(n) = name.strip_prefix("max-") {
+                (Comparison::Le, n)
+            } else {
+                (Comparison::Eq, name)
+            };
+            return match name {
+                "orientation" if cmp == Comparison::Eq => match value {
+                    "landscape" => Some(Self::Landscape(true)),
+                    "portrait" => Some(Self::Landscape(false)),
+                    _ => None,
+                },
+                "prefers-color-scheme" if cmp == Comparison::Eq => match value {
+                    "dark" => Some(Self::Dark(true)),
+                    "light" => Some(Self::Dark(false)),
+                    _ => None,
+                },
+                _ => Self::parse_range(name, cmp, value),
+            };
+        }
+        // Range syntax, `width >= 600px` or `600px <= width`
+        for op in ["<=", ">=", "<", ">", "="] {
+            if let Some((left, right)) = feature.split_once(op) {
+                let cmp = Comparison::parse(op)?;
+                let (left, right) = (left.trim(), right.trim());
+                return Self::parse_range(left, cmp, right)
+                    .or_else(|| Self::parse_range(right, cmp.flip(), left));
+            }
+        }
+        None
+    }
+
+    fn parse_range(name: &str, cmp: Comparison, value: &str) -> Option<Self> {
+        match name {
+            "width" => Some(Self::Width(cmp, Length::from_str(value)?)),
+            "height" => Some(Self::Height(cmp, Length::from_str(value)?)),
+            "resolution" => Some(Self::Resolution(cmp, parse_resolution(value)?)),
+            _ => None,
+        }
+    }
+
+    fn matches(&self, env: &MediaEnv) -> bool {
+        let ctx = LengthContext {
+            root: DEFAULT_FONT_SIZE,
+            font_size: DEFAULT_FONT_SIZE,
+            viewport_width: env.width,
+            viewport_height: env.height,
+        };
+        match self {
+            Self::Width(cmp, len) => cmp.test(env.width, len.to_px(&ctx)),
+            Self::Height(cmp, len) => cmp.test(env.height, len.to_px(&ctx)),
+            Self::Landscape(landscape) => (env.width >= env.height) == *landscape,
+            Self::Dark(dark) => env.dark == *dark,
+            Self::Resolution(cmp, dppx) => cmp.test(env.resolution, *dppx),
+            Self::Unknown => false,
+        }
+    }
+}
+
+fn parse_resolution(value: &str) -> Option<f32> {
+    let parse = |v: &str| f32::from_str(v.trim()).ok();
+    if let Some(v) = value.strip_suffix("dppx") {
+        parse(v)
+    } else if let Some(v) = value.strip_suffix("dpcm") {
+        parse(v).map(|v| v * 2.54 / 96.0)
+    } else if let Some(v) = value.strip_suffix("dpi") {
+        parse(v).map(|v| v / 96.0)
+    } else if let Some(v) = value.strip_suffix("x") {
+        parse(v)
+    } else {
+        None
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct MediaQuery {
+    negated: bool,
+    /// False if the media type is not a screen, e.g. `print`
+    screen: bool,
+    features: Vec<MediaFeature>,
+}
+
+impl MediaQuery {
+    fn parse(query: &str) -> Self {
+        let mut negated = false;
+        let mut screen = true;
+        let mut features = Vec::new();
+        for token in split_top_level(query, ' ') {
+            match token {
+                "not" => negated = true,
+                "only" | "and" => {}
+                "all" | "screen" => screen = true,
+                _ => {
+                    if let Some(f) = token.strip_prefix('(').and_then(|t| t.strip_suffix(')')) {
+                        features.push(MediaFeature::parse(f.trim()));
+                    } else {
+                        screen = false;
+                    }
+                }
+            }
+        }
+        Self {
+            negated,
+            screen,
+            features,
+        }
+    }
+
+    fn matches(&self, env: &MediaEnv) -> bool {
+        let matched = self.screen && self.features.iter().all(|f| f.matches(env));
+        matched != self.negated
+    }
+}
+
+/// Prelude of `@media` rule, matches if any of the queries matches
+#[derive(Clone, Debug, PartialEq)]
+pub struct MediaQueryList(Vec<MediaQuery>);
+
+impl MediaQueryList {
+    pub fn parse(value: &str) -> Self {
+        let value = value.trim().to_lowercase();
+        let list = split_top_level(&value, ',')
+            .into_iter()
+            .map(MediaQuery::parse)
+            .collect();
+        Self(list)
+    }
+
+    pub fn matches(&self, env: &MediaEnv) -> bool {
+        self.0.is_empty() || self.0.iter().any(|q| q.matches(env))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::style::media::{MediaEnv, MediaQueryList};
+
+    #[test]
+    fn test_media_query() {
+        let env = MediaEnv {
+            width: 800.0,
+            height: 600.0,
+            dark: true,
+            resolution: 2.0,
+        };
+        let matches = |query: &str| MediaQueryList::parse(query).matches(&env);
+        assert!(matches(
+            "screen and (min-width: 600px) and (orientation: landscape)"
+        ));
+        assert!(!matches("(max-width: 600px)"));
+        assert!(matches("(max-width: 600px), (prefers-color-scheme: dark)"));
+        assert!(matches("not print"));
+        assert!(!matches("not all and (min-resolution: 2dppx)"));
+        assert!(matches("(width >= 800px) and (400px < height)"));
+        assert!(!matches("(hover: hover)"));
+    }
+}
@@ -0,0 +1,202 @@
+use crate::style::color::parse_color;
+use crate::style::length::{Length, LengthContext};
+use crate::style::{split_top_level, PropValueParse};
+use skia_safe::Color;
+
+/// One shadow of `box-shadow` or `text-shadow`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Shadow {
+    pub offset_x: Length,
+    pub offset_y: Length,
+    pub blur: Length,
+    pub spread: Length,
+    /// None means currentColor
+    pub color: Option<Color>,
+    pub inset: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ComputedShadow {
+    pub offset_x: f32,
+    pub offset_y: f32,
+    pub blur: f32,
+    pub spread: f32,
+    pub color: Option<Color>,
+    pub inset: bool,
+}
+
+impl ComputedShadow {
+    pub fn color_or(&self, current_color: Color) -> Color {
+        self.color.unwrap_or(current_color)
+    }
+
+    /// Sigma of the gaussian blur, the css blur radius is twice of it
+    pub fn blur_sigma(&self) -> f32 {
+        self.blur / 2.0
+    }
+
+    /// Distance the shadow reaches outside the box, (top, right, bottom, left)
+    pub fn outset(&self) -> (f32, f32, f32, f32) {
+        if self.inset {
+            return (0.0, 0.0, 0.0, 0.0);
+        }
+        // Gaussian blur is almost invisible beyond 3 sigma
+        let extent = self.spread + self.blur_sigma() * 3.0;
+        (
+            (extent - self.offset_y).max(0.0),
+            (extent + self.offset_x).max(0.0),
+            (extent + self.offset_y).max(0.0),
+            (extent - self.offset_x).max(0.0),
+        )
+    }
+}
+
+/// Max distance a list of shadows reaches outside the box, (top, right, bottom, left)
+pub fn shadows_outset(shadows: &[ComputedShadow]) -> (f32, f32, f32, f32) {
+    shadows.iter().fold((0.0, 0.0, 0.0, 0.0), |r, s| {
+        let (t, rt, b, l) = s.outset();
+        (r.0.max(t), r.1.max(rt), r.2.max(b), r.3.max(l))
+    })
+}
+
+impl Shadow {
+    pub fn compute(&self, ctx: &LengthContext) -> ComputedShadow {
+        ComputedShadow {
+            offset_x: self.offset_x.to_px(ctx),
+            offset_y: self.offset_y.to_px(ctx),
+            blur: self.blur.to_px(ctx).max(0.0),
+            spread: self.spread.to_px(ctx),
+            color: self.color,
+            inset: self.inset,
+        }
+    }
+
+    fn parse(value: &str, allow_spread_and_inset: bool) -> Option<Self> {
+        let mut lengths = Vec::new();
+        let mut color = None;
+        let mut inset = false;
+        for token in split_top_level(value, ' ') {
+            if token.eq_ignore_ascii_case("inset") && allow_spread_and_inset && !inset {
+                inset = true;
+            } else if let Some(len) = Length::from_str(token) {
+                lengths.push(len);
+            } else if token.eq_ignore_ascii_case("currentcolor") {
+                color = None;
+            } else if color.is_none() {
+                color = Some(parse_color(token)?);
+            } else {
+                return None;
+            }
+        }
+        let max_lengths = if allow_spread_and_inset { 4 } else { 3 };
+        if lengths.len() < 2 || lengths.len() > max_lengths {
+            return None;
+        }
+        let zero = Length::PX(0.0);
+        Some(Self {
+            offset_x: lengths[0],
+            offset_y: lengths[1],
+            blur: lengths.get(2).cloned().unwrap_or(zero),
+            spread: lengths.get(3).cloned().unwrap_or(zero),
+            color,
+            inset,
+        })
+    }
+
+    fn to_style_string(&self, with_spread: bool) -> String {
+        let mut parts = Vec::new();
+        if self.inset {
+            parts.push("inset".to_string());
+        }
+        parts.push(self.offset_x.to_str());
+        parts.push(self.offset_y.to_str());
+        parts.push(self.blur.to_str());
+        if with_spread {
+            parts.push(self.spread.to_str());
+        }
+        if let Some(c) = &self.color {
+            parts.push(c.to_style_string());
+        }
+        parts.join(" ")
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct BoxShadow(pub Vec<Shadow>);
+
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct TextShadow(pub Vec<Shadow>);
+
+fn parse_shadow_list(value: &str, allow_spread_and_inset: bool) -> Option<Vec<Shadow>> {
+    let value = value.trim();
+    if value.eq_ignore_ascii_case("none") {
+        return Some(Vec::new());
+    }
+    split_top_level(value, ',')
+        .into_iter()
+        .map(|s| Shadow::parse(s, allow_spread_and_inset))
+        .collect()
+}
+
+impl PropValueParse for BoxShadow {
+    fn parse_prop_value(value: &str) -> Option<Self> {
+        parse_shadow_list(value, true).map(Self)
+    }
+    fn to_style_string(&self) -> String {
+        if self.0.is_empty() {
+            return "none".to_string();
+        }
+        let list: Vec<String> = self.0.iter().map(|s| s.to_style_string(true)).collect();
+        list.join(", ")
+    }
+}
+
+impl PropValueParse for TextShadow {
+    fn parse_prop_value(value: &str) -> Option<Self> {
+        parse_shadow_list(value, false).map(Self)
+    }
+    fn to_style_string(&self) -> String {
+        if self.0.is_empty() {
+            return "none".to_string();
+        }
+        let list: Vec<String> = self.0.iter().map(|s| s.to_style_string(false)).collect();
+        list.join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::style::length::Length;
+    use crate::style::shadow::{BoxShadow, TextShadow};
+    use crate::style::{split_top_level, PropValueParse};
+    use skia_safe::Color;
+
+    #[test]
+    fn test_parse_box_shadow() {
+        let bs = BoxShadow::parse_prop_value("2px 4px 6px 1px rgb(10, 20, 30), inset 0 0 3px red")
+            .unwrap();
+        assert_eq!(2, bs.0.len());
+        let first = &bs.0[0];
+        assert_eq!(Length::PX(2.0), first.offset_x);
+        assert_eq!(Length::PX(4.0), first.offset_y);
+        assert_eq!(Length::PX(6.0), first.blur);
+        assert_eq!(Length::PX(1.0), first.spread);
+        assert_eq!(Some(Color::from_rgb(10, 20, 30)), first.color);
+        assert!(!first.inset);
+        let second = &bs.0[1];
+        assert!(second.inset);
+        assert_eq!(Some(Color::RED), second.color);
+
+        assert!(BoxShadow::parse_prop_value("none").unwrap().0.is_empty());
+        assert!(BoxShadow::parse_prop_value("1px").is_none());
+    }
+
+    #[test]
+    fn test_parse_text_shadow() {
+        let ts = TextShadow::parse_prop_value("1px 1px 2px").unwrap();
+        assert_eq!(1, ts.0.len());
+        assert_eq!(None, ts.0[0].color);
+        assert!(TextShadow::parse_prop_value("inset 1px 1px").is_none());
+        assert!(TextShadow::parse_prop_value("1px 1px 2px 3px").is_none());
+    }
+}
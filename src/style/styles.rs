@@ -1,7 +1,7 @@
 use crate::style::{ResolvedStyleProp, StylePropKey};
 use std::collections::{HashMap, HashSet};
 
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct Styles {
     list: HashMap<StylePropKey, ResolvedStyleProp>,
 }
@@ -17,6 +17,14 @@ impl Styles {
         }
     }
 
+    pub fn get(&self, key: &StylePropKey) -> Option<&ResolvedStyleProp> {
+        self.list.get(key)
+    }
+
+    pub fn insert(&mut self, value: ResolvedStyleProp) {
+        self.list.insert(value.key(), value);
+    }
+
     pub fn compute_changed_style<F: Fn(StylePropKey) -> ResolvedStyleProp>(
         &self,
         old_styles: &Styles,
@@ -0,0 +1,250 @@
+use crate::style::length::{Length, LengthContext};
+use crate::style::PropValueParse;
+use crate::text::TextAlign;
+use std::str::FromStr;
+
+impl PropValueParse for TextAlign {
+    fn parse_prop_value(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "left" => Some(Self::Left),
+            "right" => Some(Self::Right),
+            "center" => Some(Self::Center),
+            "justify" => Some(Self::Justify),
+            "start" => Some(Self::Start),
+            "end" => Some(Self::End),
+            _ => None,
+        }
+    }
+    fn to_style_string(&self) -> String {
+        match self {
+            TextAlign::Left => "left",
+            TextAlign::Right => "right",
+            TextAlign::Center => "center",
+            TextAlign::Justify => "justify",
+            TextAlign::Start => "start",
+            TextAlign::End => "end",
+        }
+        .to_owned()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TextOverflow {
+    Clip,
+    /// Overflowing text is replaced by `…`
+    Ellipsis,
+}
+
+impl PropValueParse for TextOverflow {
+    fn parse_prop_value(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "clip" => Some(Self::Clip),
+            "ellipsis" => Some(Self::Ellipsis),
+            _ => None,
+        }
+    }
+    fn to_style_string(&self) -> String {
+        match self {
+            TextOverflow::Clip => "clip",
+            TextOverflow::Ellipsis => "ellipsis",
+        }
+        .to_owned()
+    }
+}
+
+/// Text keeps its spaces by default (`pre-wrap`), spaces are only collapsed with `normal`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WhiteSpace {
+    /// Spaces and newlines are collapsed, lines are wrapped
+    Normal,
+    /// Spaces and newlines are collapsed, lines are not wrapped
+    NoWrap,
+    /// Spaces and newlines are preserved, lines are not wrapped
+    Pre,
+    /// Spaces and newlines are preserved, lines are wrapped
+    PreWrap,
+}
+
+impl WhiteSpace {
+    pub fn wraps(&self) -> bool {
+        matches!(self, WhiteSpace::Normal | WhiteSpace::PreWrap)
+    }
+
+    pub fn preserves_spaces(&self) -> bool {
+        matches!(self, WhiteSpace::Pre | WhiteSpace::PreWrap)
+    }
+}
+
+impl PropValueParse for WhiteSpace {
+    fn parse_prop_value(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "normal" => Some(Self::Normal),
+            "nowrap" => Some(Self::NoWrap),
+            "pre" => Some(Self::Pre),
+            "pre-wrap" => Some(Self::PreWrap),
+            _ => None,
+        }
+    }
+    fn to_style_string(&self) -> String {
+        match self {
+            WhiteSpace::Normal => "normal",
+            WhiteSpace::NoWrap => "nowrap",
+            WhiteSpace::Pre => "pre",
+            WhiteSpace::PreWrap => "pre-wrap",
+        }
+        .to_owned()
+    }
+}
+
+/// Where lines may be broken, words longer than the line are always broken between chars
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WordBreak {
+    /// Break after spaces and hyphens, and around CJK chars
+    Normal,
+    /// Break between any chars
+    BreakAll,
+    /// Same as `Normal` except that CJK text is not broken
+    KeepAll,
+}
+
+impl PropValueParse for WordBreak {
+    fn parse_prop_value(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "normal" => Some(Self::Normal),
+            "break-all" => Some(Self::BreakAll),
+            "keep-all" => Some(Self::KeepAll),
+            _ => None,
+        }
+    }
+    fn to_style_string(&self) -> String {
+        match self {
+            WordBreak::Normal => "normal",
+            WordBreak::BreakAll => "break-all",
+            WordBreak::KeepAll => "keep-all",
+        }
+        .to_owned()
+    }
+}
+
+/// Max count of lines, the last line ends with an ellipsis if some lines are hidden
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LineClamp {
+    None,
+    Lines(usize),
+}
+
+impl LineClamp {
+    pub fn max_lines(&self) -> Option<usize> {
+        match self {
+            LineClamp::None => None,
+            LineClamp::Lines(n) => Some(*n),
+        }
+    }
+}
+
+impl PropValueParse for LineClamp {
+    fn parse_prop_value(value: &str) -> Option<Self> {
+        let value = value.trim();
+        if value.eq_ignore_ascii_case("none") {
+            Some(Self::None)
+        } else {
+            usize::from_str(value).ok().map(Self::Lines)
+        }
+    }
+    fn to_style_string(&self) -> String {
+        match self {
+            LineClamp::None => "none".to_string(),
+            LineClamp::Lines(n) => n.to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LetterSpacing {
+    Normal,
+    Length(Length),
+}
+
+impl LetterSpacing {
+    pub fn to_px(&self, length_context: &LengthContext) -> f32 {
+        match self {
+            LetterSpacing::Normal => 0.0,
+            LetterSpacing::Length(l) => l.to_px(length_context),
+        }
+    }
+}
+
+impl PropValueParse for LetterSpacing {
+    fn parse_prop_value(value: &str) -> Option<Self> {
+        let value = value.trim();
+        if value.eq_ignore_ascii_case("normal") {
+            Some(Self::Normal)
+        } else {
+            Length::from_str(value).map(Self::Length)
+        }
+    }
+    fn to_style_string(&self) -> String {
+        match self {
+            LetterSpacing::Normal => "normal".to_string(),
+            LetterSpacing::Length(l) => l.to_style_string(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TextTransform {
+    None,
+    Uppercase,
+    Lowercase,
+    /// The first letter of each word is uppercased
+    Capitalize,
+}
+
+impl TextTransform {
+    /// Transform `ch` following `prev`, chars mapping to multiple chars are kept as is
+    /// so that the char count of text is not changed
+    pub fn apply(&self, prev: Option<char>, ch: char) -> char {
+        let upper = match self {
+            TextTransform::None => return ch,
+            TextTransform::Uppercase => true,
+            TextTransform::Lowercase => false,
+            TextTransform::Capitalize => {
+                if prev.map(|c| c.is_alphanumeric()).unwrap_or(false) {
+                    return ch;
+                }
+                true
+            }
+        };
+        let mut mapped = if upper {
+            ch.to_uppercase().collect::<Vec<_>>()
+        } else {
+            ch.to_lowercase().collect::<Vec<_>>()
+        };
+        if mapped.len() == 1 {
+            mapped.remove(0)
+        } else {
+            ch
+        }
+    }
+}
+
+impl PropValueParse for TextTransform {
+    fn parse_prop_value(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "none" => Some(Self::None),
+            "uppercase" => Some(Self::Uppercase),
+            "lowercase" => Some(Self::Lowercase),
+            "capitalize" => Some(Self::Capitalize),
+            _ => None,
+        }
+    }
+    fn to_style_string(&self) -> String {
+        match self {
+            TextTransform::None => "none",
+            TextTransform::Uppercase => "uppercase",
+            TextTransform::Lowercase => "lowercase",
+            TextTransform::Capitalize => "capitalize",
+        }
+        .to_owned()
+    }
+}
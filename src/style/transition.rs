@@ -0,0 +1,211 @@
+use crate::animation::timing_function::TimingFunction;
+use crate::style::{split_top_level, PropValueParse, StylePropKey};
+use crate::style_list::StyleList;
+use std::str::FromStr;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TransitionPropertyValue {
+    All,
+    None,
+    /// Shorthands are expanded to their longhand keys
+    Keys(Vec<StylePropKey>),
+}
+
+impl TransitionPropertyValue {
+    fn parse(value: &str) -> Self {
+        let name = value.trim().to_lowercase().replace("-", "");
+        match name.as_str() {
+            "all" => Self::All,
+            "none" => Self::None,
+            _ => {
+                let keys = StyleList::expand_style(&name, "0")
+                    .into_iter()
+                    .filter_map(|(k, _)| StylePropKey::parse(k))
+                    .collect();
+                Self::Keys(keys)
+            }
+        }
+    }
+
+    pub fn matches(&self, key: StylePropKey) -> bool {
+        match self {
+            TransitionPropertyValue::All => true,
+            TransitionPropertyValue::None => false,
+            TransitionPropertyValue::Keys(keys) => keys.contains(&key),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TransitionProperty {
+    pub list: Vec<TransitionPropertyValue>,
+    text: String,
+}
+
+/// Comma separated times in milliseconds
+#[derive(Clone, Debug, PartialEq)]
+pub struct TimeList(pub Vec<f32>);
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TimingFunctionList(pub Vec<TimingFunction>);
+
+impl Default for TransitionProperty {
+    fn default() -> Self {
+        Self {
+            list: vec![TransitionPropertyValue::All],
+            text: "all".to_string(),
+        }
+    }
+}
+
+impl Default for TimeList {
+    fn default() -> Self {
+        Self(vec![0.0])
+    }
+}
+
+impl Default for TimingFunctionList {
+    fn default() -> Self {
+        Self(vec![TimingFunction::default()])
+    }
+}
+
+/// Parse time to milliseconds, a number without unit is in milliseconds
+pub fn parse_time(value: &str) -> Option<f32> {
+    let value = value.trim();
+    if let Some(v) = value.strip_suffix("ms") {
+        f32::from_str(v.trim()).ok()
+    } else if let Some(v) = value.strip_suffix("s") {
+        f32::from_str(v.trim()).ok().map(|v| v * 1000.0)
+    } else {
+        f32::from_str(value).ok()
+    }
+}
+
+impl PropValueParse for TransitionProperty {
+    fn parse_prop_value(value: &str) -> Option<Self> {
+        let list: Vec<_> = split_top_level(value, ',')
+            .into_iter()
+            .map(TransitionPropertyValue::parse)
+            .collect();
+        if list.is_empty() {
+            return None;
+        }
+        Some(Self {
+            list,
+            text: value.trim().to_string(),
+        })
+    }
+    fn to_style_string(&self) -> String {
+        self.text.clone()
+    }
+}
+
+impl PropValueParse for TimeList {
+    fn parse_prop_value(value: &str) -> Option<Self> {
+        let list = split_top_level(value, ',')
+            .into_iter()
+            .map(parse_time)
+            .collect::<Option<Vec<_>>>()?;
+        if list.is_empty() {
+            return None;
+        }
+        Some(Self(list))
+    }
+    fn to_style_string(&self) -> String {
+        let list: Vec<String> = self.0.iter().map(|t| format!("{}ms", t)).collect();
+        list.join(", ")
+    }
+}
+
+impl PropValueParse for TimingFunctionList {
+    fn parse_prop_value(value: &str) -> Option<Self> {
+        let list = split_top_level(value, ',')
+            .into_iter()
+            .map(TimingFunction::parse_prop_value)
+            .collect::<Option<Vec<_>>>()?;
+        if list.is_empty() {
+            return None;
+        }
+        Some(Self(list))
+    }
+    fn to_style_string(&self) -> String {
+        let list: Vec<String> = self.0.iter().map(|t| t.to_style_string()).collect();
+        list.join(", ")
+    }
+}
+
+/// Expand `transition` shorthand to longhand properties
+pub fn expand_transition(value: &str) -> Vec<(&'static str, String)> {
+    let keys = [
+        "TransitionProperty",
+        "TransitionDuration",
+        "TransitionTimingFunction",
+        "TransitionDelay",
+    ];
+    let value = value.trim();
+    if value.eq_ignore_ascii_case("inherit") || value.eq_ignore_ascii_case("unset") {
+        return keys.iter().map(|k| (*k, value.to_string())).collect();
+    }
+    let mut properties = Vec::new();
+    let mut durations = Vec::new();
+    let mut timing_functions = Vec::new();
+    let mut delays = Vec::new();
+    for layer in split_top_level(value, ',') {
+        let mut property = "all";
+        let mut times = Vec::new();
+        let mut timing_function = "ease";
+        for token in split_top_level(layer, ' ') {
+            if parse_time(token).is_some() {
+                times.push(token);
+            } else if TimingFunction::parse_prop_value(token).is_some() {
+                timing_function = token;
+            } else {
+                property = token;
+            }
+        }
+        properties.push(property.to_string());
+        durations.push(times.first().unwrap_or(&"0").to_string());
+        delays.push(times.get(1).unwrap_or(&"0").to_string());
+        timing_functions.push(timing_function.to_string());
+    }
+    let values = [
+        properties.join(", "),
+        durations.join(", "),
+        timing_functions.join(", "),
+        delays.join(", "),
+    ];
+    keys.into_iter().zip(values).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::style::transition::{
+        expand_transition, TimeList, TransitionProperty, TransitionPropertyValue,
+    };
+    use crate::style::{PropValueParse, StylePropKey};
+
+    #[test]
+    fn test_parse_transition() {
+        let property = TransitionProperty::parse_prop_value("opacity, border-radius").unwrap();
+        assert_eq!(
+            TransitionPropertyValue::Keys(vec![StylePropKey::Opacity]),
+            property.list[0]
+        );
+        assert!(property.list[1].matches(StylePropKey::BorderBottomLeftRadius));
+        let times = TimeList::parse_prop_value("1.5s, 100ms, 50").unwrap();
+        assert_eq!(vec![1500.0, 100.0, 50.0], times.0);
+
+        let list = expand_transition("opacity 0.2s ease-in, transform 1s linear 100ms");
+        assert_eq!(
+            ("TransitionProperty", "opacity, transform".to_string()),
+            list[0]
+        );
+        assert_eq!(("TransitionDuration", "0.2s, 1s".to_string()), list[1]);
+        assert_eq!(
+            ("TransitionTimingFunction", "ease-in, linear".to_string()),
+            list[2]
+        );
+        assert_eq!(("TransitionDelay", "0, 100ms".to_string()), list[3]);
+    }
+}
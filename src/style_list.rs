@@ -115,6 +115,14 @@ impl StyleList {
             FixedStyleProp::FontFamily(StylePropVal::Inherit),
             FixedStyleProp::FontWeight(StylePropVal::Inherit),
             FixedStyleProp::FontStyle(StylePropVal::Inherit),
+            FixedStyleProp::TextAlign(StylePropVal::Inherit),
+            FixedStyleProp::TextOverflow(StylePropVal::Inherit),
+            FixedStyleProp::WhiteSpace(StylePropVal::Inherit),
+            FixedStyleProp::WordBreak(StylePropVal::Inherit),
+            FixedStyleProp::LineClamp(StylePropVal::Inherit),
+            FixedStyleProp::LetterSpacing(StylePropVal::Inherit),
+            FixedStyleProp::TextTransform(StylePropVal::Inherit),
+            FixedStyleProp::Direction(StylePropVal::Inherit),
         ];
         for d in default_styles {
             default_style_props.push(ParsedStyleProp::Fixed(d));
@@ -304,9 +312,8 @@ impl StyleList {
         }
         let key = k.to_lowercase().replace("-", "");
         match key.as_str() {
-            "background" => {
-                vec![("BackgroundColor", v_str.to_string())]
-            }
+            "background" => crate::style::background::expand_background(v_str),
+            "transition" => crate::style::transition::expand_transition(v_str),
             "gap" => {
                 vec![
                     ("RowGap", v_str.to_string()),
@@ -30,6 +30,16 @@ pub struct TextStyle {
     background_paint: Paint,
     font_style: FontStyle,
     decoration_type: TextDecoration,
+    decoration_style: TextDecorationStyle,
+    /// Foreground color is used if none
+    decoration_color: Option<Color>,
+    /// Thickness recommended by the font is used if none
+    decoration_thickness: Option<f32>,
+    letter_spacing: f32,
+    word_spacing: f32,
+    /// Vertical offset of baseline, positive is downward
+    baseline_shift: f32,
+    link: Option<String>,
 }
 
 impl TextStyle {
@@ -79,6 +89,66 @@ impl TextStyle {
     pub fn set_decoration_type(&mut self, decoration_type: TextDecoration) {
         self.decoration_type = decoration_type;
     }
+
+    pub fn decoration_type(&self) -> TextDecoration {
+        self.decoration_type
+    }
+
+    pub fn set_decoration_style(&mut self, decoration_style: TextDecorationStyle) {
+        self.decoration_style = decoration_style;
+    }
+
+    pub fn decoration_style(&self) -> TextDecorationStyle {
+        self.decoration_style
+    }
+
+    pub fn set_decoration_color(&mut self, color: Option<Color>) {
+        self.decoration_color = color;
+    }
+
+    pub fn decoration_color(&self) -> Option<Color> {
+        self.decoration_color
+    }
+
+    pub fn set_decoration_thickness(&mut self, thickness: Option<f32>) {
+        self.decoration_thickness = thickness;
+    }
+
+    pub fn decoration_thickness(&self) -> Option<f32> {
+        self.decoration_thickness
+    }
+
+    pub fn set_letter_spacing(&mut self, letter_spacing: f32) {
+        self.letter_spacing = letter_spacing;
+    }
+
+    pub fn letter_spacing(&self) -> f32 {
+        self.letter_spacing
+    }
+
+    pub fn set_word_spacing(&mut self, word_spacing: f32) {
+        self.word_spacing = word_spacing;
+    }
+
+    pub fn word_spacing(&self) -> f32 {
+        self.word_spacing
+    }
+
+    pub fn set_baseline_shift(&mut self, baseline_shift: f32) {
+        self.baseline_shift = baseline_shift;
+    }
+
+    pub fn baseline_shift(&self) -> f32 {
+        self.baseline_shift
+    }
+
+    pub fn set_link(&mut self, link: Option<String>) {
+        self.link = link;
+    }
+
+    pub fn link(&self) -> Option<&String> {
+        self.link.as_ref()
+    }
 }
 
 bitflags! {
@@ -93,6 +163,34 @@ bitflags! {
     }
 }
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum TextDecorationStyle {
+    Solid,
+    Double,
+    Dotted,
+    Dashed,
+    Wavy,
+}
+
+impl Default for TextDecorationStyle {
+    fn default() -> Self {
+        Self::Solid
+    }
+}
+
+impl TextDecorationStyle {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "solid" => Some(Self::Solid),
+            "double" => Some(Self::Double),
+            "dotted" => Some(Self::Dotted),
+            "dashed" => Some(Self::Dashed),
+            "wavy" => Some(Self::Wavy),
+            _ => None,
+        }
+    }
+}
+
 pub const ALL_TEXT_DECORATIONS: TextDecoration = TextDecoration::ALL;
 
 impl Default for TextDecoration {
@@ -1,17 +1,24 @@
+pub mod decoration;
+pub mod find;
 mod line;
 mod util;
 
 use crate as deft;
 use crate::base::EventContext;
 use crate::color::parse_hex_color;
-use crate::element::paragraph::simple_paragraph_builder::SimpleParagraphBuilder;
+use crate::element::common::image_object::ImageObject;
+use crate::element::paragraph::simple_paragraph_builder::{
+    SimpleParagraphBuilder, OBJECT_REPLACEMENT_CHARACTER,
+};
 use crate::element::paragraph::ParagraphParams;
 use crate::element::text::intersect_range;
-use crate::element::text::simple_text_paragraph::SimpleTextParagraph;
+use crate::element::text::simple_text_paragraph::{
+    InlineObject, InlineObjectAlign, SimpleTextParagraph,
+};
 use crate::element::{ElementBackend, ElementWeak};
 use crate::event::{
     ClickEvent, Event, KeyDownEvent, KeyEventDetail, MouseDownEvent, MouseMoveEvent, MouseUpEvent,
-    KEY_MOD_CTRL,
+    KEY_MOD_CTRL, KEY_MOD_SHIFT,
 };
 use crate::font::family::{FontFamilies, FontFamily};
 use crate::number::DeNan;
@@ -20,14 +27,22 @@ use crate::render::RenderFn;
 use crate::string::StringUtils;
 use crate::style::color::parse_optional_color_str;
 use crate::style::font::FontStyle;
+use crate::style::shadow::ComputedShadow;
+use crate::style::text::{LineClamp, TextOverflow, TextTransform, WhiteSpace, WordBreak};
 use crate::style::PropValueParse;
+use crate::text::textbox::decoration::DecorationRange;
+use crate::text::textbox::find::{find_matches, FindOptions, FindState, FIND_DECORATION_GROUP};
 use crate::text::textbox::line::Line;
 use crate::text::textbox::util::{parse_optional_text_decoration, parse_optional_weight};
-use crate::text::{TextAlign, TextStyle};
-use crate::{base, js_deserialize, js_serialize, some_or_continue};
+use crate::text::{TextAlign, TextDecorationStyle, TextStyle};
+use crate::{base, js_deserialize, js_serialize, some_or_break, some_or_continue, some_or_return};
 use serde::{Deserialize, Serialize};
 use skia_safe::font_style::{Weight, Width};
 use skia_safe::{Color, Paint};
+use unicode_bidi::{BidiInfo, Level};
+use unicode_segmentation::UnicodeSegmentation;
+use winit::keyboard::NamedKey;
+use yoga::Direction;
 
 #[cfg(target_os = "windows")]
 pub const DEFAULT_FALLBACK_FONTS: &str = "sans-serif,Microsoft YaHei,Segoe UI Emoji";
@@ -47,35 +62,89 @@ pub const DEFAULT_FALLBACK_FONTS: &str = "Noto Serif,sans-serif";
 
 const ZERO_WIDTH_WHITESPACE: &str = "\u{200B}";
 
+/// Modifier of moving the caret by word
+#[cfg(target_os = "macos")]
+const WORD_NAVIGATION_MOD: u32 = crate::event::KEY_MOD_ALT;
+#[cfg(not(target_os = "macos"))]
+const WORD_NAVIGATION_MOD: u32 = KEY_MOD_CTRL;
+
+/// Modifier of moving the caret to the start or end of the text with Home and End
+#[cfg(target_os = "macos")]
+const TEXT_NAVIGATION_MOD: u32 = crate::event::KEY_MOD_META;
+#[cfg(not(target_os = "macos"))]
+const TEXT_NAVIGATION_MOD: u32 = KEY_MOD_CTRL;
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(tag = "type", rename_all = "camelCase")]
 pub enum TextElement {
     Text(TextUnit),
+    Image(ImageUnit),
+    Placeholder(PlaceholderUnit),
 }
 
 js_serialize!(TextElement);
 js_deserialize!(TextElement);
 
 impl TextElement {
-    fn atom_count(&self) -> usize {
+    /// Images and placeholders are single atoms
+    pub fn atom_count(&self) -> usize {
         match self {
             TextElement::Text(text) => text.text.chars_count(),
+            TextElement::Image(_) | TextElement::Placeholder(_) => 1,
         }
     }
-    fn text(&self) -> &str {
+
+    /// Images and placeholders are represented by the object replacement character
+    pub fn text(&self) -> &str {
         match self {
             TextElement::Text(t) => t.text.as_str(),
+            TextElement::Image(_) | TextElement::Placeholder(_) => OBJECT_REPLACEMENT_CHARACTER,
         }
     }
 
     fn get_text(&self, begin: usize, end: usize) -> &str {
         match self {
             TextElement::Text(t) => t.text.substring(begin, end - begin),
+            TextElement::Image(_) | TextElement::Placeholder(_) => {
+                if begin < end {
+                    OBJECT_REPLACEMENT_CHARACTER
+                } else {
+                    ""
+                }
+            }
+        }
+    }
+
+    /// Returns the part of element between atom offsets
+    fn slice(&self, begin: usize, end: usize) -> TextElement {
+        match self {
+            TextElement::Text(t) => TextElement::Text(TextUnit {
+                text: t.text.substring(begin, end - begin).to_string(),
+                ..t.clone()
+            }),
+            _ => self.clone(),
         }
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+/// Append elements to `target`, adjacent text units with the same style are merged
+pub fn append_elements(target: &mut Vec<TextElement>, elements: Vec<TextElement>) {
+    for e in elements {
+        if let (Some(TextElement::Text(last)), TextElement::Text(unit)) = (target.last_mut(), &e) {
+            if last.has_same_style(unit) {
+                last.text.push_str(&unit.text);
+                continue;
+            }
+        }
+        target.push(e);
+    }
+}
+
+pub fn elements_atom_count(elements: &[TextElement]) -> usize {
+    elements.iter().map(|e| e.atom_count()).sum()
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct TextUnit {
     pub text: String,
@@ -86,11 +155,112 @@ pub struct TextUnit {
     pub weight: Option<String>,
     pub background_color: Option<String>,
     pub style: Option<String>,
+    /// Extra space after each char
+    pub letter_spacing: Option<f32>,
+    /// Extra space after each space char
+    pub word_spacing: Option<f32>,
+    /// One of `solid`, `double`, `dotted`, `dashed` and `wavy`
+    pub text_decoration_style: Option<String>,
+    pub text_decoration_color: Option<String>,
+    pub text_decoration_thickness: Option<f32>,
+    /// `super` or `sub`, the font size is reduced if it is not specified
+    pub vertical_align: Option<String>,
+    /// Target emitted by [`LinkClickEvent`](crate::event::LinkClickEvent) when the unit is
+    /// clicked, only rich text elements handle links
+    pub link: Option<String>,
 }
 
 js_serialize!(TextUnit);
 js_deserialize!(TextUnit);
 
+impl TextUnit {
+    fn has_same_style(&self, other: &TextUnit) -> bool {
+        self.font_families == other.font_families
+            && self.font_size == other.font_size
+            && self.color == other.color
+            && self.text_decoration_line == other.text_decoration_line
+            && self.weight == other.weight
+            && self.background_color == other.background_color
+            && self.style == other.style
+            && self.letter_spacing == other.letter_spacing
+            && self.word_spacing == other.word_spacing
+            && self.text_decoration_style == other.text_decoration_style
+            && self.text_decoration_color == other.text_decoration_color
+            && self.text_decoration_thickness == other.text_decoration_thickness
+            && self.vertical_align == other.vertical_align
+            && self.link == other.link
+    }
+}
+
+/// Image flowing with text
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageUnit {
+    pub src: String,
+    pub width: f32,
+    pub height: f32,
+    /// One of `baseline`, `middle`, `top` and `bottom`, defaults to `baseline`
+    pub vertical_align: Option<String>,
+    /// Distance between the bottom of image and the baseline when aligned with baseline
+    pub baseline_offset: Option<f32>,
+}
+
+/// Empty box flowing with text, reserves space for widgets placed over the text
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaceholderUnit {
+    pub width: f32,
+    pub height: f32,
+    /// One of `baseline`, `middle`, `top` and `bottom`, defaults to `baseline`
+    pub vertical_align: Option<String>,
+    /// Distance between the bottom of placeholder and the baseline when aligned with baseline
+    pub baseline_offset: Option<f32>,
+}
+
+/// State of text transform and space collapsing across the units of a line
+struct TextProcessState {
+    prev_char: Option<char>,
+    after_space: bool,
+}
+
+impl TextProcessState {
+    fn new() -> Self {
+        // Spaces at the start of line are collapsed
+        Self {
+            prev_char: None,
+            after_space: true,
+        }
+    }
+
+    /// Transform chars and collapse spaces of `text` following previous units, collapsed spaces
+    /// are replaced with zero width spaces so that the char count is kept
+    fn process(&mut self, params: &ParagraphParams, text: &str) -> String {
+        let collapse = !params.white_space.preserves_spaces();
+        let mut result = String::with_capacity(text.len());
+        for ch in text.chars() {
+            let mut processed = params.text_transform.apply(self.prev_char, ch);
+            self.prev_char = Some(ch);
+            if collapse && ch.is_whitespace() && ch != '\u{00A0}' {
+                if self.after_space {
+                    processed = '\u{200B}';
+                } else {
+                    processed = ' ';
+                    self.after_space = true;
+                }
+            } else {
+                self.after_space = false;
+            }
+            result.push(processed);
+        }
+        result
+    }
+
+    fn add_atom(&mut self) {
+        self.prev_char = None;
+        self.after_space = false;
+    }
+}
+
 #[derive(Ord, PartialOrd, Eq, PartialEq, Debug, Copy, Clone, Serialize, Deserialize)]
 pub struct TextCoord(pub usize, pub usize);
 
@@ -102,6 +272,19 @@ impl TextCoord {
 
 js_serialize!(TextCoord);
 
+enum CaretMotion {
+    /// Move to the right if true
+    Horizontal(bool),
+    /// Move up if true
+    Vertical(bool),
+    /// Move forward if true
+    Word(bool),
+    /// Move to the end of the visual line if true
+    LineEdge(bool),
+    /// Move to the end of the text if true
+    TextEdge(bool),
+}
+
 pub struct TextBox {
     params: ParagraphParams,
     lines: Vec<Line>,
@@ -112,6 +295,13 @@ pub struct TextBox {
     selection_fg: Paint,
     width: f32,
     padding: (f32, f32, f32, f32),
+    text_shadow: Vec<ComputedShadow>,
+    decorations: Vec<DecorationRange>,
+    find_state: Option<FindState>,
+    /// Matches of find are searched again before painting if true
+    find_outdated: bool,
+    /// Lines after `max_lines` visual lines are hidden
+    max_lines: Option<usize>,
     /// (row_offset, column_offset)
     caret: TextCoord,
     vertical_caret_moving_coord_x: f32,
@@ -145,9 +335,114 @@ impl TextBox {
 
     pub fn clear(&mut self) {
         self.lines.clear();
+        // Offsets of decorations refer to the removed text
+        self.decorations.clear();
         self.request_layout();
     }
 
+    pub fn add_decoration(&mut self, decoration: DecorationRange) {
+        if decoration.start < decoration.end {
+            self.decorations.push(decoration);
+            self.request_repaint();
+        }
+    }
+
+    /// Remove decorations of `group`, all decorations are removed if `group` is None
+    pub fn remove_decorations(&mut self, group: Option<&str>) {
+        self.decorations.retain(|d| !d.is_in_group(group));
+        self.request_repaint();
+    }
+
+    /// Highlight occurrences of `query` and activate the first one, matches are searched again
+    /// when the text changes
+    pub fn find(&mut self, query: &str, options: FindOptions) -> Vec<(TextCoord, TextCoord)> {
+        self.find_state = Some(FindState {
+            query: query.to_string(),
+            matches: Vec::new(),
+            options,
+            active: Some(0),
+        });
+        self.refresh_find();
+        self.get_find_matches()
+    }
+
+    pub fn get_find_matches(&self) -> Vec<(TextCoord, TextCoord)> {
+        let state = match &self.find_state {
+            Some(s) => s,
+            None => return Vec::new(),
+        };
+        state
+            .matches
+            .iter()
+            .filter_map(|(start, end)| {
+                let start = self.get_text_coord_by_char_offset(*start)?;
+                let end = self.get_text_coord_by_char_offset(*end)?;
+                Some((start, end))
+            })
+            .collect()
+    }
+
+    /// Activate the match at `index`, returns the bounds of the match
+    pub fn set_active_match(&mut self, index: usize) -> Option<base::Rect> {
+        if self.find_outdated {
+            self.refresh_find();
+        }
+        let state = self.find_state.as_mut()?;
+        let (start, end) = *state.matches.get(index)?;
+        state.active = Some(index);
+        self.update_find_decorations();
+        let start_rect = self.get_char_rect(self.get_text_coord_by_char_offset(start)?)?;
+        let end_rect = self
+            .get_text_coord_by_char_offset(end - 1)
+            .and_then(|c| self.get_char_rect(c))
+            .unwrap_or(start_rect);
+        let left = f32::min(start_rect.x, end_rect.x);
+        let top = f32::min(start_rect.y, end_rect.y);
+        let right = f32::max(start_rect.right(), end_rect.right());
+        let bottom = f32::max(start_rect.bottom(), end_rect.bottom());
+        Some(base::Rect::from_ltrb(left, top, right, bottom))
+    }
+
+    pub fn clear_find(&mut self) {
+        self.find_state = None;
+        self.find_outdated = false;
+        self.remove_decorations(Some(FIND_DECORATION_GROUP));
+    }
+
+    fn refresh_find(&mut self) {
+        self.find_outdated = false;
+        let text = self.get_text();
+        let state = match &mut self.find_state {
+            Some(s) => s,
+            None => return,
+        };
+        state.matches = find_matches(&text, &state.query, &state.options);
+        state.active = if state.matches.is_empty() {
+            None
+        } else {
+            state.active.map(|a| usize::min(a, state.matches.len() - 1))
+        };
+        self.update_find_decorations();
+    }
+
+    fn update_find_decorations(&mut self) {
+        self.decorations
+            .retain(|d| !d.is_in_group(Some(FIND_DECORATION_GROUP)));
+        if let Some(state) = &self.find_state {
+            self.decorations.extend(state.build_decorations());
+        }
+        self.request_repaint();
+    }
+
+    pub fn get_decorations(&self) -> &Vec<DecorationRange> {
+        &self.decorations
+    }
+
+    pub fn set_decorations(&mut self, decorations: Vec<DecorationRange>) {
+        self.decorations = decorations;
+        self.request_repaint();
+    }
+
     pub fn measure_line(&self, units: Vec<TextElement>) -> (f32, f32) {
         let mut sk_paragraph = Self::build_paragraph(&self.params, &units);
         sk_paragraph.layout(f32::NAN);
@@ -203,6 +498,71 @@ impl TextBox {
         }
     }
 
+    pub fn set_align(&mut self, align: TextAlign) {
+        if self.params.align != align {
+            self.params.align = align;
+            self.rebuild_paragraph();
+        }
+    }
+
+    pub fn set_white_space(&mut self, white_space: WhiteSpace) {
+        if self.params.white_space != white_space {
+            self.params.white_space = white_space;
+            self.rebuild_paragraph();
+        }
+    }
+
+    pub fn set_word_break(&mut self, word_break: WordBreak) {
+        if self.params.word_break != word_break {
+            self.params.word_break = word_break;
+            self.rebuild_paragraph();
+        }
+    }
+
+    pub fn set_text_overflow(&mut self, text_overflow: TextOverflow) {
+        if self.params.text_overflow != text_overflow {
+            self.params.text_overflow = text_overflow;
+            self.rebuild_paragraph();
+        }
+    }
+
+    /// Default letter spacing of units without `letter_spacing`
+    pub fn set_letter_spacing(&mut self, letter_spacing: f32) {
+        if self.params.letter_spacing != letter_spacing {
+            self.params.letter_spacing = letter_spacing;
+            self.rebuild_paragraph();
+        }
+    }
+
+    pub fn set_text_transform(&mut self, text_transform: TextTransform) {
+        if self.params.text_transform != text_transform {
+            self.params.text_transform = text_transform;
+            self.rebuild_paragraph();
+        }
+    }
+
+    /// Set the base direction of paragraphs
+    pub fn set_direction(&mut self, direction: Direction) {
+        if self.params.direction != direction {
+            self.params.direction = direction;
+            self.rebuild_paragraph();
+        }
+    }
+
+    pub fn set_line_clamp(&mut self, line_clamp: LineClamp) {
+        let max_lines = line_clamp.max_lines();
+        if self.max_lines != max_lines {
+            self.max_lines = max_lines;
+            self.invalid_all_lines();
+            self.request_layout();
+        }
+    }
+
+    /// Shadows are only painted, so changing them doesn't need layout
+    pub fn set_text_shadow(&mut self, text_shadow: Vec<ComputedShadow>) {
+        self.text_shadow = text_shadow;
+    }
+
     pub fn get_paragraph_params(&self) -> &ParagraphParams {
         &self.params
     }
@@ -230,11 +590,24 @@ impl TextBox {
 
     pub fn layout(&mut self) {
         let (_, padding_right, _, padding_left) = self.padding;
-        for ln in &mut self.lines {
+        let lines_count = self.lines.len();
+        let mut remaining_lines = self.max_lines;
+        for (i, ln) in self.lines.iter_mut().enumerate() {
+            // Budget of lines depends on previous lines
+            let has_more_lines = i + 1 < lines_count;
+            if ln
+                .sk_paragraph
+                .set_max_lines(remaining_lines, has_more_lines)
+            {
+                ln.layout_calculated = false;
+            }
             if !ln.layout_calculated {
                 ln.force_layout(self.width - padding_left - padding_right);
                 ln.layout_calculated = true;
             }
+            if let Some(remaining) = &mut remaining_lines {
+                *remaining -= usize::min(ln.sk_paragraph.line_count(), *remaining);
+            }
         }
     }
 
@@ -412,6 +785,16 @@ impl TextBox {
         }
     }
 
+    /// Returns the link of the text under the pixel coordinate
+    pub fn get_link_by_pixel_coord(&mut self, coord: (f32, f32)) -> Option<String> {
+        let text_coord = self.get_text_coord_by_pixel_coord(coord);
+        let rect = self.get_char_rect(text_coord)?;
+        if !rect.contains_point(coord.0, coord.1) {
+            return None;
+        }
+        self.lines.get(text_coord.0)?.get_link(text_coord.1)
+    }
+
     pub fn get_text_coord_by_char_offset(&self, caret: usize) -> Option<TextCoord> {
         let mut col = caret;
         let mut row = 0;
@@ -425,8 +808,28 @@ impl TextBox {
         None
     }
 
+    pub fn get_char_offset_by_text_coord(&self, coord: TextCoord) -> usize {
+        let mut offset = coord.1;
+        for ln in self.lines.iter().take(coord.0) {
+            offset += ln.atom_count() + 1;
+        }
+        offset
+    }
+
     pub fn get_caret_rect(&mut self) -> Option<base::Rect> {
-        self.get_char_rect(self.caret)
+        self.get_caret_rect_at(self.caret)
+    }
+
+    /// Bounds of the char at `coord` with `x` moved to where the caret is painted, which is
+    /// the right edge of right-to-left chars
+    pub fn get_caret_rect_at(&mut self, coord: TextCoord) -> Option<base::Rect> {
+        let mut rect = self.get_char_rect(coord)?;
+        let line = self.lines.get(coord.0)?;
+        let layout = line.sk_paragraph.layout.as_ref()?;
+        if layout.is_char_rtl(coord.1) {
+            rect.x += rect.width;
+        }
+        Some(rect)
     }
 
     pub fn get_char_rect(&mut self, coord: TextCoord) -> Option<crate::base::Rect> {
@@ -465,8 +868,14 @@ impl TextBox {
                     event.offset_x + scroll_x,
                     event.offset_y + scroll_y,
                 ));
-                self.update_caret(begin_coord);
-                self.selection_start(begin_coord);
+                if event.click_count == 2 {
+                    self.select_word_at(begin_coord);
+                } else if event.click_count >= 3 {
+                    self.select_line(begin_coord.0);
+                } else {
+                    self.update_caret(begin_coord);
+                    self.selection_start(begin_coord);
+                }
                 return true;
             }
         } else if let Some(e) = MouseMoveEvent::cast(event) {
@@ -484,9 +893,14 @@ impl TextBox {
                 return self.selection_end();
             }
         } else if let Some(e) = ClickEvent::cast(event) {
-            let caret = self
-                .get_text_coord_by_pixel_coord((e.0.offset_x + scroll_x, e.0.offset_y + scroll_y));
-            self.update_caret(caret);
+            // The caret is placed at the end of selected word or line by multi-click
+            if e.0.click_count <= 1 {
+                let caret = self.get_text_coord_by_pixel_coord((
+                    e.0.offset_x + scroll_x,
+                    e.0.offset_y + scroll_y,
+                ));
+                self.update_caret(caret);
+            }
         }
         false
     }
@@ -519,6 +933,20 @@ impl TextBox {
         Some(self.lines.get(row)?.get_text())
     }
 
+    /// Returns elements of line `row` between atom offsets `start` and `end`
+    pub fn get_line_elements(
+        &self,
+        row: usize,
+        start: usize,
+        end: usize,
+    ) -> Option<Vec<TextElement>> {
+        Some(self.lines.get(row)?.sub_elements(start, end))
+    }
+
+    pub fn get_line_atom_count(&self, row: usize) -> Option<usize> {
+        Some(self.lines.get(row)?.atom_count())
+    }
+
     pub fn get_selection(&self) -> Option<(TextCoord, TextCoord)> {
         self.selection
     }
@@ -555,39 +983,191 @@ impl TextBox {
         }
     }
 
-    pub fn move_caret(&mut self, mut delta: isize) {
-        let mut row = self.caret.0;
-        let mut col = self.caret.1 as isize;
-        loop {
-            let lines = self.get_lines();
-            let line = match lines.get(row) {
-                None => return,
-                Some(ln) => ln,
-            };
-            let atom_count = line.atom_count() as isize;
-            col += delta;
-            if col > atom_count {
-                delta -= col - atom_count;
-                row += 1;
-                col = 0;
-                continue;
-            } else if col < 0 {
-                if row == 0 {
-                    return;
-                }
-                delta += -col;
-                row -= 1;
-                let prev_line = lines.get(row);
-                col = prev_line.unwrap().atom_count() as isize;
-                continue;
-            } else {
-                let new_caret = (row, col as usize);
-                self.update_caret_value(TextCoord::new(new_caret), false);
-                break;
+    /// Move the caret by `delta` grapheme clusters in logical order, across lines
+    pub fn move_caret(&mut self, delta: isize) {
+        let mut caret = self.caret;
+        for _ in 0..delta.unsigned_abs() {
+            caret = some_or_break!(self.next_caret(caret, delta > 0));
+        }
+        self.update_caret_value(caret, false);
+    }
+
+    /// Move the caret to the adjacent grapheme cluster on the right if `right` is true,
+    /// clusters of RTL runs are passed in visual order
+    pub fn move_caret_visually(&mut self, right: bool) {
+        let TextCoord(row, col) = self.caret;
+        let line = some_or_return!(self.lines.get(row));
+        let text = line.get_text();
+        let rtl = self.params.direction == Direction::RTL;
+        let line_starts = line.sk_paragraph.get_line_start_offsets();
+        let stops = visual_caret_stops(&text, rtl, &line_starts);
+        let index = stops.iter().position(|s| *s == col);
+        let next_index = match index {
+            Some(i) if right => Some(i + 1),
+            Some(i) => i.checked_sub(1),
+            None => None,
+        };
+        let new_caret = match next_index.and_then(|i| stops.get(i)) {
+            Some(c) => TextCoord(row, *c),
+            None => {
+                // The following line is on the right of LTR paragraphs
+                let forward = right != rtl;
+                let edge = if forward { line.atom_count() } else { 0 };
+                some_or_return!(self.next_caret(TextCoord(row, edge), forward))
+            }
+        };
+        self.update_caret_value(new_caret, false);
+    }
+
+    /// The caret after moving one grapheme cluster forward or backward from `caret`
+    fn next_caret(&self, caret: TextCoord, forward: bool) -> Option<TextCoord> {
+        let TextCoord(row, col) = caret;
+        let boundaries = grapheme_boundaries(&self.get_line_text(row)?);
+        if forward {
+            match boundaries.iter().find(|b| **b > col) {
+                Some(b) => Some(TextCoord(row, *b)),
+                None if row + 1 < self.lines.len() => Some(TextCoord(row + 1, 0)),
+                None => None,
+            }
+        } else {
+            match boundaries.iter().rev().find(|b| **b < col) {
+                Some(b) => Some(TextCoord(row, *b)),
+                None if row > 0 => Some(TextCoord(row - 1, self.lines[row - 1].atom_count())),
+                None => None,
             }
         }
     }
 
+    /// Move the caret by navigation keys, the selection is extended from the previous caret
+    /// if shift is pressed. Returns false if the key is not a navigation key.
+    pub fn on_navigation_key(&mut self, key: &NamedKey, modifiers: u32) -> bool {
+        let select = modifiers & KEY_MOD_SHIFT != 0;
+        let motion = match (key, modifiers & !KEY_MOD_SHIFT) {
+            (NamedKey::ArrowLeft, 0) => CaretMotion::Horizontal(false),
+            (NamedKey::ArrowRight, 0) => CaretMotion::Horizontal(true),
+            (NamedKey::ArrowUp, 0) => CaretMotion::Vertical(true),
+            (NamedKey::ArrowDown, 0) => CaretMotion::Vertical(false),
+            (NamedKey::ArrowLeft, WORD_NAVIGATION_MOD) => CaretMotion::Word(false),
+            (NamedKey::ArrowRight, WORD_NAVIGATION_MOD) => CaretMotion::Word(true),
+            (NamedKey::Home, 0) => CaretMotion::LineEdge(false),
+            (NamedKey::End, 0) => CaretMotion::LineEdge(true),
+            (NamedKey::Home, TEXT_NAVIGATION_MOD) => CaretMotion::TextEdge(false),
+            (NamedKey::End, TEXT_NAVIGATION_MOD) => CaretMotion::TextEdge(true),
+            #[cfg(target_os = "macos")]
+            (NamedKey::ArrowLeft, crate::event::KEY_MOD_META) => CaretMotion::LineEdge(false),
+            #[cfg(target_os = "macos")]
+            (NamedKey::ArrowRight, crate::event::KEY_MOD_META) => CaretMotion::LineEdge(true),
+            #[cfg(target_os = "macos")]
+            (NamedKey::ArrowUp, crate::event::KEY_MOD_META) => CaretMotion::TextEdge(false),
+            #[cfg(target_os = "macos")]
+            (NamedKey::ArrowDown, crate::event::KEY_MOD_META) => CaretMotion::TextEdge(true),
+            _ => return false,
+        };
+        let old_caret = self.caret;
+        let anchor = match self.selection {
+            Some((start, end)) if end == old_caret => start,
+            Some((start, end)) if start == old_caret => end,
+            _ => old_caret,
+        };
+        match motion {
+            CaretMotion::Horizontal(right) => self.move_caret_visually(right),
+            CaretMotion::Vertical(is_up) => self.move_caret_vertical(is_up),
+            CaretMotion::Word(forward) => self.move_caret_by_word(forward),
+            CaretMotion::LineEdge(to_end) => self.move_caret_to_line_edge(to_end),
+            CaretMotion::TextEdge(to_end) => self.move_caret_to_text_edge(to_end),
+        }
+        let caret = self.caret;
+        if select && anchor != caret {
+            self.select(TextCoord::min(anchor, caret), TextCoord::max(anchor, caret));
+        } else if self.selection.is_some() {
+            self.unselect();
+        }
+        true
+    }
+
+    /// Move the caret to the end of the next word or the start of the previous word, words are
+    /// separated by Unicode word boundaries
+    pub fn move_caret_by_word(&mut self, forward: bool) {
+        let TextCoord(row, col) = self.caret;
+        let text = some_or_return!(self.get_line_text(row));
+        let atom_count = text.chars_count();
+        let segments = word_segments(&text);
+        let new_caret = if forward {
+            match segments
+                .iter()
+                .find(|(_, end, is_word)| *is_word && *end > col)
+            {
+                Some((_, end, _)) => TextCoord(row, *end),
+                None if col == atom_count && row + 1 < self.lines.len() => TextCoord(row + 1, 0),
+                None => TextCoord(row, atom_count),
+            }
+        } else {
+            match segments
+                .iter()
+                .rev()
+                .find(|(start, _, is_word)| *is_word && *start < col)
+            {
+                Some((start, _, _)) => TextCoord(row, *start),
+                None if col == 0 && row > 0 => TextCoord(row - 1, self.lines[row - 1].atom_count()),
+                None => TextCoord(row, 0),
+            }
+        };
+        self.update_caret_value(new_caret, false);
+    }
+
+    /// Move the caret to the start or end of the visual line, the end of a wrapped line is
+    /// before its last char, which is usually the space where the line is broken
+    pub fn move_caret_to_line_edge(&mut self, to_end: bool) {
+        let TextCoord(row, col) = self.caret;
+        let line = some_or_return!(self.lines.get(row));
+        let starts = line.sk_paragraph.get_line_start_offsets();
+        let index = starts.iter().rposition(|s| *s <= col).unwrap_or(0);
+        let start = starts.get(index).copied().unwrap_or(0);
+        let new_col = if !to_end {
+            start
+        } else if let Some(next_start) = starts.get(index + 1) {
+            usize::max(start, next_start - 1)
+        } else {
+            line.atom_count()
+        };
+        self.update_caret_value(TextCoord(row, new_col), false);
+    }
+
+    pub fn move_caret_to_text_edge(&mut self, to_end: bool) {
+        let new_caret = if to_end {
+            self.get_end_text_coord()
+        } else {
+            TextCoord(0, 0)
+        };
+        self.update_caret_value(new_caret, false);
+    }
+
+    /// Select the word or the spaces at `coord`, the caret is moved to the end of selection
+    pub fn select_word_at(&mut self, coord: TextCoord) {
+        let text = some_or_return!(self.get_line_text(coord.0));
+        let segments = word_segments(&text);
+        let segment = segments
+            .iter()
+            .find(|(start, end, _)| *start <= coord.1 && coord.1 < *end)
+            .or(segments.last());
+        let (start, end) = match segment {
+            Some((start, end, _)) => (TextCoord(coord.0, *start), TextCoord(coord.0, *end)),
+            None => (coord, coord),
+        };
+        self.end_select();
+        self.select(start, end);
+        self.update_caret_value(end, false);
+    }
+
+    /// Select the whole line `row`, the caret is moved to the end of line
+    pub fn select_line(&mut self, row: usize) {
+        let atom_count = some_or_return!(self.get_line_atom_count(row));
+        let end = TextCoord(row, atom_count);
+        self.end_select();
+        self.select(TextCoord(row, 0), end);
+        self.update_caret_value(end, false);
+    }
+
     pub fn move_caret_vertical(&mut self, is_up: bool) {
         let caret = self.caret;
         let (current_row, current_col) = (self.caret.0, self.caret.1);
@@ -595,7 +1175,7 @@ impl TextBox {
             None => return,
             Some(height) => height,
         };
-        let caret_coord = match self.get_char_rect(caret) {
+        let caret_coord = match self.get_caret_rect_at(caret) {
             None => return,
             Some(rect) => rect,
         };
@@ -640,6 +1220,39 @@ impl TextBox {
         result.unwrap_or(default)
     }
 
+    fn build_inline_object(
+        width: f32,
+        height: f32,
+        vertical_align: &Option<String>,
+        baseline_offset: Option<f32>,
+        image: Option<ImageObject>,
+    ) -> InlineObject {
+        let align = vertical_align
+            .as_ref()
+            .and_then(|v| InlineObjectAlign::parse(v))
+            .unwrap_or(InlineObjectAlign::Baseline);
+        InlineObject {
+            width,
+            height,
+            align,
+            baseline_offset: baseline_offset.unwrap_or(0.0),
+            image,
+        }
+    }
+
+    /// Style of text without unit styles, inline objects are aligned with it
+    fn build_paragraph_text_style(paragraph_params: &ParagraphParams) -> TextStyle {
+        let mut text_style = TextStyle::new();
+        text_style.set_font_size(paragraph_params.font_size);
+        let font_style = skia_safe::FontStyle::new(
+            paragraph_params.font_weight,
+            Width::NORMAL,
+            paragraph_params.font_style.to_slant(),
+        );
+        text_style.set_font_style(font_style);
+        text_style
+    }
+
     pub fn build_paragraph(
         paragraph_params: &ParagraphParams,
         units: &Vec<TextElement>,
@@ -650,6 +1263,7 @@ impl TextBox {
         let mut pb = SimpleParagraphBuilder::new(paragraph_params);
         let p_color = paragraph_params.color;
         let mask_char = paragraph_params.mask_char;
+        let mut text_state = TextProcessState::new();
         for u in units {
             match u {
                 TextElement::Text(unit) => {
@@ -662,8 +1276,22 @@ impl TextBox {
                         None => FontFamilies::default(),
                     };
                     text_style.set_font_families(Some(unit_font_families));
-                    let font_size = unit.font_size.unwrap_or(paragraph_params.font_size);
+                    let parent_font_size = paragraph_params.font_size;
+                    let (default_font_size, baseline_shift) =
+                        match unit.vertical_align.as_ref().map(|v| v.as_str()) {
+                            Some("super") => (parent_font_size * 0.7, -parent_font_size * 0.4),
+                            Some("sub") => (parent_font_size * 0.7, parent_font_size * 0.2),
+                            _ => (parent_font_size, 0.0),
+                        };
+                    let font_size = unit.font_size.unwrap_or(default_font_size);
                     text_style.set_font_size(font_size);
+                    text_style.set_baseline_shift(baseline_shift);
+                    text_style.set_letter_spacing(
+                        unit.letter_spacing
+                            .unwrap_or(paragraph_params.letter_spacing),
+                    );
+                    text_style.set_word_spacing(unit.word_spacing.unwrap_or(0.0));
+                    text_style.set_link(unit.link.clone());
 
                     let weight = parse_optional_weight(unit.weight.as_ref())
                         .unwrap_or(paragraph_params.font_weight);
@@ -677,6 +1305,15 @@ impl TextBox {
                     let decoration =
                         parse_optional_text_decoration(unit.text_decoration_line.as_ref());
                     text_style.set_decoration_type(decoration);
+                    if let Some(style) = &unit.text_decoration_style {
+                        text_style.set_decoration_style(
+                            TextDecorationStyle::parse(style).unwrap_or_default(),
+                        );
+                    }
+                    text_style.set_decoration_color(parse_optional_color_str(
+                        unit.text_decoration_color.as_ref(),
+                    ));
+                    text_style.set_decoration_thickness(unit.text_decoration_thickness);
 
                     let color = parse_optional_color_str(unit.color.as_ref()).unwrap_or(p_color);
                     let mut paint = Paint::default();
@@ -694,9 +1331,33 @@ impl TextBox {
                         let mask_str = mc.to_string().repeat(unit.text.chars_count());
                         pb.add_text(&mask_str);
                     } else {
-                        pb.add_text(&unit.text);
+                        pb.add_text(text_state.process(paragraph_params, &unit.text));
                     }
                 }
+                TextElement::Image(unit) => {
+                    let object = Self::build_inline_object(
+                        unit.width,
+                        unit.height,
+                        &unit.vertical_align,
+                        unit.baseline_offset,
+                        Some(ImageObject::new(&unit.src)),
+                    );
+                    pb.push_style(&Self::build_paragraph_text_style(paragraph_params));
+                    pb.add_inline_object(object);
+                    text_state.add_atom();
+                }
+                TextElement::Placeholder(unit) => {
+                    let object = Self::build_inline_object(
+                        unit.width,
+                        unit.height,
+                        &unit.vertical_align,
+                        unit.baseline_offset,
+                        None,
+                    );
+                    pb.push_style(&Self::build_paragraph_text_style(paragraph_params));
+                    pb.add_inline_object(object);
+                    text_state.add_atom();
+                }
             }
         }
         pb.add_text(ZERO_WIDTH_WHITESPACE);
@@ -717,6 +1378,12 @@ impl TextBox {
             font_weight: Weight::NORMAL,
             font_style: FontStyle::Normal,
             mask_char: None,
+            white_space: WhiteSpace::PreWrap,
+            word_break: WordBreak::Normal,
+            text_overflow: TextOverflow::Clip,
+            letter_spacing: 0.0,
+            text_transform: TextTransform::None,
+            direction: Direction::LTR,
         };
 
         let mut selection_bg = Paint::default();
@@ -732,6 +1399,11 @@ impl TextBox {
             selection_fg,
             width: f32::NAN,
             padding: (0.0, 0.0, 0.0, 0.0),
+            text_shadow: Vec::new(),
+            decorations: Vec::new(),
+            find_state: None,
+            find_outdated: false,
+            max_lines: None,
             caret: TextCoord(0, 0),
             vertical_caret_moving_coord_x: 0.0,
             repaint_callback: Box::new(|| {}),
@@ -749,14 +1421,19 @@ impl TextBox {
     }
 
     pub fn render(&mut self) -> RenderFn {
+        if self.find_outdated {
+            self.refresh_find();
+        }
         let mut consumed_top = 0.0;
         let mut consumed_rows = 0usize;
 
         let selection = self.selection;
         let selection_bg = self.selection_bg.clone();
         let selection_fg = self.selection_fg.clone();
+        let text_color = self.params.color;
 
         let mut line_painters = Vec::with_capacity(self.lines.len());
+        let mut ln_char_offset = 0;
         for ln in &mut self.lines {
             let ln_row = consumed_rows;
             consumed_rows += 1;
@@ -766,10 +1443,23 @@ impl TextBox {
             consumed_top += ln_height;
             let ln_bottom = consumed_top;
             let atom_count = ln.atom_count();
+            let ln_start = ln_char_offset;
+            ln_char_offset += atom_count + 1;
             let ln_layout = some_or_continue!(ln.sk_paragraph.layout.clone());
+            let decorations = self
+                .decorations
+                .iter()
+                .filter(|d| d.start < ln_start + atom_count && d.end > ln_start)
+                .map(|d| {
+                    let start = usize::max(d.start, ln_start) - ln_start;
+                    let end = usize::min(d.end, ln_start + atom_count) - ln_start;
+                    (d.clone(), ln_layout.get_range_rects(start, end))
+                })
+                .collect::<Vec<_>>();
 
             let selection_bg = selection_bg.clone();
             let selection_fg = selection_fg.clone();
+            let text_shadow = self.text_shadow.clone();
             let ln_renderer = move |painter: &Painter| {
                 let clip_rect = painter.canvas.local_clip_bounds();
                 if let Some(cp) = clip_rect {
@@ -779,7 +1469,30 @@ impl TextBox {
                         return false;
                     }
                 }
+                let canvas = painter.canvas;
+                canvas.save();
+                canvas.translate((0.0, ln_top));
+                for (d, rects) in decorations.iter().filter(|(d, _)| d.is_background()) {
+                    d.paint(painter, rects, text_color);
+                }
+                canvas.restore();
+                // The first shadow is on the top
+                for s in text_shadow.iter().rev() {
+                    ln_layout.paint_shadow(
+                        painter,
+                        (0.0, ln_top).into(),
+                        (s.offset_x, s.offset_y),
+                        s.blur_sigma(),
+                        s.color_or(text_color),
+                    );
+                }
                 ln_layout.paint(painter, (0.0, ln_top).into());
+                canvas.save();
+                canvas.translate((0.0, ln_top));
+                for (d, rects) in decorations.iter().filter(|(d, _)| !d.is_background()) {
+                    d.paint(painter, rects, text_color);
+                }
+                canvas.restore();
 
                 if atom_count > 0 {
                     if let Some(selection_range) = selection {
@@ -838,6 +1551,7 @@ impl TextBox {
     }
 
     fn request_layout(&mut self) {
+        self.find_outdated = self.find_state.is_some();
         (self.layout_callback)();
     }
 
@@ -846,16 +1560,122 @@ impl TextBox {
     }
 }
 
+/// Char ranges of the segments between Unicode word boundaries, the flag is true for words
+/// and false for spaces and punctuations
+fn word_segments(text: &str) -> Vec<(usize, usize, bool)> {
+    let mut segments = Vec::new();
+    let mut offset = 0;
+    for segment in text.split_word_bounds() {
+        let char_count = segment.chars_count();
+        let is_word = segment.chars().any(|c| c.is_alphanumeric());
+        segments.push((offset, offset + char_count, is_word));
+        offset += char_count;
+    }
+    segments
+}
+
+/// Char offsets of the grapheme cluster boundaries in `text`, including 0 and the end
+fn grapheme_boundaries(text: &str) -> Vec<usize> {
+    let mut boundaries = vec![0];
+    let mut offset = 0;
+    for grapheme in text.graphemes(true) {
+        offset += grapheme.chars_count();
+        boundaries.push(offset);
+    }
+    boundaries
+}
+
+/// Caret offsets of a paragraph from left to right, `line_starts` are the char offsets of its
+/// visual lines. Offsets are ordered by where the caret is painted, which is the leading edge of
+/// the cluster after the offset, and offsets painted at the same position are only listed once.
+fn visual_caret_stops(text: &str, rtl: bool, line_starts: &[usize]) -> Vec<usize> {
+    let base_level = if rtl { Level::rtl() } else { Level::ltr() };
+    let bidi_info = BidiInfo::new(text, Some(base_level));
+    let para = match bidi_info.paragraphs.first() {
+        Some(p) => p,
+        None => return vec![0],
+    };
+    let mut byte_offsets = text.char_indices().map(|(i, _)| i).collect::<Vec<_>>();
+    byte_offsets.push(text.len());
+    let char_count = byte_offsets.len() - 1;
+    let boundaries = grapheme_boundaries(text);
+    let line_starts = if line_starts.is_empty() {
+        &[0][..]
+    } else {
+        line_starts
+    };
+
+    let mut lines = Vec::new();
+    for (i, start) in line_starts.iter().enumerate() {
+        let end = line_starts.get(i + 1).copied().unwrap_or(char_count);
+        lines.push((usize::min(*start, char_count), usize::min(end, char_count)));
+    }
+    // Lines of RTL paragraphs are continued on the left
+    if rtl {
+        lines.reverse();
+    }
+    let mut stops = Vec::new();
+    for (start, end) in lines {
+        let line = byte_offsets[start].max(para.range.start)..byte_offsets[end].min(para.range.end);
+        if line.is_empty() {
+            continue;
+        }
+        let (levels, runs) = bidi_info.visual_runs(para, line);
+        // Offsets with the x position of the caret, counted in clusters from the left of line
+        let mut line_stops = Vec::new();
+        let mut left = 0;
+        for run in runs {
+            let run_start = byte_offsets.partition_point(|b| *b < run.start);
+            let run_end = byte_offsets.partition_point(|b| *b < run.end);
+            let run_bounds = boundaries
+                .iter()
+                .copied()
+                .filter(|b| run_start <= *b && *b <= run_end)
+                .collect::<Vec<_>>();
+            let clusters = run_bounds.len().saturating_sub(1);
+            let rtl_run = levels[run.start].is_rtl();
+            for (i, b) in run_bounds.into_iter().enumerate() {
+                // The end of a run is the start of another one, except at the end of text where
+                // the caret is at the trailing edge of the last cluster
+                if i == clusters && b != char_count {
+                    continue;
+                }
+                let x = if rtl_run {
+                    left + clusters - i
+                } else {
+                    left + i
+                };
+                line_stops.push((x, b));
+            }
+            left += clusters;
+        }
+        line_stops.sort_by_key(|(x, _)| *x);
+        line_stops.dedup_by_key(|(x, _)| *x);
+        stops.extend(line_stops.into_iter().map(|(_, b)| b));
+    }
+    if stops.is_empty() {
+        stops.push(0);
+    }
+    stops
+}
+
 #[cfg(test)]
 mod tests {
     use crate::element::common::editable::Editable;
     use crate::element::paragraph::ParagraphParams;
+    use crate::event::KEY_MOD_SHIFT;
     use crate::font::family::{FontFamilies, FontFamily};
     use crate::style::font::FontStyle;
-    use crate::text::textbox::{TextBox, TextElement, TextUnit};
+    use crate::style::text::{LineClamp, TextOverflow, TextTransform, WhiteSpace, WordBreak};
+    use crate::text::textbox::find::FindOptions;
+    use crate::text::textbox::{
+        append_elements, PlaceholderUnit, TextBox, TextCoord, TextElement, TextUnit,
+    };
     use crate::text::TextAlign;
     use measure_time::print_time;
     use skia_safe::font_style::Weight;
+    use winit::keyboard::NamedKey;
+    use yoga::Direction;
 
     // #[test]
     fn text_text_layout() {
@@ -865,6 +1685,69 @@ mod tests {
         assert_ne!(0.0, text.max_intrinsic_width());
     }
 
+    fn text_element(text: &str) -> TextElement {
+        TextElement::Text(text_unit(text))
+    }
+
+    fn text_unit(text: &str) -> TextUnit {
+        TextUnit {
+            text: text.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_inline_placeholder() {
+        let placeholder = TextElement::Placeholder(PlaceholderUnit {
+            width: 30.0,
+            height: 40.0,
+            vertical_align: None,
+            baseline_offset: None,
+        });
+        let mut text = TextBox::new();
+        text.add_line(vec![text_element("ab"), placeholder, text_element("c")]);
+        text.layout();
+        assert_eq!(4, text.get_atom_count());
+        assert_eq!("ab\u{FFFC}c", text.get_text());
+        assert!(text.height() >= 40.0);
+        let rect = text.get_char_rect(TextCoord(0, 2)).unwrap();
+        assert_eq!(30.0, rect.width);
+        assert_eq!(40.0, rect.height);
+
+        let mut elements = text.get_line_elements(0, 1, 4).unwrap();
+        assert_eq!(3, elements.len());
+        append_elements(&mut elements, vec![text_element("d")]);
+        assert_eq!(3, elements.len());
+        let text = elements.iter().map(|e| e.text()).collect::<String>();
+        assert_eq!("b\u{FFFC}cd", text);
+    }
+
+    #[test]
+    fn test_spacing_and_link() {
+        let mut text = TextBox::new();
+        text.add_line(vec![text_element("a b")]);
+        text.layout();
+        let width = text.max_intrinsic_width();
+
+        let mut spaced = TextBox::new();
+        let mut unit = text_unit("a b");
+        unit.letter_spacing = Some(2.0);
+        unit.word_spacing = Some(4.0);
+        unit.link = Some("https://example.com".to_string());
+        spaced.add_line(vec![TextElement::Text(unit), text_element("c")]);
+        spaced.layout();
+        let c_width = spaced.get_char_rect(TextCoord(0, 3)).unwrap().width;
+        assert!((spaced.max_intrinsic_width() - c_width - width - 10.0).abs() < 0.01);
+
+        let rect = spaced.get_char_rect(TextCoord(0, 0)).unwrap();
+        let link = spaced.get_link_by_pixel_coord((rect.x + 1.0, rect.y + 1.0));
+        assert_eq!(Some("https://example.com".to_string()), link);
+        let rect = spaced.get_char_rect(TextCoord(0, 3)).unwrap();
+        assert!(spaced
+            .get_link_by_pixel_coord((rect.x + 1.0, rect.y + 1.0))
+            .is_none());
+    }
+
     #[test]
     fn test_measure() {
         let text_demo = include_str!("../../Cargo.lock");
@@ -878,6 +1761,120 @@ mod tests {
         // let result = font.measure_text(text.as_str(), None);
     }
 
+    #[test]
+    fn test_find() {
+        let mut text = TextBox::new();
+        text.add_line(vec![text_element("Error: disk")]);
+        text.add_line(vec![text_element("errors, error_code error")]);
+        text.layout();
+        let options = FindOptions {
+            whole_word: true,
+            ..Default::default()
+        };
+        let matches = text.find("error", options);
+        assert_eq!(
+            vec![
+                (TextCoord(0, 0), TextCoord(0, 5)),
+                (TextCoord(1, 19), TextCoord(1, 24))
+            ],
+            matches
+        );
+        assert_eq!(2, text.get_decorations().len());
+
+        text.add_line(vec![text_element("error")]);
+        text.layout();
+        text.render();
+        assert_eq!(3, text.get_find_matches().len());
+        assert!(text.set_active_match(2).is_some());
+
+        text.clear_find();
+        assert!(text.get_decorations().is_empty());
+    }
+
+    #[test]
+    fn test_grapheme_navigation() {
+        let mut text = TextBox::new();
+        text.add_line(vec![text_element("e\u{301}x")]);
+        text.add_line(vec![text_element("नमस्ते")]);
+        text.layout();
+        text.move_caret(1);
+        assert_eq!(TextCoord(0, 2), text.get_caret());
+        text.move_caret(-1);
+        assert_eq!(TextCoord(0, 0), text.get_caret());
+
+        // The conjunct "स्ते" is a single cluster
+        text.update_caret_value(TextCoord(1, 0), false);
+        let carets = (0..3)
+            .map(|_| {
+                text.on_navigation_key(&NamedKey::ArrowRight, 0);
+                text.get_caret()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![TextCoord(1, 1), TextCoord(1, 2), TextCoord(1, 6)],
+            carets
+        );
+        text.on_navigation_key(&NamedKey::ArrowLeft, 0);
+        assert_eq!(TextCoord(1, 2), text.get_caret());
+    }
+
+    #[test]
+    fn test_rtl_navigation() {
+        let mut text = TextBox::new();
+        text.add_line(vec![text_element("ab אבג")]);
+        text.layout();
+        text.update_caret_value(TextCoord(0, 2), false);
+        let mut press = |key: NamedKey| {
+            text.on_navigation_key(&key, 0);
+            text.get_caret()
+        };
+        // The end of line is painted at the left edge of the RTL run, the start of the run at
+        // its right edge
+        assert_eq!(TextCoord(0, 6), press(NamedKey::ArrowRight));
+        assert_eq!(TextCoord(0, 5), press(NamedKey::ArrowRight));
+        assert_eq!(TextCoord(0, 4), press(NamedKey::ArrowRight));
+        assert_eq!(TextCoord(0, 3), press(NamedKey::ArrowRight));
+        assert_eq!(TextCoord(0, 3), press(NamedKey::ArrowRight));
+        assert_eq!(TextCoord(0, 4), press(NamedKey::ArrowLeft));
+        assert_eq!(TextCoord(0, 5), press(NamedKey::ArrowLeft));
+        assert_eq!(TextCoord(0, 6), press(NamedKey::ArrowLeft));
+        assert_eq!(TextCoord(0, 2), press(NamedKey::ArrowLeft));
+    }
+
+    #[test]
+    fn test_word_navigation() {
+        let mut text = TextBox::new();
+        text.add_line(vec![text_element("hello, world")]);
+        text.add_line(vec![text_element("foo")]);
+        text.layout();
+        let ctrl_right = |text: &mut TextBox| {
+            text.move_caret_by_word(true);
+            text.get_caret()
+        };
+        assert_eq!(TextCoord(0, 5), ctrl_right(&mut text));
+        assert_eq!(TextCoord(0, 12), ctrl_right(&mut text));
+        assert_eq!(TextCoord(1, 0), ctrl_right(&mut text));
+        text.move_caret_by_word(false);
+        assert_eq!(TextCoord(0, 12), text.get_caret());
+        text.move_caret_by_word(false);
+        assert_eq!(TextCoord(0, 7), text.get_caret());
+
+        text.move_caret_to_line_edge(false);
+        assert_eq!(TextCoord(0, 0), text.get_caret());
+        text.on_navigation_key(&NamedKey::End, KEY_MOD_SHIFT);
+        assert_eq!(
+            Some((TextCoord(0, 0), TextCoord(0, 12))),
+            text.get_selection()
+        );
+        text.move_caret_to_text_edge(true);
+        assert_eq!(TextCoord(1, 3), text.get_caret());
+
+        text.select_word_at(TextCoord(0, 8));
+        assert_eq!(Some("world".to_string()), text.get_selection_text());
+        text.select_line(1);
+        assert_eq!(Some("foo".to_string()), text.get_selection_text());
+    }
+
     #[cfg(test)]
     fn test_layout_performance() {
         let text_demo = include_str!("../../Cargo.lock");
@@ -891,6 +1888,12 @@ mod tests {
             font_style: FontStyle::Normal,
             text_wrap: Some(false),
             mask_char: None,
+            white_space: WhiteSpace::Normal,
+            word_break: WordBreak::Normal,
+            text_overflow: TextOverflow::Clip,
+            letter_spacing: 0.0,
+            text_transform: TextTransform::None,
+            direction: Direction::LTR,
         };
         let mut text = String::new();
         for _ in 0..200 {
@@ -902,15 +1905,57 @@ mod tests {
         print_time!("build paragraph time");
         let unit = TextElement::Text(TextUnit {
             text: text.clone(),
-            font_families: None,
-            font_size: None,
-            color: None,
-            text_decoration_line: None,
-            weight: None,
-            background_color: None,
-            style: None,
+            ..Default::default()
         });
         let mut p = TextBox::build_paragraph(&params, &vec![unit]);
         p.layout(600.0);
     }
+
+    #[test]
+    fn test_text_layout_props() {
+        let measure = |text: &str, transform: TextTransform| {
+            let mut text_box = TextBox::new();
+            text_box.set_white_space(WhiteSpace::Normal);
+            text_box.set_text_transform(transform);
+            text_box.add_line(vec![text_element(text)]);
+            text_box.layout();
+            text_box.max_intrinsic_width()
+        };
+        let word_width = measure("aaa", TextTransform::None);
+        assert_eq!(
+            measure("a b", TextTransform::None),
+            measure("a   b", TextTransform::None)
+        );
+        assert_eq!(
+            measure("AB", TextTransform::None),
+            measure("ab", TextTransform::Uppercase)
+        );
+        // Spaces are kept unless white-space is normal
+        let mut preserved = TextBox::new();
+        preserved.add_line(vec![text_element("a   b")]);
+        preserved.layout();
+        assert!(preserved.max_intrinsic_width() > measure("a b", TextTransform::None));
+
+        let mut text = TextBox::new();
+        text.add_line(vec![text_element("aaa bbb")]);
+        text.set_layout_width(word_width + 1.0);
+        text.layout();
+        let second_word = text.get_char_rect(TextCoord(0, 4)).unwrap();
+        assert_eq!(0.0, second_word.x);
+        assert!(second_word.y > 0.0);
+        let height = text.height();
+
+        text.set_line_clamp(LineClamp::Lines(1));
+        text.layout();
+        assert!(text.height() < height);
+        assert!(text.get_char_rect(TextCoord(0, 4)).is_none());
+
+        let mut centered = TextBox::new();
+        centered.set_align(TextAlign::Center);
+        centered.add_line(vec![text_element("aaa")]);
+        centered.set_layout_width(word_width + 100.0);
+        centered.layout();
+        let rect = centered.get_char_rect(TextCoord(0, 0)).unwrap();
+        assert!((rect.x - 50.0).abs() < 0.01);
+    }
 }
@@ -0,0 +1,98 @@
+use crate::element::text::simple_text_paragraph::draw_wavy_line;
+use crate::paint::Painter;
+use crate::style::color::parse_optional_color_str;
+use crate::{js_deserialize, js_serialize};
+use serde::{Deserialize, Serialize};
+use skia_safe::{Color, Paint, PaintStyle, Rect};
+
+pub const SPELLCHECK_DECORATION_GROUP: &str = "spellcheck";
+/// Underline of the uncommitted composition text of input methods
+pub const PREEDIT_DECORATION_GROUP: &str = "preedit";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum DecorationKind {
+    Underline,
+    /// Background painted under the text
+    Highlight,
+    /// Wavy underline, e.g. for misspelled words
+    Squiggle,
+}
+
+/// Decoration of the chars between `start` and `end`, the line breaks between lines are counted
+/// as one char
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DecorationRange {
+    pub start: usize,
+    pub end: usize,
+    pub kind: DecorationKind,
+    pub color: Option<String>,
+    /// Decorations are removed by group, e.g. `spellcheck` or `search`
+    pub group: Option<String>,
+}
+
+js_serialize!(DecorationRange);
+js_deserialize!(DecorationRange);
+
+impl DecorationRange {
+    pub fn new(start: usize, end: usize, kind: DecorationKind) -> Self {
+        Self {
+            start,
+            end,
+            kind,
+            color: None,
+            group: None,
+        }
+    }
+
+    pub fn with_group(mut self, group: &str) -> Self {
+        self.group = Some(group.to_string());
+        self
+    }
+
+    pub fn is_in_group(&self, group: Option<&str>) -> bool {
+        match group {
+            None => true,
+            Some(g) => self.group.as_deref() == Some(g),
+        }
+    }
+
+    /// Whether the decoration is painted under the text
+    pub fn is_background(&self) -> bool {
+        self.kind == DecorationKind::Highlight
+    }
+
+    /// Paint the decoration over `rects`, which are the bounds of the decorated chars
+    pub fn paint(&self, painter: &Painter, rects: &[Rect], text_color: Color) {
+        let canvas = painter.canvas;
+        let color = parse_optional_color_str(self.color.as_ref());
+        let mut paint = Paint::default();
+        paint.set_anti_alias(true);
+        match self.kind {
+            DecorationKind::Highlight => {
+                paint.set_color(color.unwrap_or(Color::from_argb(110, 255, 214, 0)));
+                for r in rects {
+                    canvas.draw_rect(r, &paint);
+                }
+            }
+            DecorationKind::Underline => {
+                paint.set_style(PaintStyle::Stroke);
+                paint.set_stroke_width(1.0);
+                paint.set_color(color.unwrap_or(text_color));
+                for r in rects {
+                    let y = r.bottom - 0.5;
+                    canvas.draw_line((r.left, y), (r.right, y), &paint);
+                }
+            }
+            DecorationKind::Squiggle => {
+                paint.set_style(PaintStyle::Stroke);
+                paint.set_stroke_width(1.0);
+                paint.set_color(color.unwrap_or(Color::from_rgb(230, 40, 40)));
+                for r in rects {
+                    draw_wavy_line(painter, (r.left, r.right), r.bottom - 1.5, 1.0, &paint);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use crate::text::textbox::decoration::{DecorationKind, DecorationRange};
+use crate::{js_deserialize, js_serialize};
+use serde::{Deserialize, Serialize};
+
+pub const FIND_DECORATION_GROUP: &str = "find";
+
+const DEFAULT_ACTIVE_HIGHLIGHT_COLOR: &str = "#FF9632C0";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FindOptions {
+    pub case_sensitive: bool,
+    /// Only match the query surrounded by non-word chars
+    pub whole_word: bool,
+    pub highlight_color: Option<String>,
+    /// Color of the active match, which is scrolled into view
+    pub active_highlight_color: Option<String>,
+}
+
+js_serialize!(FindOptions);
+js_deserialize!(FindOptions);
+
+/// Matches of the last find, offsets are chars of the whole text
+pub struct FindState {
+    pub query: String,
+    pub matches: Vec<(usize, usize)>,
+    pub options: FindOptions,
+    pub active: Option<usize>,
+}
+
+impl FindState {
+    pub fn build_decorations(&self) -> Vec<DecorationRange> {
+        self.matches
+            .iter()
+            .enumerate()
+            .map(|(i, (start, end))| {
+                let color = if self.active == Some(i) {
+                    let color = self.options.active_highlight_color.as_deref();
+                    Some(color.unwrap_or(DEFAULT_ACTIVE_HIGHLIGHT_COLOR).to_string())
+                } else {
+                    self.options.highlight_color.clone()
+                };
+                DecorationRange {
+                    color,
+                    ..DecorationRange::new(*start, *end, DecorationKind::Highlight)
+                        .with_group(FIND_DECORATION_GROUP)
+                }
+            })
+            .collect()
+    }
+}
+
+/// Char ranges of the non-overlapping occurrences of `query` in `text`
+pub fn find_matches(text: &str, query: &str, options: &FindOptions) -> Vec<(usize, usize)> {
+    let text = text.chars().collect::<Vec<char>>();
+    let query = query.chars().collect::<Vec<char>>();
+    let mut result = Vec::new();
+    if query.is_empty() || query.len() > text.len() {
+        return result;
+    }
+    let char_eq = |a: char, b: char| {
+        a == b || (!options.case_sensitive && a.to_lowercase().eq(b.to_lowercase()))
+    };
+    let mut start = 0;
+    while start + query.len() <= text.len() {
+        let end = start + query.len();
+        let matched = text[start..end]
+            .iter()
+            .zip(&query)
+            .all(|(a, b)| char_eq(*a, *b));
+        if matched && (!options.whole_word || is_whole_word(&text, start, end)) {
+            result.push((start, end));
+            start = end;
+        } else {
+            start += 1;
+        }
+    }
+    result
+}
+
+fn is_whole_word(text: &[char], start: usize, end: usize) -> bool {
+    let starts_word = start == 0 || !is_word_char(text[start - 1]) || !is_word_char(text[start]);
+    let ends_word = end == text.len() || !is_word_char(text[end]) || !is_word_char(text[end - 1]);
+    starts_word && ends_word
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
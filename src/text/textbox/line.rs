@@ -62,13 +62,47 @@ impl Line {
         result.to_string()
     }
 
+    /// Returns elements between atom offsets, text units on the edges are cut
+    pub fn sub_elements(&self, start: ColOffset, end: ColOffset) -> Vec<TextElement> {
+        let mut result = Vec::new();
+        let mut processed_atom_count = 0;
+        for u in &self.units {
+            let unit_atom_count = u.atom_count();
+            if let Some(intersect) = intersect_range(
+                (start, end),
+                (processed_atom_count, unit_atom_count + processed_atom_count),
+            ) {
+                result.push(u.slice(
+                    intersect.0 - processed_atom_count,
+                    intersect.1 - processed_atom_count,
+                ));
+            }
+            processed_atom_count += unit_atom_count;
+            if processed_atom_count >= end {
+                break;
+            }
+        }
+        result
+    }
+
+    /// Returns the link of the text unit containing the atom at `col`
+    pub fn get_link(&self, col: ColOffset) -> Option<String> {
+        let mut processed_atom_count = 0;
+        for u in &self.units {
+            processed_atom_count += u.atom_count();
+            if col < processed_atom_count {
+                return match u {
+                    TextElement::Text(t) => t.link.clone(),
+                    _ => None,
+                };
+            }
+        }
+        None
+    }
+
     pub fn get_column_by_pixel_coord(&self, coord: (f32, f32)) -> usize {
-        let (x, _y) = coord;
-        let atom_count = self.atom_count();
-        if atom_count == 0 {
+        if self.atom_count() == 0 {
             0
-        } else if x > self.sk_paragraph.max_intrinsic_width() {
-            atom_count
         } else {
             self.sk_paragraph.get_char_offset_at_coordinate(coord)
         }
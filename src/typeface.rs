@@ -1,30 +1,70 @@
 use crate as deft;
+use crate::element::paragraph::parse_optional_weight;
+use crate::element::paragraph::simple_paragraph_builder::FONT_MANAGER;
+use crate::font::Font;
 use crate::js_deserialize;
+use crate::resource::Resource;
 use deft_macros::js_func;
+use log::error;
 use serde::{Deserialize, Serialize};
+use skia_safe::font_style::{Slant, Weight, Width};
+use skia_safe::FontStyle;
 
+/// Source of typeface, one of `path`, `data` and `family` is required
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct TypefaceSource {
-    family: String,
+    /// Path of font file, path with `res://` prefix is read from resources
+    path: Option<String>,
+    /// Content of font file
+    data: Option<Vec<u8>>,
+    /// Family of installed fonts
+    family: Option<String>,
     weight: Option<String>,
 }
 js_deserialize!(TypefaceSource);
 
+/// Load font file as `family`, path with `res://` prefix is read from resources
+pub fn load_font_file(path: &str, family: &str) -> Option<Font> {
+    if let Some(key) = path.strip_prefix("res://") {
+        Resource::read(key, |data| {
+            Font::from_bytes(data.clone(), 0, family.to_string())
+        })
+        .flatten()
+    } else {
+        Font::from_file(path, 0, family.to_string())
+    }
+}
+
 #[js_func]
-pub fn typeface_create(_name: String, _source: TypefaceSource) -> bool {
-    //TODO fix
-    false
-    /*
-    TYPEFACES.with_borrow_mut(|m| {
-        let fm = FONT_MGR.with(|fm| fm.clone());
-        let weight = parse_optional_weight(source.weight.as_ref()).unwrap_or(Weight::NORMAL);
-        let mut font_style = FontStyle::new(weight, Width::NORMAL, Upright);
-        if let Some(tf) = fm.match_family_style(&source.family, font_style) {
-            m.register_typeface(tf, Some(name.as_str()));
-            true
-        } else {
-            false
+pub fn typeface_create(name: String, source: TypefaceSource) -> bool {
+    let mut fm = FONT_MANAGER.with(|fm| fm.clone());
+    let weight = parse_optional_weight(source.weight.as_ref());
+    let font = if let Some(data) = source.data {
+        Font::from_bytes(data, 0, name.clone())
+    } else if let Some(path) = &source.path {
+        load_font_file(path, &name)
+    } else if let Some(family) = &source.family {
+        let weight = weight.unwrap_or(Weight::NORMAL);
+        let font_style = FontStyle::new(weight, Width::NORMAL, Slant::Upright);
+        fm.get_by_family_name(family, &font_style)
+    } else {
+        error!(
+            "path, data or family is required to create typeface: {}",
+            name
+        );
+        return false;
+    };
+    let mut font = match font {
+        Some(f) => f,
+        None => {
+            error!("failed to create typeface: {}", name);
+            return false;
         }
-    })
-     */
+    };
+    if let Some(weight) = weight {
+        let style = font.style();
+        font = font.synthesize(swash::Weight(*weight as u16), style);
+    }
+    fm.register_font(&name, font);
+    true
 }
@@ -1,4 +1,9 @@
+pub mod accessibility;
+pub mod command;
+pub mod driver;
+pub mod headless;
 pub mod page;
+pub mod platform_window;
 pub mod popup;
 
 use crate as deft;
@@ -11,12 +16,12 @@ use crate::base::{
 use crate::cursor::search_cursor;
 use crate::element::body::Body;
 use crate::element::util::get_tree_level;
-use crate::element::{Element, ElementBackend, ElementParent};
+use crate::element::{Element, ElementBackend, ElementParent, ElementType, CSS_MANAGER};
 use crate::error::{DeftError, DeftResult};
 use crate::event::{
-    build_modifier, named_key_to_str, str_to_named_key, BlurEvent, ClickEvent, ClickEventListener,
-    ContextMenuEvent, DragOverEvent, DragStartEvent, DropEvent, DroppedFileEvent, FocusEvent,
-    FocusShiftEvent, HoveredFileEvent, KeyDownEvent, KeyEventDetail, KeyUpEvent, MouseDownEvent,
+    build_modifier, named_key_to_str, str_to_named_key, BlurEvent, ClickEvent, ContextMenuEvent,
+    DragOverEvent, DragStartEvent, DropEvent, DroppedFileEvent, FocusEvent, FocusShiftEvent,
+    HoveredFileEvent, ImePreeditEvent, KeyDownEvent, KeyEventDetail, KeyUpEvent, MouseDownEvent,
     MouseEnterEvent, MouseLeaveEvent, MouseMoveEvent, MouseUpEvent, MouseWheelEvent,
     TextInputEvent, TouchCancelEvent, TouchEndEvent, TouchMoveEvent, TouchStartEvent, KEY_MOD_ALT,
     KEY_MOD_CTRL, KEY_MOD_META, KEY_MOD_SHIFT,
@@ -27,33 +32,37 @@ use crate::ext::ext_window::{
 };
 use crate::frame_rate::FrameRateController;
 use crate::js::{BorrowFromJs, FromJsValue, JsError};
-use crate::menu::{build_menu_elements, Menu};
-use crate::mrc::Mrc;
+use crate::menu::menubar::{set_native_menubar, MenuBar};
+use crate::menu::{Menu, MenuView};
 use crate::paint::{PaintContext, Painter, RenderTree};
 use crate::platform::support_multiple_windows;
 use crate::render::painter::ElementPainter;
 use crate::resource_table::ResourceTable;
 use crate::state::{State, StateManager, StateMutRef};
 use crate::style::length::LengthContext;
+use crate::style::media::MediaEnv;
 use crate::style::style_vars::StyleVars;
 use crate::timer::{set_timeout_nanos, TimerHandle};
 use crate::tooltip::Tooltip;
+use crate::window::accessibility::AccessibilityAdapter;
+use crate::window::command::{ChordMatch, CommandRegistry, KeyChord};
+use crate::window::headless::HeadlessWindow;
 use crate::window::page::Page;
+use crate::window::platform_window::PlatformWindow;
 use crate::window::popup::Popup;
 use crate::{
-    base, bind_js_event_listener, ok_or_return, send_app_event, show_focus_hint, some_or_return,
-    warn_time,
+    base, bind_js_event_listener, ok_or_return, send_app_event, show_focus_hint, snapshot,
+    some_or_return, warn_time,
 };
 use anyhow::Error;
 use deft_macros::{js_methods, window_event};
 use log::{debug, error};
 use quick_js::{JsValue, ValueError};
-use skia_safe::{Color, Point};
+use skia_safe::{Color, IRect, Point};
 use skia_window::renderer::Renderer;
 use skia_window::skia_window::{RenderBackendType, SkiaWindow};
 use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
-use std::ops::Deref;
 use std::string::ToString;
 use std::time::SystemTime;
 use std::{env, mem};
@@ -63,8 +72,6 @@ use winit::event::{
     ElementState, Ime, Modifiers, MouseButton, MouseScrollDelta, TouchPhase, WindowEvent,
 };
 use winit::keyboard::{Key, KeyCode, NamedKey, PhysicalKey};
-#[cfg(windows)]
-use winit::platform::windows::WindowExtWindows;
 #[cfg(x11_platform)]
 use winit::platform::x11::WindowAttributesExtX11;
 use winit::window::{
@@ -84,6 +91,11 @@ struct MouseDownInfo {
     window_y: f32,
 }
 
+/// Max interval in milliseconds between clicks of a multi-click
+const MULTI_CLICK_INTERVAL: u128 = 500;
+/// Max distance between clicks of a multi-click
+const MULTI_CLICK_DISTANCE: f32 = 4.0;
+
 struct TouchingInfo {
     start_time: SystemTime,
     times: u32,
@@ -93,6 +105,10 @@ struct TouchingInfo {
     touches: HashMap<u64, Touch>,
 }
 
+fn is_headless_env() -> bool {
+    env::var("DEFT_HEADLESS").unwrap_or("0".to_string()).as_str() != "0"
+}
+
 fn treat_mouse_as_touch() -> bool {
     std::env::var("MOUSE_AS_TOUCH")
         .unwrap_or("0".to_string())
@@ -142,7 +158,7 @@ impl LayerRoot {
 pub struct Window {
     handle: WindowHandle,
     id: i32,
-    pub(crate) window: SkiaWindow,
+    pub(crate) window: PlatformWindow,
     cursor_position: LogicalPosition<f64>,
     pub(crate) window_type: WindowType,
     cursor_root_position: LogicalPosition<f64>,
@@ -151,6 +167,13 @@ pub struct Window {
     focusing: Option<Element>,
     /// (element, button)
     pressing: Option<(Element, MouseDownInfo)>,
+    /// Menu set by [`Window::set_menubar`], either shown natively or by `menubar`
+    menubar_menu: Option<Menu>,
+    /// (menu bar, root of its layer)
+    menubar: Option<(MenuBar, Element)>,
+    commands: CommandRegistry,
+    last_mouse_down: Option<(SystemTime, MouseDownInfo)>,
+    click_count: u32,
     drag_window_called: bool,
     touching: TouchingInfo,
     dragging: bool,
@@ -176,6 +199,9 @@ pub struct Window {
     render_backend_types: Vec<RenderBackendType>,
     /// (ElementId, Tooltip)
     tooltip_instance: Option<(u32, Tooltip)>,
+    accessibility: Option<AccessibilityAdapter>,
+    /// Results of `@media` conditions when styles were selected
+    media_matches: Vec<bool>,
 }
 
 #[derive(Clone, PartialEq)]
@@ -237,14 +263,17 @@ impl Window {
     ) -> Result<WindowHandle, DeftError> {
         let handle = Self::create_inner(attrs, raw_attrs);
         let mut ws = handle.upgrade_mut()?;
-        send_app_event(AppEvent::BindWindow(ws.get_id())).unwrap();
+        ws.post_app_event(AppEvent::BindWindow(ws.get_id()));
         ws.update_inset(InsetType::Ime, Rect::new_empty());
         ws.update_inset(InsetType::Navigation, Rect::new_empty());
         ws.update_inset(InsetType::StatusBar, Rect::new_empty());
+        ws.update_menubar_height();
 
         let winit_window_id = ws.get_window_id();
         let wid = ws.get_id();
-        WINIT_TO_WINDOW.with_borrow_mut(|m| m.insert(winit_window_id, handle.clone()));
+        if !ws.window.is_headless() {
+            WINIT_TO_WINDOW.with_borrow_mut(|m| m.insert(winit_window_id, handle.clone()));
+        }
         WINDOWS.with_borrow_mut(|m| m.insert(wid, handle.clone()));
         Ok(handle)
     }
@@ -330,8 +359,21 @@ impl Window {
         let render_backend_types = RenderBackendType::merge(&user_pf_backends, &env_pf_backends);
         let render_backend_types =
             RenderBackendType::merge(&render_backend_types, &RenderBackendType::all());
-        let window = Self::create_window(attributes.clone(), &render_backend_types);
+        let window = if attrs.headless.unwrap_or(false) || is_headless_env() {
+            PlatformWindow::Headless(HeadlessWindow::new(
+                attributes.title.clone(),
+                attrs.width.unwrap_or(800.0) as u32,
+                attrs.height.unwrap_or(600.0) as u32,
+                1.0,
+            ))
+        } else {
+            PlatformWindow::Native(Self::create_window(
+                attributes.clone(),
+                &render_backend_types,
+            ))
+        };
         // window.set_ime_allowed(true);
+        let accessibility = AccessibilityAdapter::new(&window, id);
         let body = Element::create(Body::create);
         let mut render_tree = HashMap::new();
         render_tree.insert(body.clone(), RenderTree::new(0));
@@ -347,6 +389,11 @@ impl Window {
                 cursor_root_position: LogicalPosition { x: 0.0, y: 0.0 },
                 layer_roots: vec![LayerRoot::new(body, 0.0, 0.0)],
                 pressing: None,
+                menubar_menu: None,
+                menubar: None,
+                commands: CommandRegistry::new(),
+                last_mouse_down: None,
+                click_count: 0,
                 focusing: None,
                 hover: None,
                 modifiers: Modifiers::default(),
@@ -381,6 +428,8 @@ impl Window {
                 layout_dirty_list: HashMap::new(),
                 pages: Vec::new(),
                 tooltip_instance: None,
+                accessibility,
+                media_matches: Vec::new(),
             };
             win_info.on_resize();
             wsm.new_state(win_info)
@@ -414,7 +463,14 @@ impl Window {
     }
 
     pub fn resume(&mut self) {
-        self.window = Self::create_window(self.attributes.clone(), &self.render_backend_types);
+        if self.window.is_headless() {
+            return;
+        }
+        self.window = PlatformWindow::Native(Self::create_window(
+            self.attributes.clone(),
+            &self.render_backend_types,
+        ));
+        self.accessibility = AccessibilityAdapter::new(&self.window, self.id);
         #[cfg(ohos)]
         crate::platform::resume_ime();
     }
@@ -422,10 +478,23 @@ impl Window {
     pub fn notify_update(&mut self) {
         if !self.dirty {
             self.dirty = true;
-            send_app_event(AppEvent::Update(self.get_id())).unwrap();
+            self.post_app_event(AppEvent::Update(self.get_id()));
+        }
+    }
+
+    /// Headless windows may live without an app event loop, e.g. in tests, so sending
+    /// is allowed to fail for them.
+    fn post_app_event(&self, event: AppEvent) {
+        let result = send_app_event(event);
+        if !self.window.is_headless() {
+            result.unwrap();
         }
     }
 
+    pub fn is_headless(&self) -> bool {
+        self.window.is_headless()
+    }
+
     pub fn invalid_layout(&mut self, element: Element) {
         // Note: Uncomment to debug layout problems
         // if self.layout_dirty_list.is_empty() { crate::trace::print_trace("layout dirty") }
@@ -586,21 +655,160 @@ impl Window {
         Popup::new_ex(content, target, &self.handle, focusable)
     }
 
+    /// Show `menu` at the position, the accelerators of its items work while it is open
     #[js_func]
-    pub fn popup_menu(&self, menu: Menu, x: f32, y: f32) {
+    pub fn popup_menu(&mut self, menu: Menu, x: f32, y: f32) {
         let target = Rect::new(x, y, 1.0, 1.0);
-        let mut el = build_menu_elements(menu);
-        let mut popup_holder: Mrc<Option<Popup>> = Mrc::new(None);
-        {
-            let popup_holder = popup_holder.clone();
-            el.register_event_listener(ClickEventListener::new(move |_, _| {
-                if let Some(popup) = popup_holder.deref() {
-                    let _ = popup.close();
-                }
-            }));
+        self.register_menu_shortcuts(menu.clone());
+        let mut view = MenuView::popup(menu.clone(), &self.handle, target);
+        let handle = self.handle.clone();
+        view.set_close_callback(move || {
+            if let Ok(mut window) = handle.upgrade_mut() {
+                window.unregister_menu_shortcuts(menu.clone());
+            }
+        });
+    }
+
+    /// Trigger the items of `menu` by their accelerators even if the menu is not shown, see
+    /// [`CommandRegistry::bind_menu`]
+    #[js_func]
+    pub fn register_menu_shortcuts(&mut self, menu: Menu) {
+        self.commands.bind_menu(&menu);
+    }
+
+    #[js_func]
+    pub fn unregister_menu_shortcuts(&mut self, menu: Menu) {
+        self.commands.unbind_menu(&menu);
+    }
+
+    /// Show `menu` as the menu bar of the window, the native menu bar is used if a
+    /// [`MenuBarHandler`](crate::menu::menubar::MenuBarHandler) accepts it.
+    #[js_func]
+    pub fn set_menubar(&mut self, menu: Option<Menu>) {
+        if let Some((mut bar, body)) = self.menubar.take() {
+            bar.close_menu();
+            self.layer_roots.retain(|lr| lr.body != body);
         }
-        let p = self.popup(el, target);
-        popup_holder.replace(p);
+        if let Some(old_menu) = self.menubar_menu.take() {
+            self.unregister_menu_shortcuts(old_menu);
+        }
+        let native = set_native_menubar(&self.handle, menu.as_ref());
+        if let Some(menu) = menu {
+            self.menubar_menu = Some(menu.clone());
+            self.register_menu_shortcuts(menu.clone());
+            if !native {
+                let (bar, bar_element) = MenuBar::new(menu, &self.handle);
+                let mut body = Element::create(Body::create);
+                body.tag = "body".to_string();
+                body.set_element_type(ElementType::Widget);
+                body.add_child(bar_element, 0).unwrap();
+                body.set_parent(ElementParent::Window(self.handle.clone()));
+                body.set_attribute("theme".to_string(), self.get_theme());
+                // Above the body but below pages and popups
+                self.layer_roots
+                    .insert(1, LayerRoot::new_not_focusable(body.clone(), 0.0, 0.0));
+                self.invalid_layout(body.clone());
+                self.menubar = Some((bar, body));
+            }
+        }
+        self.update_menubar_attribute();
+        self.notify_update();
+    }
+
+    fn update_menubar_attribute(&mut self) {
+        let mut body = self.layer_roots[0].body.clone();
+        if self.menubar.is_some() {
+            body.set_attribute("menubar".to_string(), "".to_string());
+        } else {
+            body.remove_attribute("menubar".to_string());
+        }
+    }
+
+    /// Update the `deft-menubar-height` style variable, which is used as the top padding of the
+    /// body. Returns true if the height changed.
+    fn update_menubar_height(&mut self) -> bool {
+        let height = match &self.menubar {
+            Some((_, body)) => body.get_bounds().height,
+            None => 0.0,
+        };
+        let value = format!("{:.6}", height);
+        if self.style_vars.get("deft-menubar-height") == Some(value.as_str()) {
+            return false;
+        }
+        self.style_vars.set("deft-menubar-height", &value);
+        self.layer_roots[0].body.mark_style_dirty();
+        true
+    }
+
+    fn is_menubar_root(&self, element: &Element) -> bool {
+        self.menubar.as_ref().map(|(_, body)| body == element) == Some(true)
+    }
+
+    pub fn get_command_registry(&mut self) -> &mut CommandRegistry {
+        &mut self.commands
+    }
+
+    /// Register a command which can be bound to key chords by [`Window::bind_key`]
+    #[js_func]
+    pub fn register_command(&mut self, name: String, callback: JsValue) {
+        self.commands.register(&name, move || {
+            if let Err(e) = callback.clone().call_as_function(vec![]) {
+                error!("Error calling command callback: {}", e);
+            }
+        });
+    }
+
+    #[js_func]
+    pub fn unregister_command(&mut self, name: String) {
+        self.commands.unregister(&name);
+    }
+
+    /// The command is disabled while `predicate` returns false
+    #[js_func]
+    pub fn set_command_enabled_predicate(&mut self, name: String, predicate: JsValue) {
+        self.commands.set_enabled_predicate(&name, move || {
+            match predicate.clone().call_as_function(vec![]) {
+                Ok(JsValue::Bool(enabled)) => enabled,
+                Ok(_) => true,
+                Err(e) => {
+                    error!("Error calling command predicate: {}", e);
+                    false
+                }
+            }
+        });
+    }
+
+    /// Bind a key chord like `Ctrl+K Ctrl+S` to the command, the binding only works when the
+    /// focused element is inside `scope` if any
+    #[js_func]
+    pub fn bind_key(
+        &mut self,
+        chord: String,
+        command: String,
+        scope: Option<Element>,
+    ) -> DeftResult<()> {
+        let chord = KeyChord::parse(&chord).ok_or(DeftError::InvalidParameter)?;
+        self.commands.bind(chord, &command, scope.as_ref());
+        Ok(())
+    }
+
+    #[js_func]
+    pub fn unbind_key(&mut self, chord: String, command: String) -> DeftResult<()> {
+        let chord = KeyChord::parse(&chord).ok_or(DeftError::InvalidParameter)?;
+        self.commands.unbind(&chord, &command);
+        Ok(())
+    }
+
+    /// Returns the key chords bound to the command, e.g. `["Ctrl+K Ctrl+S"]`
+    #[js_func]
+    pub fn get_command_bindings(&self, command: String) -> Vec<String> {
+        let bindings = self.commands.get_bindings(&command);
+        bindings.iter().map(|c| c.to_string()).collect()
+    }
+
+    #[js_func]
+    pub fn execute_command(&self, name: String) -> bool {
+        self.commands.execute(&name)
     }
 
     pub fn allow_close(&mut self) -> bool {
@@ -614,6 +822,15 @@ impl Window {
         }
     }
 
+    pub fn handle_preedit(&mut self, text: &str, cursor: Option<(usize, usize)>) {
+        if let Some(focusing) = &self.focusing {
+            focusing.emit(ImePreeditEvent {
+                text: text.to_string(),
+                cursor,
+            });
+        }
+    }
+
     pub fn handle_key(
         &mut self,
         modifiers: u32,
@@ -637,17 +854,61 @@ impl Window {
             repeat,
             pressed,
         };
-
-        if let Some(focusing) = &self.focusing {
-            if detail.pressed {
-                focusing.emit(KeyDownEvent(detail));
-            } else {
+        if !detail.pressed {
+            if let Some(focusing) = &self.focusing {
                 focusing.emit(KeyUpEvent(detail));
             }
+            return;
+        }
+        // The following strokes of a multi-stroke chord are not sent to the focused element
+        if self.commands.is_pending() {
+            self.handle_shortcut(&detail);
+            return;
+        }
+        // Shortcuts only take the keys which are neither consumed nor prevented by the
+        // focused element, e.g. Ctrl+C in text inputs
+        match self.focusing.clone() {
+            Some(focusing) => {
+                let handle = self.handle.clone();
+                focusing.emit_with_callback(KeyDownEvent(detail.clone()), move |ctx| {
+                    if !ctx.prevent_default {
+                        if let Ok(mut window) = handle.upgrade_mut() {
+                            window.handle_shortcut(&detail);
+                        }
+                    }
+                });
+            }
+            None => {
+                self.handle_shortcut(&detail);
+            }
+        }
+    }
+
+    /// Handle the key by key chords of commands, accelerators of menus and mnemonics of the
+    /// menu bar, returns true if handled
+    fn handle_shortcut(&mut self, detail: &KeyEventDetail) -> bool {
+        match self.commands.handle_key(detail, self.focusing.as_ref()) {
+            ChordMatch::Execute(action) => {
+                action();
+                return true;
+            }
+            ChordMatch::Pending => return true,
+            ChordMatch::Unmatched => {}
         }
+        if detail.modifiers == KEY_MOD_ALT {
+            if let (Some((bar, _)), Some(key)) = (&mut self.menubar, &detail.key) {
+                if bar.open_by_mnemonic(key) {
+                    return true;
+                }
+            }
+        }
+        false
     }
 
     pub fn handle_event(&mut self, event: WindowEvent) {
+        if let Some(accessibility) = &mut self.accessibility {
+            accessibility.process_event(&self.window, &event);
+        }
         match event {
             WindowEvent::RedrawRequested => {
                 self.dirty = true;
@@ -655,15 +916,27 @@ impl Window {
             }
             WindowEvent::Resized(_physical_size) => {
                 self.on_resize();
+                self.update_media_styles();
+            }
+            WindowEvent::ThemeChanged(_) => {
+                self.update_theme();
+                self.update_media_styles();
+            }
+            WindowEvent::ScaleFactorChanged { .. } => {
+                self.update_media_styles();
             }
             WindowEvent::ModifiersChanged(new_modifiers) => self.modifiers = new_modifiers,
             WindowEvent::Ime(ime) => match ime {
                 Ime::Enabled => {}
-                Ime::Preedit(_, _) => {}
+                Ime::Preedit(text, cursor) => {
+                    self.handle_preedit(&text, cursor);
+                }
                 Ime::Commit(str) => {
                     self.handle_input(&str);
                 }
-                Ime::Disabled => {}
+                Ime::Disabled => {
+                    self.handle_preedit("", None);
+                }
             },
             WindowEvent::KeyboardInput { event, .. } => {
                 let scancode = match event.physical_key {
@@ -1100,15 +1373,31 @@ impl Window {
         };
         match state {
             ElementState::Pressed => {
-                self.pressing = Some((
-                    node.clone(),
-                    MouseDownInfo {
-                        button,
-                        button_enum: mouse_button,
-                        window_x,
-                        window_y,
-                    },
-                ));
+                let down_info = MouseDownInfo {
+                    button,
+                    button_enum: mouse_button,
+                    window_x,
+                    window_y,
+                };
+                let is_multi_click = match &self.last_mouse_down {
+                    Some((time, last)) => {
+                        last.button == button
+                            && (last.window_x - window_x).abs() <= MULTI_CLICK_DISTANCE
+                            && (last.window_y - window_y).abs() <= MULTI_CLICK_DISTANCE
+                            && time
+                                .elapsed()
+                                .map(|d| d.as_millis() <= MULTI_CLICK_INTERVAL)
+                                .unwrap_or(false)
+                    }
+                    None => false,
+                };
+                self.click_count = if is_multi_click {
+                    self.click_count + 1
+                } else {
+                    1
+                };
+                self.last_mouse_down = Some((SystemTime::now(), down_info.clone()));
+                self.pressing = Some((node.clone(), down_info));
                 self.emit_mouse_event(
                     &mut node, event_type, button, window_x, window_y, screen_x, screen_y,
                 );
@@ -1321,6 +1610,7 @@ impl Window {
             node.update_select_style_recurse();
             node.emit(FocusEvent);
             self.window.set_ime_allowed(node.allow_ime);
+            self.update_accessibility();
         }
     }
 
@@ -1375,6 +1665,7 @@ impl Window {
                     let p_bounds = p.upgrade().unwrap().get_content_bounds();
                     (p_bounds.width, p_bounds.height)
                 }
+                ElementParent::Window(_) if self.is_menubar_root(&root) => (win_width, f32::NAN),
                 ElementParent::Window(_) => (width, height),
                 ElementParent::Page(_) => (f32::NAN, f32::NAN),
             };
@@ -1400,7 +1691,12 @@ impl Window {
         if self.next_frame_timer_handle.is_some() {
             return ResultWaiter::new_finished(false);
         }
-        let sleep_time = self.frame_rate_controller.next_frame();
+        // Headless windows are driven by the caller, so frames are never throttled
+        let sleep_time = if self.window.is_headless() {
+            0
+        } else {
+            self.frame_rate_controller.next_frame()
+        };
         if sleep_time > 0 {
             let me = self.handle.clone();
             let next_frame_timer_handle = set_timeout_nanos(
@@ -1429,25 +1725,30 @@ impl Window {
             // skip duplicate update
             return ResultWaiter::new_finished(false);
         }
-        let (viewport_width, viewport_height) = self.get_inner_size();
+        self.update_render_tree();
+        let r = self.paint();
+        self.update_accessibility();
+        self.layout_dirty_list.clear();
+        self.dirty = false;
+        r
+    }
+
+    /// Resolve styles, calculate layout and rebuild render nodes of all layer roots
+    fn update_render_tree(&mut self) {
         warn_time!(16, "update window");
         for lr in &mut self.layer_roots.clone() {
-            let body = &mut lr.body;
-            let length_ctx = LengthContext {
-                root: body.style.font_size,
-                font_size: body.style.font_size,
-                viewport_width,
-                viewport_height,
-            };
-            //TODO compute font size only when any font size changed
-            body.resolve_style_vars_recurse(&self.style_vars);
-            body.compute_font_size_recurse(&length_ctx);
-            body.apply_style_update(false, &length_ctx);
+            self.update_layer_style(&mut lr.body);
         }
         let dirty_roots = mem::take(&mut self.layout_dirty_list);
         let layout_dirty = !dirty_roots.is_empty();
         if layout_dirty {
             self.update_layout(dirty_roots.values().cloned().collect());
+            if self.update_menubar_height() {
+                // The padding of the body depends on the height of the menu bar
+                let mut body = self.layer_roots[0].body.clone();
+                self.update_layer_style(&mut body);
+                self.update_layout(vec![body]);
+            }
             //TODO should move to Popup?
             let win_size = self
                 .window
@@ -1482,16 +1783,27 @@ impl Window {
             self.render_tree.insert(body, rt);
         }
         // }
-        let r = self.paint();
-        self.layout_dirty_list.clear();
-        self.dirty = false;
-        r
+    }
+
+    fn update_layer_style(&self, body: &mut Element) {
+        let (viewport_width, viewport_height) = self.get_inner_size();
+        let length_ctx = LengthContext {
+            root: body.style.font_size,
+            font_size: body.style.font_size,
+            viewport_width,
+            viewport_height,
+        };
+        //TODO compute font size only when any font size changed
+        body.resolve_style_vars_recurse(&self.style_vars);
+        body.compute_font_size_recurse(&length_ctx);
+        body.apply_style_update(false, &length_ctx);
     }
 
     #[js_func]
     pub fn set_body(&mut self, body: Element) -> DeftResult<()> {
         self.layer_roots[0] = LayerRoot::new(body.clone(), 0.0, 0.0);
         self.init_element_root(body, ElementParent::Window(self.handle.clone()));
+        self.update_menubar_attribute();
         Ok(())
     }
 
@@ -1527,14 +1839,7 @@ impl Window {
     fn init_element_root(&mut self, mut body: Element, parent: ElementParent) {
         body.set_parent(parent);
         body.set_focusable(true);
-        let theme = match env::var("DEFT_THEME") {
-            Ok(str) => str,
-            Err(_) => match self.window.theme().unwrap_or(Theme::Light) {
-                Theme::Light => "light".to_string(),
-                Theme::Dark => "dark".to_string(),
-            },
-        };
-        body.set_attribute("theme".to_string(), theme);
+        body.set_attribute("theme".to_string(), self.get_theme());
         // if self.focusing.is_none() {
         // TODO move focusing to page?
         self.focus(body.clone());
@@ -1542,6 +1847,49 @@ impl Window {
         self.invalid_layout(body);
     }
 
+    /// Update the `theme` attribute of the root elements, including the one of the menu bar
+    fn update_theme(&mut self) {
+        let theme = self.get_theme();
+        for lr in &self.layer_roots {
+            let mut body = lr.body.clone();
+            body.set_attribute("theme".to_string(), theme.clone());
+        }
+    }
+
+    fn get_theme(&self) -> String {
+        match env::var("DEFT_THEME") {
+            Ok(str) => str,
+            Err(_) => match self.window.theme().unwrap_or(Theme::Light) {
+                Theme::Light => "light".to_string(),
+                Theme::Dark => "dark".to_string(),
+            },
+        }
+    }
+
+    pub fn get_media_env(&self) -> MediaEnv {
+        let (width, height) = self.get_inner_size();
+        MediaEnv {
+            width,
+            height,
+            dark: self.get_theme() == "dark",
+            resolution: self.window.scale_factor() as f32,
+        }
+    }
+
+    /// Select styles again if any `@media` condition changed
+    fn update_media_styles(&mut self) {
+        let env = self.get_media_env();
+        let media_matches = CSS_MANAGER.with_borrow(|cm| cm.eval_media(&env));
+        if media_matches != self.media_matches {
+            self.media_matches = media_matches;
+            // Pages, popups and the menu bar have their own roots
+            for lr in &self.layer_roots {
+                let mut body = lr.body.clone();
+                body.update_select_style_recurse();
+            }
+        }
+    }
+
     #[js_func]
     pub fn get_body(&self) -> Option<Element> {
         Some(self.layer_roots[0].body.clone())
@@ -1563,6 +1911,10 @@ impl Window {
             width: size.width,
             height: size.height,
         });
+        if self.window.is_headless() {
+            self.on_resize();
+            self.update_media_styles();
+        }
     }
 
     fn on_resize(&mut self) {
@@ -1591,18 +1943,18 @@ impl Window {
     pub fn request_next_frame_callback(&mut self, callback: Callback) {
         self.next_frame_callbacks.push(callback);
         if self.next_frame_callbacks.len() == 1 {
-            send_app_event(AppEvent::Update(self.get_id())).unwrap();
+            self.post_app_event(AppEvent::Update(self.get_id()));
         }
     }
 
     pub fn request_next_paint_callback(&mut self, callback: Callback) {
         self.next_paint_callbacks.push(callback);
         if self.next_paint_callbacks.len() == 1 {
-            send_app_event(AppEvent::Update(self.get_id())).unwrap();
+            self.post_app_event(AppEvent::Update(self.get_id()));
         }
     }
 
-    fn paint(&mut self) -> ResultWaiter<bool> {
+    pub fn paint(&mut self) -> ResultWaiter<bool> {
         let size = self.window.inner_size();
         let (width, height) = (size.width, size.height);
         // print_time!("paint time: {} {}", width, height);
@@ -1616,6 +1968,82 @@ impl Window {
         for cb in paint_callbacks {
             cb.call();
         }
+        let renderer = self.create_renderer(width, height);
+        let waiter_finisher = waiter.clone();
+        let window_id = self.get_id();
+        let headless = self.window.is_headless();
+        self.renderer_idle = false;
+        self.window.render_with_result(renderer, move |r| {
+            waiter_finisher.finish(r);
+            if !headless {
+                send_app_event(AppEvent::RenderIdle(window_id)).unwrap();
+            }
+        });
+        if headless {
+            // Headless rendering is synchronous
+            self.renderer_idle = true;
+        }
+        waiter
+    }
+
+    /// Capture the current frame of window as png, or only the area of `element` if specified.
+    #[js_func]
+    pub fn capture(&mut self, element: Option<Element>) -> Result<Vec<u8>, DeftError> {
+        if self.dirty {
+            self.update_render_tree();
+        }
+        let size = self.window.inner_size();
+        let (width, height) = (size.width, size.height);
+        if width == 0 || height == 0 {
+            return Err(DeftError::InvalidState);
+        }
+        let scale_factor = self.window.scale_factor() as f32;
+        let bounds = match &element {
+            None => None,
+            Some(element) => {
+                let rect = self
+                    .get_element_window_bounds(element)
+                    .ok_or(DeftError::InvalidParameter)?;
+                Some(IRect::from_ltrb(
+                    (rect.left * scale_factor).floor() as i32,
+                    (rect.top * scale_factor).floor() as i32,
+                    (rect.right * scale_factor).ceil() as i32,
+                    (rect.bottom * scale_factor).ceil() as i32,
+                ))
+            }
+        };
+        let renderer = self.create_renderer(width, height);
+        let mut offscreen =
+            HeadlessWindow::new(String::new(), width, height, scale_factor as f64);
+        offscreen.render_with_result(renderer, |_| {});
+        snapshot::encode_png(&offscreen.snapshot(), bounds)
+    }
+
+    /// Capture the window (or `element`) and compare it with the golden png file at `path`.
+    #[js_func]
+    pub fn match_snapshot(
+        &mut self,
+        path: String,
+        element: Option<Element>,
+    ) -> Result<(), DeftError> {
+        let png = self.capture(element)?;
+        snapshot::match_snapshot(path, &png)
+    }
+
+    /// Bounds of `element` in logical window coordinates, available after the render tree is built.
+    pub fn get_element_window_bounds(&self, element: &Element) -> Option<skia_safe::Rect> {
+        let root = element.get_root_element();
+        let lr = self.layer_roots.iter().find(|lr| lr.body == root)?;
+        let matrix = self
+            .render_tree
+            .get(&root)?
+            .get_element_total_matrix(element)?;
+        let (width, height) = element.get_size();
+        let (rect, _) = matrix.map_rect(skia_safe::Rect::from_xywh(0.0, 0.0, width, height));
+        Some(rect.with_offset((lr.x, lr.y)))
+    }
+
+    fn create_renderer(&mut self, width: u32, height: u32) -> Renderer {
         let scale_factor = self.window.scale_factor() as f32;
         let background_color = self.background_color;
         let viewport = Rect::new(
@@ -1641,37 +2069,27 @@ impl Window {
             //TODO notify absolute position change
             paint_tree.push((pt, x, y));
         }
-        let waiter_finisher = waiter.clone();
-        let window_id = self.get_id();
-        self.renderer_idle = false;
-        self.window.render_with_result(
-            Renderer::new(move |canvas, ctx| {
-                // print_time!("drawing time");
-                canvas.save();
-                if scale_factor != 1.0 {
-                    canvas.scale((scale_factor, scale_factor));
-                }
-                canvas.clear(background_color);
-                let mut element_painter = ElementPainter::take(ctx);
-                element_painter.update_viewport(scale_factor, viewport);
-                element_painter.set_layer_cache(layer_cache_enabled);
-                let paint_context = PaintContext { scale_factor };
-                let painter = Painter::new(canvas, paint_context);
-                for (tree, x, y) in &mut paint_tree {
-                    painter.canvas.save();
-                    painter.canvas.translate((*x, *y));
-                    element_painter.draw_root(&painter, tree, ctx);
-                    painter.canvas.restore();
-                }
-                element_painter.put(ctx);
-                canvas.restore();
-            }),
-            move |r| {
-                waiter_finisher.finish(r);
-                send_app_event(AppEvent::RenderIdle(window_id)).unwrap();
-            },
-        );
-        waiter
+        Renderer::new(move |canvas, ctx| {
+            // print_time!("drawing time");
+            canvas.save();
+            if scale_factor != 1.0 {
+                canvas.scale((scale_factor, scale_factor));
+            }
+            canvas.clear(background_color);
+            let mut element_painter = ElementPainter::take(ctx);
+            element_painter.update_viewport(scale_factor, viewport);
+            element_painter.set_layer_cache(layer_cache_enabled);
+            let paint_context = PaintContext { scale_factor };
+            let painter = Painter::new(canvas, paint_context);
+            for (tree, x, y) in &mut paint_tree {
+                painter.canvas.save();
+                painter.canvas.translate((*x, *y));
+                element_painter.draw_root(&painter, tree, ctx);
+                painter.canvas.restore();
+            }
+            element_painter.put(ctx);
+            canvas.restore();
+        })
     }
 
     #[inline]
@@ -1702,7 +2120,9 @@ impl Window {
 
     fn get_node_by_pos(&self, window_x: f32, window_y: f32) -> Option<(Element, f32, f32)> {
         // debug!("search node time in layers");
-        let lr = self.get_focused_layer();
+        let lr = self
+            .get_menubar_layer_at(window_x, window_y)
+            .unwrap_or_else(|| self.get_focused_layer());
         let (body, x, y) = (&lr.body, lr.x, lr.y);
         let (eo, x, y) = self
             .render_tree
@@ -1714,6 +2134,22 @@ impl Window {
         Some((element, x, y))
     }
 
+    /// Returns the layer of the menu bar if it contains the point and no page is shown
+    fn get_menubar_layer_at(&self, window_x: f32, window_y: f32) -> Option<&LayerRoot> {
+        let (_, body) = self.menubar.as_ref()?;
+        if self.get_focused_layer().body != self.layer_roots[0].body {
+            return None;
+        }
+        let lr = self.layer_roots.iter().find(|lr| &lr.body == body)?;
+        let (width, height) = body.get_size();
+        let (x, y) = (window_x - lr.x, window_y - lr.y);
+        if x >= 0.0 && x < width && y >= 0.0 && y < height {
+            Some(lr)
+        } else {
+            None
+        }
+    }
+
     fn emit_mouse_event(
         &mut self,
         node: &mut Element,
@@ -1748,6 +2184,12 @@ impl Window {
             window_y,
             screen_x,
             screen_y,
+            click_count: match event_type_enum {
+                MouseEventType::MouseDown
+                | MouseEventType::MouseUp
+                | MouseEventType::MouseClick => self.click_count,
+                _ => 0,
+            },
         };
         match event_type_enum {
             MouseEventType::MouseDown => node.emit(MouseDownEvent(detail)),
@@ -1907,3 +2349,97 @@ pub fn window_check_update(window_id: i32) {
         }
     });
 }
+
+#[test]
+fn test_headless_window() {
+    use crate::element::container::Container;
+    let handle = Window::create(WindowAttrs {
+        width: Some(100.0),
+        height: Some(80.0),
+        headless: Some(true),
+        ..Default::default()
+    })
+    .unwrap();
+    let mut window = handle.upgrade_mut().unwrap();
+    let mut body = Element::create(Body::create);
+    body.add_child(Element::create(Container::create), 0).unwrap();
+    window.set_body(body).unwrap();
+    assert!(window.is_headless());
+    assert_eq!((100.0, 80.0), window.get_inner_size());
+    assert!(window.update().wait_result(|r| *r));
+    assert!(window.renderer_idle);
+    let png = window.capture(None).unwrap();
+    let image = snapshot::decode_png(&png).unwrap();
+    assert_eq!((100, 80), (image.width(), image.height()));
+}
+
+#[test]
+fn test_translucent_layer_overflow() {
+    use crate::element::container::Container;
+    let handle = Window::create(WindowAttrs {
+        width: Some(100.0),
+        height: Some(80.0),
+        headless: Some(true),
+        ..Default::default()
+    })
+    .unwrap();
+    let mut window = handle.upgrade_mut().unwrap();
+    let mut body = Element::create(Body::create);
+    let mut translucent = Element::create(Container::create);
+    translucent.set_style(JsValue::String(
+        "opacity: 0.5; width: 20px; height: 20px".to_string(),
+    ));
+    // Sublayer outside of the bounds of the translucent layer
+    let mut overflow = Element::create(Container::create);
+    overflow.set_style(JsValue::String(
+        "position: relative; left: 30px; width: 20px; height: 20px; background: #f00".to_string(),
+    ));
+    translucent.add_child(overflow, 0).unwrap();
+    body.add_child(translucent, 0).unwrap();
+    window.set_body(body).unwrap();
+    assert!(window.update().wait_result(|r| *r));
+    let png = window.capture(None).unwrap();
+    let image = image::load_from_memory_with_format(&png, image::ImageFormat::Png)
+        .unwrap()
+        .into_rgba8();
+    let pixel = image.get_pixel(40, 10).0;
+    assert!(pixel[0] > pixel[1]);
+    assert!(pixel[3] > 0);
+}
+
+#[test]
+fn test_text_shadow_overflow() {
+    use crate::element::label::Label;
+    let handle = Window::create(WindowAttrs {
+        width: Some(100.0),
+        height: Some(80.0),
+        headless: Some(true),
+        ..Default::default()
+    })
+    .unwrap();
+    let mut window = handle.upgrade_mut().unwrap();
+    let mut body = Element::create(Body::create);
+    let mut label = Element::create(Label::create);
+    label.set_style(JsValue::String(
+        "width: 30px; height: 30px; font-size: 24px; color: #000; text-shadow: 40px 0 #f00"
+            .to_string(),
+    ));
+    label
+        .get_backend_mut_as::<Label>()
+        .set_text("MM".to_string());
+    body.add_child(label, 0).unwrap();
+    window.set_body(body).unwrap();
+    assert!(window.update().wait_result(|r| *r));
+    let png = window.capture(None).unwrap();
+    let image = image::load_from_memory_with_format(&png, image::ImageFormat::Png)
+        .unwrap()
+        .into_rgba8();
+    // The shadow is painted outside of the label
+    let shadow_painted = (30..80).any(|x| {
+        (0..30).any(|y| {
+            let pixel = image.get_pixel(x, y).0;
+            pixel[0] > 128 && pixel[1] < 64
+        })
+    });
+    assert!(shadow_painted);
+}
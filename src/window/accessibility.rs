@@ -0,0 +1,394 @@
+use crate::app::AppEvent;
+use crate::base::MouseEventType;
+use crate::element::Element;
+use crate::ext::ext_window::WINDOWS;
+use crate::send_app_event;
+use crate::window::platform_window::PlatformWindow;
+use crate::window::Window;
+use crate::some_or_return;
+use accesskit::{
+    Action, ActionData, ActionHandler, ActionRequest, ActivationHandler, Affine, Node, NodeId,
+    Rect, Role, Tree, TreeUpdate,
+};
+use winit::event::WindowEvent;
+
+/// Id of the node representing the window itself, element nodes use the eid which starts from 1.
+pub const WINDOW_NODE_ID: NodeId = NodeId(0);
+
+/// Actions of assistive technologies may arrive on any thread, so they are forwarded
+/// to the app thread before touching the window.
+struct ActionForwarder {
+    window_id: i32,
+}
+
+impl ActionHandler for ActionForwarder {
+    fn do_action(&mut self, request: ActionRequest) {
+        let window_id = self.window_id;
+        let _ = send_app_event(AppEvent::Callback(Box::new(move || {
+            with_window(window_id, |w| w.handle_accessibility_action(request));
+        })));
+    }
+}
+
+/// The tree is built on the app thread and pushed by [`Window::update_accessibility`] later.
+struct ActivationForwarder {
+    window_id: i32,
+}
+
+impl ActivationHandler for ActivationForwarder {
+    fn request_initial_tree(&mut self) -> Option<TreeUpdate> {
+        let window_id = self.window_id;
+        let _ = send_app_event(AppEvent::Callback(Box::new(move || {
+            with_window(window_id, |w| w.update_accessibility());
+        })));
+        None
+    }
+}
+
+#[cfg(all(feature = "accessibility", linux_platform))]
+struct NoopDeactivationHandler;
+
+#[cfg(all(feature = "accessibility", linux_platform))]
+impl accesskit::DeactivationHandler for NoopDeactivationHandler {
+    fn deactivate_accessibility(&mut self) {
+        // Do nothing
+    }
+}
+
+fn with_window<F: FnOnce(&mut Window)>(window_id: i32, callback: F) {
+    WINDOWS.with_borrow_mut(|m| {
+        if let Some(f) = m.get_mut(&window_id) {
+            if let Ok(mut f) = f.upgrade_mut() {
+                callback(&mut f);
+            }
+        }
+    });
+}
+
+/// Publish the accessibility tree of a native window to the platform accessibility api.
+pub struct AccessibilityAdapter {
+    #[cfg(all(feature = "accessibility", linux_platform))]
+    adapter: accesskit_unix::Adapter,
+    #[cfg(all(feature = "accessibility", windows_platform))]
+    adapter: accesskit_windows::SubclassingAdapter,
+    #[cfg(all(feature = "accessibility", macos_platform))]
+    adapter: accesskit_macos::SubclassingAdapter,
+}
+
+impl AccessibilityAdapter {
+    /// Returns None for headless windows and platforms without accessibility support.
+    #[cfg(all(feature = "accessibility", linux_platform))]
+    pub fn new(window: &PlatformWindow, window_id: i32) -> Option<Self> {
+        window.native()?;
+        let adapter = accesskit_unix::Adapter::new(
+            ActivationForwarder { window_id },
+            ActionForwarder { window_id },
+            NoopDeactivationHandler,
+        );
+        Some(Self { adapter })
+    }
+
+    /// Returns None for headless windows and platforms without accessibility support.
+    #[cfg(all(feature = "accessibility", windows_platform))]
+    pub fn new(window: &PlatformWindow, window_id: i32) -> Option<Self> {
+        use raw_window_handle::RawWindowHandle;
+        let hwnd = match window.raw_window_handle().ok()? {
+            RawWindowHandle::Win32(handle) => handle.hwnd.get() as *mut _,
+            _ => return None,
+        };
+        let adapter = accesskit_windows::SubclassingAdapter::new(
+            accesskit_windows::HWND(hwnd),
+            ActivationForwarder { window_id },
+            ActionForwarder { window_id },
+        );
+        Some(Self { adapter })
+    }
+
+    /// Returns None for headless windows and platforms without accessibility support.
+    #[cfg(all(feature = "accessibility", macos_platform))]
+    pub fn new(window: &PlatformWindow, window_id: i32) -> Option<Self> {
+        use raw_window_handle::RawWindowHandle;
+        let view = match window.raw_window_handle().ok()? {
+            RawWindowHandle::AppKit(handle) => handle.ns_view.as_ptr(),
+            _ => return None,
+        };
+        let adapter = unsafe {
+            accesskit_macos::SubclassingAdapter::new(
+                view,
+                ActivationForwarder { window_id },
+                ActionForwarder { window_id },
+            )
+        };
+        Some(Self { adapter })
+    }
+
+    /// Returns None for headless windows and platforms without accessibility support.
+    #[cfg(not(all(
+        feature = "accessibility",
+        any(linux_platform, windows_platform, macos_platform)
+    )))]
+    pub fn new(_window: &PlatformWindow, _window_id: i32) -> Option<Self> {
+        None
+    }
+
+    /// Push a new tree, `updater` is only called when assistive technologies are listening.
+    pub fn update_if_active(&mut self, updater: impl FnOnce() -> TreeUpdate) {
+        #[cfg(all(feature = "accessibility", linux_platform))]
+        self.adapter.update_if_active(updater);
+        #[cfg(all(feature = "accessibility", any(windows_platform, macos_platform)))]
+        if let Some(events) = self.adapter.update_if_active(updater) {
+            events.raise();
+        }
+        #[cfg(not(all(
+            feature = "accessibility",
+            any(linux_platform, windows_platform, macos_platform)
+        )))]
+        let _ = updater;
+    }
+
+    pub fn process_event(&mut self, window: &PlatformWindow, event: &WindowEvent) {
+        #[cfg(all(feature = "accessibility", linux_platform))]
+        match event {
+            WindowEvent::Moved(_) | WindowEvent::Resized(_) => {
+                let outer_position = window.outer_position().unwrap_or_default().cast::<f64>();
+                let outer_size = window.outer_size().cast::<f64>();
+                let inner_position = window.inner_position().unwrap_or_default().cast::<f64>();
+                let inner_size = window.inner_size().cast::<f64>();
+                self.adapter.set_root_window_bounds(
+                    Rect::from_origin_size(
+                        (outer_position.x, outer_position.y),
+                        (outer_size.width, outer_size.height),
+                    ),
+                    Rect::from_origin_size(
+                        (inner_position.x, inner_position.y),
+                        (inner_size.width, inner_size.height),
+                    ),
+                );
+            }
+            WindowEvent::Focused(is_focused) => {
+                self.adapter.update_window_focus_state(*is_focused);
+            }
+            _ => {}
+        }
+        #[cfg(all(feature = "accessibility", macos_platform))]
+        if let WindowEvent::Focused(is_focused) = event {
+            if let Some(events) = self.adapter.update_view_focus_state(*is_focused) {
+                events.raise();
+            }
+        }
+        #[cfg(not(all(feature = "accessibility", linux_platform)))]
+        let _ = (window, event);
+    }
+}
+
+impl Window {
+    /// Push the accessibility tree to the platform if any assistive technology is listening.
+    pub fn update_accessibility(&mut self) {
+        let mut adapter = some_or_return!(self.accessibility.take());
+        adapter.update_if_active(|| self.build_accessibility_tree());
+        self.accessibility = Some(adapter);
+    }
+
+    /// Build a full accessibility tree of all layers, the window itself is the root node.
+    pub fn build_accessibility_tree(&self) -> TreeUpdate {
+        let mut nodes = Vec::new();
+        let mut root = Node::new(Role::Window);
+        root.set_label(self.window.title());
+        // Element bounds are logical
+        root.set_transform(Affine::scale(self.window.scale_factor()));
+        for lr in &self.layer_roots {
+            let child = self.build_accessibility_node(&lr.body, &mut nodes);
+            root.push_child(child);
+        }
+        nodes.push((WINDOW_NODE_ID, root));
+
+        let focus = self
+            .focusing
+            .as_ref()
+            .map(|e| NodeId(e.get_eid() as u64))
+            .filter(|id| nodes.iter().any(|(n, _)| n == id))
+            .unwrap_or(WINDOW_NODE_ID);
+        let mut tree = Tree::new(WINDOW_NODE_ID);
+        tree.toolkit_name = Some("deft".to_string());
+        tree.toolkit_version = Some(env!("CARGO_PKG_VERSION").to_string());
+        TreeUpdate {
+            nodes,
+            tree: Some(tree),
+            focus,
+        }
+    }
+
+    fn build_accessibility_node(
+        &self,
+        element: &Element,
+        nodes: &mut Vec<(NodeId, Node)>,
+    ) -> NodeId {
+        let id = NodeId(element.get_eid() as u64);
+        let mut node = Node::new(Role::GenericContainer);
+        if let Some(b) = self.get_element_window_bounds(element) {
+            node.set_bounds(Rect::new(
+                b.left as f64,
+                b.top as f64,
+                b.right as f64,
+                b.bottom as f64,
+            ));
+        }
+        if element.is_disabled() {
+            node.set_disabled();
+        } else if element.is_focusable() {
+            node.add_action(Action::Focus);
+        }
+        let tooltip = element.get_tooltip();
+        if !tooltip.is_empty() {
+            node.set_description(tooltip);
+        }
+        let max_scroll_top = element.get_max_scroll_top();
+        if max_scroll_top > 0.0 {
+            node.set_scroll_y(element.get_scroll_top() as f64);
+            node.set_scroll_y_min(0.0);
+            node.set_scroll_y_max(max_scroll_top as f64);
+            node.add_action(Action::ScrollUp);
+            node.add_action(Action::ScrollDown);
+            node.add_action(Action::SetScrollOffset);
+        }
+        let max_scroll_left = element.get_max_scroll_left();
+        if max_scroll_left > 0.0 {
+            node.set_scroll_x(element.get_scroll_left() as f64);
+            node.set_scroll_x_min(0.0);
+            node.set_scroll_x_max(max_scroll_left as f64);
+            node.add_action(Action::ScrollLeft);
+            node.add_action(Action::ScrollRight);
+            node.add_action(Action::SetScrollOffset);
+        }
+        let expose_children = element
+            .clone()
+            .get_backend_mut()
+            .build_accessibility_node(&mut node);
+        if expose_children {
+            for child in element.get_children() {
+                let child_id = self.build_accessibility_node(&child, nodes);
+                node.push_child(child_id);
+            }
+        }
+        nodes.push((id, node));
+        id
+    }
+
+    pub fn handle_accessibility_action(&mut self, request: ActionRequest) {
+        if request.target == WINDOW_NODE_ID {
+            return;
+        }
+        let eid = request.target.0 as u32;
+        let mut element = some_or_return!(self
+            .layer_roots
+            .iter()
+            .find_map(|lr| self.get_element_by_id(&lr.body, eid)));
+        if element.get_backend_mut().do_accessibility_action(&request) {
+            return;
+        }
+        let content_bounds = element.get_content_bounds();
+        let (page_width, page_height) = (content_bounds.width, content_bounds.height);
+        match request.action {
+            Action::Focus => self.focus(element),
+            Action::Blur => {
+                if self.is_focusing(&element) {
+                    let body = self.get_focused_layer().body.clone();
+                    self.focus(body);
+                }
+            }
+            Action::Click => self.click_accessibility_target(element),
+            Action::ScrollUp => element.set_scroll_top(element.get_scroll_top() - page_height),
+            Action::ScrollDown => element.set_scroll_top(element.get_scroll_top() + page_height),
+            Action::ScrollLeft => element.set_scroll_left(element.get_scroll_left() - page_width),
+            Action::ScrollRight => element.set_scroll_left(element.get_scroll_left() + page_width),
+            Action::SetScrollOffset => {
+                if let Some(ActionData::SetScrollOffset(p)) = request.data {
+                    element.set_scroll_left(p.x as f32);
+                    element.set_scroll_top(p.y as f32);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Emit a left click at the center of element without moving the cursor
+    fn click_accessibility_target(&mut self, mut element: Element) {
+        let bounds = some_or_return!(self.get_element_window_bounds(&element));
+        let (x, y) = (bounds.center_x(), bounds.center_y());
+        let (win_x, win_y) = self.inner_position();
+        for ty in [
+            MouseEventType::MouseDown,
+            MouseEventType::MouseUp,
+            MouseEventType::MouseClick,
+        ] {
+            self.emit_mouse_event(&mut element, ty, 1, x, y, x + win_x, y + win_y);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::element::body::Body;
+    use crate::element::button::Button;
+    use crate::element::checkbox::Checkbox;
+    use crate::element::label::Label;
+    use crate::element::Element;
+    use crate::ext::ext_window::WindowAttrs;
+    use crate::window::accessibility::WINDOW_NODE_ID;
+    use crate::window::Window;
+    use accesskit::{Action, ActionRequest, NodeId, Role, Toggled};
+
+    #[test]
+    fn test_accessibility_tree() {
+        let handle = Window::create(WindowAttrs {
+            width: Some(100.0),
+            height: Some(100.0),
+            headless: Some(true),
+            ..Default::default()
+        })
+        .unwrap();
+        let mut body = Element::create(Body::create);
+        let mut button = Element::create(Button::create);
+        let mut label = Element::create(Label::create);
+        label.get_backend_mut_as::<Label>().set_text("OK".to_string());
+        button.add_child(label.clone(), 0).unwrap();
+        let mut checkbox = Element::create(Checkbox::create);
+        checkbox
+            .get_backend_mut_as::<Checkbox>()
+            .set_label("Remember me".to_string());
+        body.add_child(button.clone(), 0).unwrap();
+        body.add_child(checkbox.clone(), 1).unwrap();
+        let mut window = handle.upgrade_mut().unwrap();
+        window.set_body(body).unwrap();
+        window.update_render_tree();
+
+        let tree = window.build_accessibility_tree();
+        let node = |id: NodeId| tree.nodes.iter().find(|(n, _)| *n == id).map(|(_, n)| n);
+        assert_eq!(Role::Window, node(WINDOW_NODE_ID).unwrap().role());
+        let button_node = node(NodeId(button.get_eid() as u64)).unwrap();
+        assert_eq!(Role::Button, button_node.role());
+        assert_eq!(&[NodeId(label.get_eid() as u64)], button_node.children());
+        let label_node = node(NodeId(label.get_eid() as u64)).unwrap();
+        assert_eq!(Some("OK"), label_node.value());
+        let checkbox_node = node(NodeId(checkbox.get_eid() as u64)).unwrap();
+        assert_eq!(Role::CheckBox, checkbox_node.role());
+        assert_eq!(Some("Remember me"), checkbox_node.label());
+        assert_eq!(Some(Toggled::False), checkbox_node.toggled());
+        // Internal children of checkbox are hidden
+        assert!(checkbox_node.children().is_empty());
+
+        window.handle_accessibility_action(ActionRequest {
+            action: Action::Click,
+            target: NodeId(checkbox.get_eid() as u64),
+            data: None,
+        });
+        assert!(checkbox.get_backend_as::<Checkbox>().is_checked());
+
+        window.handle_accessibility_action(ActionRequest {
+            action: Action::Focus,
+            target: NodeId(button.get_eid() as u64),
+            data: None,
+        });
+        assert_eq!(NodeId(button.get_eid() as u64), window.build_accessibility_tree().focus);
+    }
+}
@@ -0,0 +1,456 @@
+use crate::element::{Element, ElementWeak};
+use crate::event::KeyEventDetail;
+use crate::menu::accelerator::Accelerator;
+use crate::menu::Menu;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::rc::Rc;
+use winit::keyboard::NamedKey;
+
+/// Sequence of key strokes separated by spaces, e.g. `Ctrl+S` or `Ctrl+K Ctrl+S`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyChord {
+    pub strokes: Vec<Accelerator>,
+}
+
+impl KeyChord {
+    pub fn parse(value: &str) -> Option<Self> {
+        let strokes = value
+            .split_whitespace()
+            .map(Accelerator::parse)
+            .collect::<Option<Vec<_>>>()?;
+        if strokes.is_empty() {
+            return None;
+        }
+        Some(Self { strokes })
+    }
+
+    fn starts_with(&self, strokes: &[Accelerator]) -> bool {
+        self.strokes.len() >= strokes.len()
+            && self
+                .strokes
+                .iter()
+                .zip(strokes)
+                .all(|(a, b)| a.modifiers == b.modifiers && a.key.eq_ignore_ascii_case(&b.key))
+    }
+}
+
+impl Display for KeyChord {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (i, stroke) in self.strokes.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}", stroke)?;
+        }
+        Ok(())
+    }
+}
+
+struct Command {
+    action: Rc<dyn Fn()>,
+    enabled: Option<Rc<dyn Fn() -> bool>>,
+}
+
+enum BindingTarget {
+    Command(String),
+    /// Item of a menu bound by its own accelerator, see [`CommandRegistry::bind_menu`]
+    MenuItem(Menu, Rc<dyn Fn()>),
+}
+
+struct KeyBinding {
+    chord: KeyChord,
+    target: BindingTarget,
+    /// The binding only works when the focused element is inside the subtree of the scope
+    scope: Option<ElementWeak>,
+}
+
+impl KeyBinding {
+    fn is_command(&self, name: &str) -> bool {
+        matches!(&self.target, BindingTarget::Command(c) if c == name)
+    }
+}
+
+pub(crate) enum ChordMatch {
+    /// The chord is complete, the action of the command should be called
+    Execute(Rc<dyn Fn()>),
+    /// The key is part of a multi-stroke chord and should not be dispatched to elements
+    Pending,
+    /// The key is not bound
+    Unmatched,
+}
+
+/// Named commands of a window and the key chords bound to them
+#[derive(Default)]
+pub struct CommandRegistry {
+    commands: HashMap<String, Command>,
+    bindings: Vec<KeyBinding>,
+    /// Strokes of a multi-stroke chord pressed so far
+    pending: Vec<Accelerator>,
+}
+
+impl CommandRegistry {
+    pub fn new() -> Self {
+        Self {
+            commands: HashMap::new(),
+            bindings: Vec::new(),
+            pending: Vec::new(),
+        }
+    }
+
+    /// Register a command, a previous command with the same name is replaced
+    pub fn register<F: Fn() + 'static>(&mut self, name: &str, action: F) {
+        let command = Command {
+            action: Rc::new(action),
+            enabled: None,
+        };
+        self.commands.insert(name.to_string(), command);
+    }
+
+    /// Remove the command and its bindings
+    pub fn unregister(&mut self, name: &str) {
+        self.commands.remove(name);
+        self.bindings.retain(|b| !b.is_command(name));
+    }
+
+    /// The command is not executed while `predicate` returns false
+    pub fn set_enabled_predicate<F: Fn() -> bool + 'static>(&mut self, name: &str, predicate: F) {
+        if let Some(command) = self.commands.get_mut(name) {
+            command.enabled = Some(Rc::new(predicate));
+        }
+    }
+
+    pub fn is_enabled(&self, name: &str) -> bool {
+        match self.commands.get(name) {
+            Some(command) => command.enabled.as_ref().map(|p| p()).unwrap_or(true),
+            None => false,
+        }
+    }
+
+    /// Bind `chord` to the command, the binding only works when the focused element is inside
+    /// `scope` if any. Bindings of inner scopes take precedence.
+    pub fn bind(&mut self, chord: KeyChord, command: &str, scope: Option<&Element>) {
+        self.bindings.push(KeyBinding {
+            chord,
+            target: BindingTarget::Command(command.to_string()),
+            scope: scope.map(|s| s.as_weak()),
+        });
+    }
+
+    pub fn unbind(&mut self, chord: &KeyChord, command: &str) {
+        self.bindings
+            .retain(|b| &b.chord != chord || !b.is_command(command));
+    }
+
+    /// Returns the chords bound to the command, e.g. to be shown in menus
+    pub fn get_bindings(&self, command: &str) -> Vec<KeyChord> {
+        self.bindings
+            .iter()
+            .filter(|b| b.is_command(command))
+            .map(|b| b.chord.clone())
+            .collect()
+    }
+
+    /// Bind the accelerators of the items of `menu` globally, items referring to commands are
+    /// triggered by the bindings of their commands. Menus bound later take precedence over
+    /// previous menus, but not over commands.
+    pub fn bind_menu(&mut self, menu: &Menu) {
+        self.unbind_menu(menu);
+        let bindings = menu
+            .collect_accelerators()
+            .into_iter()
+            .map(|(accelerator, action)| KeyBinding {
+                chord: KeyChord {
+                    strokes: vec![accelerator],
+                },
+                target: BindingTarget::MenuItem(menu.clone(), action),
+                scope: None,
+            });
+        self.bindings.splice(0..0, bindings);
+    }
+
+    pub fn unbind_menu(&mut self, menu: &Menu) {
+        self.bindings
+            .retain(|b| !matches!(&b.target, BindingTarget::MenuItem(m, _) if m == menu));
+    }
+
+    /// Call the action of the command if it is enabled
+    pub fn execute(&self, name: &str) -> bool {
+        if !self.is_enabled(name) {
+            return false;
+        }
+        let action = match self.commands.get(name) {
+            Some(command) => command.action.clone(),
+            None => return false,
+        };
+        action();
+        true
+    }
+
+    /// Whether some strokes of a multi-stroke chord are pressed
+    pub(crate) fn is_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// Feed a pressed key, `focusing` is used to resolve the scopes of bindings
+    pub(crate) fn handle_key(
+        &mut self,
+        detail: &KeyEventDetail,
+        focusing: Option<&Element>,
+    ) -> ChordMatch {
+        if is_modifier_key(detail) {
+            return ChordMatch::Unmatched;
+        }
+        let stroke = match &detail.key {
+            Some(key) => Accelerator {
+                modifiers: detail.modifiers,
+                key: key.clone(),
+            },
+            None => return self.reset_pending(),
+        };
+        let ancestors = get_ancestors(focusing);
+        let mut strokes = self.pending.clone();
+        strokes.push(stroke);
+        // (scope depth, is menu item, binding), the nearest scope has the smallest depth
+        let mut candidates = Vec::new();
+        for binding in &self.bindings {
+            if !binding.chord.starts_with(&strokes) {
+                continue;
+            }
+            let depth = match &binding.scope {
+                None => usize::MAX,
+                Some(scope) => {
+                    let scope = match scope.upgrade() {
+                        Ok(scope) => scope,
+                        Err(_) => continue,
+                    };
+                    match ancestors.iter().position(|e| e == &scope) {
+                        Some(depth) => depth,
+                        None => continue,
+                    }
+                }
+            };
+            let is_menu_item = matches!(binding.target, BindingTarget::MenuItem(..));
+            candidates.push((depth, is_menu_item, binding));
+        }
+        candidates.sort_by_key(|(depth, is_menu_item, _)| (*depth, *is_menu_item));
+        // The innermost scope with enabled bindings decides, so that a chord in progress in an
+        // inner scope is not taken by a complete chord of an outer scope
+        for scope_candidates in candidates.chunk_by(|a, b| a.0 == b.0) {
+            let mut pending = false;
+            for (_, _, binding) in scope_candidates {
+                let action = match &binding.target {
+                    BindingTarget::Command(name) => match self.commands.get(name) {
+                        Some(command) if self.is_enabled(name) => command.action.clone(),
+                        _ => continue,
+                    },
+                    BindingTarget::MenuItem(_, action) => action.clone(),
+                };
+                if binding.chord.strokes.len() == strokes.len() {
+                    self.pending.clear();
+                    return ChordMatch::Execute(action);
+                }
+                pending = true;
+            }
+            if pending {
+                self.pending = strokes;
+                return ChordMatch::Pending;
+            }
+        }
+        self.reset_pending()
+    }
+
+    /// An unbound key ends the current chord, the key is swallowed if a chord was in progress
+    fn reset_pending(&mut self) -> ChordMatch {
+        if self.pending.is_empty() {
+            ChordMatch::Unmatched
+        } else {
+            self.pending.clear();
+            ChordMatch::Pending
+        }
+    }
+}
+
+fn is_modifier_key(detail: &KeyEventDetail) -> bool {
+    matches!(
+        detail.named_key,
+        Some(NamedKey::Control)
+            | Some(NamedKey::Shift)
+            | Some(NamedKey::Alt)
+            | Some(NamedKey::AltGraph)
+            | Some(NamedKey::Super)
+            | Some(NamedKey::Meta)
+    )
+}
+
+/// Returns `element` and its ancestors, from the nearest to the root
+fn get_ancestors(element: Option<&Element>) -> Vec<Element> {
+    let mut result = Vec::new();
+    let mut next = element.cloned();
+    while let Some(e) = next {
+        next = e.get_parent();
+        result.push(e);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::element::container::Container;
+    use crate::element::Element;
+    use crate::event::{KeyEventDetail, KEY_MOD_CTRL};
+    use crate::menu::{Menu, StandardMenuItem};
+    use crate::window::command::{ChordMatch, CommandRegistry, KeyChord};
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    fn key(modifiers: u32, key: &str) -> KeyEventDetail {
+        KeyEventDetail {
+            scancode: None,
+            modifiers,
+            ctrl_key: modifiers & KEY_MOD_CTRL != 0,
+            alt_key: false,
+            meta_key: false,
+            shift_key: false,
+            named_key: None,
+            key: Some(key.to_string()),
+            key_str: Some(key.to_string()),
+            repeat: false,
+            pressed: true,
+        }
+    }
+
+    fn press(registry: &mut CommandRegistry, detail: KeyEventDetail) -> &'static str {
+        press_in(registry, detail, None)
+    }
+
+    fn press_in(
+        registry: &mut CommandRegistry,
+        detail: KeyEventDetail,
+        focusing: Option<&Element>,
+    ) -> &'static str {
+        match registry.handle_key(&detail, focusing) {
+            ChordMatch::Execute(action) => {
+                action();
+                "execute"
+            }
+            ChordMatch::Pending => "pending",
+            ChordMatch::Unmatched => "unmatched",
+        }
+    }
+
+    #[test]
+    fn test_parse_chord() {
+        let chord = KeyChord::parse("Ctrl+K  ctrl+s").unwrap();
+        assert_eq!(2, chord.strokes.len());
+        assert_eq!("Ctrl+K Ctrl+S", chord.to_string());
+        assert!(KeyChord::parse("").is_none());
+        assert!(KeyChord::parse("Ctrl+K Ctrl+").is_none());
+    }
+
+    #[test]
+    fn test_multi_stroke_chord() {
+        let saved = Rc::new(Cell::new(0));
+        let enabled = Rc::new(Cell::new(true));
+        let mut registry = CommandRegistry::new();
+        let s = saved.clone();
+        registry.register("save-all", move || s.set(s.get() + 1));
+        let e = enabled.clone();
+        registry.set_enabled_predicate("save-all", move || e.get());
+        registry.bind(KeyChord::parse("Ctrl+K Ctrl+S").unwrap(), "save-all", None);
+        assert_eq!(
+            vec!["Ctrl+K Ctrl+S"],
+            registry
+                .get_bindings("save-all")
+                .iter()
+                .map(|c| c.to_string())
+                .collect::<Vec<_>>()
+        );
+
+        assert_eq!("pending", press(&mut registry, key(KEY_MOD_CTRL, "k")));
+        assert_eq!("execute", press(&mut registry, key(KEY_MOD_CTRL, "S")));
+        assert_eq!(1, saved.get());
+
+        // An unbound key cancels the chord and is swallowed
+        assert_eq!("pending", press(&mut registry, key(KEY_MOD_CTRL, "k")));
+        assert_eq!("pending", press(&mut registry, key(0, "x")));
+        assert_eq!("unmatched", press(&mut registry, key(KEY_MOD_CTRL, "s")));
+
+        enabled.set(false);
+        assert_eq!("unmatched", press(&mut registry, key(KEY_MOD_CTRL, "k")));
+        assert!(!registry.execute("save-all"));
+        assert_eq!(1, saved.get());
+    }
+
+    #[test]
+    fn test_scoped_chord() {
+        let executed = Rc::new(Cell::new(""));
+        let mut registry = CommandRegistry::new();
+        let e = executed.clone();
+        registry.register("global", move || e.set("global"));
+        let e = executed.clone();
+        registry.register("editor", move || e.set("editor"));
+        let checked = Rc::new(Cell::new(0));
+        let c = checked.clone();
+        registry.set_enabled_predicate("editor", move || {
+            c.set(c.get() + 1);
+            true
+        });
+        registry.bind(KeyChord::parse("Ctrl+K").unwrap(), "global", None);
+
+        let mut root = Element::create(Container::create);
+        let editor = Element::create(Container::create);
+        root.add_child(editor.clone(), 0).unwrap();
+        let chord = KeyChord::parse("Ctrl+K Ctrl+S").unwrap();
+        registry.bind(chord, "editor", Some(&editor));
+
+        // The chord in progress in the inner scope wins over the complete global chord
+        let k = key(KEY_MOD_CTRL, "k");
+        assert_eq!("pending", press_in(&mut registry, k, Some(&editor)));
+        let s = key(KEY_MOD_CTRL, "s");
+        assert_eq!("execute", press_in(&mut registry, s, Some(&editor)));
+        assert_eq!("editor", executed.get());
+
+        // Predicates of bindings out of scope are not evaluated
+        checked.set(0);
+        let k = key(KEY_MOD_CTRL, "k");
+        assert_eq!("execute", press_in(&mut registry, k, Some(&root)));
+        assert_eq!("global", executed.get());
+        assert_eq!(0, checked.get());
+    }
+
+    #[test]
+    fn test_menu_bindings() {
+        let executed = Rc::new(Cell::new(""));
+        let mut registry = CommandRegistry::new();
+        let e = executed.clone();
+        registry.register("copy", move || e.set("command"));
+        registry.bind(KeyChord::parse("Ctrl+C").unwrap(), "copy", None);
+
+        let e = executed.clone();
+        let mut paste = StandardMenuItem::new("Paste", move || e.set("paste"));
+        paste.set_accelerator(Some("Ctrl+V".to_string()));
+        let e = executed.clone();
+        let mut copy = StandardMenuItem::new("Copy", move || e.set("copy item"));
+        copy.set_accelerator(Some("Ctrl+C".to_string()));
+        let mut menu = Menu::new();
+        menu.add_standard_item(paste);
+        menu.add_standard_item(copy.clone());
+        registry.bind_menu(&menu);
+
+        assert_eq!("execute", press(&mut registry, key(KEY_MOD_CTRL, "v")));
+        assert_eq!("paste", executed.get());
+        // Commands take precedence over the accelerators of menus
+        assert_eq!("execute", press(&mut registry, key(KEY_MOD_CTRL, "c")));
+        assert_eq!("command", executed.get());
+
+        // Items referring to commands are triggered by the bindings of the commands
+        copy.set_command(Some("copy".to_string()));
+        registry.unregister("copy");
+        registry.bind_menu(&menu);
+        assert_eq!("unmatched", press(&mut registry, key(KEY_MOD_CTRL, "c")));
+
+        registry.unbind_menu(&menu);
+        assert_eq!("unmatched", press(&mut registry, key(KEY_MOD_CTRL, "v")));
+    }
+}
@@ -0,0 +1,340 @@
+use crate as deft;
+use crate::element::Element;
+use crate::error::{DeftError, DeftResult};
+use crate::event::str_to_named_key;
+use crate::js::{BorrowFromJs, FromJsValue};
+use crate::window::{Window, WindowHandle};
+use deft_macros::js_methods;
+use quick_js::{JsValue, ValueError};
+use winit::dpi::LogicalPosition;
+use winit::event::{ElementState, MouseButton, TouchPhase};
+
+/// Inject synthetic input into a window, e.g. for scripted ui tests.
+///
+/// Positions are logical window coordinates. Elements are addressed by their eid.
+pub struct WindowDriver {
+    window: WindowHandle,
+}
+
+impl BorrowFromJs for WindowDriver {
+    fn borrow_from_js<R, F: FnOnce(&mut Self) -> R>(
+        value: JsValue,
+        receiver: F,
+    ) -> Result<R, ValueError> {
+        let window = WindowHandle::from_js_value(value)?;
+        Ok(receiver(&mut WindowDriver::new(window)))
+    }
+}
+
+fn parse_mouse_button(button: i32) -> MouseButton {
+    match button {
+        2 => MouseButton::Right,
+        3 => MouseButton::Middle,
+        4 => MouseButton::Back,
+        5 => MouseButton::Forward,
+        _ => MouseButton::Left,
+    }
+}
+
+#[js_methods]
+impl WindowDriver {
+    pub fn new(window: WindowHandle) -> Self {
+        Self { window }
+    }
+
+    fn with_window<R, F: FnOnce(&mut Window) -> R>(&self, callback: F) -> DeftResult<R> {
+        let mut window = self.window.upgrade_mut()?;
+        // Hit testing depends on the render tree, make sure it is up to date
+        if window.dirty {
+            window.update_render_tree();
+        }
+        Ok(callback(&mut window))
+    }
+
+    /// Find element by eid in all layers of window
+    pub fn find_element(&self, eid: u32) -> Option<Element> {
+        let window = self.window.upgrade_mut().ok()?;
+        window
+            .layer_roots
+            .iter()
+            .find_map(|lr| window.get_element_by_id(&lr.body, eid))
+    }
+
+    /// Center of the element in window coordinates
+    #[js_func]
+    pub fn element_center(&self, eid: u32) -> Result<(f32, f32), DeftError> {
+        let element = self.find_element(eid).ok_or(DeftError::InvalidParameter)?;
+        let bounds = self.with_window(|w| w.get_element_window_bounds(&element))?;
+        bounds
+            .map(|b| (b.center_x(), b.center_y()))
+            .ok_or(DeftError::InvalidState)
+    }
+
+    #[js_func]
+    pub fn mouse_move(&self, x: f32, y: f32) -> Result<(), DeftError> {
+        self.with_window(|w| {
+            let (win_x, win_y) = w.inner_position();
+            w.cursor_position = LogicalPosition::new(x as f64, y as f64);
+            w.cursor_root_position =
+                LogicalPosition::new((x + win_x) as f64, (y + win_y) as f64);
+            w.handle_cursor_moved();
+        })
+    }
+
+    /// Press mouse button at position, 1 = left, 2 = right, 3 = middle
+    #[js_func]
+    pub fn mouse_down(&self, x: f32, y: f32, button: i32) -> Result<(), DeftError> {
+        self.mouse_move(x, y)?;
+        self.with_window(|w| w.emit_click(parse_mouse_button(button), ElementState::Pressed))
+    }
+
+    #[js_func]
+    pub fn mouse_up(&self, x: f32, y: f32, button: i32) -> Result<(), DeftError> {
+        self.mouse_move(x, y)?;
+        self.with_window(|w| w.emit_click(parse_mouse_button(button), ElementState::Released))
+    }
+
+    #[js_func]
+    pub fn click(&self, x: f32, y: f32) -> Result<(), DeftError> {
+        self.mouse_down(x, y, 1)?;
+        self.mouse_up(x, y, 1)
+    }
+
+    #[js_func]
+    pub fn click_element(&self, eid: u32) -> Result<(), DeftError> {
+        let (x, y) = self.element_center(eid)?;
+        self.click(x, y)
+    }
+
+    #[js_func]
+    pub fn wheel(&self, x: f32, y: f32, cols: f32, rows: f32) -> Result<(), DeftError> {
+        self.mouse_move(x, y)?;
+        self.with_window(|w| w.handle_mouse_wheel((cols, rows)))
+    }
+
+    #[js_func]
+    pub fn wheel_element(&self, eid: u32, cols: f32, rows: f32) -> Result<(), DeftError> {
+        let (x, y) = self.element_center(eid)?;
+        self.wheel(x, y, cols, rows)
+    }
+
+    /// Send key event to the focused element, `key` is a named key(e.g. "Enter") or a character
+    #[js_func]
+    pub fn key(&self, key: String, modifiers: u32, pressed: bool) -> Result<(), DeftError> {
+        let named_key = str_to_named_key(&key);
+        let key_str = match named_key {
+            Some(_) => None,
+            None => Some(key.clone()),
+        };
+        self.with_window(|w| {
+            w.handle_key(
+                modifiers,
+                None,
+                named_key,
+                Some(key),
+                key_str,
+                false,
+                pressed,
+            )
+        })
+    }
+
+    #[js_func]
+    pub fn press_key(&self, key: String, modifiers: u32) -> Result<(), DeftError> {
+        self.key(key.clone(), modifiers, true)?;
+        self.key(key, modifiers, false)
+    }
+
+    /// Commit text to the focused element like an input method does
+    #[js_func]
+    pub fn input(&self, text: String) -> Result<(), DeftError> {
+        self.with_window(|w| w.handle_input(&text))
+    }
+
+    #[js_func]
+    pub fn focus_element(&self, eid: u32) -> Result<(), DeftError> {
+        let element = self.find_element(eid).ok_or(DeftError::InvalidParameter)?;
+        self.with_window(|w| w.focus(element))
+    }
+
+    #[js_func]
+    pub fn touch_start(&self, id: u32, x: f32, y: f32) -> Result<(), DeftError> {
+        self.touch(id, TouchPhase::Started, x, y)
+    }
+
+    #[js_func]
+    pub fn touch_move(&self, id: u32, x: f32, y: f32) -> Result<(), DeftError> {
+        self.touch(id, TouchPhase::Moved, x, y)
+    }
+
+    #[js_func]
+    pub fn touch_end(&self, id: u32, x: f32, y: f32) -> Result<(), DeftError> {
+        self.touch(id, TouchPhase::Ended, x, y)
+    }
+
+    #[js_func]
+    pub fn touch_cancel(&self, id: u32, x: f32, y: f32) -> Result<(), DeftError> {
+        self.touch(id, TouchPhase::Cancelled, x, y)
+    }
+
+    #[js_func]
+    pub fn tap(&self, x: f32, y: f32) -> Result<(), DeftError> {
+        self.touch_start(0, x, y)?;
+        self.touch_end(0, x, y)
+    }
+
+    /// Touch sequence from (x1, y1) to (x2, y2) with `steps` move events
+    #[js_func]
+    pub fn swipe(
+        &self,
+        x1: f32,
+        y1: f32,
+        x2: f32,
+        y2: f32,
+        steps: u32,
+    ) -> Result<(), DeftError> {
+        self.touch_start(0, x1, y1)?;
+        let steps = steps.max(1);
+        for i in 1..=steps {
+            let p = i as f32 / steps as f32;
+            self.touch_move(0, x1 + (x2 - x1) * p, y1 + (y2 - y1) * p)?;
+        }
+        self.touch_end(0, x2, y2)
+    }
+
+    pub fn touch(&self, id: u32, phase: TouchPhase, x: f32, y: f32) -> Result<(), DeftError> {
+        self.with_window(|w| {
+            w.emit_touch_event(id as u64, phase, x, y);
+        })
+    }
+
+    /// Run a pending update of window, so that effects of injected events get painted
+    #[js_func]
+    pub fn flush(&self) -> Result<(), DeftError> {
+        let mut window = self.window.upgrade_mut()?;
+        window.update();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::element::body::Body;
+    use crate::element::container::Container;
+    use crate::element::Element;
+    use crate::event::{ClickEventListener, KEY_MOD_ALT, KEY_MOD_CTRL};
+    use crate::ext::ext_window::WindowAttrs;
+    use crate::menu::{Menu, StandardMenuItem, SubmenuItem};
+    use crate::window::driver::WindowDriver;
+    use crate::window::Window;
+    use quick_js::JsValue;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_click_element() {
+        let handle = Window::create(WindowAttrs {
+            width: Some(100.0),
+            height: Some(100.0),
+            headless: Some(true),
+            ..Default::default()
+        })
+        .unwrap();
+        let mut body = Element::create(Body::create);
+        let mut button = Element::create(Container::create);
+        button.set_style(JsValue::String("width: 40px; height: 30px".to_string()));
+        let clicked = Rc::new(Cell::new(0));
+        let clicked_count = clicked.clone();
+        button.register_event_listener(ClickEventListener::new(move |_e, _ctx| {
+            clicked_count.set(clicked_count.get() + 1);
+        }));
+        body.add_child(button.clone(), 0).unwrap();
+        handle.upgrade_mut().unwrap().set_body(body).unwrap();
+
+        let driver = WindowDriver::new(handle);
+        let (x, y) = driver.element_center(button.get_eid()).unwrap();
+        assert_eq!((20.0, 15.0), (x, y));
+        driver.click_element(button.get_eid()).unwrap();
+        assert_eq!(1, clicked.get());
+        driver.click(80.0, 80.0).unwrap();
+        assert_eq!(1, clicked.get());
+    }
+
+    #[test]
+    fn test_menu_shortcuts() {
+        let handle = Window::create(WindowAttrs {
+            width: Some(100.0),
+            height: Some(100.0),
+            headless: Some(true),
+            ..Default::default()
+        })
+        .unwrap();
+        let copied = Rc::new(Cell::new(0));
+        let copied_count = copied.clone();
+        let mut copy = StandardMenuItem::new("Copy", move || {
+            copied_count.set(copied_count.get() + 1);
+        });
+        copy.set_accelerator(Some("Ctrl+C".to_string()));
+        let mut menu = Menu::new();
+        menu.add_standard_item(copy);
+
+        let driver = WindowDriver::new(handle.clone());
+        handle
+            .upgrade_mut()
+            .unwrap()
+            .register_menu_shortcuts(menu.clone());
+        driver.press_key("c".to_string(), KEY_MOD_CTRL).unwrap();
+        assert_eq!(1, copied.get());
+        handle
+            .upgrade_mut()
+            .unwrap()
+            .unregister_menu_shortcuts(menu);
+        driver.press_key("c".to_string(), KEY_MOD_CTRL).unwrap();
+        assert_eq!(1, copied.get());
+    }
+
+    #[test]
+    fn test_menubar_shortcuts() {
+        let handle = Window::create(WindowAttrs {
+            width: Some(100.0),
+            height: Some(100.0),
+            headless: Some(true),
+            ..Default::default()
+        })
+        .unwrap();
+        let copied = Rc::new(Cell::new(0));
+        let copied_count = copied.clone();
+        let mut copy = StandardMenuItem::new("Copy", move || {
+            copied_count.set(copied_count.get() + 1);
+        });
+        copy.set_accelerator(Some("Ctrl+C".to_string()));
+        let mut edit_menu = Menu::new();
+        edit_menu.add_standard_item(copy);
+        let mut menu = Menu::new();
+        menu.add_submenu(SubmenuItem::new("&Edit".to_string(), edit_menu));
+
+        let driver = WindowDriver::new(handle.clone());
+        handle
+            .upgrade_mut()
+            .unwrap()
+            .set_menubar(Some(menu.clone()));
+        driver.press_key("c".to_string(), KEY_MOD_CTRL).unwrap();
+        assert_eq!(1, copied.get());
+
+        // Alt+E opens the submenu of `&Edit`
+        driver.press_key("e".to_string(), KEY_MOD_ALT).unwrap();
+        let (_, bar_root) = handle.upgrade_mut().unwrap().menubar.clone().unwrap();
+        let edit_item = bar_root.get_children()[0].get_children()[0].clone();
+        assert!(edit_item.get_attribute("active".to_string()).is_some());
+
+        // Replacing the menu bar removes the accelerators of the old menu
+        handle.upgrade_mut().unwrap().set_menubar(Some(Menu::new()));
+        driver.press_key("c".to_string(), KEY_MOD_CTRL).unwrap();
+        assert_eq!(1, copied.get());
+
+        handle.upgrade_mut().unwrap().set_menubar(Some(menu));
+        handle.upgrade_mut().unwrap().set_menubar(None);
+        driver.press_key("c".to_string(), KEY_MOD_CTRL).unwrap();
+        assert_eq!(1, copied.get());
+    }
+}
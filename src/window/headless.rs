@@ -0,0 +1,116 @@
+use crate::renderer::CpuRenderer;
+use skia_safe::Image;
+use skia_window::context::{IRenderContext, RenderContext, UserContext};
+use skia_window::layer::ILayer;
+use skia_window::renderer::Renderer;
+use std::cell::Cell;
+use winit::dpi::{PhysicalSize, Size};
+use winit::window::WindowId;
+
+thread_local! {
+    static NEXT_HEADLESS_ID: Cell<u64> = Cell::new(u64::MAX);
+}
+
+struct HeadlessRenderContext;
+
+impl IRenderContext for HeadlessRenderContext {
+    fn create_layer(&mut self, width: usize, height: usize) -> Option<Box<dyn ILayer>> {
+        Some(Box::new(CpuRenderer::new(width as i32, height as i32)))
+    }
+
+    fn flush(&mut self) {
+        // Do nothing
+    }
+}
+
+/// A window without any display server connection, which paints into a cpu raster surface.
+pub struct HeadlessWindow {
+    id: WindowId,
+    title: String,
+    width: u32,
+    height: u32,
+    scale_factor: f64,
+    visible: bool,
+    renderer: CpuRenderer,
+    context: HeadlessRenderContext,
+    user_context: UserContext,
+}
+
+impl HeadlessWindow {
+    pub fn new(title: String, width: u32, height: u32, scale_factor: f64) -> Self {
+        let raw_id = NEXT_HEADLESS_ID.get();
+        NEXT_HEADLESS_ID.set(raw_id - 1);
+        let (width, height) = (width.max(1), height.max(1));
+        Self {
+            id: WindowId::from(raw_id),
+            title,
+            width,
+            height,
+            scale_factor,
+            visible: true,
+            renderer: CpuRenderer::new(width as i32, height as i32),
+            context: HeadlessRenderContext,
+            user_context: UserContext::new(),
+        }
+    }
+
+    pub fn id(&self) -> WindowId {
+        self.id
+    }
+
+    pub fn scale_factor(&self) -> f64 {
+        self.scale_factor
+    }
+
+    pub fn inner_size(&self) -> PhysicalSize<u32> {
+        PhysicalSize::new(self.width, self.height)
+    }
+
+    pub fn title(&self) -> String {
+        self.title.clone()
+    }
+
+    pub fn set_title(&mut self, title: &str) {
+        self.title = title.to_string();
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+    }
+
+    pub fn request_inner_size(&mut self, size: Size) -> PhysicalSize<u32> {
+        let size: PhysicalSize<u32> = size.to_physical(self.scale_factor);
+        self.resize_surface(size.width, size.height);
+        size
+    }
+
+    pub fn resize_surface(&mut self, width: u32, height: u32) {
+        let (width, height) = (width.max(1), height.max(1));
+        if width != self.width || height != self.height {
+            self.width = width;
+            self.height = height;
+            self.renderer = CpuRenderer::new(width as i32, height as i32);
+        }
+    }
+
+    /// Render synchronously, the callback is called before returning.
+    pub fn render_with_result<C: FnOnce(bool) + Send + 'static>(
+        &mut self,
+        renderer: Renderer,
+        callback: C,
+    ) {
+        let canvas = self.renderer.canvas();
+        let mut ctx = RenderContext::new(&mut self.context, &mut self.user_context);
+        renderer.render(canvas, &mut ctx);
+        callback(true);
+    }
+
+    /// Snapshot of the last painted frame.
+    pub fn snapshot(&mut self) -> Image {
+        self.renderer.surface().image_snapshot()
+    }
+}
@@ -0,0 +1,251 @@
+use crate::window::headless::HeadlessWindow;
+use raw_window_handle::{HandleError, HasRawWindowHandle, RawWindowHandle};
+use skia_window::renderer::Renderer;
+use skia_window::skia_window::SkiaWindow;
+use winit::dpi::{PhysicalPosition, PhysicalSize, Position, Size};
+use winit::error::{ExternalError, NotSupportedError};
+use winit::monitor::MonitorHandle;
+#[cfg(windows)]
+use winit::platform::windows::WindowExtWindows;
+use winit::window::{Cursor, CursorGrabMode, Fullscreen, ResizeDirection, Theme, WindowId};
+
+/// The surface a deft window paints into, either a native window or a headless one.
+pub enum PlatformWindow {
+    Native(SkiaWindow),
+    Headless(HeadlessWindow),
+}
+
+impl PlatformWindow {
+    pub fn is_headless(&self) -> bool {
+        matches!(self, PlatformWindow::Headless(_))
+    }
+
+    pub fn native(&self) -> Option<&SkiaWindow> {
+        match self {
+            PlatformWindow::Native(w) => Some(w),
+            PlatformWindow::Headless(_) => None,
+        }
+    }
+
+    pub fn headless_mut(&mut self) -> Option<&mut HeadlessWindow> {
+        match self {
+            PlatformWindow::Native(_) => None,
+            PlatformWindow::Headless(w) => Some(w),
+        }
+    }
+
+    pub fn id(&self) -> WindowId {
+        match self {
+            PlatformWindow::Native(w) => w.id(),
+            PlatformWindow::Headless(w) => w.id(),
+        }
+    }
+
+    pub fn scale_factor(&self) -> f64 {
+        match self {
+            PlatformWindow::Native(w) => w.scale_factor(),
+            PlatformWindow::Headless(w) => w.scale_factor(),
+        }
+    }
+
+    pub fn inner_size(&self) -> PhysicalSize<u32> {
+        match self {
+            PlatformWindow::Native(w) => w.inner_size(),
+            PlatformWindow::Headless(w) => w.inner_size(),
+        }
+    }
+
+    pub fn outer_size(&self) -> PhysicalSize<u32> {
+        match self {
+            PlatformWindow::Native(w) => w.outer_size(),
+            PlatformWindow::Headless(w) => w.inner_size(),
+        }
+    }
+
+    pub fn inner_position(&self) -> Result<PhysicalPosition<i32>, NotSupportedError> {
+        match self {
+            PlatformWindow::Native(w) => w.inner_position(),
+            PlatformWindow::Headless(_) => Ok(PhysicalPosition::new(0, 0)),
+        }
+    }
+
+    pub fn outer_position(&self) -> Result<PhysicalPosition<i32>, NotSupportedError> {
+        match self {
+            PlatformWindow::Native(w) => w.outer_position(),
+            PlatformWindow::Headless(_) => Ok(PhysicalPosition::new(0, 0)),
+        }
+    }
+
+    pub fn set_outer_position<P: Into<Position>>(&self, position: P) {
+        if let PlatformWindow::Native(w) = self {
+            w.set_outer_position(position);
+        }
+    }
+
+    pub fn request_inner_size<S: Into<Size>>(&mut self, size: S) -> Option<PhysicalSize<u32>> {
+        match self {
+            PlatformWindow::Native(w) => w.request_inner_size(size),
+            PlatformWindow::Headless(w) => Some(w.request_inner_size(size.into())),
+        }
+    }
+
+    pub fn resize_surface(&mut self, width: u32, height: u32) {
+        match self {
+            PlatformWindow::Native(w) => w.resize_surface(width, height),
+            PlatformWindow::Headless(w) => w.resize_surface(width, height),
+        }
+    }
+
+    pub fn render_with_result<C: FnOnce(bool) + Send + 'static>(
+        &mut self,
+        renderer: Renderer,
+        callback: C,
+    ) {
+        match self {
+            PlatformWindow::Native(w) => w.render_with_result(renderer, callback),
+            PlatformWindow::Headless(w) => w.render_with_result(renderer, callback),
+        }
+    }
+
+    pub fn title(&self) -> String {
+        match self {
+            PlatformWindow::Native(w) => w.title(),
+            PlatformWindow::Headless(w) => w.title(),
+        }
+    }
+
+    pub fn set_title(&mut self, title: &str) {
+        match self {
+            PlatformWindow::Native(w) => w.set_title(title),
+            PlatformWindow::Headless(w) => w.set_title(title),
+        }
+    }
+
+    pub fn is_visible(&self) -> Option<bool> {
+        match self {
+            PlatformWindow::Native(w) => w.is_visible(),
+            PlatformWindow::Headless(w) => Some(w.is_visible()),
+        }
+    }
+
+    pub fn set_visible(&mut self, visible: bool) {
+        match self {
+            PlatformWindow::Native(w) => w.set_visible(visible),
+            PlatformWindow::Headless(w) => w.set_visible(visible),
+        }
+    }
+
+    pub fn theme(&self) -> Option<Theme> {
+        self.native()?.theme()
+    }
+
+    pub fn current_monitor(&self) -> Option<MonitorHandle> {
+        self.native()?.current_monitor()
+    }
+
+    pub fn is_decorated(&self) -> bool {
+        match self {
+            PlatformWindow::Native(w) => w.is_decorated(),
+            // Avoid treating window edges as resize handles
+            PlatformWindow::Headless(_) => true,
+        }
+    }
+
+    pub fn set_cursor(&self, cursor: impl Into<Cursor>) {
+        if let PlatformWindow::Native(w) = self {
+            w.set_cursor(cursor);
+        }
+    }
+
+    pub fn set_cursor_visible(&self, visible: bool) {
+        if let PlatformWindow::Native(w) = self {
+            w.set_cursor_visible(visible);
+        }
+    }
+
+    pub fn set_cursor_grab(&self, mode: CursorGrabMode) -> Result<(), ExternalError> {
+        match self {
+            PlatformWindow::Native(w) => w.set_cursor_grab(mode),
+            PlatformWindow::Headless(_) => Ok(()),
+        }
+    }
+
+    pub fn set_ime_allowed(&self, allowed: bool) {
+        if let PlatformWindow::Native(w) = self {
+            w.set_ime_allowed(allowed);
+        }
+    }
+
+    pub fn set_ime_cursor_area<P: Into<Position>, S: Into<Size>>(&self, position: P, size: S) {
+        if let PlatformWindow::Native(w) = self {
+            w.set_ime_cursor_area(position, size);
+        }
+    }
+
+    pub fn drag_window(&self) -> Result<(), ExternalError> {
+        match self {
+            PlatformWindow::Native(w) => w.drag_window(),
+            PlatformWindow::Headless(_) => Ok(()),
+        }
+    }
+
+    pub fn drag_resize_window(&self, direction: ResizeDirection) -> Result<(), ExternalError> {
+        match self {
+            PlatformWindow::Native(w) => w.drag_resize_window(direction),
+            PlatformWindow::Headless(_) => Ok(()),
+        }
+    }
+
+    pub fn set_fullscreen(&self, fullscreen: Option<Fullscreen>) {
+        if let PlatformWindow::Native(w) = self {
+            w.set_fullscreen(fullscreen);
+        }
+    }
+
+    pub fn fullscreen(&self) -> Option<Fullscreen> {
+        self.native()?.fullscreen()
+    }
+
+    pub fn set_maximized(&self, maximized: bool) {
+        if let PlatformWindow::Native(w) = self {
+            w.set_maximized(maximized);
+        }
+    }
+
+    pub fn is_maximized(&self) -> bool {
+        match self {
+            PlatformWindow::Native(w) => w.is_maximized(),
+            PlatformWindow::Headless(_) => false,
+        }
+    }
+
+    pub fn set_minimized(&self, minimized: bool) {
+        if let PlatformWindow::Native(w) = self {
+            w.set_minimized(minimized);
+        }
+    }
+
+    pub fn is_minimized(&self) -> Option<bool> {
+        self.native()?.is_minimized()
+    }
+
+    pub fn set_modal(&self, owner: &PlatformWindow) {
+        if let (PlatformWindow::Native(w), PlatformWindow::Native(o)) = (self, owner) {
+            w.set_modal(o);
+        }
+    }
+
+    #[cfg(windows)]
+    pub fn set_enable(&self, enabled: bool) {
+        if let PlatformWindow::Native(w) = self {
+            w.set_enable(enabled);
+        }
+    }
+
+    pub fn raw_window_handle(&self) -> Result<RawWindowHandle, HandleError> {
+        match self {
+            PlatformWindow::Native(w) => w.raw_window_handle(),
+            PlatformWindow::Headless(_) => Err(HandleError::Unavailable),
+        }
+    }
+}
@@ -6,7 +6,7 @@ use crate::event::ClickEventListener;
 use crate::ext::ext_window::WindowAttrs;
 use crate::platform::support_multiple_windows;
 use crate::window::page::PageWeak;
-use crate::window::{Window, WindowHandle, WindowResizeEventListener};
+use crate::window::{Window, WindowCloseEventListener, WindowHandle, WindowResizeEventListener};
 use crate::winit::dpi::Position;
 use crate::{js_weak_value, ok_or_return};
 use deft_macros::{js_methods, mrc_object};
@@ -23,6 +23,7 @@ enum PopupWrapper {
 #[mrc_object]
 pub struct Popup {
     wrapper: PopupWrapper,
+    close_callback: Option<Box<dyn FnOnce()>>,
 }
 
 js_weak_value!(Popup, PopupWeak);
@@ -40,7 +41,8 @@ impl Popup {
     ) -> Popup {
         //TODO no unwrap
         let mut owner = owner_handle.upgrade_mut().unwrap();
-        if support_multiple_windows() {
+        // Headless windows have nothing to position a popup window against
+        if support_multiple_windows() && !owner.is_headless() {
             let (win_x, win_y) = owner.inner_position();
             let pos_x = target.x + win_x;
             let pos_y = target.bottom() + win_y;
@@ -56,6 +58,7 @@ impl Popup {
                 closable: None,
                 minimizable: None,
                 maximizable: None,
+                headless: None,
                 window_type: Some("menu".to_string()),
                 preferred_renderers: Some(vec!["softbuffer".to_string()]),
             };
@@ -93,25 +96,53 @@ impl Popup {
                     }
                 }
             }));
-
-            PopupData {
-                wrapper: PopupWrapper::Window(window_handle),
-            }
-            .to_ref()
+            Self::from_window(window_handle)
         } else {
             let page = owner.create_page_ex(element, target.x, target.bottom(), focusable);
-            let page_weak = page.as_weak();
+            let popup = PopupData {
+                wrapper: PopupWrapper::Page(page.as_weak()),
+                close_callback: None,
+            }
+            .to_ref();
+            let popup_weak = popup.as_weak();
             page.get_body()
                 .clone()
                 .register_event_listener(ClickEventListener::new(move |_e, _ctx| {
-                    if let Ok(p) = page_weak.upgrade() {
+                    if let Ok(p) = popup_weak.upgrade() {
                         p.close();
                     }
                 }));
-            PopupData {
-                wrapper: PopupWrapper::Page(page.as_weak()),
-            }
-            .to_ref()
+            popup
+        }
+    }
+
+    fn from_window(window_handle: WindowHandle) -> Popup {
+        let popup = PopupData {
+            wrapper: PopupWrapper::Window(window_handle.clone()),
+            close_callback: None,
+        }
+        .to_ref();
+        // Popup windows are also closed directly, e.g. by clicking outside of them
+        let popup_weak = popup.as_weak();
+        if let Ok(mut window) = window_handle.upgrade_mut() {
+            window.register_event_listener(WindowCloseEventListener::new(move |_e, _| {
+                if let Ok(p) = popup_weak.upgrade() {
+                    p.run_close_callback();
+                }
+            }));
+        }
+        popup
+    }
+
+    /// `callback` is called once when the popup is closed, including closing by clicking
+    /// outside of it
+    pub fn set_close_callback<F: FnOnce() + 'static>(&mut self, callback: F) {
+        self.close_callback = Some(Box::new(callback));
+    }
+
+    fn run_close_callback(&self) {
+        if let Some(callback) = self.clone().close_callback.take() {
+            callback();
         }
     }
 
@@ -124,10 +155,12 @@ impl Popup {
                 }
             }
             PopupWrapper::Page(page) => {
-                let page = page.upgrade().unwrap();
-                page.close();
+                if let Ok(page) = page.upgrade() {
+                    page.close();
+                }
             }
         }
+        self.run_close_callback();
     }
 }
 
@@ -143,3 +176,35 @@ fn fix_pos(offset: f32, target_length: f32, content_length: f32, max_length: f32
         offset
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::ext::ext_window::WindowAttrs;
+    use crate::window::popup::Popup;
+    use crate::window::Window;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_close_popup_window_directly() {
+        let attrs = || WindowAttrs {
+            width: Some(100.0),
+            height: Some(100.0),
+            headless: Some(true),
+            ..Default::default()
+        };
+        // Closing the last window exits the app
+        let _owner = Window::create(attrs()).unwrap();
+        let popup_window = Window::create(attrs()).unwrap();
+        let mut popup = Popup::from_window(popup_window.clone());
+        let closed = Rc::new(Cell::new(0));
+        let closed_count = closed.clone();
+        popup.set_close_callback(move || closed_count.set(closed_count.get() + 1));
+
+        popup_window.upgrade_mut().unwrap().close().unwrap();
+        assert_eq!(1, closed.get());
+        // The callback is called only once
+        popup.close();
+        assert_eq!(1, closed.get());
+    }
+}